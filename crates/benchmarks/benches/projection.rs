@@ -0,0 +1,147 @@
+use benchmarks::windows::foundation::collections::IVectorView;
+use benchmarks::windows::foundation::{
+    AsyncActionCompletedHandler, AsyncStatus, IUriRuntimeClassFactory, IWwwFormUrlDecoderEntry, Uri,
+};
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use winrt::{ComInterface, HString, RuntimeType};
+
+fn activation(c: &mut Criterion) {
+    let mut group = c.benchmark_group("activation");
+
+    group.bench_function("projected", |b| {
+        b.iter(|| Uri::create_uri(black_box("http://kennykerr.ca")).unwrap())
+    });
+
+    // The hand-written version reuses the factory rather than looking it up for each call.
+    let factory = winrt::activation::factory::<Uri, IUriRuntimeClassFactory>().unwrap();
+    let uri = HString::from("http://kennykerr.ca");
+
+    group.bench_function("abi", |b| {
+        b.iter(|| unsafe {
+            let this = factory.as_raw();
+            let mut result = Uri::default();
+            ((*(*this)).create_uri)(this, black_box(uri.abi()), result.set_abi())
+                .and_then(|| result)
+                .unwrap()
+        })
+    });
+
+    group.finish();
+}
+
+fn method_call(c: &mut Criterion) {
+    let mut group = c.benchmark_group("method_call");
+    let uri = Uri::create_uri("http://kennykerr.ca").unwrap();
+
+    group.bench_function("projected", |b| b.iter(|| black_box(&uri).port().unwrap()));
+
+    group.bench_function("abi", |b| {
+        b.iter(|| unsafe {
+            let this = black_box(&uri).as_raw();
+            let mut port = 0;
+            ((*(*this)).port)(this, &mut port)
+                .and_then(|| port)
+                .unwrap()
+        })
+    });
+
+    // Methods of non-default interfaces require a QueryInterface for each call.
+    group.bench_function("non_default", |b| {
+        b.iter(|| black_box(&uri).to_string().unwrap())
+    });
+
+    group.finish();
+}
+
+fn string_conversion(c: &mut Criterion) {
+    let mut group = c.benchmark_group("string_conversion");
+    let value = "http://kennykerr.ca/some/longer/path?with=a&query=string";
+    let hstring = HString::from(value);
+
+    group.bench_function("from_str", |b| b.iter(|| HString::from(black_box(value))));
+    group.bench_function("static", |b| {
+        b.iter(|| {
+            black_box(winrt::h!(
+                "http://kennykerr.ca/some/longer/path?with=a&query=string"
+            ))
+        })
+    });
+    group.bench_function("to_string", |b| {
+        b.iter(|| String::from(black_box(&hstring)))
+    });
+    group.bench_function("clone", |b| b.iter(|| black_box(&hstring).clone()));
+
+    group.finish();
+}
+
+fn delegate_invocation(c: &mut Criterion) {
+    let mut group = c.benchmark_group("delegate_invocation");
+    let waker = futures::task::noop_waker();
+
+    group.bench_function("create", |b| {
+        b.iter(|| winrt::future::completed_handler::<AsyncActionCompletedHandler>(&waker))
+    });
+
+    let handler = winrt::future::completed_handler::<AsyncActionCompletedHandler>(&waker);
+
+    group.bench_function("invoke", |b| {
+        b.iter(|| unsafe {
+            winrt::future::set_waker(&handler, &waker).unwrap();
+            let this = handler.as_raw();
+            ((*(*this)).invoke)(this, std::ptr::null_mut(), AsyncStatus::Completed.abi())
+                .ok()
+                .unwrap()
+        })
+    });
+
+    group.finish();
+}
+
+fn collection_iteration(c: &mut Criterion) {
+    let mut group = c.benchmark_group("collection_iteration");
+    let uri = Uri::create_uri("http://kennykerr.ca?a=1&b=2&c=3&d=4&e=5&f=6&g=7&h=8").unwrap();
+    let query = uri.query_parsed().unwrap();
+
+    group.bench_function("projected", |b| {
+        b.iter(|| {
+            let mut count = 0;
+            for entry in black_box(&query).clone() {
+                count += entry.name().unwrap().len();
+            }
+            count
+        })
+    });
+
+    let view: IVectorView<IWwwFormUrlDecoderEntry> = query.clone().into();
+
+    group.bench_function("abi", |b| {
+        b.iter(|| unsafe {
+            let this = black_box(&view).as_raw();
+            let mut size = 0;
+            ((*(*this)).size)(this, &mut size).ok().unwrap();
+            let mut count = 0;
+
+            for index in 0..size {
+                let mut entry = IWwwFormUrlDecoderEntry::default();
+                ((*(*this)).get_at)(this, index, entry.set_abi())
+                    .ok()
+                    .unwrap();
+                count += entry.name().unwrap().len();
+            }
+
+            count
+        })
+    });
+
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    activation,
+    method_call,
+    string_conversion,
+    delegate_invocation,
+    collection_iteration
+);
+criterion_main!(benches);
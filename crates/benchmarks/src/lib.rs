@@ -0,0 +1,11 @@
+//! Benchmarks measuring the overhead of the projection relative to hand-written ABI calls.
+//!
+//! Run them on Windows with `cargo bench -p benchmarks`.
+
+#[cfg(windows)]
+winrt::import!(
+    dependencies
+        "os"
+    modules
+        "windows.foundation.collections"
+);
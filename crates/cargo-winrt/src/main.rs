@@ -0,0 +1,379 @@
+//! Installs Windows Metadata from NuGet packages
+//!
+//! `cargo winrt install <package> <version> [--sha512 <hash>] [--dir <dir>]` downloads a package
+//! such as `Microsoft.Windows.SDK.Contracts` from nuget.org over HTTPS, checks the package's
+//! SHA-512 hash, and copies the `.winmd` files it contains into `<dir>/<package>.<version>`,
+//! where `<dir>` defaults to `winmd`. The files keep their paths within the package, such as
+//! `lib/uap10.0/`, so they can then be listed under `dependencies` in `import!` with a pattern
+//! such as `winmd/**/*.winmd`, or one that picks a single target framework, so that a project
+//! builds without Visual Studio or `nuget.exe` installed.
+//!
+//! The hash pins the exact package the project was developed against, so it must be given with
+//! `--sha512`, as base64 as nuget.org shows it or as hex, unless the version being installed is
+//! already recorded in the current directory's `winmd.lock`. A hash published by the same server
+//! that serves the package wouldn't detect a package that was replaced.
+//!
+//! When the current directory has a `winmd.lock`, as created by building with the
+//! `WINRT_UPDATE_LOCKFILE` environment variable set, the package's version and hash are recorded
+//! in it alongside the hashes of the metadata files.
+
+use sha2::{Digest, Sha512};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::process::exit;
+use winmd::{LockedPackage, Lockfile};
+
+const USAGE: &str =
+    "usage: cargo winrt install <package> <version> [--sha512 <hash>] [--dir <dir>]";
+const PACKAGES: &str = "https://api.nuget.org/v3-flatcontainer";
+const LOCKFILE: &str = "winmd.lock";
+
+// Packages are read into memory before being checked, so their size is limited.
+const MAX_PACKAGE_SIZE: u64 = 512 * 1024 * 1024;
+
+struct Install {
+    package: String,
+    version: String,
+    sha512: Option<String>,
+    dir: PathBuf,
+}
+
+fn main() {
+    // Cargo passes the subcommand's name as the first argument.
+    let mut args = std::env::args().skip(1).peekable();
+
+    if args.peek().map(String::as_str) == Some("winrt") {
+        args.next();
+    }
+
+    let args: Vec<String> = args.collect();
+
+    let install = parse_args(&args).unwrap_or_else(|| {
+        eprintln!("{}", USAGE);
+        exit(2);
+    });
+
+    if let Err(error) = install.run() {
+        eprintln!("error: {}", error);
+        exit(1);
+    }
+}
+
+fn parse_args(args: &[String]) -> Option<Install> {
+    let mut args = args.iter();
+
+    if args.next()? != "install" {
+        return None;
+    }
+
+    let mut positional = Vec::new();
+    let mut sha512 = None;
+    let mut dir = PathBuf::from("winmd");
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--sha512" => sha512 = Some(args.next()?.clone()),
+            "--dir" => dir = PathBuf::from(args.next()?),
+            _ if arg.starts_with("--") => return None,
+            _ => positional.push(arg.clone()),
+        }
+    }
+
+    match positional.as_slice() {
+        [package, version] => Some(Install {
+            package: package.clone(),
+            version: version.clone(),
+            sha512,
+            dir,
+        }),
+        _ => None,
+    }
+}
+
+impl Install {
+    fn run(&self) -> Result<(), String> {
+        // The NuGet APIs expect lowercase identifiers and versions.
+        let id = self.package.to_lowercase();
+        let version = self.version.to_lowercase();
+
+        let expected = match &self.sha512 {
+            Some(hash) => hash.clone(),
+            None => self.locked_hash(Path::new(LOCKFILE))?,
+        };
+
+        let package = download(&format!(
+            "{}/{}/{}/{}.{}.nupkg",
+            PACKAGES, id, version, id, version
+        ))?;
+
+        let actual = Sha512::digest(&package);
+
+        if decode_hash(&expected)? != actual.as_slice() {
+            return Err(format!(
+                "The hash of {} {} doesn't match: expected {}, found {}",
+                self.package,
+                self.version,
+                expected,
+                base64::Engine::encode(&base64::engine::general_purpose::STANDARD, actual)
+            ));
+        }
+
+        let files = extract_winmd(
+            &package,
+            &self.dir.join(format!("{}.{}", self.package, self.version)),
+        )?;
+
+        if files.is_empty() {
+            return Err(format!(
+                "{} {} doesn't contain any .winmd files",
+                self.package, self.version
+            ));
+        }
+
+        for file in &files {
+            println!("{}", file.display());
+        }
+
+        let sha512 = base64::Engine::encode(&base64::engine::general_purpose::STANDARD, actual);
+
+        println!(
+            "Installed {} files from {} {} (sha512 {})",
+            files.len(),
+            self.package,
+            self.version,
+            sha512
+        );
+
+        if Path::new(LOCKFILE).exists() {
+            self.lock(Path::new(LOCKFILE), sha512)?;
+        }
+
+        Ok(())
+    }
+
+    // The hash recorded in the lockfile for the version being installed.
+    fn locked_hash(&self, path: &Path) -> Result<String, String> {
+        let lockfile = if path.exists() {
+            read_lockfile(path)?
+        } else {
+            Lockfile::default()
+        };
+
+        match lockfile.packages.get(&self.package) {
+            Some(package) if package.version == self.version => Ok(package.sha512.clone()),
+            _ => Err(format!(
+                "{} {} isn't recorded in {}, so its hash must be given with --sha512",
+                self.package, self.version, LOCKFILE
+            )),
+        }
+    }
+
+    // Records the installed package in the lockfile.
+    fn lock(&self, path: &Path, sha512: String) -> Result<(), String> {
+        let mut lockfile = read_lockfile(path)?;
+
+        let package = LockedPackage {
+            version: self.version.clone(),
+            sha512,
+        };
+
+        lockfile.packages.insert(self.package.clone(), package);
+
+        std::fs::write(path, lockfile.to_toml())
+            .map_err(|error| format!("Could not write {}: {}", path.display(), error))?;
+
+        println!(
+            "Recorded {} {} in {}",
+            self.package,
+            self.version,
+            path.display()
+        );
+        Ok(())
+    }
+}
+
+fn read_lockfile(path: &Path) -> Result<Lockfile, String> {
+    std::fs::read_to_string(path)
+        .map_err(|error| error.to_string())
+        .and_then(|source| Lockfile::from_toml(&source))
+        .map_err(|error| format!("Could not read {}: {}", path.display(), error))
+}
+
+fn download(url: &str) -> Result<Vec<u8>, String> {
+    if !url.starts_with("https://") {
+        return Err(format!("Refusing to download {} without HTTPS", url));
+    }
+
+    let response = ureq::get(url)
+        .call()
+        .map_err(|error| format!("Could not download {}: {}", url, error))?;
+
+    // One byte more than the limit is read so that a larger package is reported rather than
+    // truncated.
+    let mut bytes = Vec::new();
+    response
+        .into_reader()
+        .take(MAX_PACKAGE_SIZE + 1)
+        .read_to_end(&mut bytes)
+        .map_err(|error| format!("Could not download {}: {}", url, error))?;
+
+    if bytes.len() as u64 > MAX_PACKAGE_SIZE {
+        return Err(format!(
+            "{} is larger than the {} MB limit",
+            url,
+            MAX_PACKAGE_SIZE / (1024 * 1024)
+        ));
+    }
+
+    Ok(bytes)
+}
+
+// Hashes are accepted as hex or as base64, as shown on nuget.org.
+fn decode_hash(hash: &str) -> Result<Vec<u8>, String> {
+    let hash = hash.trim();
+
+    if hash.len() == 128 && hash.bytes().all(|byte| byte.is_ascii_hexdigit()) {
+        return Ok((0..hash.len())
+            .step_by(2)
+            .map(|index| u8::from_str_radix(&hash[index..index + 2], 16).unwrap())
+            .collect());
+    }
+
+    match base64::Engine::decode(&base64::engine::general_purpose::STANDARD, hash) {
+        Ok(bytes) if bytes.len() == 64 => Ok(bytes),
+        _ => Err(format!("`{}` is not a SHA-512 hash", hash)),
+    }
+}
+
+// Copies the .winmd files from the package into the directory, returning the files written.
+// Files keep their paths within the package, as a package may ship different files with the same
+// name for each target framework, and entries can't be written outside of the directory.
+fn extract_winmd(package: &[u8], dir: &Path) -> Result<Vec<PathBuf>, String> {
+    let mut archive = zip::ZipArchive::new(std::io::Cursor::new(package))
+        .map_err(|error| format!("Could not read the package: {}", error))?;
+
+    let mut files: Vec<PathBuf> = Vec::new();
+
+    for index in 0..archive.len() {
+        let mut entry = archive
+            .by_index(index)
+            .map_err(|error| format!("Could not read the package: {}", error))?;
+
+        let path = match entry.enclosed_name() {
+            Some(name) if winmd::load_winmd::is_winmd(name) => dir.join(name),
+            _ => continue,
+        };
+
+        let mut contents = Vec::new();
+        entry
+            .read_to_end(&mut contents)
+            .map_err(|error| format!("Could not read {}: {}", entry.name(), error))?;
+
+        path.parent()
+            .map_or(Ok(()), std::fs::create_dir_all)
+            .and_then(|_| std::fs::write(&path, contents))
+            .map_err(|error| format!("Could not write {}: {}", path.display(), error))?;
+
+        files.push(path);
+    }
+
+    Ok(files)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hashes() {
+        let digest = Sha512::digest(b"abc");
+        let hex: String = digest.iter().map(|byte| format!("{:02x}", byte)).collect();
+        let base64 = base64::Engine::encode(&base64::engine::general_purpose::STANDARD, digest);
+
+        assert_eq!(decode_hash(&hex).unwrap(), digest.as_slice());
+        assert_eq!(decode_hash(&base64).unwrap(), digest.as_slice());
+        assert!(decode_hash("abc").is_err());
+    }
+
+    #[test]
+    fn extract() {
+        use std::io::Write;
+
+        let mut package = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+        let options = zip::write::FileOptions::default();
+
+        for (name, contents) in [
+            ("lib/net45/Package.winmd", "net45"),
+            ("lib/uap10.0/Package.winmd", "uap10.0"),
+            ("lib/uap10.0/Package.xml", "xml"),
+            ("../Outside.winmd", "outside"),
+        ] {
+            package.start_file(name, options).unwrap();
+            package.write_all(contents.as_bytes()).unwrap();
+        }
+
+        let package = package.finish().unwrap().into_inner();
+        let dir = std::env::temp_dir().join(format!("cargo_winrt_{}", std::process::id()));
+        let files = extract_winmd(&package, &dir).unwrap();
+
+        // Files with the same name for different target frameworks are both kept.
+        let net45 = dir.join("lib").join("net45").join("Package.winmd");
+        let uap = dir.join("lib").join("uap10.0").join("Package.winmd");
+        assert_eq!(files, [net45.clone(), uap.clone()]);
+        assert_eq!(std::fs::read_to_string(net45).unwrap(), "net45");
+        assert_eq!(std::fs::read_to_string(uap).unwrap(), "uap10.0");
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn args() {
+        let args: Vec<String> = [
+            "install",
+            "Microsoft.Windows.SDK.Contracts",
+            "10.0.22621.2428",
+        ]
+        .iter()
+        .map(|arg| arg.to_string())
+        .collect();
+
+        let install = parse_args(&args).unwrap();
+        assert_eq!(install.package, "Microsoft.Windows.SDK.Contracts");
+        assert_eq!(install.dir, Path::new("winmd"));
+        assert!(install.sha512.is_none());
+
+        assert!(parse_args(&args[..2]).is_none());
+    }
+
+    #[test]
+    fn locked_hash() {
+        let path = std::env::temp_dir().join(format!("cargo_winrt_{}.lock", std::process::id()));
+        let args: Vec<String> = ["install", "Package", "1.0.0"]
+            .iter()
+            .map(|arg| arg.to_string())
+            .collect();
+        let install = parse_args(&args).unwrap();
+
+        // A hash must be pinned before a package is downloaded.
+        assert!(install.locked_hash(&path).is_err());
+
+        let mut lockfile = Lockfile::default();
+        lockfile.packages.insert(
+            "Package".to_string(),
+            LockedPackage {
+                version: "1.0.0".to_string(),
+                sha512: "hash".to_string(),
+            },
+        );
+        std::fs::write(&path, lockfile.to_toml()).unwrap();
+        assert_eq!(install.locked_hash(&path).unwrap(), "hash");
+
+        let args: Vec<String> = ["install", "Package", "2.0.0"]
+            .iter()
+            .map(|arg| arg.to_string())
+            .collect();
+        assert!(parse_args(&args).unwrap().locked_hash(&path).is_err());
+
+        std::fs::remove_file(path).unwrap();
+    }
+}
@@ -1,17 +1,219 @@
 extern crate proc_macro;
 
 use proc_macro::{TokenStream, TokenTree};
-use winmd::{TypeLimits, TypeReader, TypeStage};
+use winmd::{
+    CoverageReport, ImportSummary, Lockfile, MetadataReport, Renames, SizeReport, TypeLimits,
+    TypeOptions, TypeReader, TypeStage,
+};
 
-use std::collections::BTreeSet;
+use std::collections::{BTreeMap, BTreeSet};
 use std::path::{Path, PathBuf};
 
 /// A macro for generating WinRT modules into the current module
+///
+/// All of the types in the namespaces listed under `modules` are generated, while the types
+/// listed under `types` only bring in the types that they depend on. Any types from other
+/// namespaces that the generated types depend on are generated as well, so that a namespace such
+/// as `windows.media.capture` can be imported without also listing `windows.storage.streams`.
+///
+/// A class's methods include those of all of its interfaces. When two of its interfaces
+/// declare methods with the same name, the method of the later interface is generated with the
+/// interface's name appended, so that `IClosable::Close` becomes `close_closable` on a class
+/// whose default interface also has a `Close` method. Each such method's documentation names
+/// the interface method it calls.
+///
+/// The `dependencies` category lists `.winmd` files, directories containing them, or `"os"` for
+/// the metadata installed with Windows. Relative paths are resolved against the importing
+/// crate's directory, so that metadata vendored in a repository is found wherever it's built,
+/// and `${env:NAME}` is replaced with the value of an environment variable, as in
+/// `"${env:WindowsSdkDir}/UnionMetadata"`.
+///
+/// Only the top level of a directory is searched, while a path with wildcards searches below
+/// it, where `*` matches any part of a name, `?` any one character, and `**` any number of
+/// directories, regardless of case. A dependency starting with `!` excludes the files it would
+/// otherwise include, for instance to leave out one of the contracts found in a NuGet package:
+///
+/// ```ignore
+/// dependencies
+///     "packages/**/lib/uap10.0/*.winmd"
+///     "!packages/**/Windows.Foundation.FoundationContract.winmd"
+/// ```
+///
+/// The `options` category accepts `"strict"`, which instead fails with a list of the namespaces
+/// that are depended on but not listed under `modules`, for crates that want every generated
+/// namespace to be listed explicitly.
+///
+/// A type defined with different members by more than one dependency, as when mixing metadata
+/// from two versions of the Windows SDK, is read from the file with the highest contract version
+/// and warned about, naming both files. The `"strict"` option turns the warning into an error.
+///
+/// The dependencies are validated before they're read, so that corrupt or unsupported metadata
+/// fails with the file, table and row of the problem. The `"skip_validation"` option skips this
+/// for metadata that is known to be well-formed, at the cost of a less helpful panic otherwise.
+///
+/// The `options` category accepts `"erased"`, which generates the methods of generic interfaces
+/// as thin wrappers around a single type-erased specialization. This trades a little runtime
+/// cost for smaller binaries and faster compiles when many generic instantiations are used.
+///
+/// The `options` category also accepts `"nullable"`, which generates methods that return
+/// classes, interfaces, delegates or objects as `Result<Option<T>>` so that a null return is
+/// `None` rather than a value wrapping a null pointer.
+///
+/// The `options` category also accepts `"interface_names"`, which generates a
+/// `register_interface_names` function alongside the imported modules. Once it has been called,
+/// `winrt::diagnostics::interface_name` names the imported interfaces and delegates by their
+/// IIDs, as do the events that the runtime logs for failed conversions.
+///
+/// The `options` category also accepts `"inline"`, which marks the generated property getters
+/// and setters `#[inline]`, and `"must_use"`, which marks the generated methods and default
+/// constructors `#[must_use]`. Both are off by default as they add to debug build times.
+///
+/// The `options` category also accepts `"version_checks=N"`, where `N` is the oldest version of
+/// the `Windows.Foundation.UniversalApiContract` the crate supports, such as `7` for Windows 10
+/// version 1809. The methods of classes that come from interfaces introduced by later versions
+/// of the contract first check that the running version of Windows has them, failing with
+/// `ErrorKind::NotSupportedOnThisVersion` otherwise, so that an app can call newer APIs where
+/// they're available rather than being built separately for each version of Windows.
+///
+/// The `renames` category names a TOML file, relative to the importing crate's directory, that
+/// controls the casing of methods and struct fields and renames individual types and members,
+/// so that the names can match existing C++/WinRT code:
+///
+/// ```toml
+/// # Keep the names of methods and struct fields as they are in metadata.
+/// casing = "pascal"
+///
+/// [types]
+/// "Windows.Foundation.Uri" = "WinUri"
+///
+/// [members]
+/// "Windows.Foundation.Uri.Domain" = "Host"
+/// ```
+///
+/// Renamed types and methods are generated as aliases, leaving the projection's own names in
+/// place, while the fields of structs outside of `Windows.Foundation` are renamed outright.
+///
+/// When the `WINRT_COVERAGE_REPORT` environment variable names a file, each import appends a
+/// line of JSON to it for every type in the listed namespaces, or method of a generated type,
+/// that was skipped along with the reason it was skipped: `unsupported_type`, `attribute` or
+/// `api_contract` for definitions that aren't projected, `filtered` or `deprecated` for types
+/// that nothing listed reaches, and `method_collision` for methods whose names collide.
+///
+/// When the `WINRT_SIZE_REPORT` environment variable names a file, each import appends a line
+/// of JSON to it for every generated namespace and type, from largest to smallest, with the
+/// number of tokens generated for it and its share of the total. The imports responsible for
+/// most of the compile time can then be pruned, for instance by importing specific `types`
+/// rather than whole `modules`.
+///
+/// When the `WINRT_IMPORT_SUMMARY` environment variable is set, each import prints a line such
+/// as `winrt::import! in app (windows.foundation): 3 namespaces, 120 types, 950 methods,
+/// expansion took 840 ms` while it's compiled, so that an import that balloons the build is
+/// noticed as soon as it's added.
+///
+/// When the `WINRT_METADATA_REPORT` environment variable names a file, each import appends a
+/// line of JSON to it for every generated type, describing its Rust path, IID, interfaces, and
+/// the signature and vtable slot of each method, so that tools such as binding generators for
+/// other languages can consume the projection without reading the metadata themselves.
+///
+/// When a `winmd.lock` file exists in the importing crate's directory, the content hash of each
+/// metadata file the import depends on must match the hash recorded for it, so that a machine
+/// or CI image with a different Windows SDK or NuGet package fails to build rather than
+/// generating a different projection. Building with the `WINRT_UPDATE_LOCKFILE` environment
+/// variable set creates the lockfile or records the current hashes in it.
 #[proc_macro]
 pub fn import(stream: TokenStream) -> TokenStream {
-    let (_dependencies, namespaces) = parse_import_stream(stream);
+    let started = std::time::Instant::now();
+    let Import {
+        dependencies,
+        namespaces,
+        types,
+        options,
+        renames,
+    } = parse_import_stream(stream);
 
-    let reader = &TypeReader::from_os();
+    // The summary names the import by what it lists, as macros can't tell where they were called.
+    let listed: Vec<&str> = namespaces
+        .iter()
+        .chain(types.iter())
+        .map(String::as_str)
+        .collect();
+    let label = format!(
+        "{} ({})",
+        std::env::var("CARGO_PKG_NAME").unwrap_or_default(),
+        listed.join(", ")
+    );
+
+    let lockfile = match check_lockfile(&dependencies) {
+        Ok(lockfile) => lockfile.map(|path| track(&path)),
+        Err(message) => return quote::quote! { compile_error!(#message); }.into(),
+    };
+
+    let files = match winmd::load_winmd::from_files(dependencies.into_keys()) {
+        Ok(files) => files,
+        Err(error) => {
+            let message = error.to_string();
+            return quote::quote! { compile_error!(#message); }.into();
+        }
+    };
+
+    let reader = if options.contains("skip_validation") {
+        TypeReader::try_new_unvalidated(files)
+    } else {
+        TypeReader::try_new(files)
+    };
+
+    let reader = match reader {
+        Ok(reader) => reader,
+        Err(error) => {
+            let message = error.to_string();
+            return quote::quote! { compile_error!(#message); }.into();
+        }
+    };
+    let reader = &reader;
+
+    // Types defined differently by two files, such as from two versions of the SDK, are read
+    // from the file with the highest contract version, which is warned about since it's rarely
+    // intended, or fails under the `strict` option.
+    let conflicts = if reader.conflicts.is_empty() {
+        quote::quote! {}
+    } else {
+        let mut conflicts: Vec<String> = reader
+            .conflicts
+            .iter()
+            .take(MAX_CONFLICTS)
+            .map(ToString::to_string)
+            .collect();
+
+        if reader.conflicts.len() > MAX_CONFLICTS {
+            conflicts.push(format!(
+                "and {} more",
+                reader.conflicts.len() - MAX_CONFLICTS
+            ));
+        }
+
+        if options.contains("strict") {
+            let message = format!(
+                "The dependencies define types differently: {}. Remove the conflicting files or the `strict` option to use the definitions with the highest contract versions.",
+                conflicts.join("; ")
+            );
+
+            return quote::quote! { compile_error!(#message); }.into();
+        }
+
+        let message = format!(
+            "the dependencies define types differently: {}",
+            conflicts.join("; ")
+        );
+
+        // There's no stable way for a macro to warn, so a deprecated constant is used instead.
+        quote::quote! {
+            const _: () = {
+                #[deprecated(note = #message)]
+                const WINRT_CONFLICTING_METADATA: () = ();
+                WINRT_CONFLICTING_METADATA
+            };
+        }
+    };
 
     let mut limits = TypeLimits::default();
 
@@ -19,9 +221,208 @@ pub fn import(stream: TokenStream) -> TokenStream {
         limits.insert(reader, &namespace);
     }
 
-    let stage = TypeStage::from_limits(reader, &limits);
-    let tree = stage.into_tree();
-    let stream = tree.to_tokens();
+    for name in types {
+        limits.insert_type(reader, &name);
+    }
+
+    let mut stage = TypeStage::from_limits(reader, &limits);
+
+    if options.contains("strict") {
+        let unlisted = stage.unlisted_namespaces(&limits);
+
+        if !unlisted.is_empty() {
+            let namespaces: Vec<String> = unlisted
+                .iter()
+                .map(|(namespace, name)| {
+                    format!("`{}` (used by `{}`)", namespace.to_lowercase(), name)
+                })
+                .collect();
+
+            let message = format!(
+                "The imported types depend on namespaces that aren't listed under `modules`: {}. Add them to `modules` or remove the `strict` option to generate them automatically.",
+                namespaces.join(", ")
+            );
+
+            return quote::quote! { compile_error!(#message); }.into();
+        }
+    }
+
+    if let Some(path) = std::env::var_os("WINRT_COVERAGE_REPORT") {
+        let report = CoverageReport::new(reader, &limits, &stage);
+
+        if let Err(error) = append(Path::new(&path), &report.to_json_lines()) {
+            let message = format!("Could not write the coverage report {:?}: {}", path, error);
+            return quote::quote! { compile_error!(#message); }.into();
+        }
+    }
+
+    let (renames, renames_file) = match renames {
+        Some(path) => match std::fs::read_to_string(&path)
+            .map_err(|error| error.to_string())
+            .and_then(|source| Renames::from_toml(&source))
+        {
+            Ok(renames) => (renames, Some(track(&path))),
+            Err(error) => {
+                let message = format!("Could not read the renames {:?}: {}", path, error);
+                return quote::quote! { compile_error!(#message); }.into();
+            }
+        },
+        None => (Renames::default(), None),
+    };
+
+    stage.rename(reader, &renames);
+    let mut tree = stage.into_tree();
+    tree.insert_aliases(&renames);
+    let options = TypeOptions {
+        erased: options.contains("erased"),
+        nullable: options.contains("nullable"),
+        interface_names: options.contains("interface_names"),
+        inline: options.contains("inline"),
+        must_use: options.contains("must_use"),
+        version_checks: options.iter().find_map(|option| version_checks(option)),
+    };
+
+    let code = tree.generate(options);
+
+    if let Some(path) = std::env::var_os("WINRT_SIZE_REPORT") {
+        let report = SizeReport::from_code(&tree, &code);
+
+        if let Err(error) = append(Path::new(&path), &report.to_json_lines()) {
+            let message = format!("Could not write the size report {:?}: {}", path, error);
+            return quote::quote! { compile_error!(#message); }.into();
+        }
+    }
+
+    if let Some(path) = std::env::var_os("WINRT_METADATA_REPORT") {
+        let report = MetadataReport::new(&tree);
+
+        if let Err(error) = append(Path::new(&path), &report.to_json_lines()) {
+            let message = format!("Could not write the metadata report {:?}: {}", path, error);
+            return quote::quote! { compile_error!(#message); }.into();
+        }
+    }
+
+    let tokens = tree.to_tokens_from_code(code, options);
+
+    if std::env::var_os("WINRT_IMPORT_SUMMARY").is_some() {
+        eprintln!(
+            "winrt::import! in {}: {}, expansion took {} ms",
+            label,
+            ImportSummary::new(&tree),
+            started.elapsed().as_millis()
+        );
+    }
+
+    quote::quote! { #lockfile #renames_file #conflicts #tokens }.into()
+}
+
+// Makes the crate depend on a file that the expansion is generated from, so that changing the
+// file expands the import again. Paths given to `include_bytes!` are relative to the source file
+// rather than the current directory, so the path is made absolute.
+fn track(path: &Path) -> proc_macro2::TokenStream {
+    let path = std::env::current_dir()
+        .map(|dir| dir.join(path))
+        .unwrap_or_else(|_| path.to_path_buf());
+    let path = path.to_string_lossy();
+
+    quote::quote! {
+        const _: &[u8] = include_bytes!(#path);
+    }
+}
+
+// The minimum version of the UniversalApiContract given by a `version_checks=N` option.
+fn version_checks(option: &str) -> Option<u16> {
+    option
+        .strip_prefix("version_checks=")
+        .and_then(|version| version.trim().parse().ok())
+}
+
+// The number of conflicting types listed by the warning or error.
+const MAX_CONFLICTS: usize = 10;
+
+// Verifies the dependencies against the crate's lockfile or, when updating, records them in it.
+// Returns the path of the lockfile, if there is one.
+fn check_lockfile(dependencies: &BTreeMap<PathBuf, PathBuf>) -> Result<Option<PathBuf>, String> {
+    let path = match std::env::var_os("CARGO_MANIFEST_DIR") {
+        Some(dir) => Path::new(&dir).join("winmd.lock"),
+        None => return Ok(None),
+    };
+
+    let update = std::env::var_os("WINRT_UPDATE_LOCKFILE").is_some();
+
+    if !update && !path.exists() {
+        return Ok(None);
+    }
+
+    let actual = Lockfile::from_files(dependencies)
+        .map_err(|error| format!("Could not hash the metadata files: {}", error))?;
+
+    let mut locked = if path.exists() {
+        std::fs::read_to_string(&path)
+            .map_err(|error| error.to_string())
+            .and_then(|source| Lockfile::from_toml(&source))
+            .map_err(|error| format!("Could not read the lockfile {:?}: {}", path, error))?
+    } else {
+        Lockfile::default()
+    };
+
+    if update {
+        locked.update(actual);
+        std::fs::write(&path, locked.to_toml())
+            .map_err(|error| format!("Could not write the lockfile {:?}: {}", path, error))?;
+    } else {
+        locked.verify(&actual).map_err(|drifted| {
+            format!(
+                "The metadata doesn't match the lockfile {:?}: {}. Build with the `WINRT_UPDATE_LOCKFILE` environment variable set to update it.",
+                path, drifted
+            )
+        })?;
+    }
+
+    Ok(Some(path))
+}
+
+fn append(path: &Path, contents: &str) -> std::io::Result<()> {
+    use std::io::Write;
+
+    std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)?
+        .write_all(contents.as_bytes())
+}
+
+/// Generates the static UTF-16 data and fast-pass header for the `h!` macro
+#[doc(hidden)]
+#[proc_macro]
+pub fn __hstring(stream: TokenStream) -> TokenStream {
+    let literal = syn::parse_macro_input!(stream as syn::LitStr);
+    let wide: Vec<u16> = literal.value().encode_utf16().collect();
+
+    // Empty strings are represented by a null HSTRING.
+    if wide.is_empty() {
+        return quote::quote! {
+            pub static HSTRING: ::winrt::StaticHString = ::winrt::StaticHString {
+                header: 0 as *const ::winrt::StaticHeader,
+            };
+        }
+        .into();
+    }
+
+    let len = wide.len() as u32;
+    let capacity = wide.len() + 1;
+
+    let stream = quote::quote! {
+        static DATA: [u16; #capacity] = [#(#wide,)* 0];
+        static HEADER: ::winrt::StaticHeader = ::winrt::StaticHeader {
+            flags: 1,
+            len: #len,
+            _0: 0,
+            _1: 0,
+            data: &DATA as *const [u16; #capacity] as *const u16,
+        };
+        pub static HSTRING: ::winrt::StaticHString = ::winrt::StaticHString { header: &HEADER };
+    };
 
     stream.into()
 }
@@ -31,14 +432,35 @@ enum ImportCategory {
     None,
     Dependency,
     Namespace,
+    Type,
+    Option,
+    Renames,
+}
+
+/// The contents of an `import!` macro
+struct Import {
+    /// The paths to the dependencies, each with the directory it was found in
+    dependencies: BTreeMap<PathBuf, PathBuf>,
+    /// The namespaces listed under `modules`
+    namespaces: BTreeSet<String>,
+    /// The types listed under `types`
+    types: BTreeSet<String>,
+    /// The options listed under `options`
+    options: BTreeSet<String>,
+    /// The path to the renames file, if any
+    renames: Option<PathBuf>,
 }
 
-/// Parse `import!` macro and return a set of paths to dependencies and
-/// a set to all the namespaces referenced
-fn parse_import_stream(stream: TokenStream) -> (BTreeSet<PathBuf>, BTreeSet<String>) {
+/// Parse `import!` macro and return the dependencies, namespaces, types, options and renames file
+/// that it lists
+fn parse_import_stream(stream: TokenStream) -> Import {
     let mut category = ImportCategory::None;
-    let mut dependencies = BTreeSet::<PathBuf>::new();
-    let mut modules = BTreeSet::<String>::new();
+    let mut dependencies = BTreeMap::<PathBuf, PathBuf>::new();
+    let mut namespaces = BTreeSet::<String>::new();
+    let mut types = BTreeSet::<String>::new();
+    let mut options = BTreeSet::<String>::new();
+    let mut renames = None;
+    let mut excludes = BTreeMap::<PathBuf, PathBuf>::new();
     let mut stream = stream.into_iter().peekable();
 
     while let Some(token) = stream.next() {
@@ -47,7 +469,10 @@ fn parse_import_stream(stream: TokenStream) -> (BTreeSet<PathBuf>, BTreeSet<Stri
                 match value.to_string().as_ref() {
                     "dependencies" => category = ImportCategory::Dependency,
                     "modules" => category = ImportCategory::Namespace,
-                    value => panic!("winrt::import macro expects either `dependencies` or `modules` but found `{}`", value),
+                    "types" => category = ImportCategory::Type,
+                    "options" => category = ImportCategory::Option,
+                    "renames" => category = ImportCategory::Renames,
+                    value => panic!("winrt::import macro expects either `dependencies`, `modules`, `types`, `options`, or `renames` but found `{}`", value),
                 }
                 if let Some(TokenTree::Punct(p)) = stream.peek() {
                     if p.as_char() == ':' {
@@ -57,14 +482,50 @@ fn parse_import_stream(stream: TokenStream) -> (BTreeSet<PathBuf>, BTreeSet<Stri
             }
             TokenTree::Literal(value) => match category {
                 ImportCategory::None => panic!(
-                    "winrt::import macro expects either `dependencies` or `modules` but found `{}`",
+                    "winrt::import macro expects either `dependencies`, `modules`, `types`, `options`, or `renames` but found `{}`",
                     value
                 ),
                 ImportCategory::Dependency => {
-                    dependencies.append(&mut to_dependencies(value.to_string().trim_matches('"')));
+                    let dependency = expand_env(value.to_string().trim_matches('"'));
+
+                    match dependency.strip_prefix('!') {
+                        Some(excluded) => excludes.append(&mut to_dependencies(excluded)),
+                        None => dependencies.append(&mut to_dependencies(&dependency)),
+                    }
                 }
                 ImportCategory::Namespace => {
-                    modules.insert(namespace_literal_to_rough_namespace(&value.to_string()));
+                    namespaces.insert(namespace_literal_to_rough_namespace(&value.to_string()));
+                }
+                ImportCategory::Type => {
+                    types.insert(namespace_literal_to_rough_namespace(&value.to_string()));
+                }
+                ImportCategory::Option => {
+                    let option = value.to_string().trim_matches('"').to_string();
+
+                    if !matches!(
+                        option.as_str(),
+                        "erased"
+                            | "nullable"
+                            | "strict"
+                            | "interface_names"
+                            | "inline"
+                            | "must_use"
+                            | "skip_validation"
+                    ) && version_checks(&option).is_none()
+                    {
+                        panic!("winrt::import macro found an unrecognized option: {}", option);
+                    }
+
+                    options.insert(option);
+                }
+                ImportCategory::Renames => {
+                    if renames.is_some() {
+                        panic!("winrt::import macro expects a single renames file");
+                    }
+
+                    renames = Some(to_manifest_path(expand_env(
+                        value.to_string().trim_matches('"'),
+                    )));
                 }
             },
             _ => panic!(
@@ -74,13 +535,94 @@ fn parse_import_stream(stream: TokenStream) -> (BTreeSet<PathBuf>, BTreeSet<Stri
         }
     }
 
-    (dependencies, modules)
+    // Files are excluded once all of the dependencies are known, regardless of their order.
+    dependencies.retain(|path, _| !excludes.contains_key(path));
+
+    Import {
+        dependencies,
+        namespaces,
+        types,
+        options,
+        renames,
+    }
 }
 
-/// Returns the paths to resolved dependencies
-fn to_dependencies<P: AsRef<Path>>(dependency: P) -> BTreeSet<PathBuf> {
-    let path = dependency.as_ref();
-    let mut result = BTreeSet::new();
+/// Replaces each `${env:NAME}` in a path with the value of the environment variable, so that
+/// metadata outside of the crate, such as an SDK installation, can be found portably
+fn expand_env(path: &str) -> String {
+    let mut result = String::with_capacity(path.len());
+    let mut rest = path;
+
+    while let Some(start) = rest.find("${env:") {
+        let end = rest[start..]
+            .find('}')
+            .unwrap_or_else(|| panic!("The path {:?} has an unterminated `${{env:`", path))
+            + start;
+
+        let name = &rest[start + 6..end];
+        let value = std::env::var(name).unwrap_or_else(|_| {
+            panic!(
+                "The path {:?} uses the environment variable `{}`, which is not set",
+                path, name
+            )
+        });
+
+        result.push_str(&rest[..start]);
+        result.push_str(&value);
+        rest = &rest[end + 1..];
+    }
+
+    result.push_str(rest);
+    result
+}
+
+/// Resolves a relative path against the importing crate's directory if it exists there, rather
+/// than the compiler's working directory, which differs within a workspace
+fn to_manifest_path<P: AsRef<Path>>(path: P) -> PathBuf {
+    let path = path.as_ref();
+
+    if path.is_relative() {
+        if let Some(dir) = std::env::var_os("CARGO_MANIFEST_DIR") {
+            let manifest_path = PathBuf::from(dir).join(path);
+
+            if manifest_path.exists() {
+                return manifest_path;
+            }
+        }
+    }
+
+    path.to_path_buf()
+}
+
+/// Returns the paths to resolved dependencies, each with the directory that it was found in
+fn to_dependencies<P: AsRef<Path>>(dependency: P) -> BTreeMap<PathBuf, PathBuf> {
+    let dependency = dependency.as_ref();
+
+    if let Some(pattern) = dependency
+        .to_str()
+        .filter(|d| winmd::load_winmd::is_pattern(d))
+    {
+        // The components before the first wildcard name the directory to search.
+        let components: Vec<&str> = pattern.split(['/', '\\']).collect();
+        let split = components
+            .iter()
+            .position(|component| winmd::load_winmd::is_pattern(component))
+            .unwrap();
+
+        let root = components[..split].join("/");
+        let root = to_manifest_path(if root.is_empty() { "." } else { &root });
+        let pattern = components[split..].join("/");
+
+        return winmd::load_winmd::find_files(&root, &pattern)
+            .unwrap_or_else(|e| panic!("Could not resolve dependency {:?}: {}", dependency, e))
+            .into_iter()
+            .map(|path| (path, root.clone()))
+            .collect();
+    }
+
+    let path = to_manifest_path(dependency);
+    let path = path.as_path();
+    let mut result = BTreeMap::new();
 
     if path.is_dir() {
         let paths = std::fs::read_dir(path).unwrap_or_else(|e| {
@@ -89,26 +631,25 @@ fn to_dependencies<P: AsRef<Path>>(dependency: P) -> BTreeSet<PathBuf> {
                 path, e
             )
         });
-        for path in paths {
-            if let Ok(path) = path {
-                let path = path.path();
-                if path.is_file() {
-                    result.insert(path);
-                }
+        for entry in paths.flatten() {
+            let file = entry.path();
+            if file.is_file() && winmd::load_winmd::is_winmd(&file) {
+                result.insert(file, path.to_path_buf());
             }
         }
     } else if path.is_file() {
-        result.insert(path.to_path_buf());
+        let dir = path.parent().unwrap_or_else(|| Path::new("")).to_path_buf();
+        result.insert(path.to_path_buf(), dir);
     } else if path.to_str().map(|p| p == "os").unwrap_or(false) {
-        let mut path = PathBuf::new();
-        let wind_dir_env = std::env::var("windir")
-            .unwrap_or_else(|_| panic!("No `windir` environment variable found"));
-        path.push(wind_dir_env);
-        path.push(SYSTEM32);
-        path.push("winmetadata");
-        result.append(&mut to_dependencies(path));
+        result.append(&mut to_dependencies(winmd::load_winmd::os_dir()));
     } else {
-        panic!("Dependency {:?} is not a file or directory", path);
+        match std::env::var_os("CARGO_MANIFEST_DIR") {
+            Some(dir) if path.is_relative() => panic!(
+                "Dependency {:?} is not a file or directory, relative to {:?} or the working directory",
+                path, dir
+            ),
+            _ => panic!("Dependency {:?} is not a file or directory", path),
+        }
     }
 
     result
@@ -125,9 +666,3 @@ fn namespace_literal_to_rough_namespace(namespace: &str) -> String {
     }
     result
 }
-
-#[cfg(target_pointer_width = "64")]
-const SYSTEM32: &str = "System32";
-
-#[cfg(target_pointer_width = "32")]
-const SYSTEM32: &str = "SysNative";
@@ -0,0 +1,32 @@
+//! Builds a projection without `std` to ensure that the runtime and generated code only need
+//! `core` and `alloc`.
+
+#![no_std]
+
+extern crate alloc;
+
+winrt::import!(
+    dependencies
+        "os"
+    modules
+        "windows.foundation"
+        "windows.foundation.collections"
+        "windows.storage.streams"
+);
+
+use alloc::string::String;
+use windows::foundation::collections::PropertySet;
+use windows::foundation::{PropertyValue, Uri};
+use winrt::Cast;
+
+pub fn domain(uri: &str) -> winrt::Result<String> {
+    let uri = Uri::create_uri(uri)?;
+    Ok(String::from(&uri.domain()?))
+}
+
+pub fn insert(key: &str, value: i32) -> winrt::Result<bool> {
+    let set = PropertySet::new()?;
+    set.insert(key, PropertyValue::create_int32(value)?)?;
+    let value: winrt::Object = set.lookup(key)?;
+    Ok(value.cast::<windows::foundation::IPropertyValue>().is_ok())
+}
@@ -0,0 +1,219 @@
+//! A `const fn` implementation of SHA-1 for computing the IIDs of parameterized types
+//!
+//! This is shared by the winrt and winmd crates, keeping the IIDs computed during code generation
+//! and at compile time in agreement, as both compute them with [`signature_guid`].
+
+#![no_std]
+
+#[cfg(test)]
+extern crate std;
+
+/// Computes the SHA-1 digest of `prefix` followed by `data`
+pub const fn sha1(prefix: &[u8], data: &[u8]) -> [u8; 20] {
+    sha1_parts(prefix, &[data])
+}
+
+// {11f47ad5-7b73-42c0-abae-878b1e16adee}
+const PINTERFACE_NAMESPACE: [u8; 16] = [
+    0x11, 0xf4, 0x7a, 0xd5, 0x7b, 0x73, 0x42, 0xc0, 0xab, 0xae, 0x87, 0x8b, 0x1e, 0x16, 0xad, 0xee,
+];
+
+/// Computes the IID of a parameterized type from its signature, given in parts that are hashed as
+/// if they were concatenated, as the fields of a GUID
+///
+/// This is the name-based (version 5) GUID of the signature in the Windows Runtime's namespace
+/// for parameterized types.
+pub const fn signature_guid(parts: &[&[u8]]) -> (u32, u16, u16, [u8; 8]) {
+    let bytes = sha1_parts(&PINTERFACE_NAMESPACE, parts);
+
+    (
+        u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]),
+        u16::from_be_bytes([bytes[4], bytes[5]]),
+        (u16::from_be_bytes([bytes[6], bytes[7]]) & 0x0fff) | (5 << 12),
+        [
+            (bytes[8] & 0x3f) | 0x80,
+            bytes[9],
+            bytes[10],
+            bytes[11],
+            bytes[12],
+            bytes[13],
+            bytes[14],
+            bytes[15],
+        ],
+    )
+}
+
+// Computes the SHA-1 digest of `prefix` followed by each of `parts`, which lets a `const fn` hash
+// a message that it has no way to allocate.
+const fn sha1_parts(prefix: &[u8], parts: &[&[u8]]) -> [u8; 20] {
+    let len = prefix.len() + total_len(parts);
+    let blocks = (len + 9).div_ceil(64);
+    let bits = (len as u64) * 8;
+
+    let mut state: [u32; 5] = [0x67452301, 0xEFCDAB89, 0x98BADCFE, 0x10325476, 0xC3D2E1F0];
+    let mut block = 0;
+
+    while block < blocks {
+        let mut w = [0u32; 80];
+        let mut i = 0;
+
+        while i < 16 {
+            let offset = block * 64 + i * 4;
+            w[i] = u32::from_be_bytes([
+                padded(prefix, parts, len, blocks, bits, offset),
+                padded(prefix, parts, len, blocks, bits, offset + 1),
+                padded(prefix, parts, len, blocks, bits, offset + 2),
+                padded(prefix, parts, len, blocks, bits, offset + 3),
+            ]);
+            i += 1;
+        }
+
+        while i < 80 {
+            w[i] = (w[i - 3] ^ w[i - 8] ^ w[i - 14] ^ w[i - 16]).rotate_left(1);
+            i += 1;
+        }
+
+        let [mut a, mut b, mut c, mut d, mut e] = state;
+        i = 0;
+
+        while i < 80 {
+            let (f, k) = if i < 20 {
+                ((b & c) | (!b & d), 0x5A827999)
+            } else if i < 40 {
+                (b ^ c ^ d, 0x6ED9EBA1)
+            } else if i < 60 {
+                ((b & c) | (b & d) | (c & d), 0x8F1BBCDC)
+            } else {
+                (b ^ c ^ d, 0xCA62C1D6)
+            };
+
+            let temp = a
+                .rotate_left(5)
+                .wrapping_add(f)
+                .wrapping_add(e)
+                .wrapping_add(k)
+                .wrapping_add(w[i]);
+
+            e = d;
+            d = c;
+            c = b.rotate_left(30);
+            b = a;
+            a = temp;
+            i += 1;
+        }
+
+        state[0] = state[0].wrapping_add(a);
+        state[1] = state[1].wrapping_add(b);
+        state[2] = state[2].wrapping_add(c);
+        state[3] = state[3].wrapping_add(d);
+        state[4] = state[4].wrapping_add(e);
+        block += 1;
+    }
+
+    let mut digest = [0u8; 20];
+    let mut i = 0;
+
+    while i < 20 {
+        digest[i] = (state[i / 4] >> (24 - (i % 4) * 8)) as u8;
+        i += 1;
+    }
+
+    digest
+}
+
+const fn total_len(parts: &[&[u8]]) -> usize {
+    let mut len = 0;
+    let mut i = 0;
+
+    while i < parts.len() {
+        len += parts[i].len();
+        i += 1;
+    }
+
+    len
+}
+
+// The byte at `offset` of the message after padding, which is followed by a single 1 bit, zeros
+// and the message length in bits so as to fill the last block.
+const fn padded(
+    prefix: &[u8],
+    parts: &[&[u8]],
+    len: usize,
+    blocks: usize,
+    bits: u64,
+    offset: usize,
+) -> u8 {
+    let end = blocks * 64;
+
+    if offset < prefix.len() {
+        prefix[offset]
+    } else if offset < len {
+        let mut offset = offset - prefix.len();
+        let mut i = 0;
+
+        while offset >= parts[i].len() {
+            offset -= parts[i].len();
+            i += 1;
+        }
+
+        parts[i][offset]
+    } else if offset == len {
+        0x80
+    } else if offset >= end - 8 {
+        (bits >> ((end - 1 - offset) * 8)) as u8
+    } else {
+        0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::format;
+    use std::string::String;
+
+    fn hex(digest: [u8; 20]) -> String {
+        digest.iter().map(|byte| format!("{:02x}", byte)).collect()
+    }
+
+    #[test]
+    fn digests() {
+        assert_eq!(
+            hex(sha1(b"", b"")),
+            "da39a3ee5e6b4b0d3255bfef95601890afd80709"
+        );
+        assert_eq!(
+            hex(sha1(b"a", b"bc")),
+            "a9993e364706816aba3e25717850c26c9cd0d89d"
+        );
+        assert_eq!(
+            hex(sha1(
+                b"abcdbcdecdefdefgefghfghighijhijk",
+                b"ijkljklmklmnlmnomnopnopq"
+            )),
+            "84983e441c3bd26ebaae4aa1f95129e5e54670f1"
+        );
+        assert_eq!(
+            hex(sha1_parts(b"", &[b"a", b"", b"b", b"c"])),
+            "a9993e364706816aba3e25717850c26c9cd0d89d"
+        );
+    }
+
+    #[test]
+    fn guids() {
+        // IVector<HString>, whose IID is {98b9acc1-4b56-532e-ac73-03d5291cca90}
+        let signature: &[u8] = b"pinterface({913337e9-11a1-4345-a3a2-4e7f956e222d};string)";
+        let expected = (
+            0x98b9_acc1,
+            0x4b56,
+            0x532e,
+            [0xac, 0x73, 0x03, 0xd5, 0x29, 0x1c, 0xca, 0x90],
+        );
+
+        assert_eq!(signature_guid(&[signature]), expected);
+        assert_eq!(
+            signature_guid(&[&signature[..11], &signature[11..], b""]),
+            expected
+        );
+    }
+}
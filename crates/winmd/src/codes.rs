@@ -1,3 +1,5 @@
+//! Coded indices, which refer to a row in one of several tables (ECMA-335 II.24.2.6)
+
 use crate::file::*;
 use crate::row::Row;
 use crate::tables::*;
@@ -5,6 +7,7 @@ use crate::TypeReader;
 
 use winmd_macros::type_code;
 
+/// Decoding of a coded index read from a table column or signature blob
 pub trait Decode {
     fn decode(code: u32, file: u16) -> Self;
 }
@@ -0,0 +1,269 @@
+use crate::file::TableIndex;
+use crate::tables::TypeDef;
+use crate::types::{MethodNames, RequiredInterface, Type};
+use crate::{TypeLimits, TypeReader, TypeStage};
+
+use std::collections::BTreeSet;
+
+/// Why a type or method was left out of the generated code
+#[derive(Clone, PartialEq, Debug)]
+pub enum SkipReason {
+    /// The type definition isn't a Windows Runtime type, which the projection can't represent
+    UnsupportedType,
+    /// Attributes only describe other types in metadata
+    Attribute,
+    /// API contracts only version other types in metadata
+    ApiContract,
+    /// The type isn't reachable from what the import lists, such as an interface exclusive to a
+    /// class that isn't generated
+    Filtered,
+    /// As with `Filtered`, but the type is also deprecated, which is usually why nothing that's
+    /// generated depends on it
+    Deprecated,
+    /// The method's name collides with a method of an earlier interface of the same type, as does
+    /// its name suffixed with its interface's, so it is only reachable by casting to the
+    /// interface that declares it
+    MethodCollision { interface: String },
+}
+
+impl SkipReason {
+    fn code(&self) -> &'static str {
+        match self {
+            SkipReason::UnsupportedType => "unsupported_type",
+            SkipReason::Attribute => "attribute",
+            SkipReason::ApiContract => "api_contract",
+            SkipReason::Filtered => "filtered",
+            SkipReason::Deprecated => "deprecated",
+            SkipReason::MethodCollision { .. } => "method_collision",
+        }
+    }
+}
+
+/// A type, or a method of a type, that code generation skipped
+#[derive(Clone, PartialEq, Debug)]
+pub struct Skipped {
+    pub namespace: String,
+    pub name: String,
+    /// The method that was skipped, or `None` if the whole type was skipped
+    pub member: Option<String>,
+    pub reason: SkipReason,
+}
+
+/// A report of everything in the relevant namespaces and types that isn't generated
+#[derive(Default, Debug)]
+pub struct CoverageReport {
+    pub skipped: Vec<Skipped>,
+}
+
+impl CoverageReport {
+    pub fn new(reader: &TypeReader, limits: &TypeLimits, stage: &TypeStage) -> Self {
+        let mut report = Self::default();
+        let mut seen = BTreeSet::new();
+
+        for file in 0..reader.files.len() as u16 {
+            for def in reader.rows(file, TableIndex::TypeDef).map(TypeDef) {
+                let (namespace, name) = def.name(reader);
+
+                if !limits.namespaces.contains(namespace) || !seen.insert((namespace, name)) {
+                    continue;
+                }
+
+                let reason = if def.ignore(reader) {
+                    ignore_reason(reader, def)
+                } else if stage.0.contains_key(&def) {
+                    continue;
+                } else if def.has_attribute(
+                    reader,
+                    ("Windows.Foundation.Metadata", "DeprecatedAttribute"),
+                ) {
+                    SkipReason::Deprecated
+                } else {
+                    SkipReason::Filtered
+                };
+
+                report.skipped.push(Skipped {
+                    namespace: namespace.to_string(),
+                    name: name.to_string(),
+                    member: None,
+                    reason,
+                });
+            }
+        }
+
+        for t in stage.0.values() {
+            let interfaces = match t {
+                Type::Class(t) => &t.interfaces,
+                Type::Interface(t) => &t.interfaces,
+                _ => continue,
+            };
+
+            report.method_collisions(t, interfaces);
+        }
+
+        report
+    }
+
+    fn method_collisions(&mut self, t: &Type, interfaces: &[RequiredInterface]) {
+        for (method, interface, name) in MethodNames::new(interfaces).collisions {
+            if name.is_none() {
+                self.skipped.push(Skipped {
+                    namespace: t.name().namespace.to_string(),
+                    name: t.name().name.to_string(),
+                    member: Some(method.name.clone()),
+                    reason: SkipReason::MethodCollision {
+                        interface: interface.name.runtime_name(),
+                    },
+                });
+            }
+        }
+    }
+
+    /// Format the report as JSON Lines, with one object for each skipped type or method
+    pub fn to_json_lines(&self) -> String {
+        let mut json = String::new();
+
+        for skipped in &self.skipped {
+            json.push_str(&format!(
+                "{{\"namespace\":{},\"name\":{},\"member\":{},\"reason\":{}",
+                quote(&skipped.namespace),
+                quote(&skipped.name),
+                skipped
+                    .member
+                    .as_ref()
+                    .map_or_else(|| "null".to_string(), |member| quote(member)),
+                quote(skipped.reason.code()),
+            ));
+
+            if let SkipReason::MethodCollision { interface } = &skipped.reason {
+                json.push_str(&format!(",\"interface\":{}", quote(interface)));
+            }
+
+            json.push_str("}\n");
+        }
+
+        json
+    }
+}
+
+fn ignore_reason(reader: &TypeReader, def: TypeDef) -> SkipReason {
+    if !def.flags(reader).windows_runtime() {
+        SkipReason::UnsupportedType
+    } else if def.extends(reader).name(reader) == ("System", "Attribute") {
+        SkipReason::Attribute
+    } else {
+        SkipReason::ApiContract
+    }
+}
+
+pub(crate) fn quote(value: &str) -> String {
+    let mut quoted = String::with_capacity(value.len() + 2);
+    quoted.push('"');
+
+    for c in value.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            c if (c as u32) < 0x20 => quoted.push_str(&format!("\\u{:04x}", c as u32)),
+            c => quoted.push(c),
+        }
+    }
+
+    quoted.push('"');
+    quoted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn skipped_types() {
+        let reader = &TypeReader::from_os();
+        let mut limits = TypeLimits::default();
+        limits.insert(reader, "windows.foundation");
+        let stage = TypeStage::from_limits(reader, &limits);
+        let report = CoverageReport::new(reader, &limits, &stage);
+
+        assert!(report.skipped.contains(&Skipped {
+            namespace: "Windows.Foundation".to_string(),
+            name: "FoundationContract".to_string(),
+            member: None,
+            reason: SkipReason::ApiContract,
+        }));
+
+        assert!(!report
+            .skipped
+            .iter()
+            .any(|skipped| skipped.name == "Uri" && skipped.member.is_none()));
+
+        // Every type of the namespace is either generated or reported with a reason.
+        for def in reader.namespace_types("Windows.Foundation") {
+            let (_, name) = def.name(reader);
+
+            assert!(
+                stage.0.contains_key(def)
+                    || report
+                        .skipped
+                        .iter()
+                        .any(|skipped| skipped.name == name && skipped.member.is_none()),
+                "{}",
+                name
+            );
+        }
+    }
+
+    #[test]
+    fn reasons() {
+        let reasons = [
+            SkipReason::UnsupportedType,
+            SkipReason::Attribute,
+            SkipReason::ApiContract,
+            SkipReason::Filtered,
+            SkipReason::Deprecated,
+        ];
+
+        let codes: Vec<&str> = reasons.iter().map(SkipReason::code).collect();
+        assert_eq!(
+            codes,
+            [
+                "unsupported_type",
+                "attribute",
+                "api_contract",
+                "filtered",
+                "deprecated"
+            ]
+        );
+    }
+
+    #[test]
+    fn json_lines() {
+        let report = CoverageReport {
+            skipped: vec![
+                Skipped {
+                    namespace: "Windows.Foundation".to_string(),
+                    name: "FoundationContract".to_string(),
+                    member: None,
+                    reason: SkipReason::ApiContract,
+                },
+                Skipped {
+                    namespace: "Windows.UI.Xaml".to_string(),
+                    name: "Window".to_string(),
+                    member: Some("close".to_string()),
+                    reason: SkipReason::MethodCollision {
+                        interface: "Windows.UI.Xaml.IWindow\"2".to_string(),
+                    },
+                },
+            ],
+        };
+
+        assert_eq!(
+            report.to_json_lines(),
+            concat!(
+                r#"{"namespace":"Windows.Foundation","name":"FoundationContract","member":null,"reason":"api_contract"}"#,
+                "\n",
+                r#"{"namespace":"Windows.UI.Xaml","name":"Window","member":"close","reason":"method_collision","interface":"Windows.UI.Xaml.IWindow\"2"}"#,
+                "\n",
+            )
+        );
+    }
+}
@@ -6,15 +6,59 @@ pub struct TableData {
     pub columns: [(u32, u32); 6],
 }
 
-#[derive(Default)]
+/// A metadata file and the layout of its tables and heaps
 pub struct WinmdFile {
-    pub bytes: Vec<u8>,
+    /// The file name, used to identify the file in errors
+    pub name: String,
+    pub bytes: FileBytes,
     pub strings: u32,
+    pub strings_size: u32,
     pub blobs: u32,
+    pub blobs_size: u32,
     pub guids: u32,
     pub tables: [TableData; 11],
 }
 
+/// The contents of a winmd file
+///
+/// Files are memory-mapped rather than read so that the multi-megabyte system metadata isn't
+/// copied on every build. Empty files, which can't be mapped, and any file that fails to map
+/// are read into memory instead. The headers are read through bounds-checked views, so that a
+/// truncated or corrupt file is reported as an error rather than read out of bounds.
+pub enum FileBytes {
+    Mapped(memmap2::Mmap),
+    Owned(Vec<u8>),
+}
+
+impl FileBytes {
+    fn open(filename: &std::path::Path) -> std::io::Result<Self> {
+        let file = std::fs::File::open(filename)?;
+
+        if file.metadata()?.len() > 0 {
+            // The mapping assumes the file isn't modified while it's in use. The metadata files
+            // aren't written by builds and, as with any other input, changing them mid-build
+            // would produce inconsistent results regardless.
+            if let Ok(map) = unsafe { memmap2::Mmap::map(&file) } {
+                return Ok(FileBytes::Mapped(map));
+            }
+        }
+
+        std::fs::read(filename).map(FileBytes::Owned)
+    }
+}
+
+impl std::ops::Deref for FileBytes {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        match self {
+            FileBytes::Mapped(map) => map,
+            FileBytes::Owned(bytes) => bytes,
+        }
+    }
+}
+
+/// The metadata tables that the reader supports
 #[repr(u16)]
 #[derive(Copy, Clone, Eq, PartialEq, Debug, PartialOrd, Ord)]
 pub enum TableIndex {
@@ -62,7 +106,7 @@ impl TableData {
 
     fn set_data(&mut self, data: &mut u32) {
         if self.row_count != 0 {
-            let next = *data + self.row_count * self.row_size;
+            let next = data.saturating_add(self.row_count.saturating_mul(self.row_size));
             self.data = *data;
             *data = next;
         }
@@ -70,93 +114,131 @@ impl TableData {
 }
 
 impl WinmdFile {
-    pub fn new<P: AsRef<std::path::Path>>(filename: P) -> Self {
-        let bytes = std::fs::read(filename.as_ref())
-            .unwrap_or_else(|e| panic!("Could not read file {:?}: {:?}", filename.as_ref(), e));
+    /// Open and parse the headers of a winmd file
+    ///
+    /// A file that can't be read, or whose headers or tables don't fit within the file, is
+    /// returned as an error naming the file.
+    pub fn new<P: AsRef<std::path::Path>>(filename: P) -> std::io::Result<Self> {
+        let name = filename.as_ref().display().to_string();
+        let bytes = FileBytes::open(filename.as_ref()).map_err(|e| {
+            std::io::Error::new(e.kind(), format!("Could not read file {}: {}", name, e))
+        })?;
         let mut file = Self {
+            name,
             bytes,
-            ..Default::default()
+            strings: 0,
+            strings_size: 0,
+            blobs: 0,
+            blobs_size: 0,
+            guids: 0,
+            tables: Default::default(),
         };
-        let dos = file.bytes.view_as::<ImageDosHeader>(0);
+
+        match file.parse() {
+            Ok(()) => Ok(file),
+            Err(e) => Err(std::io::Error::new(
+                e.kind(),
+                format!("Invalid file {}: {}", file.name, e),
+            )),
+        }
+    }
+
+    fn parse(&mut self) -> std::io::Result<()> {
+        let file = self;
+        let dos = file.bytes.view_as::<ImageDosHeader>(0)?;
 
         if dos.signature != IMAGE_DOS_SIGNATURE {
-            panic!("Invalid file: signature does not match IMAGE_DOS_SIGNATURE");
+            return Err(invalid("signature does not match IMAGE_DOS_SIGNATURE"));
         }
 
-        let pe = file.bytes.view_as::<ImageNtHeader>(dos.lfanew as u32);
+        let pe = file.bytes.view_as::<ImageNtHeader>(dos.lfanew as u32)?;
 
         let (com_virtual_address, sections) = match pe.optional_header.magic {
             MAGIC_PE32 => (
                 pe.optional_header.data_directory[IMAGE_DIRECTORY_ENTRY_COM_DESCRIPTOR as usize]
                     .virtual_address,
                 file.bytes.view_as_slice_of::<ImageSectionHeader>(
-                    dos.lfanew as u32 + sizeof::<ImageNtHeader>(),
+                    (dos.lfanew as u32).saturating_add(sizeof::<ImageNtHeader>()),
                     pe.file_header.number_of_sections as u32,
-                ),
+                )?,
             ),
             MAGIC_PE32PLUS => (
                 file.bytes
-                    .view_as::<ImageNtHeaderPlus>(dos.lfanew as u32)
+                    .view_as::<ImageNtHeaderPlus>(dos.lfanew as u32)?
                     .optional_header
                     .data_directory[IMAGE_DIRECTORY_ENTRY_COM_DESCRIPTOR as usize]
                     .virtual_address,
                 file.bytes.view_as_slice_of::<ImageSectionHeader>(
-                    dos.lfanew as u32 + sizeof::<ImageNtHeaderPlus>(),
+                    (dos.lfanew as u32).saturating_add(sizeof::<ImageNtHeaderPlus>()),
                     pe.file_header.number_of_sections as u32,
-                ),
+                )?,
             ),
-            _ => panic!("Invalid file: invalid magic"),
+            _ => return Err(invalid("invalid magic")),
         };
 
         let cli = file.bytes.view_as::<ImageCorHeader>(offset_from_rva(
-            section_from_rva(sections, com_virtual_address),
+            section_from_rva(sections, com_virtual_address)?,
             com_virtual_address,
-        ));
+        ))?;
 
         if cli.cb != sizeof::<ImageCorHeader>() {
-            panic!("Invalid file: invalid ImageCorHeader");
+            return Err(invalid("invalid ImageCorHeader"));
         }
 
         let cli_offset = offset_from_rva(
-            section_from_rva(sections, cli.meta_data.virtual_address),
+            section_from_rva(sections, cli.meta_data.virtual_address)?,
             cli.meta_data.virtual_address,
         );
 
-        if file.bytes.copy_as::<u32>(cli_offset) != STORAGE_MAGIC_SIG {
-            panic!("Invalid file: invalid STORAGE_MAGIC_SIG");
+        if file.bytes.copy_as::<u32>(cli_offset)? != STORAGE_MAGIC_SIG {
+            return Err(invalid("invalid STORAGE_MAGIC_SIG"));
         }
 
-        let version_length = file.bytes.copy_as::<u32>(cli_offset + 12);
-        let mut view = cli_offset + version_length + 20;
+        // Offsets are taken from the file, so they saturate rather than overflow and are then
+        // rejected by the bounds checks.
+        let version_length = file.bytes.copy_as::<u32>(cli_offset.saturating_add(12))?;
+        let mut view = cli_offset.saturating_add(version_length).saturating_add(20);
         let mut tables_data: (u32, u32) = (0, 0);
 
-        for _ in 0..file.bytes.copy_as::<u16>(cli_offset + version_length + 18) {
-            let stream_offset = file.bytes.copy_as::<u32>(view);
-            let stream_size = file.bytes.copy_as::<u32>(view + 4);
-            let stream_name = file.bytes.view_as_str(view + 8);
+        let stream_count = file
+            .bytes
+            .copy_as::<u16>(cli_offset.saturating_add(version_length).saturating_add(18))?;
+
+        for _ in 0..stream_count {
+            let stream_offset = file.bytes.copy_as::<u32>(view)?;
+            let stream_size = file.bytes.copy_as::<u32>(view.saturating_add(4))?;
+            let stream_name = file.bytes.view_as_str(view.saturating_add(8))?;
+            let stream = cli_offset.saturating_add(stream_offset);
             match stream_name {
-                b"#Strings" => file.strings = cli_offset + stream_offset,
-                b"#Blob" => file.blobs = cli_offset + stream_offset,
-                b"#GUID" => file.guids = cli_offset + stream_offset,
-                b"#~" => tables_data = (cli_offset + stream_offset, stream_size),
+                b"#Strings" => {
+                    file.strings = stream;
+                    file.strings_size = stream_size;
+                }
+                b"#Blob" => {
+                    file.blobs = stream;
+                    file.blobs_size = stream_size;
+                }
+                b"#GUID" => file.guids = stream,
+                b"#~" => tables_data = (stream, stream_size),
                 b"#US" => {}
-                _ => panic!("Invalid file: invalid stream name"),
+                _ => return Err(invalid("invalid stream name")),
             }
             let mut padding = 4 - stream_name.len() % 4;
             if padding == 0 {
                 padding = 4;
             }
-            view += (8 + stream_name.len() + padding) as u32;
+            view = view.saturating_add((8 + stream_name.len() + padding) as u32);
         }
 
-        let heap_sizes = file.bytes.copy_as::<u8>(tables_data.0 + 6);
+        let heap_sizes = file.bytes.copy_as::<u8>(tables_data.0.saturating_add(6))?;
         let string_index_size = if (heap_sizes & 1) == 1 { 4 } else { 2 };
         let guid_index_size = if (heap_sizes >> 1 & 1) == 1 { 4 } else { 2 };
         let blob_index_size = if (heap_sizes >> 2 & 1) == 1 { 4 } else { 2 };
-        let valid_bits = file.bytes.copy_as::<u64>(tables_data.0 + 8);
-        view = tables_data.0 + 24;
+        let valid_bits = file.bytes.copy_as::<u64>(tables_data.0.saturating_add(8))?;
+        view = tables_data.0.saturating_add(24);
 
-        // These tables are unused by WinRT, but needed temporarily to calculate sizes and offsets for subsequent tables.
+        // These tables are unused by WinRT, but needed temporarily to calculate sizes and offsets
+        // for subsequent tables.
         let unused_empty = TableData::default();
         let mut unused_assembly = TableData::default();
         let mut unused_assembly_os = TableData::default();
@@ -191,8 +273,8 @@ impl WinmdFile {
                 continue;
             }
 
-            let row_count = file.bytes.copy_as::<u32>(view);
-            view += 4;
+            let row_count = file.bytes.copy_as::<u32>(view)?;
+            view = view.saturating_add(4);
 
             match i {
                 0x00 => unused_module.row_count = row_count,
@@ -233,7 +315,7 @@ impl WinmdFile {
                 0x2a => file.tables[TableIndex::GenericParam as usize].row_count = row_count,
                 0x2b => unused_method_spec.row_count = row_count,
                 0x2c => unused_generic_param_constraint.row_count = row_count,
-                _ => unreachable!(),
+                _ => return Err(invalid("invalid table")),
             };
         }
 
@@ -564,25 +646,65 @@ impl WinmdFile {
         unused_nested_class.set_data(&mut view);
         file.tables[TableIndex::GenericParam as usize].set_data(&mut view);
 
-        file
+        // Rows are read directly from the tables so make sure that they all fit in the file.
+        for table in file.tables.iter() {
+            let end = table.data as u64 + table.row_count as u64 * table.row_size as u64;
+
+            if end > file.bytes.len() as u64 {
+                return Err(invalid("table data extends beyond the end of the file"));
+            }
+        }
+
+        Ok(())
     }
 
     pub fn type_def_table(&self) -> &TableData {
         &self.tables[TableIndex::TypeDef as usize]
     }
+
+    /// Read the value of a column in one of the file's tables
+    ///
+    /// # Panics
+    ///
+    /// Panics if the row is beyond the end of the table. The tables were checked to fit within
+    /// the file when it was opened, so any row of a table can be read.
+    pub fn u32(&self, table: TableIndex, row: u32, column: u32) -> u32 {
+        let table = &self.tables[table as usize];
+        assert!(
+            row < table.row_count,
+            "Row {} is beyond the end of a table of {} rows",
+            row,
+            table.row_count
+        );
+        let offset = table.data + row * table.row_size + table.columns[column as usize].0;
+        let value = match table.columns[column as usize].1 {
+            1 => self.bytes.copy_as::<u8>(offset).map(u32::from),
+            2 => self.bytes.copy_as::<u16>(offset).map(u32::from),
+            4 => self.bytes.copy_as::<u32>(offset),
+            _ => self.bytes.copy_as::<u64>(offset).map(|value| value as u32),
+        };
+        value.expect("The tables fit within the file")
+    }
 }
 
-fn section_from_rva(sections: &[ImageSectionHeader], rva: u32) -> &ImageSectionHeader {
+fn section_from_rva(
+    sections: &[ImageSectionHeader],
+    rva: u32,
+) -> std::io::Result<&ImageSectionHeader> {
     sections
         .iter()
         .find(|&s| {
-            rva >= s.virtual_address && rva < s.virtual_address + s.physical_address_or_virtual_size
+            rva >= s.virtual_address && rva - s.virtual_address < s.physical_address_or_virtual_size
         })
-        .expect("Invalid file")
+        .ok_or_else(|| invalid("address is not within any section"))
 }
 
 fn offset_from_rva(section: &ImageSectionHeader, rva: u32) -> u32 {
-    rva - section.virtual_address + section.pointer_to_raw_data
+    (rva - section.virtual_address).saturating_add(section.pointer_to_raw_data)
+}
+
+fn invalid(message: &str) -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::InvalidData, message)
 }
 
 fn sizeof<T>() -> u32 {
@@ -620,76 +742,77 @@ fn composite_index_size(tables: &[&TableData]) -> u32 {
 }
 
 pub(crate) trait View {
-    fn view_as<T: Pod>(&self, cli_offset: u32) -> &T;
-    fn view_as_slice_of<T: Pod>(&self, cli_offset: u32, len: u32) -> &[T];
-    fn copy_as<T: Copy + CopyPod>(&self, cli_offset: u32) -> T;
-    fn view_as_str(&self, cli_offset: u32) -> &[u8];
+    fn view_as<T: Pod>(&self, cli_offset: u32) -> std::io::Result<&T>;
+    fn view_as_slice_of<T: Pod>(&self, cli_offset: u32, len: u32) -> std::io::Result<&[T]>;
+    fn copy_as<T: Copy + CopyPod>(&self, cli_offset: u32) -> std::io::Result<T>;
+    fn view_as_str(&self, cli_offset: u32) -> std::io::Result<&[u8]>;
 }
 
-macro_rules! assert_proper_length {
-    ($self:expr, $t:ty, $cli_offset:expr, $size:expr) => {
-        let enough_room = $cli_offset + $size <= $self.len() as u32;
-        assert!(
-            enough_room,
-            "Invalid file: not enough bytes at offset {} to represent T",
-            $cli_offset
-        );
-    };
-}
+// Returns the bytes at the offset, as long as the file has enough of them
+fn bytes_at(bytes: &[u8], cli_offset: u32, size: u64) -> std::io::Result<&[u8]> {
+    let start = cli_offset as usize;
 
-macro_rules! assert_proper_length_and_alignment {
-    ($self:expr, $t:ty, $cli_offset:expr, $size:expr) => {{
-        assert_proper_length!($self, $t, $cli_offset, $size);
+    if cli_offset as u64 + size > bytes.len() as u64 {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::UnexpectedEof,
+            format!("not enough bytes at offset {}", cli_offset),
+        ));
+    }
 
-        let ptr = &$self[$cli_offset as usize] as *const u8 as *const $t;
+    Ok(&bytes[start..start + size as usize])
+}
 
-        let properly_aligned = ptr.align_offset(std::mem::align_of::<$t>()) == 0;
+fn aligned<T>(bytes: &[u8], cli_offset: u32) -> std::io::Result<*const T> {
+    let ptr = bytes.as_ptr() as *const T;
 
-        assert!(
-            properly_aligned,
-            "Invalid file: offset {} is not properly aligned to T",
-            $cli_offset
-        );
-        ptr
-    }};
+    if ptr.align_offset(std::mem::align_of::<T>()) != 0 {
+        return Err(invalid(&format!(
+            "offset {} is not properly aligned",
+            cli_offset
+        )));
+    }
+
+    Ok(ptr)
 }
 
 impl View for [u8] {
-    fn view_as<T: Pod>(&self, cli_offset: u32) -> &T {
-        let ptr = assert_proper_length_and_alignment!(self, T, cli_offset, sizeof::<T>());
+    fn view_as<T: Pod>(&self, cli_offset: u32) -> std::io::Result<&T> {
+        let bytes = bytes_at(self, cli_offset, sizeof::<T>() as u64)?;
+        let ptr = aligned::<T>(bytes, cli_offset)?;
 
-        unsafe { &*ptr }
+        Ok(unsafe { &*ptr })
     }
 
-    fn view_as_slice_of<T: Pod>(&self, cli_offset: u32, len: u32) -> &[T] {
-        let ptr = assert_proper_length_and_alignment!(self, T, cli_offset, sizeof::<T>() * len);
+    fn view_as_slice_of<T: Pod>(&self, cli_offset: u32, len: u32) -> std::io::Result<&[T]> {
+        let bytes = bytes_at(self, cli_offset, sizeof::<T>() as u64 * len as u64)?;
+        let ptr = aligned::<T>(bytes, cli_offset)?;
 
-        unsafe { std::slice::from_raw_parts(ptr, len as usize) }
+        Ok(unsafe { std::slice::from_raw_parts(ptr, len as usize) })
     }
 
-    fn copy_as<T: CopyPod>(&self, cli_offset: u32) -> T {
-        assert_proper_length!(self, T, cli_offset, sizeof::<T>());
+    fn copy_as<T: CopyPod>(&self, cli_offset: u32) -> std::io::Result<T> {
+        let bytes = bytes_at(self, cli_offset, sizeof::<T>() as u64)?;
 
         unsafe {
             let mut data = std::mem::MaybeUninit::zeroed().assume_init();
 
             std::ptr::copy_nonoverlapping(
-                self[cli_offset as usize..].as_ptr(),
+                bytes.as_ptr(),
                 &mut data as *mut T as *mut u8,
                 std::mem::size_of::<T>(),
             );
 
-            data
+            Ok(data)
         }
     }
 
-    fn view_as_str(&self, cli_offset: u32) -> &[u8] {
-        let buffer = &self[cli_offset as usize..];
+    fn view_as_str(&self, cli_offset: u32) -> std::io::Result<&[u8]> {
+        let buffer = self.get(cli_offset as usize..).unwrap_or_default();
         let index = buffer
             .iter()
             .position(|c| *c == b'\0')
-            .expect("Invalid file");
-        &self[cli_offset as usize..cli_offset as usize + index]
+            .ok_or_else(|| invalid("unterminated string"))?;
+        Ok(&buffer[..index])
     }
 }
 
@@ -915,7 +1038,7 @@ mod tests {
     fn view_bytes_as_type() {
         let bytes = [1u8, 3, 48, 90];
 
-        let foo = bytes.view_as::<Foo>(0);
+        let foo = bytes.view_as::<Foo>(0).unwrap();
         assert_eq!(foo.bar, 0x0301);
         assert_eq!(foo.baz, 48)
     }
@@ -925,23 +1048,63 @@ mod tests {
     fn panic_on_unaligned_bytes() {
         let bytes = [1u8, 3, 48, 90, 90];
 
-        let _ = bytes.view_as::<Foo>(0);
-        let _ = bytes.view_as::<Foo>(1);
+        let _ = bytes.view_as::<Foo>(0).unwrap();
+        let _ = bytes.view_as::<Foo>(1).unwrap();
     }
 
     #[test]
-    #[should_panic]
-    fn panic_on_too_few_bytes() {
+    fn error_on_too_few_bytes() {
         let bytes = [1u8, 3];
 
-        let _ = bytes.view_as::<Foo>(0);
+        assert!(bytes.view_as::<Foo>(0).is_err());
+        assert!(bytes.view_as_slice_of::<Foo>(0, 2).is_err());
+        assert!(bytes.copy_as::<u32>(u32::MAX).is_err());
+        assert!(bytes.view_as_str(0).is_err());
     }
 
     #[test]
     fn copy_bytes_as_type() {
         let bytes = [1u8, 3];
 
-        let foo = bytes.copy_as::<u16>(0);
+        let foo = bytes.copy_as::<u16>(0).unwrap();
         assert_eq!(foo, 0x0301);
     }
+
+    #[test]
+    fn truncated() {
+        let path =
+            std::env::temp_dir().join(format!("winmd_truncated_{}.winmd", std::process::id()));
+        std::fs::write(&path, b"MZ\0\0").unwrap();
+        let error = WinmdFile::new(&path).err().unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(error.kind(), std::io::ErrorKind::UnexpectedEof);
+        assert!(error.to_string().contains(&path.display().to_string()));
+    }
+
+    #[test]
+    fn mapped() {
+        let files = crate::load_winmd::from_os().unwrap();
+        assert!(!files.is_empty());
+
+        for file in files {
+            if let FileBytes::Owned(_) = file.bytes {
+                panic!("Expected the metadata to be memory-mapped");
+            }
+        }
+    }
+
+    #[test]
+    fn empty() {
+        let path = std::env::temp_dir().join(format!("winmd_empty_{}.winmd", std::process::id()));
+        std::fs::write(&path, b"").unwrap();
+        let bytes = FileBytes::open(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        if let FileBytes::Owned(bytes) = bytes {
+            assert!(bytes.is_empty());
+        } else {
+            panic!("Expected an empty file to be read rather than mapped");
+        }
+    }
 }
@@ -1,3 +1,5 @@
+//! The flags columns of the metadata tables and the categories derived from them
+
 pub struct MethodFlags(pub u32);
 pub struct TypeFlags(pub u32);
 
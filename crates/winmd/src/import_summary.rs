@@ -0,0 +1,75 @@
+use crate::types::Type;
+use crate::TypeTree;
+
+use std::fmt;
+
+/// A count of the namespaces, types and methods generated for a [`TypeTree`]
+///
+/// This is a rough measure of what an import costs to compile, cheap enough to print for every
+/// import so that a module that balloons the build stands out as soon as it's added. The
+/// [`SizeReport`](crate::SizeReport) breaks the cost down further.
+#[derive(Default, Debug, PartialEq)]
+pub struct ImportSummary {
+    /// The namespaces that have types of their own
+    pub namespaces: usize,
+    pub types: usize,
+    /// The methods generated for the types, counting the methods of each interface that a class
+    /// or interface exposes
+    pub methods: usize,
+}
+
+impl ImportSummary {
+    pub fn new(tree: &TypeTree) -> Self {
+        let mut namespaces = Vec::new();
+        tree.collect_namespaces(String::new(), &mut namespaces);
+
+        let mut types = Vec::new();
+        tree.collect_types(&mut types);
+
+        let methods = types
+            .iter()
+            .map(|t| match t {
+                Type::Class(t) => t.interfaces.iter().map(|i| i.methods.len()).sum(),
+                Type::Interface(t) => t.interfaces.iter().map(|i| i.methods.len()).sum(),
+                Type::Delegate(_) => 1,
+                Type::Enum(_) | Type::Struct(_) => 0,
+            })
+            .sum();
+
+        Self {
+            namespaces: namespaces.len(),
+            types: types.len(),
+            methods,
+        }
+    }
+}
+
+impl fmt::Display for ImportSummary {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} namespaces, {} types, {} methods",
+            self.namespaces, self.types, self.methods
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn summary() {
+        let summary = ImportSummary {
+            namespaces: 2,
+            types: 40,
+            methods: 310,
+        };
+
+        assert_eq!(summary.to_string(), "2 namespaces, 40 types, 310 methods");
+        assert_eq!(
+            ImportSummary::new(&TypeTree::default()),
+            ImportSummary::default()
+        );
+    }
+}
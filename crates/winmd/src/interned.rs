@@ -0,0 +1,93 @@
+use std::borrow::Borrow;
+use std::collections::HashSet;
+use std::fmt;
+use std::ops::Deref;
+use std::sync::Arc;
+
+/// An immutable string shared by every type name that refers to it
+///
+/// Namespaces and type names are interned once when a [`TypeReader`](crate::TypeReader) is
+/// created so that building a [`TypeName`](crate::types::TypeName) only bumps a reference count
+/// rather than copying the string out of the metadata.
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Interned(Arc<str>);
+
+impl Interned {
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Deref for Interned {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Borrow<str> for Interned {
+    fn borrow(&self) -> &str {
+        &self.0
+    }
+}
+
+impl AsRef<str> for Interned {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<&str> for Interned {
+    fn from(value: &str) -> Self {
+        Interned(value.into())
+    }
+}
+
+impl PartialEq<str> for Interned {
+    fn eq(&self, other: &str) -> bool {
+        &*self.0 == other
+    }
+}
+
+impl<'a> PartialEq<&'a str> for Interned {
+    fn eq(&self, other: &&'a str) -> bool {
+        &*self.0 == *other
+    }
+}
+
+impl PartialEq<String> for Interned {
+    fn eq(&self, other: &String) -> bool {
+        &*self.0 == other.as_str()
+    }
+}
+
+impl fmt::Display for Interned {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(&*self.0, f)
+    }
+}
+
+impl fmt::Debug for Interned {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(&*self.0, f)
+    }
+}
+
+/// A table of interned strings, used while reading metadata
+#[derive(Default)]
+pub struct StringTable {
+    strings: HashSet<Interned>,
+}
+
+impl StringTable {
+    pub fn intern(&mut self, value: &str) -> Interned {
+        if let Some(interned) = self.strings.get(value) {
+            return interned.clone();
+        }
+
+        let interned = Interned::from(value);
+        self.strings.insert(interned.clone());
+        interned
+    }
+}
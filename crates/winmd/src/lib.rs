@@ -1,21 +1,66 @@
+//! Reading of Windows Metadata (winmd) files and generation of the Rust projection
+//!
+//! Besides the projection, the crate exposes a low-level reader that other tools may build on.
+//! A [`TypeReader`] loads the metadata files, the [`tables`] module provides typed access to
+//! their rows, custom attributes are enumerated and decoded through [`tables::Attribute`], and
+//! the [`signature`] module decodes the signature blobs of methods, fields and type
+//! specifications.
+//!
+//! ```no_run
+//! use winmd::signature::MethodSig;
+//! use winmd::TypeReader;
+//!
+//! let reader = &TypeReader::from_os();
+//! let def = reader.resolve_type_def(("Windows.Foundation", "IStringable"));
+//!
+//! for method in def.methods(reader) {
+//!     let sig = MethodSig::from_method_def(reader, method);
+//!     println!("{} takes {} parameters", method.name(reader), sig.params.len());
+//! }
+//! ```
+
 mod blob;
 mod case;
-mod codes;
+pub mod codes;
+mod coverage;
 mod file;
-mod flags;
+pub mod flags;
+mod import_summary;
+mod interned;
+mod lockfile;
+mod metadata_report;
+mod renames;
 mod row;
-mod tables;
+pub mod signature;
+mod size_report;
+mod surface_diff;
+pub mod tables;
 mod type_limits;
 mod type_namespaces;
+mod type_options;
 mod type_reader;
 mod type_stage;
 mod type_tree;
 mod types;
+mod validate;
 
 pub mod load_winmd;
+pub use blob::Blob;
+pub use coverage::{CoverageReport, SkipReason, Skipped};
+pub use file::{TableIndex, WinmdFile};
+pub use import_summary::ImportSummary;
+pub use lockfile::{LockedPackage, Lockfile};
+pub use metadata_report::MetadataReport;
+pub use renames::{Casing, Renames};
+pub use row::Row;
+pub use size_report::{CodeSize, SizeReport};
+pub use surface_diff::{ChangeKind, SurfaceChange, SurfaceDiff};
 pub use type_limits::TypeLimits;
-pub use type_reader::TypeReader;
+pub use type_options::TypeOptions;
+pub use type_reader::{TypeConflict, TypeReader};
 pub use type_stage::TypeStage;
+pub use type_tree::TypeTree;
+pub use validate::ValidationError;
 
 fn format_ident(name: &str) -> proc_macro2::Ident {
     if name == "Self" {
@@ -29,8 +74,25 @@ fn format_abi_ident(name: &str) -> proc_macro2::Ident {
     quote::format_ident!("abi_{}", name)
 }
 
-#[cfg(target_pointer_width = "64")]
-const SYSTEM32: &str = "System32";
+// proc-macro2 uses either the compiler's token streams or its own for the whole process, and the
+// compiler's can only be used on the thread running a procedural macro. The workers therefore use
+// proc-macro2's own token streams until they're done, so they must only return plain data, such
+// as code rendered as a string, for the calling thread to parse. Switching modes relies on
+// proc-macro2's hidden `fallback` module, which is why proc-macro2 is pinned to an exact version.
+// Once the workers are done, the mode is detected again for the calling thread.
+fn map_types<T: Sync, R: Send, F: Fn(&T) -> R + Sync + Send>(items: &[T], f: F) -> Vec<R> {
+    use rayon::prelude::*;
+
+    struct Fallback;
+
+    impl Drop for Fallback {
+        fn drop(&mut self) {
+            proc_macro2::fallback::unforce();
+        }
+    }
+
+    proc_macro2::fallback::force();
+    let _fallback = Fallback;
 
-#[cfg(target_pointer_width = "32")]
-const SYSTEM32: &str = "SysNative";
+    items.par_iter().map(f).collect()
+}
@@ -1,30 +1,248 @@
 use crate::file::WinmdFile;
 
+use rayon::prelude::*;
 use std::path::{Path, PathBuf};
 
 /// Get [`WinmdFile`]s from the operating system
 ///
 /// This searches well known paths for Windows metadata related to
 /// operating system APIs.
-pub fn from_os() -> Vec<WinmdFile> {
-    let windir = std::env::var("windir").expect("No `windir` environent variable set");
-    let mut path = PathBuf::from(windir);
-    path.push(super::SYSTEM32);
-    path.push("winmetadata");
-    from_dir(path)
+pub fn from_os() -> std::io::Result<Vec<WinmdFile>> {
+    from_dir(os_dir())
+}
+
+/// Get the directory containing the operating system's Windows metadata
+///
+/// This is found through the `windir` environment variable, which other hosts may point at a
+/// copy of a Windows directory when cross-compiling.
+pub fn os_dir() -> PathBuf {
+    // Environment variables are only case-insensitive on Windows.
+    let windir = std::env::var_os("windir")
+        .or_else(|| std::env::var_os("WINDIR"))
+        .expect("No `windir` environment variable set");
+
+    // A 32-bit process on 64-bit Windows, whether x64 or ARM64, sees a System32 redirected to
+    // the 32-bit system directory, which lacks the metadata, so it must go through the SysNative
+    // alias instead. The pointer width alone can't tell this apart from 32-bit Windows.
+    let system32 = if std::env::var_os("PROCESSOR_ARCHITEW6432").is_some() {
+        "SysNative"
+    } else {
+        "System32"
+    };
+
+    let path = join_ignore_case(PathBuf::from(windir), system32);
+    join_ignore_case(path, "WinMetadata")
+}
+
+/// Joins a path component, matching an existing entry regardless of case as Windows would, so
+/// that a copy of a Windows directory may be used on hosts with case-sensitive file systems.
+fn join_ignore_case(path: PathBuf, name: &str) -> PathBuf {
+    let joined = path.join(name);
+
+    if joined.exists() {
+        return joined;
+    }
+
+    std::fs::read_dir(&path)
+        .ok()
+        .and_then(|entries| {
+            entries.filter_map(|entry| entry.ok()).find(|entry| {
+                entry
+                    .file_name()
+                    .to_string_lossy()
+                    .eq_ignore_ascii_case(name)
+            })
+        })
+        .map(|entry| entry.path())
+        .unwrap_or(joined)
+}
+
+/// Returns true if the path has a `.winmd` extension, regardless of case
+pub fn is_winmd(path: &Path) -> bool {
+    path.extension()
+        .map(|extension| extension.to_string_lossy().eq_ignore_ascii_case("winmd"))
+        .unwrap_or(false)
 }
 
 /// Get [`WinmdFile`]s from a directory
-pub fn from_dir<P: AsRef<Path>>(directory: P) -> Vec<WinmdFile> {
-    let files = std::fs::read_dir(directory)
-        .unwrap()
+pub fn from_dir<P: AsRef<Path>>(directory: P) -> std::io::Result<Vec<WinmdFile>> {
+    let files = std::fs::read_dir(directory)?
         .filter_map(|value| value.ok())
-        .map(|value| value.path());
-    // TODO: filter out directories and non-metadata files
+        .map(|value| value.path())
+        .filter(|path| path.is_file() && is_winmd(path));
     from_files(files)
 }
 
+/// Returns true if the path contains a `*` or `?` wildcard
+pub fn is_pattern(path: &str) -> bool {
+    path.contains(['*', '?'])
+}
+
+/// Find the `.winmd` files below a directory whose paths relative to it match a pattern
+///
+/// Patterns are matched a component at a time and regardless of case, where `*` matches any part
+/// of a name, `?` any one character, and `**` any number of directories, so that
+/// `**/lib/uap10.0/*.winmd` finds the metadata nested in NuGet packages. Symbolic links to
+/// directories aren't followed, so a link can't make the search loop. A pattern that matches no
+/// files is an error, as a missing file would be.
+pub fn find_files<P: AsRef<Path>>(directory: P, pattern: &str) -> std::io::Result<Vec<PathBuf>> {
+    let directory = directory.as_ref();
+    let components: Vec<Vec<char>> = pattern
+        .split(['/', '\\'])
+        .filter(|component| !component.is_empty())
+        .map(|component| component.to_lowercase().chars().collect())
+        .collect();
+
+    let mut files = Vec::new();
+    find_in(directory, &mut Vec::new(), &components, &mut files);
+
+    if files.is_empty() {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::NotFound,
+            format!(
+                "No .winmd files match `{}` in {}",
+                pattern,
+                directory.display()
+            ),
+        ));
+    }
+
+    files.sort();
+    Ok(files)
+}
+
+fn find_in(
+    directory: &Path,
+    relative: &mut Vec<Vec<char>>,
+    pattern: &[Vec<char>],
+    files: &mut Vec<PathBuf>,
+) {
+    let entries = match std::fs::read_dir(directory) {
+        Ok(entries) => entries,
+        Err(_) => return,
+    };
+
+    for entry in entries.filter_map(|entry| entry.ok()) {
+        let path = entry.path();
+
+        // The entry's own type, unlike the path's, doesn't follow a symbolic link.
+        let is_dir = match entry.file_type() {
+            Ok(file_type) => file_type.is_dir(),
+            Err(_) => continue,
+        };
+
+        relative.push(
+            entry
+                .file_name()
+                .to_string_lossy()
+                .to_lowercase()
+                .chars()
+                .collect(),
+        );
+
+        if is_dir {
+            find_in(&path, relative, pattern, files);
+        } else if path.is_file() && is_winmd(&path) && matches_path(pattern, relative) {
+            files.push(path);
+        }
+
+        relative.pop();
+    }
+}
+
+fn matches_path(pattern: &[Vec<char>], path: &[Vec<char>]) -> bool {
+    match pattern.split_first() {
+        None => path.is_empty(),
+        Some((first, rest)) if first[..] == ['*', '*'] => {
+            (0..=path.len()).any(|skipped| matches_path(rest, &path[skipped..]))
+        }
+        Some((first, rest)) => match path.split_first() {
+            Some((name, path)) => matches_name(first, name) && matches_path(rest, path),
+            None => false,
+        },
+    }
+}
+
+fn matches_name(pattern: &[char], name: &[char]) -> bool {
+    match pattern.split_first() {
+        None => name.is_empty(),
+        Some(('*', rest)) => (0..=name.len()).any(|skipped| matches_name(rest, &name[skipped..])),
+        Some(('?', rest)) => !name.is_empty() && matches_name(rest, &name[1..]),
+        Some((c, rest)) => name.first() == Some(c) && matches_name(rest, &name[1..]),
+    }
+}
+
 /// Get [`WinmdFile`]s from an iterator of file paths
-pub fn from_files<P: IntoIterator<Item = PathBuf>>(filenames: P) -> Vec<WinmdFile> {
-    filenames.into_iter().map(WinmdFile::new).collect()
+///
+/// The files are read and parsed in parallel. The first file that can't be read or parsed is
+/// returned as an error.
+pub fn from_files<P: IntoIterator<Item = PathBuf>>(
+    filenames: P,
+) -> std::io::Result<Vec<WinmdFile>> {
+    let filenames: Vec<PathBuf> = filenames.into_iter().collect();
+    filenames.par_iter().map(WinmdFile::new).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ignore_case() {
+        let dir = std::env::temp_dir().join(format!("winmd_ignore_case_{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("System32").join("WinMetadata")).unwrap();
+
+        let path = join_ignore_case(dir.clone(), "system32");
+        let path = join_ignore_case(path, "winmetadata");
+        assert!(path.is_dir());
+        assert!(path
+            .file_name()
+            .unwrap()
+            .to_string_lossy()
+            .eq_ignore_ascii_case("winmetadata"));
+
+        // Missing entries are joined as given.
+        assert_eq!(
+            join_ignore_case(dir.clone(), "Missing"),
+            dir.join("Missing")
+        );
+
+        assert!(is_winmd(Path::new("Windows.Foundation.winmd")));
+        assert!(is_winmd(Path::new("Windows.Foundation.WINMD")));
+        assert!(!is_winmd(Path::new("README.md")));
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn patterns() {
+        let dir = std::env::temp_dir().join(format!("winmd_patterns_{}", std::process::id()));
+        let lib = dir.join("Package").join("lib").join("uap10.0");
+        std::fs::create_dir_all(&lib).unwrap();
+        std::fs::write(dir.join("Top.winmd"), b"").unwrap();
+        std::fs::write(lib.join("Nested.winmd"), b"").unwrap();
+        std::fs::write(lib.join("Nested.xml"), b"").unwrap();
+
+        // A link back up the tree isn't followed.
+        #[cfg(unix)]
+        std::os::unix::fs::symlink(&dir, lib.join("Loop")).unwrap();
+
+        assert!(is_pattern("packages/**/*.winmd"));
+        assert!(!is_pattern("packages/lib"));
+
+        assert_eq!(
+            find_files(&dir, "**").unwrap(),
+            vec![lib.join("Nested.winmd"), dir.join("Top.winmd")]
+        );
+        assert_eq!(
+            find_files(&dir, "**/LIB/uap?0.0/*.winmd").unwrap(),
+            vec![lib.join("Nested.winmd")]
+        );
+        assert_eq!(find_files(&dir, "t*").unwrap(), vec![dir.join("Top.winmd")]);
+
+        let error = find_files(&dir, "*/Nested.winmd").unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::NotFound);
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
 }
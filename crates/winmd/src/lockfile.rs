@@ -0,0 +1,257 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// The content hashes of the metadata files that a crate's imports were built with, and the
+/// versions of the packages they were installed from
+///
+/// A lockfile pins the exact metadata, whether it comes from the Windows installation, an SDK,
+/// or a NuGet package, so that a machine or CI image with different metadata fails to build
+/// rather than silently generating a different projection. Files are identified by their path
+/// relative to the dependency they were found in, such as a directory listed under
+/// `dependencies`, as the same metadata is installed in different places on different machines
+/// while files in different packages may share a name. Packages installed with `cargo winrt`
+/// are recorded with their version and SHA-512 hash:
+///
+/// ```toml
+/// [files]
+/// "Windows.Foundation.winmd" = "4c1d0f8b0c9c2c3e5f9a4d0e6b8a7c2d1e0f3a4b"
+/// "Microsoft.Web.WebView2.1.0.864.35/lib/Microsoft.Web.WebView2.Core.winmd" = "..."
+///
+/// [packages]
+/// "Microsoft.Windows.SDK.Contracts" = { version = "10.0.22621.2428", sha512 = "..." }
+/// ```
+#[derive(Default, Debug, PartialEq)]
+pub struct Lockfile {
+    /// The SHA-1 digest of each file as lowercase hex, keyed by the file's path relative to its
+    /// dependency, with `/` separators
+    pub files: BTreeMap<String, String>,
+    /// The package that was installed for each package name
+    pub packages: BTreeMap<String, LockedPackage>,
+}
+
+/// A package recorded by a [`Lockfile`]
+#[derive(Clone, Debug, PartialEq)]
+pub struct LockedPackage {
+    pub version: String,
+    /// The package's SHA-512 hash, as base64 or hex
+    pub sha512: String,
+}
+
+impl Lockfile {
+    /// Hash the contents of metadata files, each given with the directory of the dependency it
+    /// was found in
+    pub fn from_files<P: AsRef<Path>, R: AsRef<Path>, I: IntoIterator<Item = (P, R)>>(
+        paths: I,
+    ) -> std::io::Result<Self> {
+        let mut files = BTreeMap::new();
+
+        for (path, root) in paths {
+            let path = path.as_ref();
+            let contents = std::fs::read(path)?;
+            files.insert(
+                key(path, root.as_ref()),
+                hex(&winrt_sha1::sha1(&[], &contents)),
+            );
+        }
+
+        Ok(Self {
+            files,
+            packages: BTreeMap::new(),
+        })
+    }
+
+    /// Read a lockfile from the contents of a TOML file
+    pub fn from_toml(source: &str) -> Result<Self, String> {
+        let table: toml::value::Table =
+            toml::from_str(source).map_err(|error| format!("{}", error))?;
+        let mut lockfile = Self::default();
+
+        for (key, value) in table {
+            match (key.as_str(), value) {
+                ("files", toml::Value::Table(table)) => {
+                    for (name, value) in table {
+                        match value {
+                            toml::Value::String(digest) => {
+                                lockfile.files.insert(name, digest);
+                            }
+                            _ => return Err(format!("Expected a digest for `{}`", name)),
+                        }
+                    }
+                }
+                ("packages", toml::Value::Table(table)) => {
+                    for (name, value) in table {
+                        let field = |field| value.get(field).and_then(toml::Value::as_str);
+
+                        match (field("version"), field("sha512")) {
+                            (Some(version), Some(sha512)) => {
+                                let package = LockedPackage {
+                                    version: version.to_string(),
+                                    sha512: sha512.to_string(),
+                                };
+                                lockfile.packages.insert(name, package);
+                            }
+                            _ => {
+                                return Err(format!("Expected a version and sha512 for `{}`", name))
+                            }
+                        }
+                    }
+                }
+                (key, _) => return Err(format!("Unexpected key `{}`", key)),
+            }
+        }
+
+        Ok(lockfile)
+    }
+
+    /// Format the lockfile as TOML
+    pub fn to_toml(&self) -> String {
+        let mut toml = String::from(
+            "# The metadata files that `winrt::import!` was built with. Set the\n\
+             # WINRT_UPDATE_LOCKFILE environment variable while building to update it.\n\n[files]\n",
+        );
+
+        for (name, digest) in &self.files {
+            toml.push_str(&format!("{} = {}\n", string(name), string(digest)));
+        }
+
+        if !self.packages.is_empty() {
+            toml.push_str("\n[packages]\n");
+        }
+
+        for (name, package) in &self.packages {
+            toml.push_str(&format!(
+                "{} = {{ version = {}, sha512 = {} }}\n",
+                string(name),
+                string(&package.version),
+                string(&package.sha512)
+            ));
+        }
+
+        toml
+    }
+
+    /// Add or replace the digests of the files hashed and the packages recorded in `other`
+    pub fn update(&mut self, other: Lockfile) {
+        self.files.extend(other.files);
+        self.packages.extend(other.packages);
+    }
+
+    /// Check that each of the files hashed in `actual` is locked with the same digest
+    ///
+    /// Files that are locked but not in `actual` are ignored, since each import may depend on
+    /// different files. The error lists every file that isn't locked or has changed.
+    pub fn verify(&self, actual: &Lockfile) -> Result<(), String> {
+        let mut drifted = Vec::new();
+
+        for (name, digest) in &actual.files {
+            match self.files.get(name) {
+                None => drifted.push(format!("`{}` is not locked", name)),
+                Some(locked) if locked != digest => drifted.push(format!(
+                    "`{}` has changed (locked {}, found {})",
+                    name, locked, digest
+                )),
+                _ => {}
+            }
+        }
+
+        if drifted.is_empty() {
+            Ok(())
+        } else {
+            Err(drifted.join(", "))
+        }
+    }
+}
+
+// A file outside of its dependency's directory, which can't happen for the dependencies that
+// `import!` finds, is identified by its name alone.
+fn key(path: &Path, root: &Path) -> String {
+    match path.strip_prefix(root) {
+        Ok(relative) => relative
+            .iter()
+            .map(|component| component.to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/"),
+        Err(_) => path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default(),
+    }
+}
+
+// Quotes a key or value as a TOML string, whose escapes differ from Rust's.
+fn string(value: &str) -> String {
+    toml::Value::String(value.to_string()).to_string()
+}
+
+fn hex(digest: &[u8]) -> String {
+    digest.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let dir = std::env::temp_dir().join(format!("winmd_lockfile_{}", std::process::id()));
+        let lib = dir.join("Package.1.0.0").join("lib");
+        std::fs::create_dir_all(&lib).unwrap();
+        let path = dir.join("Test.winmd");
+        std::fs::write(&path, b"abc").unwrap();
+        let nested = lib.join("Test.winmd");
+        std::fs::write(&nested, b"").unwrap();
+
+        let mut lockfile = Lockfile::from_files(vec![(&path, &dir), (&nested, &dir)]).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(
+            lockfile.files["Test.winmd"],
+            "a9993e364706816aba3e25717850c26c9cd0d89d"
+        );
+        assert_eq!(
+            lockfile.files["Package.1.0.0/lib/Test.winmd"],
+            "da39a3ee5e6b4b0d3255bfef95601890afd80709"
+        );
+
+        lockfile.packages.insert(
+            "Package".to_string(),
+            LockedPackage {
+                version: "1.0.0".to_string(),
+                sha512: "hash".to_string(),
+            },
+        );
+
+        lockfile
+            .files
+            .insert("Control\u{7}\"Quote\".winmd".to_string(), "1".to_string());
+        assert!(lockfile
+            .to_toml()
+            .contains(r#""Control\u0007\"Quote\".winmd" = "1""#));
+
+        assert_eq!(Lockfile::from_toml(&lockfile.to_toml()), Ok(lockfile));
+        assert!(Lockfile::from_toml("[files]\n\"Test.winmd\" = 1").is_err());
+        assert!(Lockfile::from_toml("[packages]\nPackage = { version = \"1.0.0\" }").is_err());
+    }
+
+    #[test]
+    fn verify() {
+        let mut locked = Lockfile::default();
+        locked.files.insert("A.winmd".to_string(), "1".to_string());
+        locked.files.insert("B.winmd".to_string(), "2".to_string());
+
+        let mut actual = Lockfile::default();
+        actual.files.insert("A.winmd".to_string(), "1".to_string());
+        assert_eq!(locked.verify(&actual), Ok(()));
+
+        actual.files.insert("B.winmd".to_string(), "3".to_string());
+        actual.files.insert("C.winmd".to_string(), "4".to_string());
+        assert_eq!(
+            locked.verify(&actual),
+            Err("`B.winmd` has changed (locked 2, found 3), `C.winmd` is not locked".to_string())
+        );
+
+        locked.update(actual);
+        assert_eq!(locked.files["B.winmd"], "3");
+        assert_eq!(locked.files.len(), 3);
+    }
+}
@@ -0,0 +1,63 @@
+//! Command line tools for Windows Metadata
+//!
+//! `winmd diff <old> <new> [--json]` reports the types and members added, removed and changed
+//! between two sets of metadata, each given as a `.winmd` file or a directory of them, such as
+//! the `UnionMetadata` directories of two Windows SDK versions.
+
+use std::path::Path;
+use std::process::exit;
+use winmd::{load_winmd, SurfaceDiff, TypeReader};
+
+const USAGE: &str = "usage: winmd diff <old> <new> [--json]";
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let json = args.iter().any(|arg| arg == "--json");
+    let args: Vec<&str> = args
+        .iter()
+        .filter(|arg| *arg != "--json")
+        .map(String::as_str)
+        .collect();
+
+    let (old, new) = match args.as_slice() {
+        ["diff", old, new] => (*old, *new),
+        _ => {
+            eprintln!("{}", USAGE);
+            exit(2);
+        }
+    };
+
+    let diff = SurfaceDiff::new(&read(old), &read(new));
+
+    if json {
+        print!("{}", diff.to_json_lines());
+    } else {
+        print!("{}", diff);
+    }
+
+    // As with `diff`, the exit code tells whether there were any differences.
+    exit(if diff.is_empty() { 0 } else { 1 });
+}
+
+fn read(path: &str) -> TypeReader {
+    let path = Path::new(path);
+
+    let files = if path.is_dir() {
+        load_winmd::from_dir(path)
+    } else if path.is_file() {
+        load_winmd::from_files(vec![path.to_path_buf()])
+    } else {
+        eprintln!("{}: no such file or directory", path.display());
+        exit(2);
+    };
+
+    let files = files.unwrap_or_else(|error| {
+        eprintln!("{}", error);
+        exit(2);
+    });
+
+    TypeReader::try_new(files).unwrap_or_else(|error| {
+        eprintln!("{}: {}", path.display(), error);
+        exit(2);
+    })
+}
@@ -0,0 +1,254 @@
+use crate::case::to_snake;
+use crate::coverage::quote;
+use crate::types::*;
+use crate::TypeTree;
+
+// The number of IUnknown and IInspectable methods preceding an interface's own methods.
+const INSPECTABLE_SLOTS: usize = 6;
+// The number of IUnknown methods preceding a delegate's `Invoke` method.
+const UNKNOWN_SLOTS: usize = 3;
+
+/// A machine-readable description of the types generated for a [`TypeTree`]
+///
+/// Each generated type is described with its Rust path, its IID, and the interfaces, methods and
+/// vtable slots behind it, so that tools such as FFI generators for other languages can consume
+/// the projection without reading the metadata or the generated code themselves.
+#[derive(Default, Debug)]
+pub struct MetadataReport {
+    /// One JSON object for each generated type, ordered by namespace and name
+    pub types: Vec<String>,
+}
+
+impl MetadataReport {
+    pub fn new(tree: &TypeTree) -> Self {
+        let mut types = Vec::new();
+        tree.collect_types(&mut types);
+
+        let mut types: Vec<(String, String)> = types
+            .iter()
+            .map(|t| (t.name().runtime_name(), type_json(t)))
+            .collect();
+
+        types.sort();
+
+        Self {
+            types: types.into_iter().map(|(_, json)| json).collect(),
+        }
+    }
+
+    /// Format the report as JSON Lines, with one object for each type
+    pub fn to_json_lines(&self) -> String {
+        let mut json = String::new();
+
+        for t in &self.types {
+            json.push_str(t);
+            json.push('\n');
+        }
+
+        json
+    }
+}
+
+fn type_json(t: &Type) -> String {
+    let name = t.name();
+    let kind = match t {
+        Type::Class(_) => "class",
+        Type::Interface(_) => "interface",
+        Type::Enum(_) => "enum",
+        Type::Struct(_) => "struct",
+        Type::Delegate(_) => "delegate",
+    };
+
+    let mut json = format!(
+        "{{\"namespace\":{},\"name\":{},\"path\":{},\"kind\":{}",
+        quote(&name.namespace),
+        quote(&name.name),
+        quote(&path(name)),
+        quote(kind),
+    );
+
+    if !name.generics.is_empty() {
+        json.push_str(&format!(
+            ",\"generics\":{}",
+            array(name.generics.iter().map(|kind| quote(&kind.runtime_name())))
+        ));
+    }
+
+    match t {
+        Type::Class(t) => {
+            json.push_str(&format!(
+                ",\"bases\":{},\"interfaces\":{}",
+                array(t.bases.iter().map(|base| quote(&base.runtime_name()))),
+                array(t.interfaces.iter().map(interface_json)),
+            ));
+        }
+        Type::Interface(t) => {
+            json.push_str(&format!(
+                ",\"iid\":{},\"interfaces\":{}",
+                iid(&t.name, &t.interfaces[0].guid),
+                array(t.interfaces.iter().map(interface_json)),
+            ));
+        }
+        Type::Delegate(t) => {
+            json.push_str(&format!(
+                ",\"iid\":{},\"method\":{}",
+                iid(&t.name, &t.guid),
+                method_json(&t.method, UNKNOWN_SLOTS),
+            ));
+        }
+        Type::Struct(t) => {
+            json.push_str(&format!(
+                ",\"size\":{},\"fields\":{}",
+                // A size that depends on the target's pointer width is left to the consumer.
+                t.size.map_or("null".to_string(), |size| size.to_string()),
+                array(t.fields.iter().map(|(name, kind)| {
+                    format!(
+                        "{{\"name\":{},\"type\":{}}}",
+                        quote(name),
+                        quote(&kind.runtime_name())
+                    )
+                })),
+            ));
+        }
+        Type::Enum(t) => {
+            json.push_str(&format!(
+                ",\"values\":{}",
+                array(t.fields.iter().map(|(name, value)| {
+                    let value = match value {
+                        EnumConstant::I32(value) => value.to_string(),
+                        EnumConstant::U32(value) => value.to_string(),
+                    };
+                    format!("{{\"name\":{},\"value\":{}}}", quote(name), value)
+                })),
+            ));
+        }
+    }
+
+    json.push('}');
+    json
+}
+
+fn interface_json(interface: &RequiredInterface) -> String {
+    let kind = match interface.kind {
+        InterfaceKind::Default => "default",
+        InterfaceKind::NonDefault => "non_default",
+        InterfaceKind::Overrides => "overrides",
+        InterfaceKind::Statics => "statics",
+    };
+
+    format!(
+        "{{\"name\":{},\"kind\":{},\"iid\":{},\"methods\":{}}}",
+        quote(&interface.name.runtime_name()),
+        quote(kind),
+        iid(&interface.name, &interface.guid),
+        array(
+            interface
+                .methods
+                .iter()
+                .enumerate()
+                .map(|(index, method)| method_json(method, INSPECTABLE_SLOTS + index))
+        ),
+    )
+}
+
+fn method_json(method: &Method, slot: usize) -> String {
+    let kind = match method.kind {
+        MethodKind::Normal => "normal",
+        MethodKind::Get => "get",
+        MethodKind::Set => "set",
+        MethodKind::Add => "add",
+        MethodKind::Remove => "remove",
+    };
+
+    format!(
+        "{{\"name\":{},\"metadata_name\":{},\"kind\":{},\"slot\":{},\"params\":{},\"return\":{}}}",
+        quote(&method.name),
+        quote(&method.metadata_name),
+        quote(kind),
+        slot,
+        array(method.params.iter().map(param_json)),
+        method
+            .return_type
+            .as_ref()
+            .map_or_else(|| "null".to_string(), param_json),
+    )
+}
+
+fn param_json(param: &Param) -> String {
+    format!(
+        "{{\"name\":{},\"type\":{},\"array\":{},\"input\":{}}}",
+        quote(&param.name),
+        quote(&param.kind.runtime_name()),
+        param.array,
+        param.input,
+    )
+}
+
+// The IID of a generic interface only exists for its specializations.
+fn iid(name: &TypeName, guid: &TypeGuid) -> String {
+    let generic = name
+        .generics
+        .iter()
+        .any(|kind| matches!(kind, TypeKind::Generic(_)));
+
+    if generic {
+        "null".to_string()
+    } else {
+        quote(&format!("{:?}", guid))
+    }
+}
+
+fn path(name: &TypeName) -> String {
+    let mut path: Vec<String> = name
+        .namespace
+        .split('.')
+        .map(|namespace| to_snake(namespace, MethodKind::Normal))
+        .collect();
+
+    path.push(match name.name.find('`') {
+        Some(tick) => name.name[..tick].to_string(),
+        None => name.name.to_string(),
+    });
+
+    path.join("::")
+}
+
+fn array<I: Iterator<Item = String>>(values: I) -> String {
+    format!("[{}]", values.collect::<Vec<String>>().join(","))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{TypeLimits, TypeReader, TypeStage};
+
+    #[test]
+    fn uri() {
+        let reader = &TypeReader::from_os();
+        let mut limits = TypeLimits::default();
+        limits.insert_type(reader, "windows.foundation.uri");
+        let tree = TypeStage::from_limits(reader, &limits).into_tree();
+        let report = MetadataReport::new(&tree);
+
+        let uri = report
+            .types
+            .iter()
+            .find(|t| t.contains("\"name\":\"Uri\""))
+            .unwrap();
+
+        assert!(uri.starts_with(
+            r#"{"namespace":"Windows.Foundation","name":"Uri","path":"windows::foundation::Uri","kind":"class""#
+        ));
+        assert!(uri.contains(r#""name":"Windows.Foundation.IUriRuntimeClass","kind":"default","iid":"9e365e57-48b2-4160-956f-c7385120bbfc""#));
+        assert!(uri.contains(r#""name":"domain","metadata_name":"Domain","kind":"get","slot":"#));
+
+        let stringable = report
+            .types
+            .iter()
+            .find(|t| t.contains("\"name\":\"IStringable\""))
+            .unwrap();
+
+        assert!(stringable.contains(r#""iid":"96369f54-8eb6-48f0-abce-c1b211e627c3""#));
+        assert!(stringable.contains(r#""slot":6,"params":[],"return":{"name":"#));
+    }
+}
@@ -0,0 +1,195 @@
+use crate::types::{Method, MethodKind};
+
+use std::collections::BTreeMap;
+
+/// How the names of methods and struct fields are cased
+#[derive(Copy, Clone, Default, PartialEq, Debug)]
+pub enum Casing {
+    /// Names are converted to snake case, such as `to_string` for `ToString`
+    #[default]
+    Snake,
+    /// Names are kept as they are in metadata, such as `ToString`, to match C++/WinRT
+    Pascal,
+}
+
+/// Renames applied to the generated types and their members
+///
+/// A renamed type or method is generated as an alias of the projection's own name, which
+/// remains available since the projection's conveniences rely on it. The fields of structs
+/// outside of `Windows.Foundation` are renamed outright. The renames can be read from a TOML file:
+///
+/// ```toml
+/// casing = "pascal"
+///
+/// [types]
+/// "Windows.Foundation.Uri" = "WinUri"
+///
+/// [members]
+/// "Windows.Foundation.Uri.Domain" = "Host"
+/// ```
+///
+/// Members are named by the full name of their type followed by their name in metadata. That is
+/// the name of the property or event for accessors, or the name of the interface method, which
+/// may also be named by the interface that declares it. A renamed property setter or event
+/// remover is prefixed as it would be otherwise, such as `SetHost` or `set_host`.
+#[derive(Default, Debug, PartialEq)]
+pub struct Renames {
+    pub casing: Casing,
+    /// New names of types, keyed by the type's full name
+    pub types: BTreeMap<String, String>,
+    /// New names of methods, properties, events and struct fields, keyed by the full name of
+    /// the type followed by the member's name in metadata
+    pub members: BTreeMap<String, String>,
+}
+
+impl Renames {
+    /// Read renames from the contents of a TOML file
+    pub fn from_toml(source: &str) -> Result<Self, String> {
+        let table: toml::value::Table =
+            toml::from_str(source).map_err(|error| format!("{}", error))?;
+        let mut renames = Self::default();
+
+        for (key, value) in table {
+            match (key.as_str(), value) {
+                ("casing", toml::Value::String(casing)) => {
+                    renames.casing = match casing.as_str() {
+                        "snake" => Casing::Snake,
+                        "pascal" => Casing::Pascal,
+                        _ => return Err(format!("Unknown casing `{}`", casing)),
+                    }
+                }
+                ("types", toml::Value::Table(table)) => renames.types = strings(&key, table)?,
+                ("members", toml::Value::Table(table)) => renames.members = strings(&key, table)?,
+                (key, _) => return Err(format!("Unexpected key `{}`", key)),
+            }
+        }
+
+        Ok(renames)
+    }
+
+    /// The alias of a method of a type, which may be looked up by the name of the type and then
+    /// that of the interface declaring the method
+    pub(crate) fn method_alias(&self, types: &[String], method: &Method) -> Option<String> {
+        let alias = match self.member(types, &method.metadata_name) {
+            Some(name) => self.with_preamble(name, method.kind),
+            None if self.casing == Casing::Pascal => {
+                self.with_preamble(&method.metadata_name, method.kind)
+            }
+            None => return None,
+        };
+
+        if alias == method.name {
+            None
+        } else {
+            Some(alias)
+        }
+    }
+
+    /// The name of a struct field, or `None` if it keeps its projected name
+    pub(crate) fn field_name(&self, t: &str, field: &str) -> Option<String> {
+        match self.member(&[t.to_string()], field) {
+            Some(name) => Some(name.to_string()),
+            None if self.casing == Casing::Pascal => Some(field.to_string()),
+            None => None,
+        }
+    }
+
+    fn member(&self, types: &[String], member: &str) -> Option<&str> {
+        types
+            .iter()
+            .find_map(|t| self.members.get(&format!("{}.{}", t, member)))
+            .map(|name| name.as_str())
+    }
+
+    fn with_preamble(&self, name: &str, kind: MethodKind) -> String {
+        let preamble = match (kind, self.casing) {
+            (MethodKind::Set, Casing::Snake) => "set_",
+            (MethodKind::Remove, Casing::Snake) => "remove_",
+            (MethodKind::Set, Casing::Pascal) => "Set",
+            (MethodKind::Remove, Casing::Pascal) => "Remove",
+            _ => "",
+        };
+
+        format!("{}{}", preamble, name)
+    }
+}
+
+fn strings(key: &str, table: toml::value::Table) -> Result<BTreeMap<String, String>, String> {
+    table
+        .into_iter()
+        .map(|(name, value)| match value {
+            toml::Value::String(value) => Ok((name, value)),
+            _ => Err(format!("Expected a string for `{}` in `[{}]`", name, key)),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_toml() {
+        let renames = Renames::from_toml(
+            r#"
+            casing = "pascal"
+
+            [types]
+            "Windows.Foundation.Uri" = "WinUri"
+
+            [members]
+            "Windows.Foundation.Uri.Domain" = "Host"
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(renames.casing, Casing::Pascal);
+        assert_eq!(renames.types["Windows.Foundation.Uri"], "WinUri");
+        assert_eq!(renames.members["Windows.Foundation.Uri.Domain"], "Host");
+
+        assert!(Renames::from_toml("casing = \"kebab\"").is_err());
+        assert!(Renames::from_toml("[methods]").is_err());
+        assert!(Renames::from_toml("[types]\nUri = 1").is_err());
+    }
+
+    #[test]
+    fn method_alias() {
+        let method = |name: &str, metadata_name: &str, kind| Method {
+            name: name.to_string(),
+            metadata_name: metadata_name.to_string(),
+            alias: None,
+            kind,
+            params: Vec::new(),
+            return_type: None,
+        };
+
+        let types = ["Windows.Foundation.Uri".to_string()];
+        let mut renames = Renames::default();
+        assert_eq!(
+            renames.method_alias(&types, &method("domain", "Domain", MethodKind::Get)),
+            None
+        );
+
+        renames.members.insert(
+            "Windows.Foundation.Uri.Domain".to_string(),
+            "host".to_string(),
+        );
+        assert_eq!(
+            renames.method_alias(&types, &method("set_domain", "Domain", MethodKind::Set)),
+            Some("set_host".to_string())
+        );
+
+        renames.casing = Casing::Pascal;
+        assert_eq!(
+            renames.method_alias(&types, &method("to_string", "ToString", MethodKind::Normal)),
+            Some("ToString".to_string())
+        );
+        assert_eq!(
+            renames.method_alias(
+                &types,
+                &method("remove_closed", "Closed", MethodKind::Remove)
+            ),
+            Some("RemoveClosed".to_string())
+        );
+    }
+}
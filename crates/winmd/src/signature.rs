@@ -0,0 +1,198 @@
+//! Decoding of the signature blobs of methods, fields and type specifications (ECMA-335 II.23.2)
+//!
+//! Unlike the projection's own type information, signatures describe types exactly as they are
+//! written in metadata, without resolving references or substituting generic arguments.
+
+use crate::blob::Blob;
+use crate::codes::{Decode, TypeDefOrRef};
+use crate::tables::{Field, MethodDef, TypeSpec};
+use crate::TypeReader;
+
+/// A type as encoded in a signature
+#[derive(Clone, PartialEq, Debug)]
+pub enum ElementType {
+    Void,
+    Bool,
+    Char,
+    I8,
+    U8,
+    I16,
+    U16,
+    I32,
+    U32,
+    I64,
+    U64,
+    F32,
+    F64,
+    String,
+    Object,
+    /// A type definition, reference or specification, such as a struct or interface
+    TypeDefOrRef(TypeDefOrRef),
+    /// The generic parameter of the enclosing type at the given position
+    GenericParam(u32),
+    /// The generic parameter of the enclosing method at the given position
+    MethodGenericParam(u32),
+    /// A generic type with its arguments
+    GenericInstance(TypeDefOrRef, Vec<ElementType>),
+    /// A single-dimensional array with a lower bound of zero
+    Array(Box<ElementType>),
+}
+
+/// A parameter or return type in a method signature
+#[derive(Clone, PartialEq, Debug)]
+pub struct ParamSig {
+    pub by_ref: bool,
+    pub kind: ElementType,
+}
+
+/// A decoded method signature
+#[derive(Clone, PartialEq, Debug)]
+pub struct MethodSig {
+    /// The calling convention byte, including the `HASTHIS` and `GENERIC` flags
+    pub calling_convention: u8,
+    pub generic_count: u32,
+    pub return_type: ParamSig,
+    pub params: Vec<ParamSig>,
+}
+
+impl MethodSig {
+    /// Decode the signature of a method definition
+    pub fn from_method_def(reader: &TypeReader, method: MethodDef) -> Self {
+        let mut blob = method.sig(reader);
+        let calling_convention = blob.read_u8();
+
+        let generic_count = if calling_convention & 0x10 != 0 {
+            blob.read_unsigned()
+        } else {
+            0
+        };
+
+        let param_count = blob.read_unsigned();
+        let return_type = ParamSig::from_blob(&mut blob);
+        let params = (0..param_count)
+            .map(|_| ParamSig::from_blob(&mut blob))
+            .collect();
+
+        Self {
+            calling_convention,
+            generic_count,
+            return_type,
+            params,
+        }
+    }
+}
+
+impl ParamSig {
+    fn from_blob(blob: &mut Blob) -> Self {
+        blob.read_modifiers();
+        let by_ref = blob.read_expected(0x10);
+
+        Self {
+            by_ref,
+            kind: ElementType::from_blob(blob),
+        }
+    }
+}
+
+impl ElementType {
+    /// Decode the type of a field
+    pub fn from_field(reader: &TypeReader, field: Field) -> Self {
+        let mut blob = field.sig(reader);
+        blob.read_unsigned();
+        blob.read_modifiers();
+        Self::from_blob(&mut blob)
+    }
+
+    /// Decode the type described by a type specification
+    pub fn from_type_spec(reader: &TypeReader, spec: TypeSpec) -> Self {
+        Self::from_blob(&mut spec.sig(reader))
+    }
+
+    /// Decode a type from the current position in a signature blob
+    ///
+    /// # Panics
+    ///
+    /// Panics if the blob contains an element type that Windows metadata does not use.
+    pub fn from_blob(blob: &mut Blob) -> Self {
+        blob.read_modifiers();
+
+        match blob.read_unsigned() {
+            0x01 => Self::Void,
+            0x02 => Self::Bool,
+            0x03 => Self::Char,
+            0x04 => Self::I8,
+            0x05 => Self::U8,
+            0x06 => Self::I16,
+            0x07 => Self::U16,
+            0x08 => Self::I32,
+            0x09 => Self::U32,
+            0x0A => Self::I64,
+            0x0B => Self::U64,
+            0x0C => Self::F32,
+            0x0D => Self::F64,
+            0x0E => Self::String,
+            0x1C => Self::Object,
+            0x11 | 0x12 => {
+                Self::TypeDefOrRef(TypeDefOrRef::decode(blob.read_unsigned(), blob.file_index))
+            }
+            0x13 => Self::GenericParam(blob.read_unsigned()),
+            0x1E => Self::MethodGenericParam(blob.read_unsigned()),
+            0x15 => {
+                blob.read_unsigned();
+                let def = TypeDefOrRef::decode(blob.read_unsigned(), blob.file_index);
+                let count = blob.read_unsigned();
+                let args = (0..count).map(|_| Self::from_blob(blob)).collect();
+                Self::GenericInstance(def, args)
+            }
+            0x1D => Self::Array(Box::new(Self::from_blob(blob))),
+            element => panic!("Unsupported element type 0x{:X} in signature", element),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn method_signature() {
+        let reader = &TypeReader::from_os();
+        let def = reader.resolve_type_def(("Windows.Foundation", "IUriRuntimeClassFactory"));
+        let method = def.methods(reader).next().unwrap();
+        let sig = MethodSig::from_method_def(reader, method);
+
+        assert!(sig.calling_convention & 0x20 != 0);
+        assert!(sig.generic_count == 0);
+        assert!(sig.params.len() == 1);
+        assert!(sig.params[0].kind == ElementType::String);
+        assert!(!sig.return_type.by_ref);
+
+        match &sig.return_type.kind {
+            ElementType::TypeDefOrRef(def) => {
+                assert!(def.name(reader) == ("Windows.Foundation", "Uri"))
+            }
+            _ => panic!(),
+        }
+    }
+
+    #[test]
+    fn generic_instance() {
+        let reader = &TypeReader::from_os();
+        let def = reader.resolve_type_def(("Windows.Foundation", "IAsyncOperation`1"));
+        let method = def
+            .methods(reader)
+            .find(|method| method.name(reader) == "get_Completed")
+            .unwrap();
+        let sig = MethodSig::from_method_def(reader, method);
+
+        match &sig.return_type.kind {
+            ElementType::GenericInstance(def, args) => {
+                assert!(
+                    def.name(reader) == ("Windows.Foundation", "AsyncOperationCompletedHandler`1")
+                );
+                assert!(args == &[ElementType::GenericParam(0)]);
+            }
+            _ => panic!(),
+        }
+    }
+}
@@ -0,0 +1,208 @@
+use crate::coverage::quote;
+use crate::{TypeOptions, TypeTree};
+
+use proc_macro2::{TokenStream, TokenTree};
+
+/// The size of the code generated for a type, or for all of the types of a namespace
+#[derive(Clone, PartialEq, Debug)]
+pub struct CodeSize {
+    pub namespace: String,
+    /// The type, or `None` for the total of the namespace
+    pub name: Option<String>,
+    /// The number of tokens, which is roughly what the compiler has to work through
+    pub tokens: usize,
+    /// The length of the code as text
+    pub bytes: usize,
+}
+
+/// A report of how much code each namespace and type contributes to a [`TypeTree`]
+///
+/// The namespaces and types are each ordered from largest to smallest, so that the imports
+/// responsible for most of the generated code, and hence of the compile time, come first.
+#[derive(Default, Debug)]
+pub struct SizeReport {
+    pub namespaces: Vec<CodeSize>,
+    pub types: Vec<CodeSize>,
+}
+
+impl SizeReport {
+    /// Generate the code for the tree with the given options and measure it
+    pub fn new(tree: &TypeTree, options: TypeOptions) -> Self {
+        Self::from_code(tree, &tree.generate(options))
+    }
+
+    /// Measure the code already generated for the tree by [`TypeTree::generate`]
+    pub fn from_code(tree: &TypeTree, code: &[(TokenStream, TokenStream)]) -> Self {
+        let mut namespaces = Vec::new();
+        tree.collect_namespaces(String::new(), &mut namespaces);
+
+        let mut types = Vec::new();
+        tree.collect_types(&mut types);
+
+        let mut report = Self {
+            namespaces: Vec::new(),
+            types: types
+                .iter()
+                .zip(code)
+                .map(|(t, (code, abi))| {
+                    let (tokens, bytes) = measure(code.clone(), abi.clone());
+
+                    CodeSize {
+                        namespace: t.name().namespace.to_string(),
+                        name: Some(t.name().name.to_string()),
+                        tokens,
+                        bytes,
+                    }
+                })
+                .collect(),
+        };
+
+        for (namespace, _) in &namespaces {
+            let sizes = report.types.iter().filter(|t| &t.namespace == namespace);
+
+            report.namespaces.push(CodeSize {
+                namespace: namespace.clone(),
+                name: None,
+                tokens: sizes.clone().map(|t| t.tokens).sum(),
+                bytes: sizes.map(|t| t.bytes).sum(),
+            });
+        }
+
+        report
+            .namespaces
+            .sort_by_key(|size| std::cmp::Reverse(size.tokens));
+        report
+            .types
+            .sort_by_key(|size| std::cmp::Reverse(size.tokens));
+        report
+    }
+
+    /// The total number of tokens generated
+    pub fn tokens(&self) -> usize {
+        self.namespaces
+            .iter()
+            .map(|namespace| namespace.tokens)
+            .sum()
+    }
+
+    /// Format the report as JSON Lines, with one object for each namespace followed by one for
+    /// each type
+    ///
+    /// Each object includes its `share` of the total number of tokens.
+    pub fn to_json_lines(&self) -> String {
+        let total = self.tokens().max(1) as f64;
+        let mut json = String::new();
+
+        for size in self.namespaces.iter().chain(self.types.iter()) {
+            json.push_str(&format!(
+                "{{\"namespace\":{},\"name\":{},\"tokens\":{},\"bytes\":{},\"share\":{:.4}}}\n",
+                quote(&size.namespace),
+                size.name
+                    .as_ref()
+                    .map_or_else(|| "null".to_string(), |name| quote(name)),
+                size.tokens,
+                size.bytes,
+                size.tokens as f64 / total,
+            ));
+        }
+
+        json
+    }
+}
+
+fn measure(code: TokenStream, abi: TokenStream) -> (usize, usize) {
+    (
+        count(code.clone()) + count(abi.clone()),
+        code.to_string().len() + abi.to_string().len(),
+    )
+}
+
+fn count(stream: TokenStream) -> usize {
+    stream
+        .into_iter()
+        .map(|tree| match tree {
+            TokenTree::Group(group) => 2 + count(group.stream()),
+            _ => 1,
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use quote::quote;
+
+    #[test]
+    fn tokens() {
+        // The delimiters of a group are counted as two tokens.
+        assert_eq!(count(quote! { pub struct Uri { ptr: usize } }), 8);
+        assert_eq!(measure(quote! { a }, quote! {}), (1, 1));
+    }
+
+    #[test]
+    fn from_code() {
+        let reader = &crate::TypeReader::from_os();
+        let mut limits = crate::TypeLimits::default();
+        limits.insert_type(reader, "windows.foundation.uri");
+        let tree = crate::TypeStage::from_limits(reader, &limits).into_tree();
+
+        // The report measures the same code that the tree's token stream is made of.
+        let code = tree.generate(TypeOptions::default());
+        let report = SizeReport::from_code(&tree, &code);
+        assert_eq!(report.types.len(), code.len());
+        assert_eq!(
+            report.tokens(),
+            SizeReport::new(&tree, TypeOptions::default()).tokens()
+        );
+
+        let uri = report
+            .types
+            .iter()
+            .find(|t| t.name.as_deref() == Some("Uri"))
+            .unwrap();
+        assert_eq!(uri.namespace, "Windows.Foundation");
+        assert!(report
+            .namespaces
+            .iter()
+            .any(|namespace| namespace.namespace == "Windows.Foundation"
+                && namespace.tokens >= uri.tokens));
+    }
+
+    #[test]
+    fn json_lines() {
+        let report = SizeReport {
+            namespaces: vec![CodeSize {
+                namespace: "Windows.Foundation".to_string(),
+                name: None,
+                tokens: 300,
+                bytes: 1200,
+            }],
+            types: vec![
+                CodeSize {
+                    namespace: "Windows.Foundation".to_string(),
+                    name: Some("Uri".to_string()),
+                    tokens: 200,
+                    bytes: 800,
+                },
+                CodeSize {
+                    namespace: "Windows.Foundation".to_string(),
+                    name: Some("IStringable".to_string()),
+                    tokens: 100,
+                    bytes: 400,
+                },
+            ],
+        };
+
+        assert_eq!(
+            report.to_json_lines(),
+            concat!(
+                r#"{"namespace":"Windows.Foundation","name":null,"tokens":300,"bytes":1200,"share":1.0000}"#,
+                "\n",
+                r#"{"namespace":"Windows.Foundation","name":"Uri","tokens":200,"bytes":800,"share":0.6667}"#,
+                "\n",
+                r#"{"namespace":"Windows.Foundation","name":"IStringable","tokens":100,"bytes":400,"share":0.3333}"#,
+                "\n",
+            )
+        );
+    }
+}
@@ -0,0 +1,391 @@
+use crate::codes::TypeDefOrRef;
+use crate::coverage::quote;
+use crate::flags::TypeCategory;
+use crate::signature::{ElementType, MethodSig, ParamSig};
+use crate::tables::TypeDef;
+use crate::TypeReader;
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// Whether a type or member was added, removed or changed, as reported by [`SurfaceDiff`]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ChangeKind {
+    Added,
+    Removed,
+    Changed,
+}
+
+/// A type or member that differs between two sets of metadata
+#[derive(Clone, Debug, PartialEq)]
+pub struct SurfaceChange {
+    pub kind: ChangeKind,
+    /// The full name of the type, such as `Windows.Foundation.Uri`
+    pub type_name: String,
+    /// The member, such as `method GetDomain` or `implements Windows.Foundation.IStringable`, or
+    /// `None` for the type itself
+    pub member: Option<String>,
+    /// The signatures of a changed member, or the kind and base of a changed type, before and
+    /// after the change
+    pub old: Option<String>,
+    pub new: Option<String>,
+}
+
+/// The differences between the API surface of two sets of metadata, such as two versions of the
+/// Windows SDK
+///
+/// Types are compared by their full names and members by their names, so that upgrading the
+/// metadata a crate depends on can be reviewed for added, removed and changed types, methods,
+/// fields and interfaces before the generated code changes underneath it. Renamed types show up
+/// as one removed and one added type.
+#[derive(Default, Debug)]
+pub struct SurfaceDiff {
+    /// The changes, ordered by type name and then member
+    pub changes: Vec<SurfaceChange>,
+}
+
+impl SurfaceDiff {
+    pub fn new(old: &TypeReader, new: &TypeReader) -> Self {
+        let old = surface(old);
+        let new = surface(new);
+        let mut changes = Vec::new();
+
+        for (name, old_type) in &old {
+            match new.get(name) {
+                None => changes.push(change(ChangeKind::Removed, name, None, None, None)),
+                Some(new_type) => diff_type(name, old_type, new_type, &mut changes),
+            }
+        }
+
+        for name in new.keys().filter(|name| !old.contains_key(*name)) {
+            changes.push(change(ChangeKind::Added, name, None, None, None));
+        }
+
+        changes.sort_by(|a, b| (&a.type_name, &a.member).cmp(&(&b.type_name, &b.member)));
+        Self { changes }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    /// Format the diff as JSON Lines, with one object for each change
+    pub fn to_json_lines(&self) -> String {
+        let mut json = String::new();
+
+        for change in &self.changes {
+            let kind = match change.kind {
+                ChangeKind::Added => "added",
+                ChangeKind::Removed => "removed",
+                ChangeKind::Changed => "changed",
+            };
+
+            json.push_str(&format!(
+                "{{\"change\":{},\"type\":{},\"member\":{},\"old\":{},\"new\":{}}}\n",
+                quote(kind),
+                quote(&change.type_name),
+                optional(&change.member),
+                optional(&change.old),
+                optional(&change.new),
+            ));
+        }
+
+        json
+    }
+}
+
+impl fmt::Display for SurfaceDiff {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for change in &self.changes {
+            let sign = match change.kind {
+                ChangeKind::Added => '+',
+                ChangeKind::Removed => '-',
+                ChangeKind::Changed => '~',
+            };
+
+            match &change.member {
+                Some(member) => writeln!(f, "{} {}: {}", sign, change.type_name, member)?,
+                None => writeln!(f, "{} {}", sign, change.type_name)?,
+            }
+
+            if change.kind == ChangeKind::Changed {
+                for line in change.old.iter().flat_map(|old| old.lines()) {
+                    writeln!(f, "    - {}", line)?;
+                }
+
+                for line in change.new.iter().flat_map(|new| new.lines()) {
+                    writeln!(f, "    + {}", line)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+// The description of a type and its members, keyed by member.
+struct TypeSurface {
+    header: String,
+    members: BTreeMap<String, String>,
+}
+
+fn surface(reader: &TypeReader) -> BTreeMap<String, TypeSurface> {
+    let mut types = BTreeMap::new();
+
+    for (namespace, defs) in &reader.types {
+        for (name, def) in defs {
+            types.insert(
+                format!("{}.{}", namespace, name),
+                type_surface(reader, *def),
+            );
+        }
+    }
+
+    types
+}
+
+fn type_surface(reader: &TypeReader, def: TypeDef) -> TypeSurface {
+    let category = def.category(reader);
+    let generics: Vec<&str> = def
+        .generics(reader)
+        .map(|param| param.name(reader))
+        .collect();
+
+    let mut header = match category {
+        TypeCategory::Interface => "interface",
+        TypeCategory::Class => "class",
+        TypeCategory::Enum => "enum",
+        TypeCategory::Struct => "struct",
+        TypeCategory::Delegate => "delegate",
+    }
+    .to_string();
+
+    if !generics.is_empty() {
+        header.push_str(&format!("<{}>", generics.join(", ")));
+    }
+
+    if category == TypeCategory::Class {
+        match def.extends(reader).name(reader) {
+            ("System", "Object") => {}
+            (namespace, name) => header.push_str(&format!(" : {}.{}", namespace, name)),
+        }
+    }
+
+    let mut members = BTreeMap::new();
+
+    for interface in def.interfaces(reader) {
+        let name = type_or_spec_name(reader, &interface.interface(reader));
+        members.insert(format!("implements {}", name), String::new());
+    }
+
+    for field in def.fields(reader) {
+        let name = field.name(reader);
+
+        // An enum's `value__` field only holds its underlying type.
+        if name == "value__" {
+            continue;
+        }
+
+        let mut description = type_name(reader, &ElementType::from_field(reader, field));
+
+        for constant in field.constants(reader) {
+            let mut value = constant.value(reader);
+
+            match constant.value_type(reader) {
+                0x08 => description.push_str(&format!(" = {}", value.read_i32())),
+                0x09 => description.push_str(&format!(" = {}", value.read_u32())),
+                _ => {}
+            }
+        }
+
+        members.insert(format!("field {}", name), description);
+    }
+
+    for method in def.methods(reader) {
+        let sig = MethodSig::from_method_def(reader, method);
+        let params: Vec<String> = sig
+            .params
+            .iter()
+            .map(|param| param_name(reader, param))
+            .collect();
+
+        let description = format!(
+            "({}) -> {}",
+            params.join(", "),
+            param_name(reader, &sig.return_type)
+        );
+
+        // Overloads share a name, so their signatures are listed together.
+        members
+            .entry(format!("method {}", method.name(reader)))
+            .and_modify(|overloads: &mut String| {
+                overloads.push('\n');
+                overloads.push_str(&description);
+            })
+            .or_insert(description);
+    }
+
+    TypeSurface { header, members }
+}
+
+fn diff_type(name: &str, old: &TypeSurface, new: &TypeSurface, changes: &mut Vec<SurfaceChange>) {
+    if old.header != new.header {
+        changes.push(change(
+            ChangeKind::Changed,
+            name,
+            None,
+            Some(&old.header),
+            Some(&new.header),
+        ));
+    }
+
+    for (member, old_description) in &old.members {
+        match new.members.get(member) {
+            None => changes.push(change(ChangeKind::Removed, name, Some(member), None, None)),
+            Some(new_description) if new_description != old_description => changes.push(change(
+                ChangeKind::Changed,
+                name,
+                Some(member),
+                Some(old_description),
+                Some(new_description),
+            )),
+            _ => {}
+        }
+    }
+
+    for member in new.members.keys() {
+        if !old.members.contains_key(member) {
+            changes.push(change(ChangeKind::Added, name, Some(member), None, None));
+        }
+    }
+}
+
+fn change(
+    kind: ChangeKind,
+    type_name: &str,
+    member: Option<&String>,
+    old: Option<&String>,
+    new: Option<&String>,
+) -> SurfaceChange {
+    SurfaceChange {
+        kind,
+        type_name: type_name.to_string(),
+        member: member.cloned(),
+        old: old.cloned(),
+        new: new.cloned(),
+    }
+}
+
+fn param_name(reader: &TypeReader, param: &ParamSig) -> String {
+    let name = type_name(reader, &param.kind);
+
+    if param.by_ref {
+        format!("&{}", name)
+    } else {
+        name
+    }
+}
+
+fn type_or_spec_name(reader: &TypeReader, kind: &TypeDefOrRef) -> String {
+    match kind {
+        TypeDefOrRef::TypeSpec(spec) => {
+            type_name(reader, &ElementType::from_type_spec(reader, *spec))
+        }
+        kind => {
+            let (namespace, name) = kind.name(reader);
+            format!("{}.{}", namespace, name)
+        }
+    }
+}
+
+fn type_name(reader: &TypeReader, kind: &ElementType) -> String {
+    match kind {
+        ElementType::Void => "void".to_string(),
+        ElementType::Bool => "bool".to_string(),
+        ElementType::Char => "char16".to_string(),
+        ElementType::I8 => "i8".to_string(),
+        ElementType::U8 => "u8".to_string(),
+        ElementType::I16 => "i16".to_string(),
+        ElementType::U16 => "u16".to_string(),
+        ElementType::I32 => "i32".to_string(),
+        ElementType::U32 => "u32".to_string(),
+        ElementType::I64 => "i64".to_string(),
+        ElementType::U64 => "u64".to_string(),
+        ElementType::F32 => "f32".to_string(),
+        ElementType::F64 => "f64".to_string(),
+        ElementType::String => "String".to_string(),
+        ElementType::Object => "Object".to_string(),
+        ElementType::TypeDefOrRef(kind) => type_or_spec_name(reader, kind),
+        ElementType::GenericParam(index) => format!("T{}", index),
+        ElementType::MethodGenericParam(index) => format!("M{}", index),
+        ElementType::GenericInstance(kind, args) => {
+            let args: Vec<String> = args.iter().map(|arg| type_name(reader, arg)).collect();
+            format!("{}<{}>", type_or_spec_name(reader, kind), args.join(", "))
+        }
+        ElementType::Array(kind) => format!("{}[]", type_name(reader, kind)),
+    }
+}
+
+fn optional(value: &Option<String>) -> String {
+    value
+        .as_ref()
+        .map_or_else(|| "null".to_string(), |value| quote(value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn described(header: &str, members: &[(&str, &str)]) -> TypeSurface {
+        TypeSurface {
+            header: header.to_string(),
+            members: members
+                .iter()
+                .map(|(member, description)| (member.to_string(), description.to_string()))
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn members() {
+        let old = described(
+            "class",
+            &[
+                ("method GetDomain", "() -> String"),
+                ("method GetPort", "() -> i32"),
+                ("implements Windows.Foundation.IStringable", ""),
+            ],
+        );
+
+        let new = described(
+            "class : Windows.Foundation.Base",
+            &[
+                ("method GetDomain", "() -> String"),
+                ("method GetPort", "(bool) -> i32"),
+                ("method GetScheme", "() -> String"),
+            ],
+        );
+
+        let mut changes = Vec::new();
+        diff_type("Windows.Foundation.Uri", &old, &new, &mut changes);
+
+        let diff = SurfaceDiff { changes };
+        assert_eq!(
+            diff.to_string(),
+            "~ Windows.Foundation.Uri\n    - class\n    + class : Windows.Foundation.Base\n\
+             - Windows.Foundation.Uri: implements Windows.Foundation.IStringable\n\
+             ~ Windows.Foundation.Uri: method GetPort\n    - () -> i32\n    + (bool) -> i32\n\
+             + Windows.Foundation.Uri: method GetScheme\n"
+        );
+
+        assert_eq!(
+            diff.to_json_lines().lines().nth(2).unwrap(),
+            r#"{"change":"changed","type":"Windows.Foundation.Uri","member":"method GetPort","old":"() -> i32","new":"(bool) -> i32"}"#
+        );
+    }
+
+    #[test]
+    fn same() {
+        let reader = &TypeReader::from_os();
+        assert!(SurfaceDiff::new(reader, reader).is_empty());
+    }
+}
@@ -3,7 +3,7 @@ use crate::codes::{AttributeType, HasAttribute, MemberRefParent};
 use crate::row::Row;
 use crate::TypeReader;
 
-#[derive(Copy, Clone, PartialEq, PartialOrd, Eq, Ord)]
+#[derive(Copy, Clone, PartialEq, PartialOrd, Eq, Ord, Debug)]
 pub struct Attribute(pub Row);
 
 impl Attribute {
@@ -29,8 +29,8 @@ impl Attribute {
 
     pub fn args(&self, reader: &TypeReader) -> Vec<(String, AttributeArg)> {
         let (mut sig, mut values) = match self.constructor(reader) {
-            AttributeType::MethodDef(method) => (reader.blob(method.0, 4), reader.blob(self.0, 2)),
-            AttributeType::MemberRef(method) => (reader.blob(method.0, 2), reader.blob(self.0, 2)),
+            AttributeType::MethodDef(method) => (method.sig(reader), reader.blob(self.0, 2)),
+            AttributeType::MemberRef(method) => (method.sig(reader), reader.blob(self.0, 2)),
         };
 
         values.read_u16();
@@ -1,11 +1,11 @@
 use crate::blob::Blob;
-use crate::codes::HasConstant;
+use crate::codes::{HasAttribute, HasConstant};
 use crate::file::TableIndex;
 use crate::row::Row;
-use crate::tables::Constant;
+use crate::tables::{Attribute, Constant};
 use crate::TypeReader;
 
-#[derive(Copy, Clone, PartialEq, PartialOrd, Eq, Ord)]
+#[derive(Copy, Clone, PartialEq, PartialOrd, Eq, Ord, Debug)]
 pub struct Field(pub Row);
 
 impl Field {
@@ -27,4 +27,15 @@ impl Field {
             )
             .map(Constant)
     }
+
+    pub fn attributes(self, reader: &TypeReader) -> impl Iterator<Item = Attribute> {
+        reader
+            .equal_range(
+                self.0.file_index,
+                TableIndex::CustomAttribute,
+                0,
+                HasAttribute::Field(self).encode(),
+            )
+            .map(Attribute)
+    }
 }
@@ -1,8 +1,9 @@
+use crate::blob::Blob;
 use crate::codes::MemberRefParent;
 use crate::row::Row;
 use crate::TypeReader;
 
-#[derive(Copy, Clone, PartialEq, PartialOrd, Eq, Ord)]
+#[derive(Copy, Clone, PartialEq, PartialOrd, Eq, Ord, Debug)]
 pub struct MemberRef(pub Row);
 
 impl MemberRef {
@@ -13,4 +14,8 @@ impl MemberRef {
     pub fn name(self, reader: &TypeReader) -> &str {
         reader.str(self.0, 1)
     }
+
+    pub fn sig(self, reader: &TypeReader) -> Blob {
+        reader.blob(self.0, 2)
+    }
 }
@@ -1,3 +1,8 @@
+//! Typed rows of the metadata tables
+//!
+//! Each type wraps a [`Row`](crate::Row) of its table and reads its columns through the
+//! [`TypeReader`](crate::TypeReader) that loaded the file.
+
 mod attribute;
 mod constant;
 mod field;
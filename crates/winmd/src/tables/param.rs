@@ -1,8 +1,11 @@
+use super::Attribute;
+use crate::codes::HasAttribute;
+use crate::file::TableIndex;
 use crate::flags::ParamFlags;
 use crate::row::Row;
 use crate::TypeReader;
 
-#[derive(Copy, Clone, PartialEq, PartialOrd, Eq, Ord)]
+#[derive(Copy, Clone, PartialEq, PartialOrd, Eq, Ord, Debug)]
 pub struct Param(pub Row);
 
 impl Param {
@@ -17,4 +20,15 @@ impl Param {
     pub fn name(self, reader: &TypeReader) -> &str {
         reader.str(self.0, 2)
     }
+
+    pub fn attributes(self, reader: &TypeReader) -> impl Iterator<Item = Attribute> {
+        reader
+            .equal_range(
+                self.0.file_index,
+                TableIndex::CustomAttribute,
+                0,
+                HasAttribute::Param(self).encode(),
+            )
+            .map(Attribute)
+    }
 }
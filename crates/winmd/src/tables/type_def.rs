@@ -1,5 +1,5 @@
 use super::Attribute;
-use crate::codes::{HasAttribute, TypeDefOrRef, TypeOrMethodDef};
+use crate::codes::{AttributeType, HasAttribute, TypeDefOrRef, TypeOrMethodDef};
 use crate::file::TableIndex;
 use crate::flags::{TypeCategory, TypeFlags};
 use crate::row::Row;
@@ -75,6 +75,66 @@ impl TypeDef {
             .unwrap()
     }
 
+    /// The version of the contract that the type belongs to, if it has one
+    pub fn contract_version(self, reader: &TypeReader) -> Option<u32> {
+        self.contract(reader).map(|(_, version)| version)
+    }
+
+    /// The name and version of the contract that the type belongs to, if it has one
+    ///
+    /// The attribute is decoded directly since its contract argument may name a type that isn't
+    /// loaded. The name is missing for the contracts themselves, which only have a version.
+    pub fn contract(self, reader: &TypeReader) -> Option<(Option<String>, u32)> {
+        let attribute = self.attributes(reader).find(|attribute| {
+            attribute.name(reader) == ("Windows.Foundation.Metadata", "ContractVersionAttribute")
+        })?;
+
+        let mut sig = match attribute.constructor(reader) {
+            AttributeType::MethodDef(method) => method.sig(reader),
+            AttributeType::MemberRef(method) => method.sig(reader),
+        };
+
+        let mut values = reader.blob(attribute.0, 2);
+        values.read_u16();
+        sig.read_unsigned();
+
+        // The version follows the contract's name, which is encoded the same whether it is a
+        // string or a type.
+        let name = if sig.read_unsigned() == 2 {
+            Some(values.read_str().to_string())
+        } else {
+            None
+        };
+
+        Some((name, values.read_u32()))
+    }
+
+    /// Returns true if the two definitions have the same kind, base type, fields and methods
+    pub fn same_members(self, reader: &TypeReader, other: TypeDef) -> bool {
+        // Interfaces have no base type to decode.
+        let extends = |def: TypeDef| {
+            if def.flags(reader).interface() {
+                return None;
+            }
+
+            match def.extends(reader) {
+                TypeDefOrRef::TypeSpec(_) => None,
+                extends => Some(extends.name(reader)),
+            }
+        };
+
+        self.flags(reader).0 == other.flags(reader).0
+            && extends(self) == extends(other)
+            && self
+                .fields(reader)
+                .map(|field| field.name(reader))
+                .eq(other.fields(reader).map(|field| field.name(reader)))
+            && self
+                .methods(reader)
+                .map(|method| method.name(reader))
+                .eq(other.methods(reader).map(|method| method.name(reader)))
+    }
+
     pub fn ignore(self, reader: &TypeReader) -> bool {
         let flags = self.flags(reader);
 
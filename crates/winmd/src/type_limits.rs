@@ -1,10 +1,19 @@
+use crate::flags::TypeCategory;
+use crate::tables::TypeDef;
 use crate::TypeReader;
 
 use std::collections::BTreeSet;
 
-/// The set of relevant namespaces
+/// The set of relevant namespaces and types
+///
+/// The types in a relevant namespace are generated, other than interfaces that are exclusive to a
+/// class and only generated if that class is reached, while a relevant type only brings in itself
+/// along with the types that it depends on.
 #[derive(Default, Debug)]
-pub struct TypeLimits(pub BTreeSet<String>);
+pub struct TypeLimits {
+    pub namespaces: BTreeSet<String>,
+    pub types: BTreeSet<TypeDef>,
+}
 
 impl TypeLimits {
     /// Insert a namespace into the set of relevant namespaces
@@ -16,16 +25,60 @@ impl TypeLimits {
             .unwrap_or_else(|| panic!("Namespace `{}` not found in winmd files", namespace));
 
         let mut namespace = found.as_str();
-        self.0.insert(namespace.to_owned());
+        self.namespaces.insert(namespace.to_owned());
 
         while let Some(pos) = namespace.rfind('.') {
             namespace = &namespace[..pos];
 
             if reader.types.contains_key(namespace) {
-                self.0.insert(namespace.to_owned());
+                self.namespaces.insert(namespace.to_owned());
             }
         }
     }
+
+    /// Insert a type, given by its lowercase namespace and name such as `windows.foundation.uri`,
+    /// into the set of relevant types
+    pub fn insert_type(&mut self, reader: &TypeReader, name: &str) {
+        let def = name
+            .rfind('.')
+            .and_then(|pos| {
+                let (namespace, name) = (&name[..pos], &name[pos + 1..]);
+
+                reader
+                    .types
+                    .iter()
+                    .find(|(found, _)| found.to_lowercase() == namespace)
+                    .and_then(|(_, types)| {
+                        types
+                            .iter()
+                            .find(|(found, _)| found.to_lowercase() == name)
+                            .map(|(_, def)| *def)
+                    })
+            })
+            .unwrap_or_else(|| panic!("Type `{}` not found in winmd files", name));
+
+        self.types.insert(def);
+    }
+
+    /// The types that resolution starts from
+    ///
+    /// These are the relevant types along with the types of the relevant namespaces, leaving out
+    /// the interfaces that are exclusive to a class. Those are only used through their class so
+    /// they're brought in by it, when it's reached, rather than on their own.
+    pub fn roots(&self, reader: &TypeReader) -> BTreeSet<TypeDef> {
+        self.namespaces
+            .iter()
+            .flat_map(|namespace| reader.namespace_types(namespace).copied())
+            .filter(|def| {
+                def.category(reader) != TypeCategory::Interface
+                    || !def.has_attribute(
+                        reader,
+                        ("Windows.Foundation.Metadata", "ExclusiveToAttribute"),
+                    )
+            })
+            .chain(self.types.iter().copied())
+            .collect()
+    }
 }
 
 #[cfg(test)]
@@ -40,29 +93,64 @@ mod tests {
             // Windows.Foundation's parent is empty so that's not included
             let mut limits = TypeLimits::default();
             limits.insert(reader, "windows.foundation");
-            assert!(limits.0.len() == 1);
-            assert!(limits.0.contains("Windows.Foundation"));
+            assert!(limits.namespaces.len() == 1);
+            assert!(limits.namespaces.contains("Windows.Foundation"));
         }
 
         {
             // Windows.Foundation.Collections's parent is not empty so it gets included
             let mut limits = TypeLimits::default();
             limits.insert(reader, "windows.foundation.collections");
-            assert!(limits.0.len() == 2);
-            assert!(limits.0.contains("Windows.Foundation"));
-            assert!(limits.0.contains("Windows.Foundation.Collections"));
+            assert!(limits.namespaces.len() == 2);
+            assert!(limits.namespaces.contains("Windows.Foundation"));
+            assert!(limits.namespaces.contains("Windows.Foundation.Collections"));
         }
 
         {
             let mut limits = TypeLimits::default();
             limits.insert(reader, "windows.foundation.collections");
             limits.insert(reader, "windows.ui.xaml.controls");
-            assert!(limits.0.len() == 5);
-            assert!(limits.0.contains("Windows.Foundation"));
-            assert!(limits.0.contains("Windows.Foundation.Collections"));
-            assert!(limits.0.contains("Windows.UI"));
-            assert!(limits.0.contains("Windows.UI.Xaml"));
-            assert!(limits.0.contains("Windows.UI.Xaml.Controls"));
+            assert!(limits.namespaces.len() == 5);
+            assert!(limits.namespaces.contains("Windows.Foundation"));
+            assert!(limits.namespaces.contains("Windows.Foundation.Collections"));
+            assert!(limits.namespaces.contains("Windows.UI"));
+            assert!(limits.namespaces.contains("Windows.UI.Xaml"));
+            assert!(limits.namespaces.contains("Windows.UI.Xaml.Controls"));
         }
     }
+
+    #[test]
+    fn test_type_inclusion() {
+        let reader = &TypeReader::from_os();
+
+        // A type doesn't bring in its namespace or any of its parents.
+        let mut limits = TypeLimits::default();
+        limits.insert_type(reader, "windows.foundation.uri");
+        assert!(limits.namespaces.is_empty());
+        assert!(limits.types.len() == 1);
+        assert!(limits.types.iter().next().unwrap().name(reader) == ("Windows.Foundation", "Uri"));
+    }
+
+    #[test]
+    fn test_namespace_roots() {
+        let reader = &TypeReader::from_os();
+
+        // A namespace's exclusive interfaces aren't roots, but they're still reached through
+        // their classes.
+        let mut limits = TypeLimits::default();
+        limits.insert(reader, "windows.foundation");
+        let roots = limits.roots(reader);
+        let names: Vec<(&str, &str)> = roots.iter().map(|def| def.name(reader)).collect();
+
+        assert!(names.contains(&("Windows.Foundation", "Uri")));
+        assert!(names.contains(&("Windows.Foundation", "IClosable")));
+        assert!(!names.contains(&("Windows.Foundation", "IUriRuntimeClass")));
+        assert!(!names.contains(&("Windows.Foundation", "IUriRuntimeClassFactory")));
+
+        let stage = crate::TypeStage::from_limits(reader, &limits);
+        assert!(stage
+            .0
+            .values()
+            .any(|t| t.name().name == "IUriRuntimeClass"));
+    }
 }
@@ -0,0 +1,26 @@
+/// Options that change the code generated for a [`TypeTree`](crate::TypeTree)
+#[derive(Copy, Clone, Default, Debug, PartialEq)]
+pub struct TypeOptions {
+    /// The methods of generic interfaces are thin wrappers around a single type-erased
+    /// specialization
+    pub erased: bool,
+    /// Methods returning classes, interfaces, delegates or objects return `Option<T>`, which is
+    /// `None` when the method returns null
+    ///
+    /// Otherwise such methods return a value wrapping the null pointer, which should be checked
+    /// with `ComInterface::is_null` when the API documents that it may return null.
+    pub nullable: bool,
+    /// A `register_interface_names` function is generated at the root of the tree that registers
+    /// the names of its non-generic interfaces and delegates with `winrt::diagnostics`
+    pub interface_names: bool,
+    /// Property getters and setters are marked `#[inline]`, which helps optimized builds at the
+    /// cost of debug build times
+    pub inline: bool,
+    /// Methods and default constructors are marked `#[must_use]`, so that ignoring their results,
+    /// including errors, is warned about by name
+    pub must_use: bool,
+    /// The methods of classes that come from interfaces introduced by a later version of the
+    /// `Windows.Foundation.UniversalApiContract` than this one check that the running version of
+    /// Windows has the contract, failing with `ErrorKind::NotSupportedOnThisVersion` otherwise
+    pub version_checks: Option<u16>,
+}
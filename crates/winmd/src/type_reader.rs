@@ -1,11 +1,14 @@
 use crate::blob::Blob;
 use crate::codes::Decode;
-use crate::file::{TableIndex, View, WinmdFile};
+use crate::file::{TableIndex, WinmdFile};
+use crate::interned::{Interned, StringTable};
 use crate::row::Row;
 use crate::tables::TypeDef;
 use crate::types::Type;
+use crate::validate::{self, ValidationError};
 
 use std::cmp::Ordering;
+use std::collections::btree_map::Entry;
 use std::collections::BTreeMap;
 
 /// A reader of type information from Windows Metadata
@@ -17,43 +20,215 @@ pub struct TypeReader {
     /// This is a mapping between namespace names and the types inside
     /// that namespace
     pub types: BTreeMap<String, BTreeMap<String, TypeDef>>,
+    /// The types defined differently by more than one file, where only the definition with the
+    /// highest contract version is read
+    pub conflicts: Vec<TypeConflict>,
+    /// The interned namespace and name of every type definition, indexed by file and row
+    names: Vec<Vec<(Interned, Interned)>>,
+}
+
+/// A type defined with different members by two files, such as metadata from two versions of
+/// the Windows SDK
+#[derive(Clone, Debug, PartialEq)]
+pub struct TypeConflict {
+    pub namespace: String,
+    pub name: String,
+    /// The name and contract version of the file whose definition is read
+    pub used: (String, Option<u32>),
+    /// The name and contract version of the file whose definition is ignored
+    pub ignored: (String, Option<u32>),
+}
+
+impl std::fmt::Display for TypeConflict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let version = |version: Option<u32>| match version {
+            Some(version) => format!("contract version {}", version),
+            None => "no contract version".to_string(),
+        };
+
+        write!(
+            f,
+            "`{}.{}` is defined differently by `{}` ({}) and `{}` ({}), so the definition from `{}` is used",
+            self.namespace,
+            self.name,
+            self.used.0,
+            version(self.used.1),
+            self.ignored.0,
+            version(self.ignored.1),
+            self.used.0
+        )
+    }
 }
 
 impl TypeReader {
+    /// Create a new [`TypeReader`] from the operating system's metadata
+    ///
+    /// # Panics
+    ///
+    /// Panics if any of the files can't be read.
     pub fn from_os() -> Self {
-        Self::new(crate::load_winmd::from_os())
+        Self::new(crate::load_winmd::from_os().unwrap_or_else(|error| panic!("{}", error)))
     }
 
     /// Create a new [`TypeReader`] from a [`WinmdFile`]s
+    ///
+    /// A namespace may be spread across several files, such as when a contract package adds to
+    /// the operating system's metadata. A type defined in more than one file is read from the
+    /// file with the highest contract version, and recorded in [`TypeReader::conflicts`] if the
+    /// files define it with different members.
+    ///
+    /// # Panics
+    ///
+    /// Panics if two files define the same type with the same contract version but different
+    /// members.
     pub fn new(files: Vec<WinmdFile>) -> Self {
+        Self::build(files).unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    /// Create a new [`TypeReader`] after validating the files
+    ///
+    /// Unlike [`TypeReader::new`], which panics wherever it first trips over invalid metadata,
+    /// this reports the file, table and row of the first problem found.
+    pub fn try_new(files: Vec<WinmdFile>) -> Result<Self, ValidationError> {
+        for file in &files {
+            validate::validate_file(file)?;
+        }
+
+        Self::build(files)
+    }
+
+    /// Create a new [`TypeReader`] without validating the files
+    ///
+    /// This skips the cost of validation for metadata that is known to be well-formed, such as
+    /// that of the operating system, while still reporting conflicting definitions. Invalid
+    /// metadata may then panic wherever it's first read, as with [`TypeReader::new`].
+    pub fn try_new_unvalidated(files: Vec<WinmdFile>) -> Result<Self, ValidationError> {
+        Self::build(files)
+    }
+
+    fn build(files: Vec<WinmdFile>) -> Result<Self, ValidationError> {
         let mut reader = Self {
-            files: Vec::default(),
+            files,
             types: BTreeMap::default(),
+            conflicts: Vec::default(),
+            names: Vec::default(),
         };
-        for (file_index, file) in files.into_iter().enumerate() {
+
+        // Strings are only copied out of the metadata once per distinct namespace and name,
+        // rather than once per row, as large metadata sets repeat them many times over.
+        let mut table = StringTable::default();
+        let mut names = Vec::with_capacity(reader.files.len());
+        let mut types = BTreeMap::<&str, BTreeMap<&str, TypeDef>>::new();
+        let mut conflicts = Vec::new();
+
+        for (file_index, file) in reader.files.iter().enumerate() {
             let row_count = file.type_def_table().row_count;
-            reader.files.push(file);
+            let mut file_names = Vec::with_capacity(row_count as usize);
 
             for row in 0..row_count {
                 let def = TypeDef(Row::new(row, TableIndex::TypeDef, file_index as u16));
+                let (namespace, name) = def.name(&reader);
+                file_names.push((table.intern(namespace), table.intern(name)));
 
                 if def.ignore(&reader) {
                     continue;
                 }
 
-                let (namespace, name) = def.name(&reader);
-                let namespace = namespace.to_string();
-                let name = name.to_string();
-
-                reader
-                    .types
-                    .entry(namespace)
-                    .or_default()
-                    .entry(name)
-                    .or_insert(def);
+                match types.entry(namespace).or_default().entry(name) {
+                    Entry::Vacant(entry) => {
+                        entry.insert(def);
+                    }
+                    Entry::Occupied(mut entry) => {
+                        let preferred = reader.merge(*entry.get(), def, &mut conflicts)?;
+                        entry.insert(preferred);
+                    }
+                }
             }
+
+            names.push(file_names);
+        }
+
+        let types = types
+            .into_iter()
+            .map(|(namespace, types)| {
+                let types = types
+                    .into_iter()
+                    .map(|(name, def)| (name.to_string(), def))
+                    .collect();
+
+                (namespace.to_string(), types)
+            })
+            .collect();
+
+        reader.types = types;
+        reader.conflicts = conflicts;
+        reader.names = names;
+        Ok(reader)
+    }
+
+    /// Choose between two definitions of the same type from different files
+    fn merge(
+        &self,
+        existing: TypeDef,
+        def: TypeDef,
+        conflicts: &mut Vec<TypeConflict>,
+    ) -> Result<TypeDef, ValidationError> {
+        let existing_version = existing.contract_version(self);
+        let version = def.contract_version(self);
+
+        if existing.same_members(self, def) {
+            return Ok(if version > existing_version {
+                def
+            } else {
+                existing
+            });
+        }
+
+        if version != existing_version {
+            let (used, ignored) = if version > existing_version {
+                (def, existing)
+            } else {
+                (existing, def)
+            };
+
+            let file = |def: TypeDef| {
+                (
+                    self.files[def.0.file_index as usize].name.clone(),
+                    def.contract_version(self),
+                )
+            };
+
+            let (namespace, name) = def.name(self);
+            conflicts.push(TypeConflict {
+                namespace: namespace.to_string(),
+                name: name.to_string(),
+                used: file(used),
+                ignored: file(ignored),
+            });
+
+            return Ok(used);
         }
-        reader
+
+        let (namespace, name) = def.name(self);
+
+        Err(ValidationError {
+            file: self.files[def.0.file_index as usize].name.clone(),
+            location: Some((TableIndex::TypeDef, def.0.index)),
+            message: format!(
+                "`{}.{}` conflicts with its definition in `{}`",
+                namespace, name, self.files[existing.0.file_index as usize].name
+            ),
+        })
+    }
+
+    /// Check that the tables, heaps and signatures of every file are well-formed
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        self.files.iter().try_for_each(validate::validate_file)
+    }
+
+    /// Get the interned namespace and name of a type definition
+    pub(crate) fn type_def_name(&self, def: TypeDef) -> (Interned, Interned) {
+        self.names[def.0.file_index as usize][def.0.index as usize].clone()
     }
 
     /// Get all the namespace names that the [`TypeReader`] knows about
@@ -94,17 +269,18 @@ impl TypeReader {
         Type::from_type_def(self, def)
     }
 
+    /// Get every row of a table in one of the reader's files
+    ///
+    /// The rows may be wrapped in the matching type from the [`tables`](crate::tables) module,
+    /// such as `reader.rows(0, TableIndex::TypeDef).map(TypeDef)`.
+    pub fn rows(&self, file: u16, table: TableIndex) -> impl Iterator<Item = Row> {
+        let row_count = self.files[file as usize].tables[table as usize].row_count;
+        (0..row_count).map(move |row| Row::new(row, table, file))
+    }
+
     /// Read a [`u32`] value from a specific [`Row`] and column
     pub fn u32(&self, row: Row, column: u32) -> u32 {
-        let file = &self.files[row.file_index as usize];
-        let table = &file.tables[row.table_index as usize];
-        let offset = table.data + row.index * table.row_size + table.columns[column as usize].0;
-        match table.columns[column as usize].1 {
-            1 => file.bytes.copy_as::<u8>(offset) as u32,
-            2 => file.bytes.copy_as::<u16>(offset) as u32,
-            4 => file.bytes.copy_as::<u32>(offset) as u32,
-            _ => file.bytes.copy_as::<u64>(offset) as u32,
-        }
+        self.files[row.file_index as usize].u32(row.table_index, row.index, column)
     }
 
     /// Read a [`&str`] value from a specific [`Row`] and column
@@ -270,3 +446,115 @@ impl TypeReader {
         (first, last)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn merge_duplicates() {
+        let reader = &TypeReader::from_os();
+        let uri = reader.resolve_type_def(("Windows.Foundation", "Uri"));
+        assert!(uri.contract_version(reader).is_some());
+        assert_eq!(
+            uri.contract(reader).unwrap().0.as_deref(),
+            Some("Windows.Foundation.FoundationContract")
+        );
+
+        // Loading the same metadata twice defines every type twice with identical members.
+        let mut files = crate::load_winmd::from_os().unwrap();
+        let count = files.len();
+        files.append(&mut crate::load_winmd::from_os().unwrap());
+        let merged = &TypeReader::new(files);
+
+        assert!(merged.types == reader.types);
+        assert!(merged
+            .types
+            .values()
+            .flat_map(|types| types.values())
+            .all(|def| (def.0.file_index as usize) < count));
+        assert!(merged.conflicts.is_empty());
+    }
+
+    // A newer copy of a file that changes one type records that type as a conflict, while the
+    // types it defines identically are merged without one.
+    #[test]
+    fn merge_conflict() {
+        let source = crate::load_winmd::from_os()
+            .unwrap()
+            .into_iter()
+            .find(|file| {
+                file.name
+                    .to_lowercase()
+                    .ends_with("windows.foundation.winmd")
+            })
+            .unwrap();
+
+        let name = source.name.clone();
+        let reader = &TypeReader::new(vec![source]);
+        let uri = reader.resolve_type_def(("Windows.Foundation", "Uri"));
+        let version = uri.contract_version(reader).unwrap();
+        let file = &reader.files[0];
+        let mut bytes = std::fs::read(&name).unwrap();
+
+        // Raise the version of Uri's contract, which other types share, by rewriting the version
+        // at the end of the attribute's value.
+        let attribute = uri
+            .attributes(reader)
+            .find(|attribute| {
+                attribute.name(reader)
+                    == ("Windows.Foundation.Metadata", "ContractVersionAttribute")
+            })
+            .unwrap();
+        let blob = (file.blobs + reader.u32(attribute.0, 2)) as usize;
+        let end = blob + 1 + bytes[blob] as usize;
+        let at = (blob..end - 3)
+            .rev()
+            .find(|at| bytes[*at..*at + 4] == version.to_le_bytes())
+            .unwrap();
+        bytes[at..at + 4].copy_from_slice(&(version + 1).to_le_bytes());
+
+        // Unseal Uri, so that it's the only type defined differently.
+        let table = &file.tables[TableIndex::TypeDef as usize];
+        let flags = (table.data + uri.0.index * table.row_size + table.columns[0].0) as usize;
+        bytes[flags + 1] ^= 0x01;
+
+        let path =
+            std::env::temp_dir().join(format!("winmd_conflict_{}.winmd", std::process::id()));
+        std::fs::write(&path, &bytes).unwrap();
+
+        let files = vec![
+            WinmdFile::new(&name).unwrap(),
+            WinmdFile::new(&path).unwrap(),
+        ];
+        let merged = TypeReader::new(files);
+        let conflicts = merged.conflicts.clone();
+        drop(merged);
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(
+            conflicts,
+            vec![TypeConflict {
+                namespace: "Windows.Foundation".to_string(),
+                name: "Uri".to_string(),
+                used: (path.display().to_string(), Some(version + 1)),
+                ignored: (name, Some(version)),
+            }]
+        );
+    }
+
+    #[test]
+    fn conflict() {
+        let conflict = TypeConflict {
+            namespace: "Windows.Foundation".to_string(),
+            name: "Uri".to_string(),
+            used: ("new.winmd".to_string(), Some(0x0002_0000)),
+            ignored: ("old.winmd".to_string(), None),
+        };
+
+        assert_eq!(
+            conflict.to_string(),
+            "`Windows.Foundation.Uri` is defined differently by `new.winmd` (contract version 131072) and `old.winmd` (no contract version), so the definition from `new.winmd` is used"
+        );
+    }
+}
@@ -1,9 +1,11 @@
+use crate::renames::Renames;
 use crate::tables::TypeDef;
 use crate::type_limits::TypeLimits;
 use crate::type_tree::TypeTree;
-use crate::types::Type;
+use crate::types::{RequiredInterface, Type};
 use crate::TypeReader;
 
+use rayon::prelude::*;
 use std::collections::*;
 
 /// A map between type def and the fully resolved types
@@ -12,39 +14,142 @@ pub struct TypeStage(pub BTreeMap<TypeDef, Type>);
 
 impl TypeStage {
     /// Resolve types from the relevant types in a [`TypeLimit`]
+    ///
+    /// The types are resolved in parallel, a wave at a time, with each wave resolving the
+    /// dependencies of the previous one that have not already been resolved. Only the types
+    /// reachable from the limits' roots are included.
     pub fn from_limits(reader: &TypeReader, limits: &TypeLimits) -> Self {
         let mut stage = Self::default();
+        let mut pending: Vec<TypeDef> = limits.roots(reader).into_iter().collect();
 
-        for namespace in &limits.0 {
-            for def in reader.namespace_types(&namespace) {
-                stage.insert(reader, *def);
+        while !pending.is_empty() {
+            let resolved: Vec<(TypeDef, Type)> = pending
+                .par_iter()
+                .map(|def| (*def, def.into_type(reader)))
+                .collect();
+
+            let mut next = BTreeSet::new();
+
+            for (def, info) in resolved {
+                next.extend(info.dependencies());
+                stage.0.insert(def, info);
             }
+
+            pending = next
+                .into_iter()
+                .filter(|def| !stage.0.contains_key(def))
+                .collect();
         }
 
         stage
     }
 
-    fn insert(&mut self, reader: &TypeReader, def: TypeDef) {
-        if !self.0.contains_key(&def) {
-            let info = def.into_type(reader);
-            let depends = info.dependencies();
-            self.0.insert(def, info);
-            for def in depends {
-                self.insert(reader, def);
+    /// Get the namespaces of resolved types that are neither relevant namespaces nor the
+    /// namespaces of relevant types, each with the name of a type that depends on it
+    ///
+    /// These are the dependencies that were brought in automatically. A type in a listed
+    /// namespace, or a listed type's namespace, is named as the dependent where there is one.
+    pub fn unlisted_namespaces(&self, limits: &TypeLimits) -> BTreeMap<String, String> {
+        let listed: BTreeSet<&str> = limits
+            .namespaces
+            .iter()
+            .map(String::as_str)
+            .chain(
+                limits
+                    .types
+                    .iter()
+                    .filter_map(|def| self.0.get(def))
+                    .map(|t| t.name().namespace.as_str()),
+            )
+            .collect();
+
+        let mut unlisted = BTreeMap::new();
+
+        for listed_only in &[true, false] {
+            for t in self.0.values() {
+                let name = t.name();
+
+                if *listed_only && !listed.contains(name.namespace.as_str()) {
+                    continue;
+                }
+
+                for dependency in t.dependencies() {
+                    let namespace = match self.0.get(&dependency) {
+                        Some(dependency) => dependency.name().namespace.as_str(),
+                        None => continue,
+                    };
+
+                    if !listed.contains(namespace) && namespace != name.namespace.as_str() {
+                        unlisted
+                            .entry(namespace.to_string())
+                            .or_insert_with(|| name.runtime_name());
+                    }
+                }
+            }
+        }
+
+        unlisted
+    }
+
+    /// Apply the casing and renames of a [`Renames`] to the methods of the resolved classes and
+    /// interfaces, and to the fields of structs outside of `Windows.Foundation`
+    ///
+    /// The fields of the `Windows.Foundation` structs keep their names since the projection's
+    /// conversions and operators for them rely on those names.
+    pub fn rename(&mut self, reader: &TypeReader, renames: &Renames) {
+        for (def, t) in &mut self.0 {
+            let full_name = format!("{}.{}", t.name().namespace, t.name().name);
+
+            match t {
+                Type::Class(t) => rename_methods(&full_name, &mut t.interfaces, renames),
+                Type::Interface(t) => rename_methods(&full_name, &mut t.interfaces, renames),
+                Type::Struct(t) if !t.name.namespace.starts_with("Windows.Foundation") => {
+                    for (field, (name, _)) in def.fields(reader).zip(t.fields.iter_mut()) {
+                        if let Some(renamed) = renames.field_name(&full_name, field.name(reader)) {
+                            *name = renamed;
+                        }
+                    }
+                }
+                _ => {}
             }
         }
     }
 
     /// Resolve the types into a type tree for code generation
+    ///
+    /// Conversions that involve other types are only generated if those types are in the tree.
     pub fn into_tree(self) -> TypeTree {
+        let point_and_size = ["Point", "Size"].iter().all(|name| {
+            self.0
+                .values()
+                .any(|t| t.name().namespace == "Windows.Foundation" && t.name().name == *name)
+        });
+
         let mut tree = TypeTree::default();
-        self.0
-            .into_iter()
-            .for_each(|(_, t)| tree.insert(t.name().namespace.clone(), t));
+        self.0.into_iter().for_each(|(_, mut t)| {
+            if let Type::Struct(t) = &mut t {
+                t.point_and_size = point_and_size;
+            }
+
+            tree.insert(t.name().namespace.to_string(), t)
+        });
         tree
     }
 }
 
+fn rename_methods(full_name: &str, interfaces: &mut [RequiredInterface], renames: &Renames) {
+    for interface in interfaces {
+        let types = [
+            full_name.to_string(),
+            format!("{}.{}", interface.name.namespace, interface.name.name),
+        ];
+
+        for method in &mut interface.methods {
+            method.alias = renames.method_alias(&types, method);
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -68,4 +173,90 @@ mod tests {
         // so that's not included.
         assert!(stage.0.values().any(|t| t.name().name == "PropertySet") == false);
     }
+
+    #[test]
+    fn test_unlisted_namespaces() {
+        let reader = &TypeReader::from_os();
+
+        let mut limits = TypeLimits::default();
+        limits.insert(reader, "windows.foundation");
+        let stage = TypeStage::from_limits(reader, &limits);
+        let unlisted = stage.unlisted_namespaces(&limits);
+
+        assert!(unlisted.contains_key("Windows.Foundation.Collections"));
+        assert!(!unlisted.contains_key("Windows.Foundation"));
+
+        limits.insert(reader, "windows.foundation.collections");
+        let stage = TypeStage::from_limits(reader, &limits);
+        assert!(stage.unlisted_namespaces(&limits).is_empty());
+
+        // A listed type's namespace is allowed, and the dependent named is a type in it.
+        let mut limits = TypeLimits::default();
+        limits.insert_type(reader, "windows.foundation.uri");
+        let stage = TypeStage::from_limits(reader, &limits);
+        let unlisted = stage.unlisted_namespaces(&limits);
+
+        assert!(!unlisted.contains_key("Windows.Foundation"));
+        assert!(unlisted["Windows.Foundation.Collections"].starts_with("Windows.Foundation."));
+        assert!(!unlisted["Windows.Foundation.Collections"]
+            .starts_with("Windows.Foundation.Collections."));
+    }
+
+    #[test]
+    fn test_reachable_inclusion() {
+        let reader = &TypeReader::from_os();
+
+        // Only Uri and the types that it depends on are included, not the rest of its namespace.
+        let mut limits = TypeLimits::default();
+        limits.insert_type(reader, "windows.foundation.uri");
+        let stage = TypeStage::from_limits(reader, &limits);
+
+        assert!(stage.0.values().any(|t| t.name().name == "Uri"));
+        assert!(stage
+            .0
+            .values()
+            .any(|t| t.name().name == "IUriRuntimeClass"));
+        assert!(stage
+            .0
+            .values()
+            .any(|t| t.name().name == "WwwFormUrlDecoder"));
+        assert!(stage.0.values().any(|t| t.name().name == "GuidHelper") == false);
+        assert!(stage.0.values().any(|t| t.name().name == "PropertyValue") == false);
+    }
+
+    #[test]
+    fn test_rename() {
+        let reader = &TypeReader::from_os();
+
+        let mut limits = TypeLimits::default();
+        limits.insert(reader, "windows.foundation");
+        let mut stage = TypeStage::from_limits(reader, &limits);
+
+        let mut renames = Renames::default();
+        renames.casing = crate::Casing::Pascal;
+        renames.members.insert(
+            "Windows.Foundation.Uri.Domain".to_string(),
+            "Host".to_string(),
+        );
+        stage.rename(reader, &renames);
+
+        let uri = stage
+            .0
+            .values()
+            .find_map(|t| match t {
+                Type::Class(t) if t.name.name == "Uri" => Some(t),
+                _ => None,
+            })
+            .unwrap();
+
+        let aliases: Vec<(&str, Option<&str>)> = uri
+            .interfaces
+            .iter()
+            .flat_map(|interface| interface.methods.iter())
+            .map(|method| (method.name.as_str(), method.alias.as_deref()))
+            .collect();
+
+        assert!(aliases.contains(&("domain", Some("Host"))));
+        assert!(aliases.contains(&("to_string", Some("ToString"))));
+    }
 }
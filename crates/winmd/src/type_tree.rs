@@ -1,13 +1,21 @@
+use crate::case;
+use crate::format_ident;
+use crate::renames::Renames;
 use crate::type_namespaces::TypeNamespaces;
-use crate::types::Type;
+use crate::types::{MethodKind, Type};
+use crate::TypeOptions;
 use proc_macro2::TokenStream;
+use quote::quote;
 use std::iter::FromIterator;
+use std::path::Path;
 
 /// A namespaced tree of types
 #[derive(Default)]
 pub struct TypeTree {
     types: Vec<Type>,
     namespaces: TypeNamespaces,
+    /// The aliases of the tree's own types, each with the name of the type it refers to
+    aliases: Vec<(String, String)>,
 }
 
 impl TypeTree {
@@ -31,22 +39,326 @@ impl TypeTree {
         }
     }
 
+    /// Add the type aliases of a [`Renames`] to the namespaces of the types they rename
+    ///
+    /// Renames of types that aren't in the tree are ignored.
+    pub fn insert_aliases(&mut self, renames: &Renames) {
+        for (full_name, alias) in &renames.types {
+            if let Some(pos) = full_name.rfind('.') {
+                self.insert_alias(&full_name[..pos], &full_name[pos + 1..], alias);
+            }
+        }
+    }
+
+    fn insert_alias(&mut self, namespace: &str, name: &str, alias: &str) {
+        let (first, rest) = match namespace.find('.') {
+            Some(pos) => (&namespace[..pos], Some(&namespace[pos + 1..])),
+            None => (namespace, None),
+        };
+
+        let tree = match self.namespaces.0.get_mut(first) {
+            Some(tree) => tree,
+            None => return,
+        };
+
+        if let Some(rest) = rest {
+            tree.insert_alias(rest, name, alias);
+        } else if tree.types.iter().any(|t| t.name().name == name) {
+            let name = name.split('`').next().unwrap().to_string();
+            tree.aliases.push((name, alias.to_string()));
+        }
+    }
+
     /// Turn the tree into a token stream for code generation
+    ///
+    /// The code for each type is generated in parallel and parsed back on the calling thread, so
+    /// that a procedural macro's own thread is the only one to use the compiler's token streams.
     pub fn to_tokens(&self) -> TokenStream {
+        self.to_tokens_with_options(TypeOptions::default())
+    }
+
+    /// Turn the tree into a token stream in which the methods of generic interfaces are thin
+    /// wrappers around a single type-erased specialization
+    ///
+    /// Instantiating a generic interface with strings, classes, interfaces or delegates then
+    /// reuses the erased specialization's code rather than compiling each method again, at the
+    /// cost of some pointer casts per call.
+    pub fn to_erased_tokens(&self) -> TokenStream {
+        self.to_tokens_with_options(TypeOptions {
+            erased: true,
+            ..TypeOptions::default()
+        })
+    }
+
+    /// Turn the tree into a token stream generated with the given options
+    pub fn to_tokens_with_options(&self, options: TypeOptions) -> TokenStream {
+        self.to_tokens_from_code(self.generate(options), options)
+    }
+
+    /// Generate the code and vtable of each of the tree's types with the given options, in the
+    /// order that [`to_tokens_from_code`](Self::to_tokens_from_code) expects
+    ///
+    /// This lets the code be measured, as by [`SizeReport`](crate::SizeReport), without
+    /// generating it a second time.
+    pub fn generate(&self, options: TypeOptions) -> Vec<(TokenStream, TokenStream)> {
+        let mut types = Vec::new();
+        self.collect_types(&mut types);
+
+        // Token streams can't be sent between threads so each type's code and vtable are rendered
+        // as strings and parsed back on the calling thread.
+        crate::map_types(&types, |t| {
+            (
+                t.to_tokens(options).to_string(),
+                t.to_abi_tokens().to_string(),
+            )
+        })
+        .into_iter()
+        .map(|(code, abi)| (code.parse().unwrap(), abi.parse().unwrap()))
+        .collect()
+    }
+
+    /// Turn the code returned by [`generate`](Self::generate) with the same options into the
+    /// tree's token stream
+    pub fn to_tokens_from_code(
+        &self,
+        code: Vec<(TokenStream, TokenStream)>,
+        options: TypeOptions,
+    ) -> TokenStream {
+        let tokens = self.combine_tokens(&mut code.into_iter());
+
+        if options.interface_names {
+            TokenStream::from_iter(vec![tokens, self.interface_names_tokens()])
+        } else {
+            tokens
+        }
+    }
+
+    /// Generates the function that registers the names of the tree's interfaces and delegates.
+    fn interface_names_tokens(&self) -> TokenStream {
+        let mut types = Vec::new();
+        self.collect_types(&mut types);
+
+        let names = types.iter().filter_map(|t| {
+            let (name, guid) = match t {
+                Type::Interface(t) => (&t.name, &t.interfaces[0].guid),
+                Type::Delegate(t) => (&t.name, &t.guid),
+                _ => return None,
+            };
+
+            if !name.generics.is_empty() {
+                return None;
+            }
+
+            let guid = guid.to_tokens();
+            let name = name.runtime_name();
+            Some(quote! { (::winrt::Guid::from_values(#guid), #name), })
+        });
+
+        quote! {
+            ::winrt::__cfg_std! {
+            /// Registers the names of the imported interfaces so that `winrt::diagnostics` can
+            /// name them by their IIDs.
+            pub fn register_interface_names() {
+                static NAMES: &[(::winrt::Guid, &str)] = &[#(#names)*];
+                ::winrt::diagnostics::register_interface_names(NAMES);
+            }
+            }
+        }
+    }
+
+    /// Write the code for each namespace into its own file in `dir`, for use by a build script
+    ///
+    /// A `winrt.rs` file declaring the namespace modules and including each namespace's file is
+    /// written alongside them, ready to be included with
+    /// `include!(concat!(env!("OUT_DIR"), "/winrt.rs"))`. A namespace's code is only regenerated
+    /// when the fingerprint of its resolved types differs from that of the previous build, so
+    /// iterating on an app that imports many namespaces only regenerates what changed, and the
+    /// files of namespaces that are no longer generated are deleted. Returns the namespaces that
+    /// were regenerated.
+    pub fn write_namespaces(
+        &self,
+        dir: &Path,
+        options: TypeOptions,
+    ) -> std::io::Result<Vec<String>> {
+        let mut namespaces = Vec::new();
+        self.collect_namespaces(String::new(), &mut namespaces);
+
+        let stale: Vec<(String, &TypeTree, String)> = namespaces
+            .iter()
+            .cloned()
+            .map(|(namespace, tree)| (namespace, tree, tree.fingerprint(options)))
+            .filter(|(namespace, _, fingerprint)| {
+                let previous = std::fs::read_to_string(dir.join(format!("{}.hash", namespace)));
+                let current = previous.map(|previous| previous == *fingerprint);
+                !current.unwrap_or(false) || !dir.join(format!("{}.rs", namespace)).exists()
+            })
+            .collect();
+
+        crate::map_types(&stale, |(namespace, tree, fingerprint)| {
+            let code = TokenStream::from_iter(
+                tree.types
+                    .iter()
+                    .map(|t| t.to_tokens(options))
+                    .chain(std::iter::once(tree.alias_tokens()))
+                    .chain(std::iter::once(abi_module_tokens(
+                        tree.types.iter().map(|t| t.to_abi_tokens()).collect(),
+                    ))),
+            );
+            std::fs::write(dir.join(format!("{}.rs", namespace)), code.to_string())?;
+            std::fs::write(dir.join(format!("{}.hash", namespace)), fingerprint)
+        })
+        .into_iter()
+        .collect::<std::io::Result<()>>()?;
+
+        // Namespaces that are no longer generated, such as when a type is removed from the
+        // imports, are deleted so that stale files don't linger. Only files with a fingerprint
+        // were written here, so anything else in the directory is left alone.
+        for entry in std::fs::read_dir(dir)? {
+            let path = entry?.path();
+
+            if path
+                .extension()
+                .map_or(true, |extension| extension != "hash")
+            {
+                continue;
+            }
+
+            let namespace = path.file_stem().unwrap_or_default().to_string_lossy();
+
+            if !namespaces.iter().any(|(current, _)| *current == namespace) {
+                let _ = std::fs::remove_file(dir.join(format!("{}.rs", namespace)));
+                std::fs::remove_file(&path)?;
+            }
+        }
+
+        let mut root = String::new();
+        self.write_modules("", &mut root);
+
+        if options.interface_names {
+            root.push_str(&self.interface_names_tokens().to_string());
+            root.push('\n');
+        }
+        let path = dir.join("winrt.rs");
+
+        if std::fs::read_to_string(&path).ok().as_ref() != Some(&root) {
+            std::fs::write(&path, root)?;
+        }
+
+        Ok(stale
+            .into_iter()
+            .map(|(namespace, _, _)| namespace)
+            .collect())
+    }
+
+    /// A hash of everything that contributes to the code generated for the tree's own types
+    fn fingerprint(&self, options: TypeOptions) -> String {
+        let mut data = format!("{}{:?}", env!("CARGO_PKG_VERSION"), options);
+
+        for t in &self.types {
+            data.push_str(&format!("{:?}", t));
+        }
+
+        data.push_str(&format!("{:?}", self.aliases));
+
+        winrt_sha1::sha1(&[], data.as_bytes())
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect()
+    }
+
+    /// Collects the full name of each namespace that has types of its own.
+    pub(crate) fn collect_namespaces<'a>(
+        &'a self,
+        namespace: String,
+        namespaces: &mut Vec<(String, &'a TypeTree)>,
+    ) {
+        for (name, tree) in self.namespaces.0.iter() {
+            tree.collect_namespaces(join_namespace(&namespace, name), namespaces);
+        }
+
+        if !self.types.is_empty() {
+            namespaces.push((namespace, self));
+        }
+    }
+
+    fn write_modules(&self, namespace: &str, code: &mut String) {
+        if !self.types.is_empty() {
+            code.push_str(&format!("include!(\"{}.rs\");\n", namespace));
+        }
+
+        for (name, tree) in self.namespaces.0.iter() {
+            let module = case::to_snake(name, MethodKind::Normal);
+            code.push_str(&format!("pub mod r#{} {{\n", module));
+            tree.write_modules(&join_namespace(namespace, name), code);
+            code.push_str("}\n");
+        }
+    }
+
+    pub(crate) fn collect_types<'a>(&'a self, types: &mut Vec<&'a Type>) {
+        types.extend(self.types.iter());
+
+        for tree in self.namespaces.0.values() {
+            tree.collect_types(types);
+        }
+    }
+
+    fn alias_tokens(&self) -> TokenStream {
+        TokenStream::from_iter(self.aliases.iter().map(|(name, alias)| {
+            let name = format_ident(name);
+            let alias = format_ident(alias);
+            quote! { pub use self::#name as #alias; }
+        }))
+    }
+
+    /// Combines the generated code, in the order of `collect_types`, into the tree's modules.
+    pub(crate) fn combine_tokens<I: Iterator<Item = (TokenStream, TokenStream)>>(
+        &self,
+        code: &mut I,
+    ) -> TokenStream {
+        let (types, abi): (Vec<TokenStream>, Vec<TokenStream>) =
+            self.types.iter().map(|_| code.next().unwrap()).unzip();
+
         TokenStream::from_iter(
-            self.types
-                .iter()
-                .map(|t| t.to_tokens())
-                .chain(std::iter::once(self.namespaces.to_tokens())),
+            types
+                .into_iter()
+                .chain(std::iter::once(self.alias_tokens()))
+                .chain(std::iter::once(abi_module_tokens(abi)))
+                .chain(std::iter::once(self.namespaces.to_tokens(code))),
         )
     }
 }
 
+/// Places the vtables of a namespace's types in a module hidden from the documentation, so that
+/// only the projected types are listed. The `ComInterface` and `RuntimeType` impls that convert
+/// the types to and from their ABI are hidden alongside the types themselves.
+fn abi_module_tokens(abi: Vec<TokenStream>) -> TokenStream {
+    if abi.iter().all(TokenStream::is_empty) {
+        return TokenStream::new();
+    }
+
+    quote! {
+        #[doc(hidden)]
+        pub mod __abi {
+            #(#abi)*
+        }
+    }
+}
+
+fn join_namespace(namespace: &str, name: &str) -> String {
+    if namespace.is_empty() {
+        name.to_string()
+    } else {
+        format!("{}.{}", namespace, name)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::TypeLimits;
+    use crate::TypeOptions;
     use crate::TypeReader;
     use crate::TypeStage;
+    use proc_macro2::{TokenStream, TokenTree};
 
     #[test]
     fn test_dependency_inclusion() {
@@ -109,4 +421,115 @@ mod tests {
                 == false
         );
     }
+
+    // Raw pointers are only handled within the generated code, so none of it needs `unsafe` to
+    // be called, whichever options it's generated with. The only unsafe functions are the
+    // runtime's own unsafe trait methods.
+    #[test]
+    fn test_no_public_unsafe_fns() {
+        let reader = &TypeReader::from_os();
+        let mut limits = TypeLimits::default();
+        limits.insert(reader, "windows.foundation");
+        limits.insert(reader, "windows.storage.streams");
+        let tree = TypeStage::from_limits(reader, &limits).into_tree();
+
+        for bits in 0..64 {
+            let options = TypeOptions {
+                erased: bits & 1 != 0,
+                nullable: bits & 2 != 0,
+                interface_names: bits & 4 != 0,
+                inline: bits & 8 != 0,
+                must_use: bits & 16 != 0,
+                version_checks: if bits & 32 != 0 { Some(1) } else { None },
+            };
+
+            let mut names = Vec::new();
+            unsafe_fns(tree.to_tokens_with_options(options), &mut names);
+            names.retain(|name| name != "from_abi");
+            assert!(names.is_empty(), "{:?}: {:?}", options, names);
+        }
+    }
+
+    // Collects the names of the functions declared `unsafe`, whatever their ABI or visibility and
+    // whether or not they're in a trait implementation. Unsafe function pointer types, such as
+    // those of the vtables, have no name and aren't collected.
+    fn unsafe_fns(tokens: TokenStream, names: &mut Vec<String>) {
+        let tokens: Vec<TokenTree> = tokens.into_iter().collect();
+
+        for (index, token) in tokens.iter().enumerate() {
+            match token {
+                TokenTree::Group(group) => unsafe_fns(group.stream(), names),
+                TokenTree::Ident(ident) if ident == "unsafe" => {
+                    let mut rest = tokens[index + 1..].iter().peekable();
+
+                    if matches!(rest.peek(), Some(TokenTree::Ident(ident)) if ident == "extern") {
+                        rest.next();
+
+                        if let Some(TokenTree::Literal(_)) = rest.peek() {
+                            rest.next();
+                        }
+                    }
+
+                    if let (Some(TokenTree::Ident(keyword)), Some(TokenTree::Ident(name))) =
+                        (rest.next(), rest.next())
+                    {
+                        if keyword == "fn" {
+                            names.push(name.to_string());
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    #[test]
+    fn test_write_namespaces() {
+        let reader = &TypeReader::from_os();
+        let mut limits = TypeLimits::default();
+        limits.insert(reader, "windows.foundation");
+        let tree = TypeStage::from_limits(reader, &limits).into_tree();
+
+        let dir = std::env::temp_dir().join(format!("winmd_namespaces_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        // The first build generates every namespace with types of its own.
+        let written = tree.write_namespaces(&dir, TypeOptions::default()).unwrap();
+        assert!(written == vec!["Windows.Foundation.Collections", "Windows.Foundation"]);
+
+        let root = std::fs::read_to_string(dir.join("winrt.rs")).unwrap();
+        assert!(root.contains("pub mod r#windows {"));
+        assert!(root.contains("include!(\"Windows.Foundation.rs\");"));
+        assert!(dir.join("Windows.Foundation.Collections.rs").exists());
+
+        // Nothing has changed so nothing is regenerated.
+        assert!(tree
+            .write_namespaces(&dir, TypeOptions::default())
+            .unwrap()
+            .is_empty());
+
+        // A namespace whose output is missing, or whose options changed, is regenerated.
+        std::fs::remove_file(dir.join("Windows.Foundation.rs")).unwrap();
+        assert!(
+            tree.write_namespaces(&dir, TypeOptions::default()).unwrap()
+                == vec!["Windows.Foundation"]
+        );
+        let erased = TypeOptions {
+            erased: true,
+            ..TypeOptions::default()
+        };
+        assert!(tree.write_namespaces(&dir, erased).unwrap().len() == 2);
+
+        // A namespace that's no longer generated is deleted, while other files are left alone.
+        std::fs::write(dir.join("Windows.Removed.rs"), "").unwrap();
+        std::fs::write(dir.join("Windows.Removed.hash"), "").unwrap();
+        std::fs::write(dir.join("other.rs"), "").unwrap();
+        assert!(tree.write_namespaces(&dir, erased).unwrap().is_empty());
+        assert!(!dir.join("Windows.Removed.rs").exists());
+        assert!(!dir.join("Windows.Removed.hash").exists());
+        assert!(dir.join("other.rs").exists());
+        assert!(dir.join("Windows.Foundation.rs").exists());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
 }
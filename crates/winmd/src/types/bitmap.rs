@@ -0,0 +1,69 @@
+use crate::types::*;
+use proc_macro2::TokenStream;
+use quote::quote;
+
+// Provides conversions between SoftwareBitmap and the tightly packed winrt::imaging::Pixels
+// buffer. The bitmap's planes are accessed directly through its locked BitmapBuffer so that
+// strides and multi-plane formats such as NV12 are handled by the runtime's pack and unpack.
+pub fn bitmap_tokens(name: &TypeName) -> TokenStream {
+    if name.namespace != "Windows.Graphics.Imaging" || name.name != "SoftwareBitmap" {
+        return quote! {};
+    }
+
+    quote! {
+        impl SoftwareBitmap {
+            /// Copies the bitmap's pixels into a tightly packed buffer.
+            pub fn to_pixels(&self) -> ::winrt::Result<::winrt::imaging::Pixels> {
+                let format = ::winrt::RuntimeType::abi(&self.bitmap_pixel_format()?);
+                let format = ::winrt::imaging::PixelFormat::from_abi(format);
+                let width = self.pixel_width()? as u32;
+                let height = self.pixel_height()? as u32;
+
+                let buffer = ::winrt::nullable::or_null(self.lock_buffer(BitmapBufferAccessMode::Read)?);
+                let planes = Self::planes(&buffer)?;
+                let reference = ::winrt::nullable::or_null(buffer.create_reference()?);
+
+                let pixels = ::winrt::interop::with_bytes(&reference, |bytes| {
+                    ::winrt::imaging::pack(width, height, format, &planes, bytes)
+                })?;
+
+                reference.close()?;
+                buffer.close()?;
+                pixels
+            }
+
+            /// Creates a bitmap from a tightly packed buffer.
+            pub fn from_pixels(pixels: &::winrt::imaging::Pixels) -> ::winrt::Result<Self> {
+                let mut format = BitmapPixelFormat::default();
+                unsafe {
+                    *::winrt::RuntimeType::set_abi(&mut format) = pixels.format.abi();
+                }
+
+                let bitmap = ::winrt::nullable::or_null(Self::create(format, pixels.width as i32, pixels.height as i32)?);
+                let buffer = ::winrt::nullable::or_null(bitmap.lock_buffer(BitmapBufferAccessMode::Write)?);
+                let planes = Self::planes(&buffer)?;
+                let reference = ::winrt::nullable::or_null(buffer.create_reference()?);
+
+                ::winrt::interop::with_bytes(&reference, |bytes| {
+                    ::winrt::imaging::unpack(pixels, &planes, bytes)
+                })??;
+
+                reference.close()?;
+                buffer.close()?;
+                Ok(bitmap)
+            }
+
+            fn planes(buffer: &BitmapBuffer) -> ::winrt::Result<::winrt::__alloc::vec::Vec<::winrt::imaging::Plane>> {
+                (0..buffer.get_plane_count()?)
+                    .map(|index| {
+                        let description = buffer.get_plane_description(index)?;
+                        Ok(::winrt::imaging::Plane {
+                            start: description.start_index as usize,
+                            stride: description.stride as usize,
+                        })
+                    })
+                    .collect()
+            }
+        }
+    }
+}
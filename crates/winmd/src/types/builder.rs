@@ -0,0 +1,202 @@
+use crate::types::*;
+use crate::*;
+use proc_macro2::TokenStream;
+use quote::quote;
+use std::iter::FromIterator;
+
+// Provides a builder for classes whose constructors are progressive overloads, where each factory
+// method takes the parameters of the one before it followed by more, such as Calendar's
+// CreateCalendarDefaultCalendarAndClock, CreateCalendar, and CreateCalendarWithTimeZone. The
+// builder's setters may be called in any order and `build` calls the overload that takes exactly
+// the parameters that were set, failing with E_INVALIDARG if there isn't one.
+pub fn builder_tokens(class: &Class) -> TokenStream {
+    if !class.builder {
+        return quote! {};
+    }
+
+    let name = &class.name;
+    let default_constructor = class.default_constructor;
+    let overloads = overloads(name, &class.interfaces);
+
+    let params = match overloads.last() {
+        Some(longest) => &longest.params,
+        None => return quote! {},
+    };
+
+    // A single factory method, or a pair with one optional parameter, is already ergonomic.
+    if params.len() < 2 || (overloads.len() + default_constructor as usize) < 2 {
+        return quote! {};
+    }
+
+    let class = name.to_tokens(&name.namespace);
+    let builder = format_ident(&format!("{}Builder", name.name));
+
+    let fields = TokenStream::from_iter(params.iter().map(|param| {
+        let field = format_ident(&param.name);
+        let tokens = param_tokens(param, &name.namespace);
+        quote! { #field: ::core::option::Option<#tokens>, }
+    }));
+
+    let setters = TokenStream::from_iter(params.iter().map(|param| {
+        let field = format_ident(&param.name);
+        let tokens = param.kind.to_tokens(&name.namespace);
+
+        if is_param(param) {
+            quote! {
+                pub fn #field<__0: ::core::convert::Into<::winrt::Param<'a, #tokens>>>(mut self, #field: __0) -> Self {
+                    self.#field = ::core::option::Option::Some(#field.into());
+                    self
+                }
+            }
+        } else {
+            quote! {
+                pub fn #field(mut self, #field: #tokens) -> Self {
+                    self.#field = ::core::option::Option::Some(#field);
+                    self
+                }
+            }
+        }
+    }));
+
+    let fields_tuple = TokenStream::from_iter(params.iter().map(|param| {
+        let field = format_ident(&param.name);
+        quote! { self.#field, }
+    }));
+
+    let default_arm = if default_constructor {
+        let none = params
+            .iter()
+            .map(|_| quote! { ::core::option::Option::None });
+        quote! { (#(#none,)*) => #class::new(), }
+    } else {
+        quote! {}
+    };
+
+    let arms = TokenStream::from_iter(overloads.iter().map(|overload| {
+        let method = format_ident(&overload.name);
+        let pattern = params.iter().enumerate().map(|(position, param)| {
+            if position < overload.params.len() {
+                let field = format_ident(&param.name);
+                quote! { ::core::option::Option::Some(#field) }
+            } else {
+                quote! { ::core::option::Option::None }
+            }
+        });
+        let args = overload
+            .params
+            .iter()
+            .map(|param| format_ident(&param.name));
+
+        quote! {
+            (#(#pattern,)*) => #class::#method(#(#args),*),
+        }
+    }));
+
+    let doc = format!(
+        "Creates a builder that calls whichever constructor of `{}` takes the parameters that are set",
+        name.name
+    );
+
+    quote! {
+        impl #class {
+            #[doc = #doc]
+            pub fn builder<'a>() -> #builder<'a> {
+                ::core::default::Default::default()
+            }
+        }
+        #[derive(Default)]
+        pub struct #builder<'a> {
+            #fields
+            __lifetime: ::core::marker::PhantomData<&'a ()>,
+        }
+        impl<'a> #builder<'a> {
+            #setters
+            pub fn build(self) -> ::winrt::Result<#class> {
+                match (#fields_tuple) {
+                    #default_arm
+                    #arms
+                    _ => ::core::result::Result::Err(::winrt::ErrorCode::INVALID_ARGUMENT.into()),
+                }
+            }
+        }
+    }
+}
+
+// Finds the longest chain of factory methods that return the class and whose parameters extend
+// one another, ordered from the fewest parameters.
+fn overloads<'a>(name: &TypeName, interfaces: &'a [RequiredInterface]) -> Vec<&'a Method> {
+    let factories: Vec<&Method> = interfaces
+        .iter()
+        .filter(|interface| interface.kind == InterfaceKind::Statics)
+        .flat_map(|interface| &interface.methods)
+        .filter(|method| is_factory(name, method))
+        .collect();
+
+    factories
+        .iter()
+        .map(|longest| {
+            let mut chain: Vec<&Method> = factories
+                .iter()
+                .filter(|method| is_prefix(&method.params, &longest.params))
+                .copied()
+                .collect();
+
+            chain.sort_by_key(|method| method.params.len());
+            chain.dedup_by_key(|method| method.params.len());
+            chain
+        })
+        .max_by_key(|chain| chain.len())
+        .unwrap_or_default()
+}
+
+fn is_factory(name: &TypeName, method: &Method) -> bool {
+    if method.kind != MethodKind::Normal || method.params.is_empty() {
+        return false;
+    }
+
+    match &method.return_type {
+        Some(Param {
+            kind: TypeKind::Class(class),
+            array: false,
+            ..
+        }) if class == name => {}
+        _ => return false,
+    }
+
+    method
+        .params
+        .iter()
+        .all(|param| param.input && !param.array && !matches!(param.kind, TypeKind::Generic(_)))
+}
+
+fn is_prefix(prefix: &[Param], params: &[Param]) -> bool {
+    prefix.len() <= params.len()
+        && prefix
+            .iter()
+            .zip(params)
+            .all(|(a, b)| a.name == b.name && a.kind == b.kind)
+}
+
+// Whether the parameter is passed as a `winrt::Param`, as with the generated methods.
+fn is_param(param: &Param) -> bool {
+    matches!(
+        param.kind,
+        TypeKind::String
+            | TypeKind::Object
+            | TypeKind::Guid
+            | TypeKind::Class(_)
+            | TypeKind::Interface(_)
+            | TypeKind::Struct(_)
+            | TypeKind::Delegate(_)
+    )
+}
+
+fn param_tokens(param: &Param, calling_namespace: &str) -> TokenStream {
+    let tokens = param.kind.to_tokens(calling_namespace);
+
+    if is_param(param) {
+        quote! { ::winrt::Param<'a, #tokens> }
+    } else {
+        tokens
+    }
+}
@@ -1,7 +1,7 @@
-use super::object::to_object_tokens;
+use super::object::{to_identity_tokens, to_object_tokens, to_try_from_object_tokens};
 use crate::tables::*;
 use crate::types::*;
-use crate::TypeReader;
+use crate::{TypeOptions, TypeReader};
 use proc_macro2::TokenStream;
 use quote::quote;
 use std::iter::FromIterator;
@@ -13,6 +13,12 @@ pub struct Class {
     pub bases: Vec<TypeName>,
     pub interfaces: Vec<RequiredInterface>,
     pub default_constructor: bool,
+    /// The deferral handed out by the class's `GetDeferral` method, if it has one
+    pub deferral: Option<TypeKind>,
+    /// Whether the class's objects may be used from any apartment
+    pub agile: bool,
+    /// Whether a builder may be generated without colliding with another type in the namespace
+    pub builder: bool,
 }
 
 impl Class {
@@ -31,8 +37,7 @@ impl Class {
             }
 
             base = reader.resolve_type_def((namespace, name));
-            let namespace = namespace.to_string();
-            let name = name.to_string();
+            let (namespace, name) = reader.type_def_name(base);
             let generics = Vec::new();
 
             let base = TypeName {
@@ -47,6 +52,7 @@ impl Class {
         }
 
         let mut default_constructor = false;
+        let mut agile = true;
 
         for attribute in def.attributes(reader) {
             match attribute.name(reader) {
@@ -68,15 +74,32 @@ impl Class {
                         None => default_constructor = true,
                     }
                 }
+                ("Windows.Foundation.Metadata", "MarshalingBehaviorAttribute") => {
+                    // The argument is a MarshalingType, which `Attribute::args` doesn't read, so
+                    // it is read directly after the blob's prolog. Only None and Standard classes
+                    // are bound to the apartment that created them.
+                    let mut blob = reader.blob(attribute.0, 2);
+                    blob.read_u16();
+                    agile = !matches!(blob.read_i32(), 1 | 3);
+                }
                 _ => {}
             }
         }
 
+        let deferral = find_deferral(reader, &interfaces);
+        let builder_name = format!("{}Builder", name.name);
+        let builder = !reader
+            .namespace_types(&name.namespace)
+            .any(|def| def.name(reader).1 == builder_name);
+
         Self {
             name,
             interfaces,
             bases,
             default_constructor,
+            deferral,
+            agile,
+            builder,
         }
     }
 
@@ -88,10 +111,10 @@ impl Class {
             .collect()
     }
 
-    pub fn to_tokens(&self) -> TokenStream {
+    pub fn to_tokens(&self, options: TypeOptions) -> TokenStream {
         let name = self.name.to_tokens(&self.name.namespace);
         let type_name = self.type_name(&name);
-        let methods = to_method_tokens(&self.name.namespace, &self.interfaces);
+        let methods = to_method_tokens(&self.name.namespace, &self.interfaces, options, self.agile);
 
         if self.interfaces[0].kind == InterfaceKind::Default {
             let guid = self.interfaces[0].guid.to_tokens();
@@ -99,8 +122,27 @@ impl Class {
                 interface.to_conversions_tokens(&self.name.namespace, &name, &TokenStream::new())
             }));
 
-            let new = if self.default_constructor {
+            let must_use = if options.must_use {
+                quote! { #[must_use] }
+            } else {
+                quote! {}
+            };
+
+            // The apartment of an object that isn't agile is recorded in debug builds, so that its
+            // methods can assert that they're called from the same apartment.
+            let new = if self.default_constructor && !self.agile {
+                quote! {
+                    #must_use
+                    pub fn new() -> ::winrt::Result<Self> {
+                        let object = ::winrt::activation::factory::<Self, ::winrt::IActivationFactory>()?.activate_instance::<Self>()?;
+                        #[cfg(debug_assertions)]
+                        ::winrt::apartment::record(&object);
+                        Ok(object)
+                    }
+                }
+            } else if self.default_constructor {
                 quote! {
+                    #must_use
                     pub fn new() -> ::winrt::Result<Self> {
                         ::winrt::activation::factory::<Self, ::winrt::IActivationFactory>()?.activate_instance::<Self>()
                     }
@@ -110,8 +152,22 @@ impl Class {
             };
 
             let object = to_object_tokens(&name, &TokenStream::new());
+            let try_from_object = to_try_from_object_tokens(&name);
+            let identity = to_identity_tokens(&name, &TokenStream::new());
             let bases = self.to_base_conversions_tokens(&self.name.namespace, &name);
             let iterator = iterator_tokens(&self.name, &self.interfaces);
+            let future = future_tokens(&self.name, &self.interfaces);
+            let deferral = deferral_tokens(&self.name, &self.interfaces, &self.deferral);
+            let json = json_tokens(&self.name, &self.interfaces);
+            let stream = stream_tokens(&self.name, &self.interfaces);
+            let debug = self.debug(&name);
+            let display = self.display(&name);
+            let bitmap = bitmap_tokens(&self.name);
+            let storage = storage_tokens(&self.name);
+            let data = data_tokens(&self.name);
+            let socket = socket_tokens(&self.name);
+            let uri = uri_tokens(&self.name);
+            let builder = builder_tokens(self);
 
             let abi_name = self.interfaces[0].name.to_abi_tokens(&self.name.namespace);
             quote! {
@@ -123,12 +179,17 @@ impl Class {
                     #methods
                 }
                 #type_name
+                #[doc(hidden)]
                 unsafe impl ::winrt::ComInterface for #name {
                     type VTable = #abi_name;
                     const IID: ::winrt::Guid = ::winrt::Guid::from_values(#guid);
                 }
+                #[doc(hidden)]
                 unsafe impl ::winrt::RuntimeType for #name {
                     type Abi = ::winrt::RawComPtr<Self>;
+                    fn select<S: ::winrt::erased::Selector>(selector: S) -> S::Output {
+                        selector.erased()
+                    }
                     fn abi(&self) -> Self::Abi {
                         <::winrt::ComPtr<Self> as ::winrt::ComInterface>::as_raw(&self.ptr)
                     }
@@ -138,8 +199,22 @@ impl Class {
                 }
                 #conversions
                 #object
+                #try_from_object
+                #identity
+                #debug
+                #display
                 #bases
                 #iterator
+                #future
+                #deferral
+                #json
+                #stream
+                #bitmap
+                #storage
+                #data
+                #socket
+                #uri
+                #builder
             }
         } else {
             quote! {
@@ -158,30 +233,90 @@ impl Class {
         TokenStream::from_iter(self.bases.iter().map(|base| {
             let into = base.to_tokens(calling_namespace);
             quote! {
-                impl ::std::convert::From<#from> for #into {
+                impl ::core::convert::From<#from> for #into {
                     fn from(value: #from) -> #into {
-                        ::std::convert::Into::<#into>::into(&value)
+                        ::core::convert::Into::<#into>::into(&value)
                     }
                 }
-                impl ::std::convert::From<&#from> for #into {
+                impl ::core::convert::From<&#from> for #into {
                     fn from(value: &#from) -> #into {
                         <#from as ::winrt::ComInterface>::query(value)
                     }
                 }
-                impl<'a> ::std::convert::Into<::winrt::Param<'a, #into>> for #from {
+                impl<'a> ::core::convert::Into<::winrt::Param<'a, #into>> for #from {
                     fn into(self) -> ::winrt::Param<'a, #into> {
-                        ::winrt::Param::Owned(::std::convert::Into::<#into>::into(self))
+                        ::winrt::Param::Owned(::core::convert::Into::<#into>::into(self))
                     }
                 }
-                impl<'a> ::std::convert::Into<::winrt::Param<'a, #into>> for &'a #from {
+                impl<'a> ::core::convert::Into<::winrt::Param<'a, #into>> for &'a #from {
                     fn into(self) -> ::winrt::Param<'a, #into> {
-                        ::winrt::Param::Owned(::std::convert::Into::<#into>::into(::std::clone::Clone::clone(self)))
+                        ::winrt::Param::Owned(::core::convert::Into::<#into>::into(self))
                     }
                 }
             }
         }))
     }
 
+    fn debug(&self, class_name: &TokenStream) -> TokenStream {
+        let string = match self.interfaces.iter().find(|interface| {
+            interface.name.namespace == "Windows.Foundation" && interface.name.name == "IStringable"
+        }) {
+            Some(interface) => {
+                let stringable = interface.name.to_tokens(&self.name.namespace);
+                quote! {
+                    if ::winrt::ComInterface::is_null(self) {
+                        None
+                    } else {
+                        ::core::convert::Into::<#stringable>::into(self).to_string().ok()
+                    }
+                }
+            }
+            None => quote! { None },
+        };
+
+        quote! {
+            impl ::core::fmt::Debug for #class_name {
+                fn fmt(&self, f: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+                    ::winrt::debug::fmt_class(
+                        f,
+                        <Self as ::winrt::RuntimeName>::NAME,
+                        ::winrt::ComInterface::as_raw(self) as ::winrt::RawPtr,
+                        #string,
+                    )
+                }
+            }
+        }
+    }
+
+    // Classes that implement IStringable are formatted with `{}` by calling ToString. A null
+    // class writes nothing, and a class whose ToString fails writes its class name instead, as
+    // formatting has no way to report the error.
+    fn display(&self, class_name: &TokenStream) -> TokenStream {
+        let interface = match self.interfaces.iter().find(|interface| {
+            interface.name.namespace == "Windows.Foundation" && interface.name.name == "IStringable"
+        }) {
+            Some(interface) => interface,
+            None => return quote! {},
+        };
+
+        let stringable = interface.name.to_tokens(&self.name.namespace);
+
+        quote! {
+            impl ::core::fmt::Display for #class_name {
+                fn fmt(&self, f: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+                    if ::winrt::ComInterface::is_null(self) {
+                        return Ok(());
+                    }
+
+                    match ::core::convert::Into::<#stringable>::into(self).to_string() {
+                        Ok(string) => ::core::fmt::Display::fmt(&string, f),
+                        Err(_) => f.write_str(<Self as ::winrt::RuntimeName>::NAME),
+                    }
+                }
+            }
+        }
+    }
+
     fn type_name(&self, class_name: &TokenStream) -> TokenStream {
         let runtime_name = self.name.runtime_name();
 
@@ -390,6 +525,57 @@ mod tests {
         assert!(interface(&t, "ICompositionObject").kind == InterfaceKind::Default);
     }
 
+    #[test]
+    fn test_agile() {
+        assert!(class(("Windows.Foundation", "Uri")).agile);
+        assert!(!class(("Windows.UI.Core", "CoreWindow")).agile);
+
+        let tokens = class(("Windows.UI.Core", "CoreWindow"))
+            .to_tokens(TypeOptions::default())
+            .to_string();
+        assert!(tokens.contains("apartment :: check"));
+        assert!(
+            tokens.contains(":: core :: debug_assert ! (:: winrt :: apartment :: is_home (self)")
+        );
+    }
+
+    #[test]
+    fn test_builder() {
+        let tokens = class(("Windows.Globalization", "Calendar"))
+            .to_tokens(TypeOptions::default())
+            .to_string();
+        assert!(tokens.contains("pub struct CalendarBuilder"));
+        assert!(tokens.contains("Calendar :: create_calendar_with_time_zone"));
+
+        // Uri's constructors take different parameters rather than extending one another.
+        let tokens = class(("Windows.Foundation", "Uri"))
+            .to_tokens(TypeOptions::default())
+            .to_string();
+        assert!(!tokens.contains("UriBuilder"));
+    }
+
+    #[test]
+    fn test_version_checks() {
+        let t = class(("Windows.UI.Composition", "Compositor"));
+        let contract = interface(&t, "ICompositor2").contract.clone().unwrap();
+        assert_eq!(
+            contract.0.as_deref(),
+            Some("Windows.Foundation.UniversalApiContract")
+        );
+
+        let tokens = |version_checks| {
+            t.to_tokens(TypeOptions {
+                version_checks,
+                ..TypeOptions::default()
+            })
+            .to_string()
+        };
+
+        assert!(tokens(Some(2)).contains("api_information :: require_contract"));
+        assert!(!tokens(Some(u16::MAX)).contains("require_contract"));
+        assert!(!tokens(None).contains("require_contract"));
+    }
+
     #[test]
     fn test_class_with_default_constructor() {
         let t = class(("Windows.UI.Composition", "Compositor"));
@@ -406,4 +592,17 @@ mod tests {
         assert!(interface.kind == InterfaceKind::Default);
         assert!(interface.name.runtime_name() == "Windows.UI.Composition.ICompositor");
     }
+
+    #[test]
+    fn test_deferral() {
+        let t = class(("Windows.ApplicationModel", "SuspendingOperation"));
+        assert!(
+            matches!(&t.deferral, Some(TypeKind::Class(name)) if name.name == "SuspendingDeferral")
+        );
+        assert!(class(("Windows.Foundation", "Uri")).deferral.is_none());
+
+        let t = class(("Windows.ApplicationModel", "SuspendingDeferral"));
+        let tokens = deferral_tokens(&t.name, &t.interfaces, &t.deferral).to_string();
+        assert!(tokens.matches("Deferral for").count() == 1);
+    }
 }
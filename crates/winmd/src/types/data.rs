@@ -0,0 +1,199 @@
+use crate::types::*;
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+
+// Provides byte-oriented helpers for the DataReader and DataWriter classes, which are how
+// Bluetooth GATT characteristics and other devices exchange their values. The integers and
+// floating point numbers are read and written with an explicit byte order, regardless of the
+// reader or writer's `byte_order`, and slices are bridged to and from `Vec<u8>`. The readers and
+// writers also implement std::io::Read and Write, loading from and storing to their streams as
+// needed, so these rely on the runtime's `std` feature. Readers and writers without a stream fail
+// to load or store with E_ILLEGAL_METHOD_CALL, which is treated as the end of the buffer.
+pub fn data_tokens(name: &TypeName) -> TokenStream {
+    if name.namespace != "Windows.Storage.Streams" {
+        return quote! {};
+    }
+
+    let numbers = ["u16", "i16", "u32", "i32", "u64", "i64", "f32", "f64"];
+
+    match name.name.as_str() {
+        "DataReader" => {
+            let reads = numbers.iter().flat_map(|number| {
+                let ty = format_ident!("{}", number);
+
+                [("le", "little"), ("be", "big")]
+                    .iter()
+                    .map(move |(suffix, order)| {
+                        let method = format_ident!("read_{}_{}", number, suffix);
+                        let from = format_ident!("from_{}_bytes", suffix);
+                        let doc = format!(
+                            "Reads a `{}` stored in {} endian byte order.",
+                            number, order
+                        );
+
+                        quote! {
+                            #[doc = #doc]
+                            pub fn #method(&self) -> ::winrt::Result<#ty> {
+                                let mut bytes = [0; ::core::mem::size_of::<#ty>()];
+                                self.read_bytes(&mut bytes)?;
+                                Ok(#ty::#from(bytes))
+                            }
+                        }
+                    })
+            });
+
+            quote! {
+                impl DataReader {
+                    /// Creates a reader over a copy of the given bytes.
+                    pub fn from_bytes(bytes: &[u8]) -> ::winrt::Result<Self> {
+                        let writer = DataWriter::new()?;
+                        writer.write_bytes(bytes)?;
+                        let buffer = ::winrt::nullable::or_null(writer.detach_buffer()?);
+                        Self::from_buffer(&buffer).map(::winrt::nullable::or_null)
+                    }
+
+                    /// Reads the given number of bytes, which must already be loaded.
+                    pub fn read_vec(&self, len: u32) -> ::winrt::Result<::winrt::__alloc::vec::Vec<u8>> {
+                        let mut bytes = ::winrt::__alloc::vec![0; len as usize];
+                        self.read_bytes(&mut bytes)?;
+                        Ok(bytes)
+                    }
+
+                    /// Reads all of the bytes that are loaded but not yet read.
+                    pub fn read_to_vec(&self) -> ::winrt::Result<::winrt::__alloc::vec::Vec<u8>> {
+                        self.read_vec(self.unconsumed_buffer_length()?)
+                    }
+
+                    /// Reads the given number of bytes as UTF-8, replacing any invalid sequences
+                    /// with the replacement character.
+                    pub fn read_utf8(&self, len: u32) -> ::winrt::Result<::winrt::__alloc::string::String> {
+                        let bytes = self.read_vec(len)?;
+                        Ok(::winrt::__alloc::string::String::from_utf8_lossy(&bytes).into_owned())
+                    }
+
+                    #(#reads)*
+                }
+                ::winrt::__cfg_std! {
+                impl ::winrt::__std::io::Read for &DataReader {
+                    /// Reads the bytes that are already loaded, or otherwise loads as many bytes as
+                    /// are available from the stream, up to the length of `buf`. This sets the
+                    /// reader's `input_stream_options` to `Partial`.
+                    fn read(&mut self, buf: &mut [u8]) -> ::winrt::__std::io::Result<usize> {
+                        if buf.is_empty() {
+                            return Ok(0);
+                        }
+
+                        let mut len = self.unconsumed_buffer_length()?;
+
+                        if len == 0 {
+                            self.set_input_stream_options(InputStreamOptions::Partial)?;
+                            let count = buf.len().min(u32::MAX as usize) as u32;
+
+                            len = match self.load_async(count) {
+                                Ok(load) => ::winrt::block_on(::winrt::nullable::or_null(load))?,
+                                Err(error) if error.code() == ::winrt::ErrorCode::ILLEGAL_METHOD_CALL => 0,
+                                Err(error) => return Err(error.into()),
+                            };
+                        }
+
+                        let len = buf.len().min(len as usize);
+                        self.read_bytes(&mut buf[..len])?;
+                        Ok(len)
+                    }
+                }
+                impl ::winrt::__std::io::Read for DataReader {
+                    fn read(&mut self, buf: &mut [u8]) -> ::winrt::__std::io::Result<usize> {
+                        ::winrt::__std::io::Read::read(&mut &*self, buf)
+                    }
+                }
+                }
+            }
+        }
+        "DataWriter" => {
+            let writes = numbers.iter().flat_map(|number| {
+                let ty = format_ident!("{}", number);
+
+                [("le", "little"), ("be", "big")]
+                    .iter()
+                    .map(move |(suffix, order)| {
+                        let method = format_ident!("write_{}_{}", number, suffix);
+                        let to = format_ident!("to_{}_bytes", suffix);
+                        let doc = format!("Writes a `{}` in {} endian byte order.", number, order);
+
+                        quote! {
+                            #[doc = #doc]
+                            pub fn #method(&self, value: #ty) -> ::winrt::Result<()> {
+                                self.write_bytes(&value.#to())
+                            }
+                        }
+                    })
+            });
+
+            quote! {
+                impl DataWriter {
+                    /// Writes the UTF-8 bytes of a string, regardless of the writer's
+                    /// `unicode_encoding`, returning the number of bytes written.
+                    pub fn write_utf8(&self, value: &str) -> ::winrt::Result<u32> {
+                        self.write_bytes(value.as_bytes())?;
+                        Ok(value.len() as u32)
+                    }
+
+                    /// Detaches the bytes written so far, leaving the writer empty.
+                    pub fn detach_bytes(&self) -> ::winrt::Result<::winrt::__alloc::vec::Vec<u8>> {
+                        let buffer = ::winrt::nullable::or_null(self.detach_buffer()?);
+                        let reader = ::winrt::nullable::or_null(DataReader::from_buffer(&buffer)?);
+                        reader.read_to_vec()
+                    }
+
+                    #(#writes)*
+                }
+                ::winrt::__cfg_std! {
+                impl ::winrt::__std::io::Write for &DataWriter {
+                    /// Buffers the bytes, storing them to the stream once 64 KiB are buffered.
+                    fn write(&mut self, buf: &[u8]) -> ::winrt::__std::io::Result<usize> {
+                        self.write_bytes(buf)?;
+
+                        if self.unstored_buffer_length()? >= 64 * 1024 {
+                            self.store()?;
+                        }
+
+                        Ok(buf.len())
+                    }
+
+                    /// Stores the buffered bytes to the stream and flushes the stream.
+                    fn flush(&mut self) -> ::winrt::__std::io::Result<()> {
+                        if self.store()? {
+                            ::winrt::block_on(::winrt::nullable::or_null(self.flush_async()?))?;
+                        }
+
+                        Ok(())
+                    }
+                }
+                impl ::winrt::__std::io::Write for DataWriter {
+                    fn write(&mut self, buf: &[u8]) -> ::winrt::__std::io::Result<usize> {
+                        ::winrt::__std::io::Write::write(&mut &*self, buf)
+                    }
+
+                    fn flush(&mut self) -> ::winrt::__std::io::Result<()> {
+                        ::winrt::__std::io::Write::flush(&mut &*self)
+                    }
+                }
+                impl DataWriter {
+                    // Stores the buffered bytes, returning false if the writer has no stream.
+                    fn store(&self) -> ::winrt::Result<bool> {
+                        match self.store_async() {
+                            Ok(store) => {
+                                ::winrt::block_on(::winrt::nullable::or_null(store))?;
+                                Ok(true)
+                            }
+                            Err(error) if error.code() == ::winrt::ErrorCode::ILLEGAL_METHOD_CALL => Ok(false),
+                            Err(error) => Err(error),
+                        }
+                    }
+                }
+                }
+            }
+        }
+        _ => quote! {},
+    }
+}
@@ -0,0 +1,73 @@
+use crate::types::*;
+use proc_macro2::TokenStream;
+use quote::quote;
+
+// Provides conversions between the Windows.Foundation DateTime and TimeSpan structs and the
+// chrono and time crates. These are only available with the winrt crate's `chrono` and `time`
+// features so the tokens are wrapped in macros that expand to nothing when they are disabled.
+pub fn date_time_tokens(name: &TypeName) -> TokenStream {
+    if name.namespace != "Windows.Foundation" {
+        return quote! {};
+    }
+
+    match name.name.as_str() {
+        "DateTime" => quote! {
+            ::winrt::__cfg_chrono! {
+                impl ::core::convert::From<DateTime> for ::winrt::chrono::DateTime<::winrt::chrono::Utc> {
+                    fn from(value: DateTime) -> Self {
+                        ::winrt::date_time::ticks_to_chrono(value.universal_time)
+                    }
+                }
+                impl ::core::convert::TryFrom<::winrt::chrono::DateTime<::winrt::chrono::Utc>> for DateTime {
+                    type Error = ::winrt::Error;
+                    fn try_from(value: ::winrt::chrono::DateTime<::winrt::chrono::Utc>) -> ::winrt::Result<Self> {
+                        Ok(Self { universal_time: ::winrt::date_time::chrono_to_ticks(&value)? })
+                    }
+                }
+            }
+            ::winrt::__cfg_time! {
+                impl ::core::convert::TryFrom<DateTime> for ::winrt::time::OffsetDateTime {
+                    type Error = ::winrt::Error;
+                    fn try_from(value: DateTime) -> ::winrt::Result<Self> {
+                        ::winrt::date_time::ticks_to_time(value.universal_time)
+                    }
+                }
+                impl ::core::convert::TryFrom<::winrt::time::OffsetDateTime> for DateTime {
+                    type Error = ::winrt::Error;
+                    fn try_from(value: ::winrt::time::OffsetDateTime) -> ::winrt::Result<Self> {
+                        Ok(Self { universal_time: ::winrt::date_time::time_to_ticks(&value)? })
+                    }
+                }
+            }
+        },
+        "TimeSpan" => quote! {
+            ::winrt::__cfg_chrono! {
+                impl ::core::convert::From<TimeSpan> for ::winrt::chrono::Duration {
+                    fn from(value: TimeSpan) -> Self {
+                        ::winrt::date_time::ticks_to_chrono_duration(value.duration)
+                    }
+                }
+                impl ::core::convert::TryFrom<::winrt::chrono::Duration> for TimeSpan {
+                    type Error = ::winrt::Error;
+                    fn try_from(value: ::winrt::chrono::Duration) -> ::winrt::Result<Self> {
+                        Ok(Self { duration: ::winrt::date_time::chrono_duration_to_ticks(&value)? })
+                    }
+                }
+            }
+            ::winrt::__cfg_time! {
+                impl ::core::convert::From<TimeSpan> for ::winrt::time::Duration {
+                    fn from(value: TimeSpan) -> Self {
+                        ::winrt::date_time::ticks_to_time_duration(value.duration)
+                    }
+                }
+                impl ::core::convert::TryFrom<::winrt::time::Duration> for TimeSpan {
+                    type Error = ::winrt::Error;
+                    fn try_from(value: ::winrt::time::Duration) -> ::winrt::Result<Self> {
+                        Ok(Self { duration: ::winrt::date_time::time_duration_to_ticks(&value)? })
+                    }
+                }
+            }
+        },
+        _ => quote! {},
+    }
+}
@@ -0,0 +1,92 @@
+use crate::types::*;
+use crate::TypeReader;
+use proc_macro2::TokenStream;
+use quote::quote;
+
+// Provides Deferral support for the various WinRT deferral types, such as Deferral and
+// SuspendingDeferral, as well as GetDeferral support for the event args that hand them out. These
+// are not unified by a common interface in metadata so they are identified by the signatures of
+// their methods: a deferral is a type with a parameterless `Complete` method that returns
+// nothing, and event args have a parameterless `GetDeferral` method returning a deferral. Each
+// trait is implemented at most once, for the first interface with a matching method.
+pub fn deferral_tokens(
+    name: &TypeName,
+    interfaces: &[RequiredInterface],
+    deferral: &Option<TypeKind>,
+) -> TokenStream {
+    let constraints = name.constraints();
+    let type_name = name.to_tokens(&name.namespace);
+    let mut tokens = TokenStream::new();
+
+    if has_complete(interfaces) {
+        tokens.extend(quote! {
+            impl<#constraints> ::winrt::Deferral for #type_name {
+                fn complete(&self) -> ::winrt::Result<()> {
+                    #type_name::complete(self)
+                }
+            }
+        });
+    }
+
+    if let Some(deferral) = deferral {
+        let deferral = deferral.to_tokens(&name.namespace);
+
+        tokens.extend(quote! {
+            impl<#constraints> ::winrt::GetDeferral for #type_name {
+                type Deferral = #deferral;
+
+                fn get_deferral(&self) -> ::winrt::Result<Self::Deferral> {
+                    #type_name::get_deferral(self).map(::winrt::nullable::or_null)
+                }
+            }
+        });
+    }
+
+    tokens
+}
+
+/// Finds the deferral returned by a parameterless `GetDeferral` method of the interfaces, if it is
+/// a class or interface with a parameterless `Complete` method.
+pub fn find_deferral(reader: &TypeReader, interfaces: &[RequiredInterface]) -> Option<TypeKind> {
+    let method = find_method(interfaces, "GetDeferral")?;
+    let deferral = &method.return_type.as_ref()?.kind;
+
+    let mut deferral_interfaces = Vec::new();
+
+    match deferral {
+        TypeKind::Class(name) => {
+            RequiredInterface::append_default(reader, name, &mut deferral_interfaces)
+        }
+        TypeKind::Interface(name) => {
+            deferral_interfaces.push(RequiredInterface::from_type_def(reader, name.def));
+            RequiredInterface::append_required(reader, name, &mut deferral_interfaces);
+        }
+        _ => return None,
+    }
+
+    if has_complete(&deferral_interfaces) {
+        Some(deferral.clone())
+    } else {
+        None
+    }
+}
+
+fn has_complete(interfaces: &[RequiredInterface]) -> bool {
+    matches!(find_method(interfaces, "Complete"), Some(method) if method.return_type.is_none())
+}
+
+// Finds the parameterless method with the given name in metadata. A method whose generated name
+// was changed, whether renamed or to avoid a collision, isn't used, as the traits forward to the
+// method's own name.
+fn find_method<'a>(interfaces: &'a [RequiredInterface], name: &str) -> Option<&'a Method> {
+    interfaces
+        .iter()
+        .filter(|interface| interface.kind != InterfaceKind::Statics)
+        .flat_map(|interface| interface.methods.iter())
+        .find(|method| {
+            method.kind == MethodKind::Normal
+                && method.metadata_name == name
+                && method.params.is_empty()
+                && method.name == crate::case::to_snake(name, MethodKind::Normal)
+        })
+}
@@ -30,13 +30,19 @@ impl Delegate {
 
     pub fn to_tokens(&self) -> TokenStream {
         let definition = self.name.to_definition_tokens(&self.name.namespace);
-        let abi_definition = self.name.to_abi_definition_tokens(&self.name.namespace);
+        let abi_name = self.name.to_abi_tokens(&self.name.namespace);
         let name = self.name.to_tokens(&self.name.namespace);
         let phantoms = self.name.phantoms();
         let constraints = self.name.constraints();
-        let abi_method = self.method.to_abi_tokens(&self.name, &self.name.namespace);
         let guid = self.guid.to_tokens();
 
+        // The IID of a generic type is that of its definition rather than the specialization.
+        let generic = if self.name.generics.is_empty() {
+            quote! {}
+        } else {
+            quote! { const GENERIC: bool = true; }
+        };
+
         quote! {
             #[repr(transparent)]
             #[derive(Default)]
@@ -44,11 +50,13 @@ impl Delegate {
                 ptr: ::winrt::IUnknown,
                 #phantoms
             }
+            #[doc(hidden)]
             unsafe impl<#constraints> ::winrt::ComInterface for #name {
-                type VTable = #abi_definition;
+                type VTable = #abi_name;
                 const IID: ::winrt::Guid = ::winrt::Guid::from_values(#guid);
+                #generic
             }
-            impl<#constraints> ::std::clone::Clone for #name {
+            impl<#constraints> ::core::clone::Clone for #name {
                 fn clone(&self) -> Self {
                     Self {
                         ptr: self.ptr.clone(),
@@ -56,14 +64,12 @@ impl Delegate {
                     }
                 }
             }
-            #[repr(C)]
-            pub struct #abi_definition where #constraints {
-                __base: [usize; 6],
-                #abi_method
-                #phantoms
-            }
+            #[doc(hidden)]
             unsafe impl<#constraints> ::winrt::RuntimeType for #name {
                 type Abi = ::winrt::RawPtr;
+                fn select<S: ::winrt::erased::Selector>(selector: S) -> S::Output {
+                    selector.erased()
+                }
                 fn abi(&self) -> Self::Abi {
                      <::winrt::IUnknown as ::winrt::ComInterface>::as_raw(&self.ptr) as Self::Abi
                 }
@@ -73,4 +79,23 @@ impl Delegate {
             }
         }
     }
+
+    /// Generates the delegate's vtable for the `__abi` module of its namespace.
+    pub fn to_abi_tokens(&self) -> TokenStream {
+        let abi_definition = self.name.to_abi_definition_tokens();
+        let phantoms = self.name.phantoms();
+        let constraints = self.name.constraints();
+        let abi_method = self
+            .method
+            .to_abi_tokens(&self.name, &self.name.abi_namespace());
+
+        quote! {
+            #[repr(C)]
+            pub struct #abi_definition where #constraints {
+                __base: [usize; 6],
+                #abi_method
+                #phantoms
+            }
+        }
+    }
 }
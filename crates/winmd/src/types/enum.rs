@@ -65,7 +65,7 @@ impl Enum {
 
         quote! {
             #[repr(transparent)]
-            #[derive(Copy, Clone, Default, Debug, Eq, PartialEq)]
+            #[derive(Copy, Clone, Default, Debug, Eq, PartialEq, Hash)]
             pub struct #name {
                 value: #repr
             }
@@ -73,6 +73,7 @@ impl Enum {
                 #![allow(non_upper_case_globals)]
                 #(#fields)*
             }
+            #[doc(hidden)]
             unsafe impl ::winrt::RuntimeType for #name {
                 type Abi = #repr;
                 fn abi(&self) -> Self::Abi {
@@ -83,6 +84,7 @@ impl Enum {
                 }
             }
             #bitwise
+            ::winrt::__serde_enum!(#name);
         }
     }
 }
@@ -94,14 +96,14 @@ fn bitwise_operators(name: &TokenStream, value_type: &EnumConstant) -> TokenStre
     }
 
     quote! {
-        impl ::std::ops::BitOr for #name {
+        impl ::core::ops::BitOr for #name {
             type Output = Self;
 
             fn bitor(self, rhs: Self) -> Self {
                 Self { value: self.value | rhs.value }
             }
         }
-        impl ::std::ops::BitAnd for #name {
+        impl ::core::ops::BitAnd for #name {
             type Output = Self;
 
             fn bitand(self, rhs: Self) -> Self {
@@ -0,0 +1,142 @@
+use crate::types::*;
+use proc_macro2::TokenStream;
+use quote::quote;
+
+// Provides Future, Cancelable and AsyncInfo support for the four WinRT async interfaces and any classes or
+// interfaces that implement any of them. The completed handler simply wakes the waker of the most
+// recent poll so the resulting futures make no assumptions about the executor or the thread they
+// are polled on. These rely on the runtime's `std` feature.
+pub fn future_tokens(name: &TypeName, interfaces: &Vec<RequiredInterface>) -> TokenStream {
+    if name.namespace == "Windows.Foundation" {
+        if let Some(handler) = completed_handler(&name.name) {
+            let constraints = name.constraints();
+            let output = output_tokens(name);
+            let handler = handler_tokens(name, handler);
+            let name = name.to_tokens(&name.namespace);
+
+            return quote! {
+                ::winrt::__cfg_std! {
+                impl<#constraints> ::core::future::Future for #name {
+                    type Output = ::winrt::Result<#output>;
+
+                    fn poll(self: ::core::pin::Pin<&mut Self>, context: &mut ::core::task::Context) -> ::core::task::Poll<Self::Output> {
+                        if self.status()? == AsyncStatus::Started {
+                            let handler = ::winrt::nullable::or_null(self.completed()?);
+
+                            if <#handler as ::winrt::ComInterface>::is_null(&handler) {
+                                self.set_completed(::winrt::future::completed_handler::<#handler>(context.waker()))?;
+                            } else {
+                                ::winrt::future::set_waker(&handler, context.waker())?;
+                            }
+
+                            // The operation may have completed before the waker was registered.
+                            if self.status()? == AsyncStatus::Started {
+                                return ::core::task::Poll::Pending;
+                            }
+                        }
+
+                        ::core::task::Poll::Ready(self.get_results())
+                    }
+                }
+                impl<#constraints> ::winrt::Cancelable for #name {
+                    fn cancel(&self) -> ::winrt::Result<()> {
+                        #name::cancel(self)
+                    }
+                }
+                impl<#constraints> ::winrt::AsyncInfo for #name {
+                    fn status(&self) -> ::winrt::Result<::winrt::AsyncState> {
+                        Ok(::winrt::AsyncState::from_abi(::winrt::RuntimeType::abi(&#name::status(self)?)))
+                    }
+                    fn error_code(&self) -> ::winrt::Result<::winrt::ErrorCode> {
+                        Ok(::winrt::ErrorCode(#name::error_code(self)?.value))
+                    }
+                    fn id(&self) -> ::winrt::Result<u32> {
+                        #name::id(self)
+                    }
+                }
+                // The projection is just a pointer so pinning never matters, even for generics.
+                impl<#constraints> ::core::marker::Unpin for #name {}
+                }
+            };
+        }
+    }
+
+    // If the class or interface is not one of the async interfaces, we then see whether it
+    // implements any one of them and forward to that interface's Future implementation.
+    for interface in interfaces {
+        if interface.name.namespace != "Windows.Foundation"
+            || completed_handler(&interface.name.name).is_none()
+        {
+            continue;
+        }
+
+        let constraints = name.constraints();
+        let output = output_tokens(&interface.name);
+        let into = interface.name.to_tokens(&name.namespace);
+        let name = name.to_tokens(&name.namespace);
+
+        return quote! {
+            ::winrt::__cfg_std! {
+            impl<#constraints> ::core::future::Future for #name {
+                type Output = ::winrt::Result<#output>;
+
+                fn poll(self: ::core::pin::Pin<&mut Self>, context: &mut ::core::task::Context) -> ::core::task::Poll<Self::Output> {
+                    let mut operation: #into = ::core::convert::From::from(&*self);
+                    ::core::future::Future::poll(::core::pin::Pin::new(&mut operation), context)
+                }
+            }
+            impl<#constraints> ::winrt::Cancelable for #name {
+                fn cancel(&self) -> ::winrt::Result<()> {
+                    let operation: #into = ::core::convert::From::from(self);
+                    ::winrt::Cancelable::cancel(&operation)
+                }
+            }
+            impl<#constraints> ::winrt::AsyncInfo for #name {
+                fn status(&self) -> ::winrt::Result<::winrt::AsyncState> {
+                    let operation: #into = ::core::convert::From::from(self);
+                    ::winrt::AsyncInfo::status(&operation)
+                }
+                fn error_code(&self) -> ::winrt::Result<::winrt::ErrorCode> {
+                    let operation: #into = ::core::convert::From::from(self);
+                    ::winrt::AsyncInfo::error_code(&operation)
+                }
+                fn id(&self) -> ::winrt::Result<u32> {
+                    let operation: #into = ::core::convert::From::from(self);
+                    ::winrt::AsyncInfo::id(&operation)
+                }
+            }
+            }
+        };
+    }
+
+    quote! {}
+}
+
+fn completed_handler(name: &str) -> Option<&'static str> {
+    match name {
+        "IAsyncAction" => Some("AsyncActionCompletedHandler"),
+        "IAsyncOperation`1" => Some("AsyncOperationCompletedHandler"),
+        "IAsyncActionWithProgress`1" => Some("AsyncActionWithProgressCompletedHandler"),
+        "IAsyncOperationWithProgress`2" => Some("AsyncOperationWithProgressCompletedHandler"),
+        _ => None,
+    }
+}
+
+fn output_tokens(name: &TypeName) -> TokenStream {
+    if name.name.starts_with("IAsyncOperation") {
+        name.generics[0].to_tokens(&name.namespace)
+    } else {
+        quote! { () }
+    }
+}
+
+fn handler_tokens(name: &TypeName, handler: &str) -> TokenStream {
+    let handler = quote::format_ident!("{}", handler);
+
+    if name.generics.is_empty() {
+        quote! { #handler }
+    } else {
+        let generics = name.generics.iter().map(|g| g.to_tokens(&name.namespace));
+        quote! { #handler::<#(#generics),*> }
+    }
+}
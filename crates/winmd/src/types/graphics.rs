@@ -0,0 +1,222 @@
+use crate::format_ident;
+use crate::types::*;
+use proc_macro2::{Ident, Literal, TokenStream};
+use quote::quote;
+
+// Provides conversions between the Windows.Foundation Point, Size, and Rect structs and
+// Windows.UI.Color and the tuples and arrays of their fields, as well as the equivalent euclid and
+// palette types with the winrt crate's `euclid` and `palette` features. Geometry is given in
+// field order, so a Rect is `(x, y, width, height)`, while colors follow the RGBA order used by
+// graphics crates rather than Color's ARGB field order. The fields are those of the resolved
+// struct, so any renames apply. A Rect is only made from a Point and a Size if both are generated,
+// since a Rect's fields don't depend on them.
+pub fn graphics_tokens(
+    name: &TypeName,
+    fields: &[(String, TypeKind)],
+    point_and_size: bool,
+) -> TokenStream {
+    let fields: Vec<Ident> = fields.iter().map(|(name, _)| format_ident(name)).collect();
+
+    let (order, kind, interop) = match (
+        name.namespace.as_str(),
+        name.name.as_str(),
+        fields.as_slice(),
+    ) {
+        ("Windows.Foundation", "Point", [x, y]) => (
+            vec![x, y],
+            quote! { f32 },
+            quote! {
+                ::winrt::__cfg_euclid! {
+                    impl<U> ::core::convert::From<::winrt::euclid::Point2D<f32, U>> for Point {
+                        fn from(value: ::winrt::euclid::Point2D<f32, U>) -> Self {
+                            Self { #x: value.x, #y: value.y }
+                        }
+                    }
+                    impl<U> ::core::convert::From<Point> for ::winrt::euclid::Point2D<f32, U> {
+                        fn from(value: Point) -> Self {
+                            Self::new(value.#x, value.#y)
+                        }
+                    }
+                }
+            },
+        ),
+        ("Windows.Foundation", "Size", [width, height]) => (
+            vec![width, height],
+            quote! { f32 },
+            quote! {
+                ::winrt::__cfg_euclid! {
+                    impl<U> ::core::convert::From<::winrt::euclid::Size2D<f32, U>> for Size {
+                        fn from(value: ::winrt::euclid::Size2D<f32, U>) -> Self {
+                            Self { #width: value.width, #height: value.height }
+                        }
+                    }
+                    impl<U> ::core::convert::From<Size> for ::winrt::euclid::Size2D<f32, U> {
+                        fn from(value: Size) -> Self {
+                            Self::new(value.#width, value.#height)
+                        }
+                    }
+                }
+            },
+        ),
+        ("Windows.Foundation", "Rect", [x, y, width, height]) => {
+            let parts = if point_and_size {
+                quote! {
+                    impl ::core::convert::From<(Point, Size)> for Rect {
+                        fn from((origin, size): (Point, Size)) -> Self {
+                            let (x, y): (f32, f32) = origin.into();
+                            let (width, height): (f32, f32) = size.into();
+                            Self { #x: x, #y: y, #width: width, #height: height }
+                        }
+                    }
+                }
+            } else {
+                quote! {}
+            };
+
+            (
+                vec![x, y, width, height],
+                quote! { f32 },
+                quote! {
+                    #parts
+                    ::winrt::__cfg_euclid! {
+                        impl<U> ::core::convert::From<::winrt::euclid::Rect<f32, U>> for Rect {
+                            fn from(value: ::winrt::euclid::Rect<f32, U>) -> Self {
+                                Self {
+                                    #x: value.origin.x,
+                                    #y: value.origin.y,
+                                    #width: value.size.width,
+                                    #height: value.size.height,
+                                }
+                            }
+                        }
+                        impl<U> ::core::convert::From<Rect> for ::winrt::euclid::Rect<f32, U> {
+                            fn from(value: Rect) -> Self {
+                                Self::new(
+                                    ::winrt::euclid::Point2D::new(value.#x, value.#y),
+                                    ::winrt::euclid::Size2D::new(value.#width, value.#height),
+                                )
+                            }
+                        }
+                    }
+                },
+            )
+        }
+        ("Windows.UI", "Color", [a, r, g, b]) => (
+            vec![r, g, b, a],
+            quote! { u8 },
+            quote! {
+                ::winrt::__cfg_palette! {
+                    impl ::core::convert::From<::winrt::palette::Srgba<u8>> for Color {
+                        fn from(value: ::winrt::palette::Srgba<u8>) -> Self {
+                            Self {
+                                #a: value.alpha,
+                                #r: value.color.red,
+                                #g: value.color.green,
+                                #b: value.color.blue,
+                            }
+                        }
+                    }
+                    impl ::core::convert::From<Color> for ::winrt::palette::Srgba<u8> {
+                        fn from(value: Color) -> Self {
+                            Self::new(value.#r, value.#g, value.#b, value.#a)
+                        }
+                    }
+                }
+            },
+        ),
+        _ => return quote! {},
+    };
+
+    let type_name = name.to_tokens(&name.namespace);
+    let kinds = order.iter().map(|_| &kind);
+    let tuple = quote! { (#(#kinds),*) };
+    let len = order.len();
+    let indexes = 0..len;
+    let elements = (0..len).map(Literal::usize_unsuffixed);
+
+    quote! {
+        impl ::core::convert::From<#tuple> for #type_name {
+            fn from(value: #tuple) -> Self {
+                Self { #(#order: value.#elements),* }
+            }
+        }
+        impl ::core::convert::From<#type_name> for #tuple {
+            fn from(value: #type_name) -> Self {
+                (#(value.#order),*)
+            }
+        }
+        impl ::core::convert::From<[#kind; #len]> for #type_name {
+            fn from(value: [#kind; #len]) -> Self {
+                Self { #(#order: value[#indexes]),* }
+            }
+        }
+        impl ::core::convert::From<#type_name> for [#kind; #len] {
+            fn from(value: #type_name) -> Self {
+                [#(value.#order),*]
+            }
+        }
+        #interop
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tables::TypeDef;
+    use crate::{Row, TableIndex};
+
+    #[test]
+    fn renamed_fields() {
+        let name = TypeName {
+            namespace: "Windows.UI".into(),
+            name: "Color".into(),
+            generics: Vec::new(),
+            def: TypeDef(Row {
+                index: 0,
+                table_index: TableIndex::TypeDef,
+                file_index: 0,
+            }),
+        };
+
+        let fields: Vec<_> = ["Alpha", "R", "G", "B"]
+            .iter()
+            .map(|field| (field.to_string(), TypeKind::U8))
+            .collect();
+
+        let tokens = graphics_tokens(&name, &fields, false).to_string();
+        assert!(tokens.contains(
+            "Self { r#R : value . 0 , r#G : value . 1 , r#B : value . 2 , r#Alpha : value . 3 }"
+        ));
+        assert!(tokens.contains("r#Alpha : value . alpha"));
+        assert!(!tokens.contains("value . a ,"));
+    }
+
+    #[test]
+    fn rect_parts() {
+        let name = TypeName {
+            namespace: "Windows.Foundation".into(),
+            name: "Rect".into(),
+            generics: Vec::new(),
+            def: TypeDef(Row {
+                index: 0,
+                table_index: TableIndex::TypeDef,
+                file_index: 0,
+            }),
+        };
+
+        let fields: Vec<_> = ["x", "y", "width", "height"]
+            .iter()
+            .map(|field| (field.to_string(), TypeKind::F32))
+            .collect();
+
+        let parts = "From < (Point , Size) > for Rect";
+        assert!(graphics_tokens(&name, &fields, true)
+            .to_string()
+            .contains(parts));
+
+        // Without Point and Size, only the conversions of the fields themselves are generated.
+        let tokens = graphics_tokens(&name, &fields, false).to_string();
+        assert!(!tokens.contains(parts));
+        assert!(tokens.contains("From < (f32 , f32 , f32 , f32) > for r#Rect"));
+    }
+}
@@ -1,4 +1,4 @@
-use super::object::to_object_tokens;
+use super::object::{to_identity_tokens, to_object_tokens, to_try_from_object_tokens};
 use crate::tables::*;
 use crate::types::*;
 use crate::*;
@@ -10,6 +10,10 @@ use std::iter::FromIterator;
 pub struct Interface {
     pub name: TypeName,
     pub interfaces: Vec<RequiredInterface>,
+    /// The deferral handed out by the interface's `GetDeferral` method, if it has one
+    pub deferral: Option<TypeKind>,
+    /// The interface is only implemented by a single class, whose methods project it
+    pub exclusive: bool,
 }
 
 impl Interface {
@@ -24,7 +28,18 @@ impl Interface {
 
         RequiredInterface::append_required(reader, &name, &mut interfaces);
 
-        Self { name, interfaces }
+        let deferral = find_deferral(reader, &interfaces);
+        let exclusive = def.has_attribute(
+            reader,
+            ("Windows.Foundation.Metadata", "ExclusiveToAttribute"),
+        );
+
+        Self {
+            name,
+            interfaces,
+            deferral,
+            exclusive,
+        }
     }
 
     pub fn dependencies(&self) -> Vec<TypeDef> {
@@ -43,9 +58,9 @@ impl Interface {
         dependencies
     }
 
-    pub fn to_tokens(&self) -> TokenStream {
+    pub fn to_tokens(&self, options: TypeOptions) -> TokenStream {
         let definition = self.name.to_definition_tokens(&self.name.namespace);
-        let abi_definition = self.name.to_abi_definition_tokens(&self.name.namespace);
+        let abi_name = self.name.to_abi_tokens(&self.name.namespace);
         let name = self.name.to_tokens(&self.name.namespace);
         let phantoms = self.name.phantoms();
         let constraints = self.name.constraints();
@@ -57,11 +72,40 @@ impl Interface {
         }));
 
         let object = to_object_tokens(&name, &constraints);
-        let methods = to_method_tokens(&self.name.namespace, &self.interfaces);
-        let abi_methods = default_interface.to_abi_method_tokens(&default_interface.name.namespace);
+        let identity = to_identity_tokens(&name, &constraints);
+        // An interface is only held when the object implements it, so its methods needn't check
+        // the version of Windows.
+        let options = TypeOptions {
+            version_checks: None,
+            ..options
+        };
+        let methods = to_method_tokens(&self.name.namespace, &self.interfaces, options, true);
         let iterator = iterator_tokens(&self.name, &self.interfaces);
+        let future = future_tokens(&self.name, &self.interfaces);
+        let deferral = deferral_tokens(&self.name, &self.interfaces, &self.deferral);
+        let json = json_tokens(&self.name, &self.interfaces);
+        let stream = stream_tokens(&self.name, &self.interfaces);
+        let property_value = property_value_tokens(&self.name);
+        let reference = reference_tokens(&self.name);
+
+        // Exclusive interfaces, such as a class's factory and statics interfaces, are left out
+        // of the documentation in favor of the class that projects them.
+        let hidden = if self.exclusive {
+            quote! { #[doc(hidden)] }
+        } else {
+            quote! {}
+        };
+
+        // The IID of a generic type is that of its definition rather than the specialization,
+        // so such types cannot be cast from an object by IID alone.
+        let (generic, try_from_object) = if self.name.generics.is_empty() {
+            (quote! {}, to_try_from_object_tokens(&name))
+        } else {
+            (quote! { const GENERIC: bool = true; }, quote! {})
+        };
 
         quote! {
+            #hidden
             #[repr(transparent)]
             #[derive(Default)]
             pub struct #definition where #constraints {
@@ -71,11 +115,13 @@ impl Interface {
             impl<#constraints> #name {
                 #methods
             }
+            #[doc(hidden)]
             unsafe impl<#constraints> ::winrt::ComInterface for #name {
-                type VTable = #abi_definition;
+                type VTable = #abi_name;
                 const IID: ::winrt::Guid = ::winrt::Guid::from_values(#guid);
+                #generic
             }
-            impl<#constraints> ::std::clone::Clone for #name {
+            impl<#constraints> ::core::clone::Clone for #name {
                 fn clone(&self) -> Self {
                     Self {
                         ptr: self.ptr.clone(),
@@ -83,14 +129,12 @@ impl Interface {
                     }
                 }
             }
-            #[repr(C)]
-            pub struct #abi_definition where #constraints {
-                __base: [usize; 6],
-                #abi_methods
-                #phantoms
-            }
+            #[doc(hidden)]
             unsafe impl<#constraints> ::winrt::RuntimeType for #name {
                 type Abi = ::winrt::RawComPtr<Self>;
+                fn select<S: ::winrt::erased::Selector>(selector: S) -> S::Output {
+                    selector.erased()
+                }
                 fn abi(&self) -> Self::Abi {
                     <::winrt::ComPtr<Self> as ::winrt::ComInterface>::as_raw(&self.ptr)
                 }
@@ -100,7 +144,33 @@ impl Interface {
             }
             #conversions
             #object
+            #try_from_object
+            #identity
             #iterator
+            #future
+            #deferral
+            #json
+            #stream
+            #property_value
+            #reference
+        }
+    }
+
+    /// Generates the interface's vtable for the `__abi` module of its namespace.
+    pub fn to_abi_tokens(&self) -> TokenStream {
+        let abi_definition = self.name.to_abi_definition_tokens();
+        let phantoms = self.name.phantoms();
+        let constraints = self.name.constraints();
+        let default_interface = &self.interfaces[0];
+        let abi_methods = default_interface.to_abi_method_tokens(&self.name.abi_namespace());
+
+        quote! {
+            #[repr(C)]
+            pub struct #abi_definition where #constraints {
+                __base: [usize; 6],
+                #abi_methods
+                #phantoms
+            }
         }
     }
 }
@@ -140,6 +210,33 @@ mod tests {
         assert!(format!("{:#?}", &t.guid) == "96369f54-8eb6-48f0-abce-c1b211e627c3");
     }
 
+    #[test]
+    fn test_hidden() {
+        let t = interface(("Windows.Foundation", "IStringable"));
+        assert!(!t.exclusive);
+
+        // The vtable is defined in the namespace's `__abi` module rather than alongside the
+        // interface.
+        let tokens = t.to_tokens(TypeOptions::default()).to_string();
+        assert!(tokens.contains("type VTable = __abi :: abi_IStringable"));
+        assert!(!tokens.contains("struct abi_IStringable"));
+        assert!(t
+            .to_abi_tokens()
+            .to_string()
+            .contains("struct abi_IStringable"));
+
+        // As are the impls that convert it to and from its ABI.
+        assert!(tokens.contains("# [doc (hidden)] unsafe impl :: winrt :: ComInterface for"));
+        assert!(tokens.contains("# [doc (hidden)] unsafe impl :: winrt :: RuntimeType for"));
+
+        let t = interface(("Windows.Foundation", "IUriRuntimeClassFactory"));
+        assert!(t.exclusive);
+        assert!(t
+            .to_tokens(TypeOptions::default())
+            .to_string()
+            .starts_with("# [doc (hidden)]"));
+    }
+
     #[test]
     fn test_async_action() {
         let t = interface(("Windows.Foundation", "IAsyncAction"));
@@ -201,4 +298,17 @@ mod tests {
         assert!(iterable.kind == InterfaceKind::NonDefault);
         assert!(iterable.name.runtime_name() == "Windows.Foundation.Collections.IIterable`1<Windows.Foundation.Collections.IKeyValuePair`2<K, V>>");
     }
+
+    #[test]
+    fn test_inherited_methods() {
+        // IObservableVector requires IVector, which in turn requires IIterable, and the methods
+        // of all three are generated on the derived interface so that it needn't be cast.
+        let t = interface(("Windows.Foundation.Collections", "IObservableVector`1"));
+        assert!(t.interfaces.len() == 3);
+
+        let tokens = t.to_tokens(TypeOptions::default()).to_string();
+        assert!(tokens.contains("pub fn vector_changed"));
+        assert!(tokens.contains("pub fn get_at"));
+        assert!(tokens.contains("pub fn first"));
+    }
 }
@@ -11,10 +11,10 @@ pub fn iterator_tokens(name: &TypeName, interfaces: &Vec<RequiredInterface>) ->
     // If the type is IIterator<T> then simply implement the Iterator trait over top.
     if name.name == "IIterator`1" && name.namespace == "Windows.Foundation.Collections" {
         return quote! {
-            impl<T: ::winrt::RuntimeType> ::std::iter::Iterator for IIterator<T> {
+            impl<T: ::winrt::RuntimeType> ::core::iter::Iterator for IIterator<T> {
                 type Item = T;
 
-                fn next(&mut self) -> ::std::option::Option<Self::Item> {
+                fn next(&mut self) -> ::core::option::Option<Self::Item> {
                     let result = self.current().ok();
 
                     if result.is_some() {
@@ -31,20 +31,86 @@ pub fn iterator_tokens(name: &TypeName, interfaces: &Vec<RequiredInterface>) ->
     // IIterator<T> returned by first() to implement the Iterator trait.
     if name.name == "IIterable`1" && name.namespace == "Windows.Foundation.Collections" {
         return quote! {
-            impl<T: ::winrt::RuntimeType> ::std::iter::IntoIterator for IIterable<T> {
+            impl<T: ::winrt::RuntimeType> ::core::iter::IntoIterator for IIterable<T> {
                 type Item = T;
                 type IntoIter = IIterator<Self::Item>;
 
                 fn into_iter(self) -> Self::IntoIter {
-                    self.first().unwrap()
+                    ::winrt::nullable::or_null(self.first().unwrap())
                 }
             }
-            impl<'a, T: ::winrt::RuntimeType> ::std::iter::IntoIterator for &'a IIterable<T> {
+            impl<'a, T: ::winrt::RuntimeType> ::core::iter::IntoIterator for &'a IIterable<T> {
                 type Item = T;
                 type IntoIter = IIterator<Self::Item>;
 
                 fn into_iter(self) -> Self::IntoIter {
-                    self.first().unwrap()
+                    ::winrt::nullable::or_null(self.first().unwrap())
+                }
+            }
+
+            impl<T: ::winrt::RuntimeType + 'static> IIterable<T> {
+                /// Copies the items into a vector, starting over with a new iterator if the
+                /// collection changes while it is being copied.
+                pub fn to_vec_snapshot(&self) -> ::winrt::Result<::winrt::__alloc::vec::Vec<T>> {
+                    ::winrt::collections::snapshot(|| {
+                        let iterator = ::winrt::nullable::or_null(self.first()?);
+                        let mut items = ::winrt::__alloc::vec::Vec::new();
+
+                        while iterator.has_current()? {
+                            items.push(iterator.current()?);
+                            iterator.move_next()?;
+                        }
+
+                        Ok(items)
+                    })
+                }
+
+                /// Iterates over the items, yielding an error of kind `CollectionChanged` if the
+                /// collection changes rather than silently ending the iteration.
+                pub fn checked_iter(&self) -> ::winrt::Result<CheckedIterator<T>> {
+                    Ok(CheckedIterator {
+                        iterator: ::winrt::nullable::or_null(self.first()?),
+                        done: false,
+                    })
+                }
+            }
+
+            /// An iterator returned by `IIterable::checked_iter`
+            pub struct CheckedIterator<T: ::winrt::RuntimeType + 'static> {
+                iterator: IIterator<T>,
+                done: bool,
+            }
+
+            impl<T: ::winrt::RuntimeType> CheckedIterator<T> {
+                fn try_next(&self) -> ::winrt::Result<::core::option::Option<T>> {
+                    if !self.iterator.has_current()? {
+                        return Ok(None);
+                    }
+
+                    let item = self.iterator.current()?;
+                    self.iterator.move_next()?;
+                    Ok(Some(item))
+                }
+            }
+
+            impl<T: ::winrt::RuntimeType> ::core::iter::Iterator for CheckedIterator<T> {
+                type Item = ::winrt::Result<T>;
+
+                fn next(&mut self) -> ::core::option::Option<Self::Item> {
+                    if self.done {
+                        return None;
+                    }
+
+                    match self.try_next() {
+                        Ok(item) => {
+                            self.done = item.is_none();
+                            item.map(Ok)
+                        }
+                        Err(error) => {
+                            self.done = true;
+                            Some(Err(::winrt::collections::checked(error)))
+                        }
+                    }
                 }
             }
         };
@@ -66,7 +132,7 @@ pub fn iterator_tokens(name: &TypeName, interfaces: &Vec<RequiredInterface>) ->
                 }
             }
 
-            impl<T: ::winrt::RuntimeType> ::std::iter::Iterator for VectorViewIterator<T> {
+            impl<T: ::winrt::RuntimeType> ::core::iter::Iterator for VectorViewIterator<T> {
                 type Item = T;
 
                 fn next(&mut self) -> Option<Self::Item> {
@@ -80,7 +146,15 @@ pub fn iterator_tokens(name: &TypeName, interfaces: &Vec<RequiredInterface>) ->
                 }
             }
 
-            impl<T: ::winrt::RuntimeType> ::std::iter::IntoIterator for IVectorView<T> {
+            impl<T: ::winrt::RuntimeType + 'static> IVectorView<T> {
+                /// Copies the items into a vector, starting over with a new iterator if the
+                /// vector changes while it is being copied.
+                pub fn to_vec_snapshot(&self) -> ::winrt::Result<::winrt::__alloc::vec::Vec<T>> {
+                    IIterable::<T>::from(self).to_vec_snapshot()
+                }
+            }
+
+            impl<T: ::winrt::RuntimeType> ::core::iter::IntoIterator for IVectorView<T> {
                 type Item = T;
                 type IntoIter = VectorViewIterator<Self::Item>;
 
@@ -88,12 +162,12 @@ pub fn iterator_tokens(name: &TypeName, interfaces: &Vec<RequiredInterface>) ->
                     VectorViewIterator::new(self)
                 }
             }
-            impl<'a, T: ::winrt::RuntimeType> ::std::iter::IntoIterator for &'a IVectorView<T> {
+            impl<'a, T: ::winrt::RuntimeType> ::core::iter::IntoIterator for &'a IVectorView<T> {
                 type Item = T;
                 type IntoIter = VectorViewIterator<Self::Item>;
 
                 fn into_iter(self) -> Self::IntoIter {
-                    VectorViewIterator::new(::std::clone::Clone::clone(self))
+                    VectorViewIterator::new(::core::clone::Clone::clone(self))
                 }
             }
         };
@@ -115,7 +189,7 @@ pub fn iterator_tokens(name: &TypeName, interfaces: &Vec<RequiredInterface>) ->
                 }
             }
 
-            impl<T: ::winrt::RuntimeType> ::std::iter::Iterator for VectorIterator<T> {
+            impl<T: ::winrt::RuntimeType> ::core::iter::Iterator for VectorIterator<T> {
                 type Item = T;
 
                 fn next(&mut self) -> Option<Self::Item> {
@@ -129,7 +203,15 @@ pub fn iterator_tokens(name: &TypeName, interfaces: &Vec<RequiredInterface>) ->
                 }
             }
 
-            impl<T: ::winrt::RuntimeType> ::std::iter::IntoIterator for IVector<T> {
+            impl<T: ::winrt::RuntimeType + 'static> IVector<T> {
+                /// Copies the items into a vector, starting over with a new iterator if the
+                /// vector changes while it is being copied.
+                pub fn to_vec_snapshot(&self) -> ::winrt::Result<::winrt::__alloc::vec::Vec<T>> {
+                    IIterable::<T>::from(self).to_vec_snapshot()
+                }
+            }
+
+            impl<T: ::winrt::RuntimeType> ::core::iter::IntoIterator for IVector<T> {
                 type Item = T;
                 type IntoIter = VectorIterator<Self::Item>;
 
@@ -137,12 +219,12 @@ pub fn iterator_tokens(name: &TypeName, interfaces: &Vec<RequiredInterface>) ->
                     VectorIterator::new(self)
                 }
             }
-            impl<'a, T: ::winrt::RuntimeType> ::std::iter::IntoIterator for &'a IVector<T> {
+            impl<'a, T: ::winrt::RuntimeType> ::core::iter::IntoIterator for &'a IVector<T> {
                 type Item = T;
                 type IntoIter = VectorIterator<Self::Item>;
 
                 fn into_iter(self) -> Self::IntoIter {
-                    VectorIterator::new(::std::clone::Clone::clone(self))
+                    VectorIterator::new(::core::clone::Clone::clone(self))
                 }
             }
         };
@@ -161,7 +243,7 @@ pub fn iterator_tokens(name: &TypeName, interfaces: &Vec<RequiredInterface>) ->
             let name = name.to_tokens(&name.namespace);
 
             return quote! {
-                impl ::std::iter::IntoIterator for #name {
+                impl ::core::iter::IntoIterator for #name {
                     type Item = #item;
                     type IntoIter = #wfc VectorViewIterator<Self::Item>;
 
@@ -169,7 +251,7 @@ pub fn iterator_tokens(name: &TypeName, interfaces: &Vec<RequiredInterface>) ->
                         #wfc VectorViewIterator::new(self.into())
                     }
                 }
-                impl<'a> ::std::iter::IntoIterator for &'a #name {
+                impl<'a> ::core::iter::IntoIterator for &'a #name {
                     type Item = #item;
                     type IntoIter = #wfc VectorViewIterator<Self::Item>;
 
@@ -188,7 +270,7 @@ pub fn iterator_tokens(name: &TypeName, interfaces: &Vec<RequiredInterface>) ->
             let name = name.to_tokens(&name.namespace);
 
             return quote! {
-                impl ::std::iter::IntoIterator for #name {
+                impl ::core::iter::IntoIterator for #name {
                     type Item = #item;
                     type IntoIter = #wfc VectorIterator<Self::Item>;
 
@@ -196,7 +278,7 @@ pub fn iterator_tokens(name: &TypeName, interfaces: &Vec<RequiredInterface>) ->
                         #wfc VectorIterator::new(self.into())
                     }
                 }
-                impl<'a> ::std::iter::IntoIterator for &'a #name {
+                impl<'a> ::core::iter::IntoIterator for &'a #name {
                     type Item = #item;
                     type IntoIter = #wfc VectorIterator<Self::Item>;
 
@@ -223,20 +305,20 @@ pub fn iterator_tokens(name: &TypeName, interfaces: &Vec<RequiredInterface>) ->
             let name = name.to_tokens(&name.namespace);
 
             quote! {
-               impl<#constraints> ::std::iter::IntoIterator for #name {
+               impl<#constraints> ::core::iter::IntoIterator for #name {
                     type Item = #item;
                     type IntoIter = #wfc IIterator<Self::Item>;
 
                     fn into_iter(self) -> Self::IntoIter {
-                        self.first().unwrap()
+                        ::winrt::nullable::or_null(self.first().unwrap())
                     }
                 }
-                impl<'a, #constraints> ::std::iter::IntoIterator for &'a #name {
+                impl<'a, #constraints> ::core::iter::IntoIterator for &'a #name {
                     type Item = #item;
                     type IntoIter = #wfc IIterator<Self::Item>;
 
                     fn into_iter(self) -> Self::IntoIter {
-                        self.first().unwrap()
+                        ::winrt::nullable::or_null(self.first().unwrap())
                     }
                 }
             }
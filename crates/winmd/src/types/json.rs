@@ -0,0 +1,137 @@
+use crate::types::*;
+use proc_macro2::TokenStream;
+use quote::quote;
+
+// Provides conversions between the Windows.Data.Json types and serde_json::Value. These are only
+// available with the winrt crate's `serde_json` feature so the tokens are wrapped in a macro that
+// expands to nothing when the feature is disabled. Values are converted recursively: IJsonValue
+// provides `to_serde_json`, which is forwarded by the classes that implement it, while JsonValue
+// provides `from_serde_json` as it has the factory methods for the scalar values.
+pub fn json_tokens(name: &TypeName, interfaces: &[RequiredInterface]) -> TokenStream {
+    if name.namespace != "Windows.Data.Json" {
+        return quote! {};
+    }
+
+    if name.name == "IJsonValue" {
+        return quote! {
+            ::winrt::__cfg_serde_json! {
+                impl IJsonValue {
+                    /// Converts the value, and any values it contains, to a `serde_json::Value`.
+                    pub fn to_serde_json(&self) -> ::winrt::Result<::winrt::serde_json::Value> {
+                        use ::winrt::serde_json::Value;
+
+                        let value_type = self.value_type()?;
+
+                        if value_type == JsonValueType::Boolean {
+                            Ok(Value::Bool(self.get_boolean()?))
+                        } else if value_type == JsonValueType::Number {
+                            let number = self.get_number()?;
+
+                            // WinRT stores all numbers as doubles so integral values are restored
+                            // as integers when they can be represented exactly.
+                            if number.fract() == 0.0 && number.abs() < 9_007_199_254_740_992.0 {
+                                Ok(Value::from(number as i64))
+                            } else {
+                                Ok(Value::from(number))
+                            }
+                        } else if value_type == JsonValueType::String {
+                            Ok(Value::String(self.get_string()?.into()))
+                        } else if value_type == JsonValueType::Array {
+                            let array = ::winrt::nullable::or_null(self.get_array()?);
+                            let mut values = ::winrt::__alloc::vec::Vec::new();
+
+                            for index in 0..array.size()? {
+                                values.push(::winrt::nullable::or_null(array.get_at(index)?).to_serde_json()?);
+                            }
+
+                            Ok(Value::Array(values))
+                        } else if value_type == JsonValueType::Object {
+                            let object = ::winrt::nullable::or_null(self.get_object()?);
+                            let iterator = ::winrt::nullable::or_null(object.first()?);
+                            let mut values = ::winrt::serde_json::Map::new();
+
+                            while iterator.has_current()? {
+                                let pair = iterator.current()?;
+                                values.insert(pair.key()?.into(), pair.value()?.to_serde_json()?);
+                                iterator.move_next()?;
+                            }
+
+                            Ok(Value::Object(values))
+                        } else {
+                            Ok(Value::Null)
+                        }
+                    }
+                }
+            }
+        };
+    }
+
+    let from = if name.name == "JsonValue" {
+        quote! {
+            /// Converts a `serde_json::Value`, and any values it contains, to an `IJsonValue`.
+            pub fn from_serde_json(value: &::winrt::serde_json::Value) -> ::winrt::Result<IJsonValue> {
+                use ::winrt::serde_json::Value;
+
+                match value {
+                    Value::Null => Ok(::winrt::nullable::or_null(Self::create_null_value()?).into()),
+                    Value::Bool(value) => Ok(::winrt::nullable::or_null(Self::create_boolean_value(*value)?).into()),
+                    Value::Number(value) => {
+                        let value = value.as_f64().unwrap_or(::core::f64::NAN);
+                        Ok(::winrt::nullable::or_null(Self::create_number_value(value)?).into())
+                    }
+                    Value::String(value) => Ok(::winrt::nullable::or_null(Self::create_string_value(value.as_str())?).into()),
+                    Value::Array(values) => {
+                        let array = JsonArray::new()?;
+
+                        for value in values {
+                            array.append(Self::from_serde_json(value)?)?;
+                        }
+
+                        Ok(array.into())
+                    }
+                    Value::Object(values) => {
+                        let object = JsonObject::new()?;
+
+                        for (key, value) in values {
+                            object.insert(key.as_str(), Self::from_serde_json(value)?)?;
+                        }
+
+                        Ok(object.into())
+                    }
+                }
+            }
+        }
+    } else {
+        quote! {}
+    };
+
+    let implements = interfaces.iter().any(|interface| {
+        interface.name.namespace == "Windows.Data.Json" && interface.name.name == "IJsonValue"
+    });
+
+    let to = if implements {
+        quote! {
+            /// Converts the value, and any values it contains, to a `serde_json::Value`.
+            pub fn to_serde_json(&self) -> ::winrt::Result<::winrt::serde_json::Value> {
+                ::core::convert::Into::<IJsonValue>::into(self).to_serde_json()
+            }
+        }
+    } else {
+        quote! {}
+    };
+
+    if from.is_empty() && to.is_empty() {
+        return quote! {};
+    }
+
+    let name = name.to_tokens(&name.namespace);
+
+    quote! {
+        ::winrt::__cfg_serde_json! {
+            impl #name {
+                #from
+                #to
+            }
+        }
+    }
+}
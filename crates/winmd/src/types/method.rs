@@ -1,7 +1,7 @@
 use crate::case::to_snake;
 use crate::tables::{AttributeArg, MethodDef, TypeDef};
 use crate::types::TypeName;
-use crate::types::{Param, RequiredInterface, TypeKind};
+use crate::types::{InterfaceKind, Param, RequiredInterface, TypeKind};
 use crate::TypeReader;
 use crate::*;
 use proc_macro2::TokenStream;
@@ -11,6 +11,10 @@ use std::iter::FromIterator;
 #[derive(Debug)]
 pub struct Method {
     pub name: String,
+    /// The name in metadata, which for accessors is that of the property or event
+    pub metadata_name: String,
+    /// Another name the method is generated under, given by the renames applied to its type
+    pub alias: Option<String>,
     pub kind: MethodKind,
     pub params: Vec<Param>,
     pub return_type: Option<Param>,
@@ -31,28 +35,27 @@ impl Method {
         method: MethodDef,
         generics: &Vec<TypeKind>,
     ) -> Method {
-        let (name, kind) = if method.flags(reader).special() {
+        let (metadata_name, kind) = if method.flags(reader).special() {
             let name = method.name(reader);
 
             if name.starts_with("get") {
-                (case::to_snake(&name[4..], MethodKind::Get), MethodKind::Get)
+                (name[4..].to_owned(), MethodKind::Get)
             } else if name.starts_with("put") {
-                (case::to_snake(&name[4..], MethodKind::Set), MethodKind::Set)
+                (name[4..].to_owned(), MethodKind::Set)
             } else if name.starts_with("add") {
-                (case::to_snake(&name[4..], MethodKind::Add), MethodKind::Add)
+                (name[4..].to_owned(), MethodKind::Add)
             } else if name.starts_with("remove") {
-                (
-                    case::to_snake(&name[7..], MethodKind::Remove),
-                    MethodKind::Remove,
-                )
+                (name[7..].to_owned(), MethodKind::Remove)
             } else {
                 // A delegate's 'Invoke' method is "special" but lacks a preamble.
-                ("invoke".to_owned(), MethodKind::Normal)
+                ("Invoke".to_owned(), MethodKind::Normal)
             }
         } else {
             (Method::name(reader, method), MethodKind::Normal)
         };
 
+        let name = case::to_snake(&metadata_name, kind);
+
         let mut blob = method.sig(reader);
 
         if blob.read_unsigned() & 0x10 != 0 {
@@ -71,12 +74,14 @@ impl Method {
             let kind = TypeKind::from_blob(&mut blob, generics);
             let input = false;
             let by_ref = true;
+            let large = !array && kind.is_large(reader);
             Some(Param {
                 name,
                 kind,
                 array,
                 input,
                 by_ref,
+                large,
             })
         };
 
@@ -91,6 +96,7 @@ impl Method {
                 let by_ref = blob.read_expected(0x10);
                 let array = blob.peek_unsigned().0 == 0x1D;
                 let kind = TypeKind::from_blob(&mut blob, generics);
+                let large = !array && kind.is_large(reader);
 
                 params.push(Param {
                     name,
@@ -98,12 +104,15 @@ impl Method {
                     array,
                     input,
                     by_ref,
+                    large,
                 });
             }
         }
 
         Method {
             name,
+            metadata_name,
+            alias: None,
             kind,
             params,
             return_type,
@@ -124,14 +133,22 @@ impl Method {
         {
             for (_, arg) in attribute.args(reader) {
                 if let AttributeArg::String(name) = arg {
-                    return case::to_snake(&name, MethodKind::Normal);
+                    return name;
                 }
             }
         }
 
-        case::to_snake(method.name(reader), MethodKind::Normal)
+        method.name(reader).to_owned()
     }
 
+    /// Generates the method's vtable entry
+    ///
+    /// Every method returns an `HRESULT`, with its result written through a trailing out pointer,
+    /// so no struct is ever returned by value across the ABI. This keeps large structs such as
+    /// `Matrix4x4` correct on x86, where a struct returned by value would go through a hidden
+    /// pointer whose placement the two sides would have to agree on. Structs are passed in by
+    /// value as the ABI requires, and `extern "system"` is `stdcall` on x86, where the callee
+    /// pops its arguments as COM expects.
     pub fn to_abi_tokens(&self, self_name: &TypeName, calling_namespace: &str) -> TokenStream {
         let abi_name = self_name.to_abi_tokens(calling_namespace);
         let name = format_ident(&self.name);
@@ -143,10 +160,27 @@ impl Method {
         );
 
         quote! {
-            pub #name: extern "system" fn(*const *const #abi_name, #params) -> ::winrt::ErrorCode,
+            pub #name: unsafe extern "system" fn(*const *const #abi_name, #params) -> ::winrt::ErrorCode,
         }
     }
 
+    /// The attributes that the options add to the generated method
+    pub fn to_attribute_tokens(&self, options: TypeOptions) -> TokenStream {
+        let inline = if options.inline && matches!(self.kind, MethodKind::Get | MethodKind::Set) {
+            quote! { #[inline] }
+        } else {
+            quote! {}
+        };
+
+        let must_use = if options.must_use {
+            quote! { #[must_use] }
+        } else {
+            quote! {}
+        };
+
+        quote! { #inline #must_use }
+    }
+
     fn to_param_tokens(&self, calling_namespace: &str) -> TokenStream {
         TokenStream::from_iter(
             self.params
@@ -186,7 +220,8 @@ impl Method {
                 | TypeKind::Generic(_) => {
                     let name = quote::format_ident!("__{}", position);
                     let into = param.kind.to_tokens(calling_namespace);
-                    tokens.push(quote! { #name: ::std::convert::Into<::winrt::Param<'a, #into>>, });
+                    tokens
+                        .push(quote! { #name: ::core::convert::Into<::winrt::Param<'a, #into>>, });
                 }
                 _ => {}
             };
@@ -199,50 +234,189 @@ impl Method {
         TokenStream::from_iter(tokens)
     }
 
-    pub fn to_default_tokens(&self, calling_namespace: &str) -> TokenStream {
+    pub fn to_default_tokens(
+        &self,
+        calling_namespace: &str,
+        version: &TokenStream,
+        nullable: bool,
+        agile: bool,
+    ) -> TokenStream {
         let method_name = format_ident(&self.name);
         let params = self.to_param_tokens(calling_namespace);
         let constraints = self.to_constraint_tokens(calling_namespace);
+        let return_type = self.to_return_type_tokens(calling_namespace, nullable);
+        let body = self.to_default_body_tokens(calling_namespace, nullable);
+        let body = self.to_checked_tokens(body, agile);
+        let into = self.to_default_into_tokens(calling_namespace, version);
+
+        quote! {
+            pub fn #method_name<#constraints>(&self, #params) -> ::winrt::Result<#return_type> {
+                #version
+                #body
+            }
+            #into
+        }
+    }
+
+    /// Generates a method of a generic interface that forwards to the interface's erased
+    /// specialization when all of the interface's type arguments are erasable. The type arguments
+    /// select the body, so only one of the two is compiled for each instantiation.
+    pub fn to_erased_tokens(
+        &self,
+        calling_namespace: &str,
+        interface: &TypeName,
+        version: &TokenStream,
+        nullable: bool,
+        agile: bool,
+    ) -> TokenStream {
+        let method_name = format_ident(&self.name);
+        let params = self.to_param_tokens(calling_namespace);
+        let constraints = self.to_constraint_tokens(calling_namespace);
+        let return_type = self.to_return_type_tokens(calling_namespace, nullable);
+        let body = self.to_default_body_tokens(calling_namespace, nullable);
+        let erased = interface.to_erased_tokens(calling_namespace);
+
+        let generics = interface
+            .generics
+            .iter()
+            .map(|g| g.to_tokens(calling_namespace));
+
+        // The arguments are passed through to whichever body is selected, since both can't take
+        // ownership of them.
+        let names: Vec<_> = self
+            .params
+            .iter()
+            .map(|param| format_ident(&param.name))
+            .collect();
+
+        let (locals, args): (Vec<_>, Vec<_>) = self
+            .params
+            .iter()
+            .enumerate()
+            .map(|(position, param)| param.to_erased_arg_tokens(calling_namespace, position))
+            .unzip();
+
+        let call = if let Some(return_type) = &self.return_type {
+            let from = return_type.to_erased_return_tokens(calling_namespace);
+            let into = return_type.to_return_tokens(calling_namespace);
+
+            if nullable && return_type.is_nullable() {
+                quote! {
+                    this.#method_name(#(#args)*).map(|value| value.map(|value| ::winrt::erased::restore::<#from, #into>(value)))
+                }
+            } else {
+                quote! {
+                    this.#method_name(#(#args)*).map(|value| ::winrt::erased::restore::<#from, #into>(value))
+                }
+            }
+        } else {
+            quote! { this.#method_name(#(#args)*) }
+        };
+
+        // Plain data structs are passed as is so the erased specialization isn't needed.
+        let into = self.to_default_into_tokens(calling_namespace, version);
+
+        let body = self.to_checked_tokens(
+            quote! {
+                <(#(#generics,)*) as ::winrt::erased::TypeArgs>::select(
+                    (#(#names,)*),
+                    |(#(#names,)*)| unsafe {
+                        let this = ::winrt::erased::erase::<Self, #erased>(self);
+                        #(#locals)*
+                        #call
+                    },
+                    |(#(#names,)*)| { #body },
+                )
+            },
+            agile,
+        );
+
+        quote! {
+            pub fn #method_name<#constraints>(&self, #params) -> ::winrt::Result<#return_type> {
+                #version
+                #body
+            }
+            #into
+        }
+    }
+
+    fn to_return_type_tokens(&self, calling_namespace: &str, nullable: bool) -> TokenStream {
+        if let Some(return_type) = &self.return_type {
+            let tokens = return_type.to_return_tokens(calling_namespace);
+
+            if nullable && return_type.is_nullable() {
+                quote! { ::core::option::Option<#tokens> }
+            } else {
+                tokens
+            }
+        } else {
+            quote! { () }
+        }
+    }
+
+    fn to_default_body_tokens(&self, calling_namespace: &str, nullable: bool) -> TokenStream {
+        let method_name = format_ident(&self.name);
         let args = self.to_abi_arg_tokens();
 
         if let Some(return_type) = &self.return_type {
             let return_arg = return_type.to_abi_return_arg_tokens(calling_namespace);
+
+            // Null is detected at the ABI boundary so that it can't be used by mistake.
+            let result = if nullable && return_type.is_nullable() {
+                quote! { ::winrt::nullable::from_abi(__ok) }
+            } else {
+                quote! { __ok }
+            };
+
+            let zeroed = if return_type.array {
+                quote! { ::core::default::Default::default() }
+            } else {
+                quote! { ::winrt::RuntimeType::zeroed() }
+            };
+
             let return_type = return_type.to_return_tokens(calling_namespace);
 
             quote! {
-                pub fn #method_name<#constraints>(&self, #params) -> ::winrt::Result<#return_type> {
-                    let this = <::winrt::ComPtr<Self> as ::winrt::ComInterface>::as_raw(&self.ptr);
-
-                    if this.is_null() {
-                        panic!("The `this` pointer was null when calling method");
-                    }
-                    unsafe {
-                        let mut __ok: #return_type = ::std::mem::zeroed();
-                        ((*(*(this))).#method_name)(this, #args #return_arg)
-                            .and_then(|| __ok )
-                    }
+                let (this, vtable) = self.ptr.vtable();
+                let mut __ok: #return_type = #zeroed;
+                unsafe {
+                    (vtable.#method_name)(this, #args #return_arg).and_then(|| #result)
                 }
             }
         } else {
             quote! {
-                pub fn #method_name<#constraints>(&self, #params) -> ::winrt::Result<()> {
-                    let this = <::winrt::ComPtr<Self> as ::winrt::ComInterface>::as_raw(&self.ptr);
-
-                    if this.is_null() {
-                        panic!("The `this` pointer was null when calling method");
-                    }
-                    unsafe {
-                        ((*(*(this))).#method_name)(this, #args).ok()
-                    }
+                let (this, vtable) = self.ptr.vtable();
+                unsafe {
+                    (vtable.#method_name)(this, #args).ok()
                 }
             }
         }
     }
 
+    fn to_default_into_tokens(
+        &self,
+        calling_namespace: &str,
+        version: &TokenStream,
+    ) -> TokenStream {
+        let method_name = format_ident(&self.name);
+
+        self.to_into_tokens(calling_namespace, quote! { &self, }, version, |_| {
+            quote! {
+                let (this, vtable) = self.ptr.vtable();
+                unsafe {
+                    (vtable.#method_name)(this, ::winrt::RuntimeType::set_abi(value)).ok()
+                }
+            }
+        })
+    }
+
     pub fn to_non_default_tokens(
         &self,
         calling_namespace: &str,
         interface: &RequiredInterface,
+        version: &TokenStream,
+        nullable: bool,
+        agile: bool,
     ) -> TokenStream {
         let method_name = format_ident(&self.name);
         let params = self.to_param_tokens(calling_namespace);
@@ -250,16 +424,54 @@ impl Method {
         let args = self.to_arg_tokens();
         let interface = interface.name.to_tokens(calling_namespace);
 
-        let return_type = if let Some(return_type) = &self.return_type {
-            return_type.to_return_tokens(calling_namespace)
-        } else {
-            quote! { () }
+        let return_type = self.to_return_type_tokens(calling_namespace, nullable);
+
+        // The interface's definition may return a generic type argument, which is not wrapped.
+        let result = match &self.return_type {
+            Some(return_type) if nullable && return_type.is_nullable() => {
+                quote! { .map(::winrt::nullable::Nullable::into_option) }
+            }
+            _ => quote! {},
         };
 
+        let into = self.to_into_tokens(calling_namespace, quote! { &self, }, version, |into_name| {
+            quote! { <#interface as ::core::convert::From<&Self>>::from(self).#into_name(value) }
+        });
+
+        let body = self.to_checked_tokens(
+            quote! {
+                <#interface as ::core::convert::From<&Self>>::from(self).#method_name(#args)#result
+            },
+            agile,
+        );
+
         quote! {
             pub fn #method_name<#constraints>(&self, #params) -> ::winrt::Result<#return_type> {
-                <#interface as ::std::convert::From<&Self>>::from(self).#method_name(#args)
+                #version
+                #body
             }
+            #into
+        }
+    }
+
+    // The methods of classes that aren't agile assert, in debug builds, that they are called from
+    // the apartment that created the object, and otherwise describe calls from the wrong
+    // apartment in the error they return. Getters that write into the caller's storage are left
+    // unchecked.
+    fn to_checked_tokens(&self, body: TokenStream, agile: bool) -> TokenStream {
+        if agile {
+            return body;
+        }
+
+        let name = &self.name;
+
+        quote! {
+            ::core::debug_assert!(
+                ::winrt::apartment::is_home(self),
+                "{}",
+                ::winrt::apartment::wrong_apartment(<Self as ::winrt::RuntimeName>::NAME, #name)
+            );
+            ::winrt::apartment::check(<Self as ::winrt::RuntimeName>::NAME, #name, { #body })
         }
     }
 
@@ -267,6 +479,8 @@ impl Method {
         &self,
         calling_namespace: &str,
         interface: &RequiredInterface,
+        version: &TokenStream,
+        nullable: bool,
     ) -> TokenStream {
         let method_name = format_ident(&self.name);
         let params = self.to_param_tokens(calling_namespace);
@@ -274,16 +488,139 @@ impl Method {
         let args = self.to_arg_tokens();
         let interface = interface.name.to_tokens(calling_namespace);
 
-        let return_type = if let Some(return_type) = &self.return_type {
-            return_type.to_return_tokens(calling_namespace)
-        } else {
-            quote! { () }
-        };
+        let return_type = self.to_return_type_tokens(calling_namespace, nullable);
+
+        let into = self.to_into_tokens(calling_namespace, quote! {}, version, |into_name| {
+            quote! { ::winrt::activation::factory::<Self, #interface>()?.#into_name(value) }
+        });
 
         quote! {
             pub fn #method_name<#constraints>(#params) -> ::winrt::Result<#return_type> {
+                #version
                 ::winrt::activation::factory::<Self, #interface>()?.#method_name(#args)
             }
+            #into
+        }
+    }
+
+    /// Generates the method under another name when the type already has a method with its own
+    /// name, forwarding to the method of the interface that declares it
+    pub fn to_disambiguated_tokens(
+        &self,
+        calling_namespace: &str,
+        interface: &RequiredInterface,
+        name: &str,
+        version: &TokenStream,
+        nullable: bool,
+        agile: bool,
+    ) -> TokenStream {
+        let doc = format!(
+            "Calls `{}::{}`, which is generated as `{}` because another interface of this type has a method named `{}`.",
+            interface.name.name.split('`').next().unwrap(),
+            self.metadata_name,
+            name,
+            self.name
+        );
+
+        let name = format_ident(name);
+        let method_name = format_ident(&self.name);
+        let params = self.to_param_tokens(calling_namespace);
+        let constraints = self.to_constraint_tokens(calling_namespace);
+        let args = self.to_arg_tokens();
+        let return_type = self.to_return_type_tokens(calling_namespace, nullable);
+        let statics = interface.kind == InterfaceKind::Statics;
+        let interface = interface.name.to_tokens(calling_namespace);
+
+        if statics {
+            return quote! {
+                #[doc = #doc]
+                pub fn #name<#constraints>(#params) -> ::winrt::Result<#return_type> {
+                    #version
+                    ::winrt::activation::factory::<Self, #interface>()?.#method_name(#args)
+                }
+            };
+        }
+
+        // The interface's definition may return a generic type argument, which is not wrapped.
+        let result = match &self.return_type {
+            Some(return_type) if nullable && return_type.is_nullable() => {
+                quote! { .map(::winrt::nullable::Nullable::into_option) }
+            }
+            _ => quote! {},
+        };
+
+        let body = self.to_checked_tokens(
+            quote! {
+                <#interface as ::core::convert::From<&Self>>::from(self).#method_name(#args)#result
+            },
+            agile,
+        );
+
+        quote! {
+            #[doc = #doc]
+            pub fn #name<#constraints>(&self, #params) -> ::winrt::Result<#return_type> {
+                #version
+                #body
+            }
+        }
+    }
+
+    /// Generates the method under its alias, forwarding to the method's own name
+    pub fn to_alias_tokens(
+        &self,
+        calling_namespace: &str,
+        alias: &str,
+        statics: bool,
+        nullable: bool,
+    ) -> TokenStream {
+        let alias = format_ident(alias);
+        let method_name = format_ident(&self.name);
+        let params = self.to_param_tokens(calling_namespace);
+        let constraints = self.to_constraint_tokens(calling_namespace);
+        let args = self.to_arg_tokens();
+        let return_type = self.to_return_type_tokens(calling_namespace, nullable);
+
+        if statics {
+            quote! {
+                #[allow(non_snake_case)]
+                pub fn #alias<#constraints>(#params) -> ::winrt::Result<#return_type> {
+                    Self::#method_name(#args)
+                }
+            }
+        } else {
+            quote! {
+                #[allow(non_snake_case)]
+                pub fn #alias<#constraints>(&self, #params) -> ::winrt::Result<#return_type> {
+                    self.#method_name(#args)
+                }
+            }
+        }
+    }
+
+    /// Generates a variant of a getter returning a large struct that writes the value directly
+    /// into the caller's storage rather than returning a copy, given the body that forwards to
+    /// the interface's variant.
+    fn to_into_tokens<F: FnOnce(&proc_macro2::Ident) -> TokenStream>(
+        &self,
+        calling_namespace: &str,
+        receiver: TokenStream,
+        version: &TokenStream,
+        body: F,
+    ) -> TokenStream {
+        let return_type = match &self.return_type {
+            Some(return_type) if self.kind == MethodKind::Get && return_type.large => return_type,
+            _ => return quote! {},
+        };
+
+        let into_name = format_ident(&format!("{}_into", self.name));
+        let return_type = return_type.to_return_tokens(calling_namespace);
+        let body = body(&into_name);
+
+        quote! {
+            pub fn #into_name(#receiver value: &mut #return_type) -> ::winrt::Result<()> {
+                #version
+                #body
+            }
         }
     }
 }
@@ -292,6 +629,7 @@ impl Method {
 mod tests {
     use super::*;
     use crate::types::*;
+    use proc_macro2::{Delimiter, TokenTree};
 
     fn method((namespace, type_name): (&str, &str), method_name: &str) -> Method {
         let reader = &TypeReader::from_os();
@@ -361,6 +699,49 @@ mod tests {
         assert!(token.runtime_name() == "Windows.Foundation.EventRegistrationToken");
     }
 
+    #[test]
+    fn test_large_struct() {
+        let matrix = method(("Windows.UI.Composition", "IVisual"), "transform_matrix");
+        assert!(matrix.kind == MethodKind::Get);
+        assert!(matrix.return_type.as_ref().unwrap().large);
+
+        let offset = method(("Windows.UI.Composition", "IVisual"), "offset");
+        assert!(!offset.return_type.as_ref().unwrap().large);
+
+        let token = method(
+            ("Windows.Foundation.Collections", "IObservableMap`2"),
+            "map_changed",
+        );
+        assert!(!token.return_type.as_ref().unwrap().large);
+    }
+
+    #[test]
+    fn test_large_struct_abi() {
+        let reader = &TypeReader::from_os();
+        let name = TypeName::from_type_def(
+            reader,
+            reader.resolve_type_def(("Windows.UI.Composition", "IVisual")),
+        );
+
+        // The getter's result is written through an explicit out pointer rather than returned.
+        let getter = method(("Windows.UI.Composition", "IVisual"), "transform_matrix")
+            .to_abi_tokens(&name, "Windows.UI.Composition")
+            .to_string();
+        assert!(getter.contains("unsafe extern \"system\" fn"));
+        assert!(getter.contains(", * mut "));
+        assert!(getter.ends_with("Matrix4x4 ,) -> :: winrt :: ErrorCode ,"));
+
+        // The setter passes the struct by value.
+        let setter = method(
+            ("Windows.UI.Composition", "IVisual"),
+            "set_transform_matrix",
+        )
+        .to_abi_tokens(&name, "Windows.UI.Composition")
+        .to_string();
+        assert!(!setter.contains(", * mut "));
+        assert!(setter.ends_with("Matrix4x4 ,) -> :: winrt :: ErrorCode ,"));
+    }
+
     #[test]
     fn test_remove_map_changed() {
         let method = method(
@@ -383,4 +764,147 @@ mod tests {
 
         assert!(token.runtime_name() == "Windows.Foundation.EventRegistrationToken");
     }
+
+    #[test]
+    fn test_attributes() {
+        let options = TypeOptions {
+            inline: true,
+            must_use: true,
+            ..TypeOptions::default()
+        };
+
+        let getter = method(("Windows.Foundation", "IUriRuntimeClass"), "domain");
+        assert!(getter.to_attribute_tokens(options).to_string() == "# [inline] # [must_use]");
+        assert!(getter
+            .to_attribute_tokens(TypeOptions::default())
+            .is_empty());
+
+        let method = method(("Windows.Foundation", "IStringable"), "to_string");
+        assert!(method.to_attribute_tokens(options).to_string() == "# [must_use]");
+    }
+
+    // Audits the vtable entry of every method of the interfaces and delegates in a few
+    // namespaces that between them use each kind of parameter. The entries must follow the
+    // ABI that the other side was compiled against on every target, including x86, so each
+    // parameter's position and shape is checked against its metadata.
+    #[test]
+    fn test_abi_signatures() {
+        let reader = &TypeReader::from_os();
+        let mut limits = crate::TypeLimits::default();
+        limits.insert(reader, "windows.foundation.collections");
+        limits.insert(reader, "windows.storage.streams");
+        limits.insert(reader, "windows.ui.composition");
+        let stage = crate::TypeStage::from_limits(reader, &limits);
+        let mut count = 0;
+
+        for t in stage.0.values() {
+            let methods: Vec<(&TypeName, &Method)> = match t {
+                Type::Interface(t) => t.interfaces[0]
+                    .methods
+                    .iter()
+                    .map(|method| (&t.name, method))
+                    .collect(),
+                Type::Delegate(t) => vec![(&t.name, &t.method)],
+                _ => continue,
+            };
+
+            for (name, method) in methods {
+                audit_abi_signature(name, method);
+                count += 1;
+            }
+        }
+
+        assert!(count > 500);
+    }
+
+    fn audit_abi_signature(name: &TypeName, method: &Method) {
+        let tokens = method.to_abi_tokens(name, &name.namespace);
+        let signature = tokens.to_string();
+        let context = format!("{}.{}: {}", name.runtime_name(), method.name, signature);
+
+        assert!(
+            signature.contains("unsafe extern \"system\" fn"),
+            "{}",
+            context
+        );
+        assert!(
+            signature.ends_with("-> :: winrt :: ErrorCode ,"),
+            "{}",
+            context
+        );
+
+        let params = tokens
+            .into_iter()
+            .find_map(|token| match token {
+                TokenTree::Group(group) if group.delimiter() == Delimiter::Parenthesis => {
+                    Some(split_params(group.stream()))
+                }
+                _ => None,
+            })
+            .unwrap();
+        let mut params = params.iter().map(String::as_str);
+
+        assert!(
+            params.next().unwrap().starts_with("* const * const"),
+            "{}",
+            context
+        );
+
+        // The return value, if any, is always the last parameter.
+        for param in method.params.iter().chain(method.return_type.iter()) {
+            let mut next = || params.next().unwrap_or_else(|| panic!("{}", context));
+
+            if param.array {
+                let (len, data) = (next(), next());
+
+                if param.input {
+                    assert!(len == "u32" && data.starts_with("* const"), "{}", context);
+                } else if param.by_ref {
+                    assert!(
+                        len == "* mut u32" && data.starts_with("* mut * mut"),
+                        "{}",
+                        context
+                    );
+                } else {
+                    assert!(len == "u32" && data.starts_with("* mut"), "{}", context);
+                }
+            } else if param.input {
+                assert!(!next().starts_with("* mut"), "{}", context);
+            } else {
+                assert!(next().starts_with("* mut"), "{}", context);
+            }
+        }
+
+        assert!(params.next().is_none(), "{}", context);
+    }
+
+    // Splits a parameter list at its top-level commas, which aren't those within the angle
+    // brackets of a generic type.
+    fn split_params(stream: TokenStream) -> Vec<String> {
+        let mut params = vec![String::new()];
+        let mut depth = 0;
+
+        for token in stream {
+            match &token {
+                TokenTree::Punct(punct) if punct.as_char() == '<' => depth += 1,
+                TokenTree::Punct(punct) if punct.as_char() == '>' && depth > 0 => depth -= 1,
+                TokenTree::Punct(punct) if punct.as_char() == ',' && depth == 0 => {
+                    params.push(String::new());
+                    continue;
+                }
+                _ => {}
+            }
+
+            let param = params.last_mut().unwrap();
+
+            if !param.is_empty() {
+                param.push(' ');
+            }
+
+            param.push_str(&token.to_string());
+        }
+
+        params.retain(|param| !param.is_empty());
+        params
+    }
 }
@@ -1,32 +1,62 @@
+mod bitmap;
+mod builder;
 mod class;
+mod data;
+mod date_time;
+mod deferral;
 mod delegate;
 mod r#enum;
+mod future;
+mod graphics;
 mod interface;
 mod iterator;
+mod json;
 mod method;
 mod namespace;
+mod numerics;
 mod object;
 mod param;
+mod property_value;
+mod reference;
 mod required_interface;
 mod required_interfaces;
+mod socket;
+mod storage;
+mod stream;
 mod r#struct;
 mod r#type;
 mod type_guid;
 mod type_kind;
 mod type_name;
+mod uri;
 
+pub(crate) use bitmap::*;
+pub(crate) use builder::*;
 pub(crate) use class::Class;
+pub(crate) use data::*;
+pub(crate) use date_time::*;
+pub(crate) use deferral::*;
 pub(crate) use delegate::Delegate;
+pub(crate) use future::*;
+pub(crate) use graphics::*;
 pub(crate) use interface::Interface;
 pub(crate) use iterator::*;
+pub(crate) use json::*;
 pub(crate) use method::*;
 pub(crate) use namespace::*;
+pub(crate) use numerics::*;
 pub(crate) use param::Param;
-pub(crate) use r#enum::Enum;
+pub(crate) use property_value::*;
+pub(crate) use r#enum::{Enum, EnumConstant};
 pub(crate) use r#struct::Struct;
 pub(crate) use r#type::Type;
+pub(crate) use reference::*;
 pub(crate) use required_interface::*;
 pub(crate) use required_interfaces::*;
+pub(crate) use socket::*;
+pub(crate) use storage::*;
+pub(crate) use stream::*;
 pub(crate) use type_guid::{GuidConstant, TypeGuid};
 pub(crate) use type_kind::TypeKind;
 pub(crate) use type_name::TypeName;
+pub(crate) use uri::*;
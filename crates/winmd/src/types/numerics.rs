@@ -0,0 +1,235 @@
+use crate::types::*;
+use proc_macro2::{Ident, TokenStream};
+use quote::{format_ident, quote};
+
+// Provides arithmetic operators for the Windows.Foundation.Numerics vector, matrix, and
+// quaternion structs, following the semantics of System.Numerics, as well as conversions to and
+// from the equivalent mint types with the winrt crate's `mint` feature. The operators are
+// implemented for both values and references so that either may be used.
+pub fn numerics_tokens(name: &TypeName, fields: &[(String, TypeKind)]) -> TokenStream {
+    if name.namespace != "Windows.Foundation.Numerics" {
+        return quote! {};
+    }
+
+    let fields: Vec<Ident> = fields
+        .iter()
+        .map(|(name, _)| format_ident!("{}", name))
+        .collect();
+    let type_name = name.name.as_str();
+    let name = name.to_tokens(&name.namespace);
+
+    let multiply = match type_name {
+        "Vector2" | "Vector3" | "Vector4" => {
+            let component = binary(
+                &name,
+                "Mul",
+                "mul",
+                &quote! { #(#fields: self.#fields * rhs.#fields),* },
+            );
+            let divide = scalar(
+                &name,
+                "Div",
+                "div",
+                &quote! { #(#fields: self.#fields / rhs),* },
+            );
+            quote! { #component #divide }
+        }
+        "Matrix3x2" => binary(
+            &name,
+            "Mul",
+            "mul",
+            &quote! {
+                m11: self.m11 * rhs.m11 + self.m12 * rhs.m21,
+                m12: self.m11 * rhs.m12 + self.m12 * rhs.m22,
+                m21: self.m21 * rhs.m11 + self.m22 * rhs.m21,
+                m22: self.m21 * rhs.m12 + self.m22 * rhs.m22,
+                m31: self.m31 * rhs.m11 + self.m32 * rhs.m21 + rhs.m31,
+                m32: self.m31 * rhs.m12 + self.m32 * rhs.m22 + rhs.m32,
+            },
+        ),
+        "Matrix4x4" => {
+            let products = (1..=4).flat_map(|row| {
+                (1..=4).map(move |column| {
+                    let field = format_ident!("{}", format!("m{}{}", row, column));
+                    let terms = (1..=4).map(|k| {
+                        let left = format_ident!("{}", format!("m{}{}", row, k));
+                        let right = format_ident!("{}", format!("m{}{}", k, column));
+                        quote! { self.#left * rhs.#right }
+                    });
+                    quote! { #field: #(#terms)+* }
+                })
+            });
+            binary(&name, "Mul", "mul", &quote! { #(#products),* })
+        }
+        "Quaternion" => binary(
+            &name,
+            "Mul",
+            "mul",
+            &quote! {
+                x: self.w * rhs.x + self.x * rhs.w + self.y * rhs.z - self.z * rhs.y,
+                y: self.w * rhs.y - self.x * rhs.z + self.y * rhs.w + self.z * rhs.x,
+                z: self.w * rhs.z + self.x * rhs.y - self.y * rhs.x + self.z * rhs.w,
+                w: self.w * rhs.w - self.x * rhs.x - self.y * rhs.y - self.z * rhs.z,
+            },
+        ),
+        _ => return quote! {},
+    };
+
+    let add = binary(
+        &name,
+        "Add",
+        "add",
+        &quote! { #(#fields: self.#fields + rhs.#fields),* },
+    );
+    let sub = binary(
+        &name,
+        "Sub",
+        "sub",
+        &quote! { #(#fields: self.#fields - rhs.#fields),* },
+    );
+    let scale = scalar(
+        &name,
+        "Mul",
+        "mul",
+        &quote! { #(#fields: self.#fields * rhs),* },
+    );
+    let mint = mint_tokens(&name, type_name);
+
+    quote! {
+        #add
+        #sub
+        #multiply
+        #scale
+        impl ::core::ops::Neg for #name {
+            type Output = #name;
+            fn neg(self) -> #name {
+                #name { #(#fields: -self.#fields),* }
+            }
+        }
+        impl ::core::ops::Neg for &#name {
+            type Output = #name;
+            fn neg(self) -> #name {
+                #name { #(#fields: -self.#fields),* }
+            }
+        }
+        #mint
+    }
+}
+
+fn binary(name: &TokenStream, trait_name: &str, method: &str, body: &TokenStream) -> TokenStream {
+    let trait_name = format_ident!("{}", trait_name);
+    let method = format_ident!("{}", method);
+
+    quote! {
+        impl ::core::ops::#trait_name<#name> for #name {
+            type Output = #name;
+            fn #method(self, rhs: #name) -> #name {
+                #name { #body }
+            }
+        }
+        impl ::core::ops::#trait_name<&#name> for &#name {
+            type Output = #name;
+            fn #method(self, rhs: &#name) -> #name {
+                #name { #body }
+            }
+        }
+    }
+}
+
+fn scalar(name: &TokenStream, trait_name: &str, method: &str, body: &TokenStream) -> TokenStream {
+    let trait_name = format_ident!("{}", trait_name);
+    let method = format_ident!("{}", method);
+
+    quote! {
+        impl ::core::ops::#trait_name<f32> for #name {
+            type Output = #name;
+            fn #method(self, rhs: f32) -> #name {
+                #name { #body }
+            }
+        }
+        impl ::core::ops::#trait_name<f32> for &#name {
+            type Output = #name;
+            fn #method(self, rhs: f32) -> #name {
+                #name { #body }
+            }
+        }
+    }
+}
+
+fn mint_tokens(name: &TokenStream, type_name: &str) -> TokenStream {
+    let (mint, into_mint, from_mint) = match type_name {
+        "Vector2" | "Vector3" | "Vector4" => {
+            let fields: &[&str] = match type_name {
+                "Vector2" => &["x", "y"],
+                "Vector3" => &["x", "y", "z"],
+                _ => &["x", "y", "z", "w"],
+            };
+            let fields: Vec<Ident> = fields
+                .iter()
+                .map(|field| format_ident!("{}", field))
+                .collect();
+            let mint = format_ident!("{}", type_name);
+            (
+                quote! { ::winrt::mint::#mint<f32> },
+                quote! { #(#fields: value.#fields),* },
+                quote! { #(#fields: value.#fields),* },
+            )
+        }
+        "Quaternion" => (
+            quote! { ::winrt::mint::Quaternion<f32> },
+            quote! { v: ::winrt::mint::Vector3 { x: value.x, y: value.y, z: value.z }, s: value.w },
+            quote! { x: value.v.x, y: value.v.y, z: value.v.z, w: value.s },
+        ),
+        // WinRT matrices are row major so their rows correspond to the vectors of a mint RowMatrix.
+        "Matrix3x2" | "Matrix4x4" => {
+            let (rows, columns, mint) = if type_name == "Matrix3x2" {
+                (3, 2, format_ident!("RowMatrix3x2"))
+            } else {
+                (4, 4, format_ident!("RowMatrix4"))
+            };
+            let vector = format_ident!("{}", format!("Vector{}", columns));
+            let axes = ["x", "y", "z", "w"];
+
+            let into_rows = (1..=rows).map(|row| {
+                let axis = format_ident!("{}", axes[row - 1]);
+                let cells = (1..=columns).map(|column| {
+                    let cell_axis = format_ident!("{}", axes[column - 1]);
+                    let field = format_ident!("{}", format!("m{}{}", row, column));
+                    quote! { #cell_axis: value.#field }
+                });
+                quote! { #axis: ::winrt::mint::#vector { #(#cells),* } }
+            });
+
+            let from_cells = (1..=rows).flat_map(|row| {
+                (1..=columns).map(move |column| {
+                    let axis = format_ident!("{}", axes[row - 1]);
+                    let cell_axis = format_ident!("{}", axes[column - 1]);
+                    let field = format_ident!("{}", format!("m{}{}", row, column));
+                    quote! { #field: value.#axis.#cell_axis }
+                })
+            });
+
+            (
+                quote! { ::winrt::mint::#mint<f32> },
+                quote! { #(#into_rows),* },
+                quote! { #(#from_cells),* },
+            )
+        }
+        _ => return quote! {},
+    };
+
+    quote! {
+        ::winrt::__cfg_mint! {
+            impl ::core::convert::From<#name> for #mint {
+                fn from(value: #name) -> Self {
+                    Self { #into_mint }
+                }
+            }
+            impl ::core::convert::From<#mint> for #name {
+                fn from(value: #mint) -> Self {
+                    Self { #from_mint }
+                }
+            }
+        }
+    }
+}
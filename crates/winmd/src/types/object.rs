@@ -3,15 +3,55 @@ use quote::quote;
 
 pub fn to_object_tokens(from: &TokenStream, constraints: &TokenStream) -> TokenStream {
     quote! {
-        impl<#constraints> ::std::convert::From<#from> for ::winrt::Object {
+        impl<#constraints> ::core::convert::From<#from> for ::winrt::Object {
             fn from(value: #from) -> ::winrt::Object {
-                unsafe { ::std::mem::transmute(value) }
+                unsafe { ::core::mem::transmute(value) }
             }
         }
-        impl<#constraints> ::std::convert::From<&#from> for ::winrt::Object {
+        impl<#constraints> ::core::convert::From<&#from> for ::winrt::Object {
             fn from(value: &#from) -> ::winrt::Object {
-                ::std::convert::From::from(::std::clone::Clone::clone(value))
+                ::core::convert::From::from(::core::clone::Clone::clone(value))
             }
         }
+        impl<'a, #constraints> ::core::convert::Into<::winrt::Param<'a, ::winrt::Object>> for #from {
+            fn into(self) -> ::winrt::Param<'a, ::winrt::Object> {
+                ::winrt::Param::Owned(::core::convert::Into::<::winrt::Object>::into(self))
+            }
+        }
+        impl<'a, #constraints> ::core::convert::Into<::winrt::Param<'a, ::winrt::Object>> for &'a #from {
+            fn into(self) -> ::winrt::Param<'a, ::winrt::Object> {
+                // Every WinRT interface derives from IInspectable, so it may be borrowed as an
+                // Object without an AddRef/Release pair.
+                ::winrt::Param::Borrowed(unsafe { &*(self as *const #from as *const ::winrt::Object) })
+            }
+        }
+    }
+}
+
+pub fn to_try_from_object_tokens(into: &TokenStream) -> TokenStream {
+    quote! {
+        impl ::core::convert::TryFrom<&::winrt::Object> for #into {
+            type Error = ::winrt::CastError;
+            fn try_from(value: &::winrt::Object) -> ::core::result::Result<Self, Self::Error> {
+                ::winrt::Cast::cast(value)
+            }
+        }
+        impl ::core::convert::TryFrom<::winrt::Object> for #into {
+            type Error = ::winrt::CastError;
+            fn try_from(value: ::winrt::Object) -> ::core::result::Result<Self, Self::Error> {
+                ::winrt::Cast::cast(&value)
+            }
+        }
+    }
+}
+
+pub fn to_identity_tokens(name: &TokenStream, constraints: &TokenStream) -> TokenStream {
+    quote! {
+        impl<#constraints> ::core::cmp::PartialEq for #name {
+            fn eq(&self, other: &Self) -> bool {
+                ::winrt::is_same_object(self, other)
+            }
+        }
+        impl<#constraints> ::core::cmp::Eq for #name {}
     }
 }
@@ -10,6 +10,7 @@ pub struct Param {
     pub array: bool,
     pub input: bool,
     pub by_ref: bool,
+    pub large: bool,
 }
 
 impl Param {
@@ -45,6 +46,19 @@ impl Param {
         }
     }
 
+    /// Whether the value may be null when returned, which is the case for classes, interfaces,
+    /// delegates and objects but not for arrays of them.
+    pub fn is_nullable(&self) -> bool {
+        !self.array
+            && matches!(
+                self.kind,
+                TypeKind::Object
+                    | TypeKind::Class(_)
+                    | TypeKind::Interface(_)
+                    | TypeKind::Delegate(_)
+            )
+    }
+
     pub fn to_return_tokens(&self, calling_namespace: &str) -> TokenStream {
         let tokens = self.kind.to_tokens(calling_namespace);
 
@@ -83,16 +97,80 @@ impl Param {
         }
     }
 
+    pub fn to_erased_return_tokens(&self, calling_namespace: &str) -> TokenStream {
+        let tokens = self.kind.to_erased_tokens(calling_namespace);
+
+        if self.array {
+            quote! { ::winrt::Array<#tokens> }
+        } else {
+            quote! { #tokens }
+        }
+    }
+
+    /// Returns any local needed to hold a converted argument, along with the argument to pass to
+    /// the erased specialization of the method.
+    pub fn to_erased_arg_tokens(
+        &self,
+        calling_namespace: &str,
+        position: usize,
+    ) -> (TokenStream, TokenStream) {
+        let name = format_ident(&self.name);
+        let tokens = self.kind.to_tokens(calling_namespace);
+        let erased = self.kind.to_erased_tokens(calling_namespace);
+
+        if self.array {
+            if self.input {
+                (
+                    quote! {},
+                    quote! { ::winrt::erased::erase_slice::<#tokens, #erased>(#name), },
+                )
+            } else if self.by_ref {
+                (
+                    quote! {},
+                    quote! { ::winrt::erased::erase_mut::<::winrt::Array<#tokens>, ::winrt::Array<#erased>>(#name), },
+                )
+            } else {
+                (
+                    quote! {},
+                    quote! { ::winrt::erased::erase_slice_mut::<#tokens, #erased>(#name), },
+                )
+            }
+        } else if self.input {
+            match self.kind {
+                TypeKind::String
+                | TypeKind::Object
+                | TypeKind::Guid
+                | TypeKind::Class(_)
+                | TypeKind::Interface(_)
+                | TypeKind::Struct(_)
+                | TypeKind::Delegate(_)
+                | TypeKind::Generic(_) => {
+                    let local = quote::format_ident!("__arg{}", position);
+                    (
+                        quote! { let #local: ::winrt::Param<#tokens> = #name.into(); },
+                        quote! { ::winrt::erased::erase::<#tokens, #erased>(::winrt::erased::param_ref(&#local)), },
+                    )
+                }
+                _ => (quote! {}, quote! { #name, }),
+            }
+        } else {
+            (
+                quote! {},
+                quote! { ::winrt::erased::erase_mut::<#tokens, #erased>(#name), },
+            )
+        }
+    }
+
     pub fn to_abi_arg_tokens(&self) -> TokenStream {
         let name = format_ident(&self.name);
 
         if self.array {
             if self.input {
-                quote! { #name.len() as u32, ::std::mem::transmute(#name.as_ptr()), }
+                quote! { #name.len() as u32, ::core::mem::transmute(#name.as_ptr()), }
             } else if self.by_ref {
                 quote! { #name.set_abi_len(), #name.set_abi(), }
             } else {
-                quote! { #name.len() as u32, ::std::mem::transmute_copy(&#name), }
+                quote! { #name.len() as u32, ::core::mem::transmute_copy(&#name), }
             }
         } else if self.input {
             if self.kind.blittable() {
@@ -0,0 +1,147 @@
+use crate::types::*;
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use std::iter::FromIterator;
+
+// The typed getters, the PropertyType each expects, the IPropertyValue method that reads it, and
+// the type it returns.
+const GETTERS: &[(&str, &str, &str, &str)] = &[
+    ("as_u8", "UInt8", "get_uint8", "u8"),
+    ("as_i16", "Int16", "get_int16", "i16"),
+    ("as_u16", "UInt16", "get_uint16", "u16"),
+    ("as_i32", "Int32", "get_int32", "i32"),
+    ("as_u32", "UInt32", "get_uint32", "u32"),
+    ("as_i64", "Int64", "get_int64", "i64"),
+    ("as_u64", "UInt64", "get_uint64", "u64"),
+    ("as_f32", "Single", "get_single", "f32"),
+    ("as_f64", "Double", "get_double", "f64"),
+    ("as_char16", "Char16", "get_char16", "::winrt::Char16"),
+    ("as_bool", "Boolean", "get_boolean", "bool"),
+    ("as_string", "String", "get_string", "::winrt::HString"),
+    ("as_guid", "Guid", "get_guid", "::winrt::Guid"),
+    ("as_date_time", "DateTime", "get_date_time", "DateTime"),
+    ("as_time_span", "TimeSpan", "get_time_span", "TimeSpan"),
+    ("as_point", "Point", "get_point", "Point"),
+    ("as_size", "Size", "get_size", "Size"),
+    ("as_rect", "Rect", "get_rect", "Rect"),
+];
+
+// Provides typed getters on Windows.Foundation.IPropertyValue, such as `as_i32` and
+// `as_f64_array`, that check the value's PropertyType before reading it and otherwise fail with
+// a PropertyTypeError naming both the expected and actual types. Every getter has an array
+// counterpart, since each PropertyType has an array type with the same getter suffixed `_array`.
+// The same getters are provided on `winrt::Object`, which can't have inherent methods outside of
+// the winrt crate, by the TypedPropertyValue trait, so that an object boxed with PropertyValue
+// needn't be cast to IPropertyValue first.
+pub fn property_value_tokens(name: &TypeName) -> TokenStream {
+    if name.namespace != "Windows.Foundation" || name.name != "IPropertyValue" {
+        return quote! {};
+    }
+
+    let getters = GETTERS
+        .iter()
+        .map(|(method, property_type, getter, return_type)| {
+            let return_type: TokenStream = return_type.parse().unwrap();
+            (
+                format_ident!("{}", method),
+                format_ident!("{}", property_type),
+                format_ident!("{}", getter),
+                return_type,
+                false,
+            )
+        });
+
+    let array_getters = GETTERS
+        .iter()
+        .map(|(method, property_type, getter, return_type)| {
+            let return_type: TokenStream = return_type.parse().unwrap();
+            (
+                format_ident!("{}_array", method),
+                format_ident!("{}Array", property_type),
+                format_ident!("{}_array", getter),
+                quote! { ::winrt::Array<#return_type> },
+                true,
+            )
+        });
+
+    let getters: Vec<_> = getters.chain(array_getters).collect();
+
+    let methods = TokenStream::from_iter(getters.iter().map(
+        |(method, property_type, getter, return_type, array)| {
+            let doc = doc(property_type, *array);
+
+            // An array is read into an `Array` rather than returned.
+            let read = if *array {
+                quote! {
+                    let mut array = ::winrt::Array::new();
+                    self.#getter(&mut array).map_err(|error| ::winrt::PropertyTypeError::failed(error, expected.value))?;
+                    Ok(array)
+                }
+            } else {
+                quote! {
+                    self.#getter().map_err(|error| ::winrt::PropertyTypeError::failed(error, expected.value))
+                }
+            };
+
+            quote! {
+                #[doc = #doc]
+                pub fn #method(&self) -> ::core::result::Result<#return_type, ::winrt::PropertyTypeError> {
+                    let expected = PropertyType::#property_type;
+                    self.check_type(expected)?;
+                    #read
+                }
+            }
+        },
+    ));
+
+    let trait_methods = TokenStream::from_iter(getters.iter().map(
+        |(method, property_type, _, return_type, array)| {
+            let doc = doc(property_type, *array);
+
+            quote! {
+                #[doc = #doc]
+                fn #method(&self) -> ::core::result::Result<#return_type, ::winrt::PropertyTypeError>;
+            }
+        },
+    ));
+
+    let object_methods = TokenStream::from_iter(getters.iter().map(
+        |(method, property_type, _, return_type, _)| {
+            quote! {
+                fn #method(&self) -> ::core::result::Result<#return_type, ::winrt::PropertyTypeError> {
+                    IPropertyValue::from_object(self, PropertyType::#property_type)?.#method()
+                }
+            }
+        },
+    ));
+
+    quote! {
+        impl IPropertyValue {
+            #methods
+            fn check_type(&self, expected: PropertyType) -> ::core::result::Result<(), ::winrt::PropertyTypeError> {
+                let actual = self.r#type().map_err(|error| ::winrt::PropertyTypeError::failed(error, expected.value))?;
+                ::winrt::PropertyTypeError::check(actual.value, expected.value)
+            }
+            fn from_object(object: &::winrt::Object, expected: PropertyType) -> ::core::result::Result<Self, ::winrt::PropertyTypeError> {
+                ::winrt::TryInto::<Self>::try_into(object).map_err(|error| ::winrt::PropertyTypeError::failed(error, expected.value))
+            }
+        }
+        /// The typed getters of `IPropertyValue` for an `Object` boxed with `PropertyValue`
+        ///
+        /// An object that isn't a property value fails with `E_NOINTERFACE`.
+        pub trait TypedPropertyValue {
+            #trait_methods
+        }
+        impl TypedPropertyValue for ::winrt::Object {
+            #object_methods
+        }
+    }
+}
+
+fn doc(property_type: &proc_macro2::Ident, array: bool) -> String {
+    format!(
+        "Returns the {} if its type is `PropertyType::{}`, or an error naming its type",
+        if array { "array" } else { "value" },
+        property_type
+    )
+}
@@ -0,0 +1,42 @@
+use crate::types::*;
+use proc_macro2::TokenStream;
+use quote::quote;
+
+// Provides conversions from Windows.Foundation.IReference<T> and IReferenceArray<T>, which are
+// how nullable values and arrays are boxed, to their Rust equivalents. A null IReference<T> is a
+// missing value, so arrays of them, as returned by sensor and device APIs, become vectors of
+// options.
+pub fn reference_tokens(name: &TypeName) -> TokenStream {
+    if name.namespace != "Windows.Foundation" {
+        return quote! {};
+    }
+
+    match name.name.as_str() {
+        "IReference`1" => quote! {
+            impl<T: ::winrt::RuntimeType + 'static> IReference<T> {
+                /// Returns the boxed value, or `None` if the reference is null.
+                pub fn to_option(&self) -> ::winrt::Result<::core::option::Option<T>> {
+                    if ::winrt::ComInterface::is_null(self) {
+                        Ok(None)
+                    } else {
+                        self.value().map(Some)
+                    }
+                }
+
+                /// Reads an array of nullable values, where null references are `None`.
+                pub fn to_options(values: &[Self]) -> ::winrt::Result<::winrt::__alloc::vec::Vec<::core::option::Option<T>>> {
+                    values.iter().map(Self::to_option).collect()
+                }
+            }
+        },
+        "IReferenceArray`1" => quote! {
+            impl<T: ::winrt::RuntimeType + ::core::clone::Clone + 'static> IReferenceArray<T> {
+                /// Copies the boxed array into a vector.
+                pub fn to_vec(&self) -> ::winrt::Result<::winrt::__alloc::vec::Vec<T>> {
+                    Ok(self.value()?.as_slice().to_vec())
+                }
+            }
+        },
+        _ => quote! {},
+    }
+}
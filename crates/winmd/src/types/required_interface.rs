@@ -1,6 +1,7 @@
+use crate::case::to_snake;
 use crate::tables::*;
 use crate::types::*;
-use crate::TypeReader;
+use crate::{TypeOptions, TypeReader};
 use proc_macro2::TokenStream;
 use quote::quote;
 use std::collections::*;
@@ -12,8 +13,13 @@ pub struct RequiredInterface {
     pub guid: TypeGuid,
     pub methods: Vec<Method>,
     pub kind: InterfaceKind,
+    /// The name and version of the contract that introduced the interface
+    pub contract: Option<(Option<String>, u32)>,
 }
 
+// The contract that versions the Windows APIs, whose major version increases with each release.
+const UNIVERSAL_API_CONTRACT: &str = "Windows.Foundation.UniversalApiContract";
+
 #[derive(Copy, Clone, PartialEq, Debug)]
 pub enum InterfaceKind {
     Default,
@@ -39,6 +45,7 @@ impl RequiredInterface {
             guid,
             methods,
             kind: InterfaceKind::NonDefault,
+            contract: def.contract(reader),
         }
     }
 
@@ -57,12 +64,14 @@ impl RequiredInterface {
             .collect();
 
         rename_collisions(&mut methods);
+        let contract = name.def.contract(reader);
 
         Self {
             name,
             guid,
             methods,
             kind,
+            contract,
         }
     }
 
@@ -110,6 +119,28 @@ impl RequiredInterface {
         }
     }
 
+    /// Checks that the running version of Windows has the interface when it was introduced by a
+    /// later version of the `UniversalApiContract` than the minimum, caching the answer for each
+    /// method.
+    pub fn to_version_check_tokens(&self, minimum: Option<u16>) -> TokenStream {
+        let (contract, version) = match (&self.contract, minimum) {
+            (Some((Some(contract), version)), Some(minimum))
+                if contract == UNIVERSAL_API_CONTRACT && (version >> 16) as u16 > minimum =>
+            {
+                (contract, version)
+            }
+            _ => return quote! {},
+        };
+
+        let major = (version >> 16) as u16;
+        let minor = (version & 0xFFFF) as u16;
+
+        quote! {
+            static PRESENT: ::core::sync::atomic::AtomicU8 = ::core::sync::atomic::AtomicU8::new(0);
+            ::winrt::api_information::require_contract(&PRESENT, #contract, #major, #minor)?;
+        }
+    }
+
     pub fn to_abi_method_tokens(&self, calling_namespace: &str) -> TokenStream {
         TokenStream::from_iter(
             self.methods
@@ -128,14 +159,26 @@ impl RequiredInterface {
             InterfaceKind::Default => {
                 let into = self.name.to_tokens(calling_namespace);
                 quote! {
-                    impl<#constraints> ::std::convert::From<#from> for #into {
+                    impl<#constraints> ::core::convert::From<#from> for #into {
                         fn from(value: #from) -> #into {
-                            unsafe { ::std::mem::transmute(value) }
+                            unsafe { ::core::mem::transmute(value) }
                         }
                     }
-                    impl<#constraints> ::std::convert::From<&#from> for #into {
+                    impl<#constraints> ::core::convert::From<&#from> for #into {
                         fn from(value: &#from) -> #into {
-                            ::std::convert::From::from(::std::clone::Clone::clone(value))
+                            ::core::convert::From::from(::core::clone::Clone::clone(value))
+                        }
+                    }
+                    impl<'a, #constraints> ::core::convert::Into<::winrt::Param<'a, #into>> for #from {
+                        fn into(self) -> ::winrt::Param<'a, #into> {
+                            ::winrt::Param::Owned(::core::convert::Into::<#into>::into(self))
+                        }
+                    }
+                    impl<'a, #constraints> ::core::convert::Into<::winrt::Param<'a, #into>> for &'a #from {
+                        fn into(self) -> ::winrt::Param<'a, #into> {
+                            // The default interface shares the class's pointer, so it may be borrowed
+                            // without an AddRef/Release pair.
+                            ::winrt::Param::Borrowed(unsafe { &*(self as *const #from as *const #into) })
                         }
                     }
                 }
@@ -144,32 +187,52 @@ impl RequiredInterface {
                 let into = self.name.to_tokens(calling_namespace);
                 if self.name.generics.is_empty() {
                     quote! {
-                        impl<#constraints> ::std::convert::From<#from> for #into {
+                        impl<#constraints> ::core::convert::From<#from> for #into {
                             fn from(value: #from) -> #into {
-                                ::std::convert::From::from(&value)
+                                ::core::convert::From::from(&value)
                             }
                         }
-                        impl<#constraints> ::std::convert::From<&#from> for #into {
+                        impl<#constraints> ::core::convert::From<&#from> for #into {
                             fn from(value: &#from) -> #into {
                                 <#from as ::winrt::ComInterface>::query(value)
                             }
                         }
+                        impl<'a, #constraints> ::core::convert::Into<::winrt::Param<'a, #into>> for #from {
+                            fn into(self) -> ::winrt::Param<'a, #into> {
+                                ::winrt::Param::Owned(::core::convert::From::from(&self))
+                            }
+                        }
+                        impl<'a, #constraints> ::core::convert::Into<::winrt::Param<'a, #into>> for &'a #from {
+                            fn into(self) -> ::winrt::Param<'a, #into> {
+                                ::winrt::Param::Owned(::core::convert::From::from(self))
+                            }
+                        }
                     }
                 } else {
                     let guid = self.guid.to_tokens();
 
                     quote! {
-                        impl<#constraints> ::std::convert::From<#from> for #into {
+                        impl<#constraints> ::core::convert::From<#from> for #into {
                             fn from(value: #from) -> #into {
-                                ::std::convert::From::from(&value)
+                                ::core::convert::From::from(&value)
                             }
                         }
-                        impl<#constraints> ::std::convert::From<&#from> for #into {
+                        impl<#constraints> ::core::convert::From<&#from> for #into {
                             fn from(value: &#from) -> #into {
                                 const IID: ::winrt::Guid = ::winrt::Guid::from_values(#guid);
                                 unsafe { <#from as ::winrt::ComInterface>::query_with_iid(value, &IID) }
                             }
                         }
+                        impl<'a, #constraints> ::core::convert::Into<::winrt::Param<'a, #into>> for #from {
+                            fn into(self) -> ::winrt::Param<'a, #into> {
+                                ::winrt::Param::Owned(::core::convert::From::from(&self))
+                            }
+                        }
+                        impl<'a, #constraints> ::core::convert::Into<::winrt::Param<'a, #into>> for &'a #from {
+                            fn into(self) -> ::winrt::Param<'a, #into> {
+                                ::winrt::Param::Owned(::core::convert::From::from(self))
+                            }
+                        }
                     }
                 }
             }
@@ -178,35 +241,156 @@ impl RequiredInterface {
     }
 }
 
+/// The methods of a type's interfaces, by how they're named on the type
+pub struct MethodNames<'a> {
+    /// The methods generated under their own names, in the order of their interfaces
+    pub generated: Vec<(&'a Method, &'a RequiredInterface)>,
+    /// The methods whose names collide with one generated earlier, with the name each is
+    /// generated under instead, or `None` if that collides as well
+    pub collisions: Vec<(&'a Method, &'a RequiredInterface, Option<String>)>,
+}
+
+impl<'a> MethodNames<'a> {
+    /// Methods that collide with one generated earlier, such as `Close` on a class implementing
+    /// two interfaces that both declare it, are generated under a name suffixed with that of
+    /// their interface. Any that still collide are left for the caller to reach by converting to
+    /// the interface.
+    pub fn new(interfaces: &'a [RequiredInterface]) -> Self {
+        let mut names = BTreeSet::new();
+        let mut generated = Vec::new();
+        let mut collisions = Vec::new();
+
+        for interface in interfaces {
+            for method in &interface.methods {
+                if names.insert(method.name.clone()) {
+                    generated.push((method, interface));
+                } else {
+                    collisions.push((method, interface));
+                }
+            }
+        }
+
+        let collisions = collisions
+            .into_iter()
+            .map(|(method, interface)| {
+                let name = disambiguated_name(&method.name, &interface.name.name);
+                let name = if names.insert(name.clone()) {
+                    Some(name)
+                } else {
+                    None
+                };
+
+                (method, interface, name)
+            })
+            .collect();
+
+        Self {
+            generated,
+            collisions,
+        }
+    }
+}
+
+/// Generates the methods of the interfaces. When `options.erased` is set the methods of a generic
+/// default interface forward to its erased specialization.
 pub fn to_method_tokens(
     calling_namespace: &str,
-    interfaces: &Vec<RequiredInterface>,
+    interfaces: &[RequiredInterface],
+    options: TypeOptions,
+    agile: bool,
 ) -> TokenStream {
+    let nullable = options.nullable;
+    let methods = MethodNames::new(interfaces);
     let mut tokens = Vec::new();
-    let mut names = BTreeSet::new();
+    let mut names: BTreeSet<&str> = methods
+        .generated
+        .iter()
+        .map(|(method, _)| method.name.as_str())
+        .chain(
+            methods
+                .collisions
+                .iter()
+                .filter_map(|(_, _, name)| name.as_deref()),
+        )
+        .collect();
 
-    for interface in interfaces {
-        for method in &interface.methods {
-            // If there are any collisions just drop and caller can QI for the actual interface.
-            if names.contains(&method.name) {
-                continue;
+    for (method, interface) in &methods.generated {
+        let version = interface.to_version_check_tokens(options.version_checks);
+
+        // The attributes apply to the method itself rather than its `_into` variant.
+        tokens.push(method.to_attribute_tokens(options));
+        tokens.push(match interface.kind {
+            InterfaceKind::Default if options.erased && !interface.name.generics.is_empty() => {
+                method.to_erased_tokens(
+                    calling_namespace,
+                    &interface.name,
+                    &version,
+                    nullable,
+                    agile,
+                )
             }
+            InterfaceKind::Default => {
+                method.to_default_tokens(calling_namespace, &version, nullable, agile)
+            }
+            InterfaceKind::NonDefault | InterfaceKind::Overrides => method.to_non_default_tokens(
+                calling_namespace,
+                interface,
+                &version,
+                nullable,
+                agile,
+            ),
+            InterfaceKind::Statics => {
+                method.to_static_tokens(calling_namespace, interface, &version, nullable)
+            }
+        });
+    }
 
-            names.insert(&method.name);
+    for (method, interface, name) in &methods.collisions {
+        if let Some(name) = name {
+            tokens.push(method.to_attribute_tokens(options));
+            tokens.push(method.to_disambiguated_tokens(
+                calling_namespace,
+                interface,
+                name,
+                &interface.to_version_check_tokens(options.version_checks),
+                nullable,
+                agile,
+            ));
+        }
+    }
 
-            tokens.push(match interface.kind {
-                InterfaceKind::Default => method.to_default_tokens(calling_namespace),
-                InterfaceKind::NonDefault | InterfaceKind::Overrides => {
-                    method.to_non_default_tokens(calling_namespace, interface)
-                }
-                InterfaceKind::Statics => method.to_static_tokens(calling_namespace, interface),
-            });
+    // Aliases are generated once the methods have claimed their own names.
+    for (method, interface) in &methods.generated {
+        if let Some(alias) = &method.alias {
+            if names.insert(alias) {
+                tokens.push(method.to_attribute_tokens(options));
+                tokens.push(method.to_alias_tokens(
+                    calling_namespace,
+                    alias,
+                    interface.kind == InterfaceKind::Statics,
+                    nullable,
+                ));
+            }
         }
     }
 
     TokenStream::from_iter(tokens)
 }
 
+/// The name a colliding method is generated under, such as `close_closable` for
+/// `IClosable::Close`
+fn disambiguated_name(method: &str, interface: &str) -> String {
+    let name = interface.split('`').next().unwrap();
+    let mut chars = name.chars();
+
+    let name = match (chars.next(), chars.next()) {
+        (Some('I'), Some(second)) if second.is_uppercase() => &name[1..],
+        _ => name,
+    };
+
+    format!("{}_{}", method, to_snake(name, MethodKind::Normal))
+}
+
 fn rename_collisions(methods: &mut Vec<Method>) {
     let mut names = BTreeSet::new();
 
@@ -218,3 +402,18 @@ fn rename_collisions(methods: &mut Vec<Method>) {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn disambiguated() {
+        assert_eq!(disambiguated_name("close", "IClosable"), "close_closable");
+        assert_eq!(
+            disambiguated_name("get_at", "IVectorView`1"),
+            "get_at_vector_view"
+        );
+        assert_eq!(disambiguated_name("size", "Item"), "size_item");
+    }
+}
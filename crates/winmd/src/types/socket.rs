@@ -0,0 +1,35 @@
+use crate::types::*;
+use proc_macro2::TokenStream;
+use quote::quote;
+
+// Exposes the input and output streams of a StreamSocket as futures::io::AsyncRead and AsyncWrite
+// so that existing async protocol libraries can run over WinRT sockets, which are the only sockets
+// available to sandboxed apps. These rely on the runtime's `futures` feature.
+pub fn socket_tokens(name: &TypeName) -> TokenStream {
+    if name.namespace != "Windows.Networking.Sockets" || name.name != "StreamSocket" {
+        return quote! {};
+    }
+
+    let streams = to_namespace_tokens("Windows.Storage.Streams", &name.namespace);
+
+    quote! {
+        ::winrt::__cfg_futures! {
+        impl StreamSocket {
+            /// Returns a reader and a writer for the socket's input and output streams, which
+            /// implement `futures::io::AsyncRead` and `futures::io::AsyncWrite` respectively.
+            pub fn to_async_io(&self) -> ::winrt::Result<(
+                ::winrt::AsyncReader<#streams IInputStream>,
+                ::winrt::AsyncWriter<#streams IOutputStream>,
+            )> {
+                let input = ::winrt::nullable::or_null(self.input_stream()?);
+                let output = ::winrt::nullable::or_null(self.output_stream()?);
+
+                Ok((
+                    ::winrt::InputStream::into_async_read(input),
+                    ::winrt::OutputStream::into_async_write(output),
+                ))
+            }
+        }
+        }
+    }
+}
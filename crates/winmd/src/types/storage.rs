@@ -0,0 +1,77 @@
+use crate::types::*;
+use proc_macro2::TokenStream;
+use quote::quote;
+
+// Bridges the brokered StorageFile and StorageFolder classes to std::path and std::fs. Items only
+// have a path when they come from the file system, so `to_path_buf` returns None for others, such
+// as files provided by an app's package or a cloud provider. Copying is driven by the runtime's
+// stream helpers so the file contents are never buffered in full. These rely on the runtime's
+// `std` feature.
+pub fn storage_tokens(name: &TypeName) -> TokenStream {
+    if name.namespace != "Windows.Storage" {
+        return quote! {};
+    }
+
+    let foundation = to_namespace_tokens("Windows.Foundation", &name.namespace);
+    let streams = to_namespace_tokens("Windows.Storage.Streams", &name.namespace);
+
+    let from_path = match name.name.as_str() {
+        "StorageFile" => quote! { get_file_from_path_async },
+        "StorageFolder" => quote! { get_folder_from_path_async },
+        _ => return quote! {},
+    };
+
+    let copy = if name.name == "StorageFile" {
+        quote! {
+            /// Copies the contents of the file to a `std::fs::File`, or any other writer,
+            /// returning the number of bytes copied.
+            pub fn copy_to_file<'a, W: ::winrt::__std::io::Write>(
+                &self,
+                file: &'a mut W,
+            ) -> ::winrt::Result<impl ::core::future::Future<Output = ::winrt::Result<u64>> + 'a> {
+                Ok(::winrt::streams::copy_to_writer(::winrt::nullable::or_null(self.open_read_async()?), file))
+            }
+
+            /// Replaces the contents of the file with the contents of a `std::fs::File`, or any
+            /// other reader, returning the number of bytes copied.
+            pub fn copy_from_file<'a, R: ::winrt::__std::io::Read>(
+                &self,
+                file: &'a mut R,
+            ) -> ::winrt::Result<impl ::core::future::Future<Output = ::winrt::Result<u64>> + 'a> {
+                Ok(::winrt::streams::copy_from_reader(
+                    ::winrt::nullable::or_null(self.open_async(FileAccessMode::ReadWrite)?),
+                    file,
+                    |stream: &#streams IRandomAccessStream, len| stream.set_size(len),
+                ))
+            }
+        }
+    } else {
+        quote! {}
+    };
+
+    let class = name.to_tokens(&name.namespace);
+
+    quote! {
+        ::winrt::__cfg_std! {
+        impl #class {
+            /// Gets the item at the given file system path.
+            pub fn from_path<P: AsRef<::winrt::__std::path::Path>>(path: P) -> ::winrt::Result<#foundation IAsyncOperation<#class>> {
+                Self::#from_path(::winrt::HString::from(path.as_ref())).map(::winrt::nullable::or_null)
+            }
+
+            /// Returns the item's file system path, if it has one.
+            pub fn to_path_buf(&self) -> ::winrt::Result<Option<::winrt::__std::path::PathBuf>> {
+                let path = self.path()?;
+
+                if path.is_empty() {
+                    Ok(None)
+                } else {
+                    Ok(Some((&path).into()))
+                }
+            }
+
+            #copy
+        }
+        }
+    }
+}
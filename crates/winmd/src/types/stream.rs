@@ -0,0 +1,105 @@
+use crate::types::*;
+use proc_macro2::TokenStream;
+use quote::quote;
+
+// Provides incremental reading and writing of IInputStream and IOutputStream and any classes or
+// interfaces that implement them. The runtime's AsyncReader and copy helpers drive the reads and
+// writes, and provide their own buffers, but can't name the projected types themselves so the
+// streams provide the operations and the length of the results. These rely on the runtime's
+// `std` feature.
+pub fn stream_tokens(name: &TypeName, interfaces: &[RequiredInterface]) -> TokenStream {
+    let mut tokens = Vec::new();
+
+    if name.namespace == "Windows.Storage.Streams" && name.name == "IInputStream" {
+        let foundation = to_namespace_tokens("Windows.Foundation", &name.namespace);
+
+        tokens.push(quote! {
+            impl ::winrt::InputStream for IInputStream {
+                type Buffer = IBuffer;
+                type Read = #foundation IAsyncOperationWithProgress::<IBuffer, u32>;
+
+                fn read_buffer(&self, buffer: &IBuffer, capacity: u32) -> ::winrt::Result<Self::Read> {
+                    self.read_async(buffer, capacity, InputStreamOptions::Partial).map(::winrt::nullable::or_null)
+                }
+
+                fn buffer_len(buffer: &IBuffer) -> ::winrt::Result<u32> {
+                    buffer.length()
+                }
+            }
+        });
+    } else if name.namespace == "Windows.Storage.Streams" && name.name == "IOutputStream" {
+        let foundation = to_namespace_tokens("Windows.Foundation", &name.namespace);
+
+        tokens.push(quote! {
+            impl ::winrt::OutputStream for IOutputStream {
+                type Buffer = IBuffer;
+                type Write = #foundation IAsyncOperationWithProgress::<u32, u32>;
+                type Flush = #foundation IAsyncOperation::<bool>;
+
+                fn write_buffer(&self, buffer: &IBuffer) -> ::winrt::Result<Self::Write> {
+                    self.write_async(buffer).map(::winrt::nullable::or_null)
+                }
+
+                fn flush_buffers(&self) -> ::winrt::Result<Self::Flush> {
+                    self.flush_async().map(::winrt::nullable::or_null)
+                }
+            }
+        });
+    }
+
+    for interface in interfaces {
+        if interface.name.namespace != "Windows.Storage.Streams" || interface.name == *name {
+            continue;
+        }
+
+        let constraints = name.constraints();
+        let into = interface.name.to_tokens(&name.namespace);
+        let name = name.to_tokens(&name.namespace);
+
+        if interface.name.name == "IInputStream" {
+            tokens.push(quote! {
+                impl<#constraints> ::winrt::InputStream for #name {
+                    type Buffer = <#into as ::winrt::InputStream>::Buffer;
+                    type Read = <#into as ::winrt::InputStream>::Read;
+
+                    fn read_buffer(&self, buffer: &Self::Buffer, capacity: u32) -> ::winrt::Result<Self::Read> {
+                        let stream: #into = ::core::convert::From::from(self);
+                        ::winrt::InputStream::read_buffer(&stream, buffer, capacity)
+                    }
+
+                    fn buffer_len(buffer: &Self::Buffer) -> ::winrt::Result<u32> {
+                        <#into as ::winrt::InputStream>::buffer_len(buffer)
+                    }
+                }
+            });
+        } else if interface.name.name == "IOutputStream" {
+            tokens.push(quote! {
+                impl<#constraints> ::winrt::OutputStream for #name {
+                    type Buffer = <#into as ::winrt::OutputStream>::Buffer;
+                    type Write = <#into as ::winrt::OutputStream>::Write;
+                    type Flush = <#into as ::winrt::OutputStream>::Flush;
+
+                    fn write_buffer(&self, buffer: &Self::Buffer) -> ::winrt::Result<Self::Write> {
+                        let stream: #into = ::core::convert::From::from(self);
+                        ::winrt::OutputStream::write_buffer(&stream, buffer)
+                    }
+
+                    fn flush_buffers(&self) -> ::winrt::Result<Self::Flush> {
+                        let stream: #into = ::core::convert::From::from(self);
+                        ::winrt::OutputStream::flush_buffers(&stream)
+                    }
+                }
+            });
+        }
+    }
+
+    if tokens.is_empty() {
+        return quote! {};
+    }
+
+    quote! {
+        ::winrt::__cfg_std! {
+            #(#tokens)*
+        }
+    }
+}
@@ -9,6 +9,14 @@ use quote::quote;
 pub struct Struct {
     pub name: TypeName,
     pub fields: Vec<(String, TypeKind)>, // TODO: might have to be a full Type to ensure we can write out nested structs for ABI layout
+    pub serializable: bool,
+    pub copyable: bool,
+    pub hashable: bool,
+    /// The size of the struct, unless it depends on the target's pointer width
+    pub size: Option<usize>,
+    /// Whether the `Windows.Foundation` Point and Size structs are generated as well, so that a
+    /// Rect may be made from them
+    pub point_and_size: bool,
 }
 
 impl Struct {
@@ -22,7 +30,26 @@ impl Struct {
             fields.push((name, kind));
         }
 
-        Self { name, fields }
+        let serializable = fields.iter().all(|(_, kind)| serializable(reader, kind));
+        let copyable = fields.iter().all(|(_, kind)| copyable(reader, kind));
+        let hashable = fields.iter().all(|(_, kind)| hashable(reader, kind));
+        let size = layout(reader, &fields).map(|(size, _)| size);
+
+        Self {
+            name,
+            fields,
+            serializable,
+            copyable,
+            hashable,
+            size,
+            point_and_size: false,
+        }
+    }
+
+    /// Returns true if the struct is plain data too large to be returned in registers, in which
+    /// case getters also have a variant that writes the value directly into the caller's storage.
+    pub fn is_large(&self) -> bool {
+        self.copyable && matches!(self.size, Some(size) if size > 16)
     }
 
     pub fn dependencies(&self) -> Vec<TypeDef> {
@@ -33,22 +60,87 @@ impl Struct {
     }
 
     pub fn to_tokens(&self) -> TokenStream {
+        // The event token is provided by the runtime crate so that it can carry helpers shared by
+        // every event source, regardless of which namespaces are imported.
+        if self.name.namespace == "Windows.Foundation" && self.name.name == "EventRegistrationToken"
+        {
+            return quote! {
+                pub use ::winrt::EventRegistrationToken;
+            };
+        }
+
         let name = self.name.to_tokens(&self.name.namespace);
 
-        let fields = self.fields.iter().map(|field| {
-            let name = format_ident(&field.0);
-            let kind = field.1.to_tokens(&self.name.namespace);
-            quote! {
-                pub #name: #kind
-            }
-        });
+        let names: Vec<_> = self
+            .fields
+            .iter()
+            .map(|field| format_ident(&field.0))
+            .collect();
+        let kinds: Vec<_> = self
+            .fields
+            .iter()
+            .map(|field| field.1.to_tokens(&self.name.namespace))
+            .collect();
 
-        quote! {
+        let mut derives = vec![
+            quote! { Clone },
+            quote! { Default },
+            quote! { Debug },
+            quote! { PartialEq },
+        ];
+
+        if self.copyable {
+            derives.push(quote! { Copy });
+        }
+
+        if self.hashable {
+            derives.push(quote! { Eq });
+            derives.push(quote! { Hash });
+        }
+
+        // Renames may give the fields names that aren't snake case.
+        let lints = if self
+            .fields
+            .iter()
+            .any(|field| field.0.chars().any(char::is_uppercase))
+        {
+            quote! { #[allow(non_snake_case)] }
+        } else {
+            quote! {}
+        };
+
+        let definition = quote! {
             #[repr(C)]
-            #[derive(Clone, Default, Debug, PartialEq)]
+            #lints
+            #[derive(#(#derives),*)]
             pub struct #name {
-                #(#fields),*
+                #(pub #names: #kinds),*
             }
+        };
+
+        // Serde support is conditional on the winrt crate's `serde` feature, which the generated
+        // code can't test directly, so the macro decides whether to add the derives.
+        let definition = if self.serializable {
+            quote! { ::winrt::__serde_struct! { #definition } }
+        } else {
+            definition
+        };
+
+        let date_time = date_time_tokens(&self.name);
+        let numerics = numerics_tokens(&self.name, &self.fields);
+        let graphics = graphics_tokens(&self.name, &self.fields, self.point_and_size);
+
+        quote! {
+            #definition
+            #lints
+            impl #name {
+                /// Creates a value from its fields, in the order they are declared.
+                #[allow(clippy::too_many_arguments)]
+                pub fn new(#(#names: #kinds),*) -> Self {
+                    Self { #(#names),* }
+                }
+            }
+            #[doc(hidden)]
             unsafe impl ::winrt::RuntimeType for #name {
                 type Abi = Self;
                 fn abi(&self) -> Self::Abi {
@@ -57,7 +149,92 @@ impl Struct {
                 fn set_abi(&mut self) -> *mut Self::Abi {
                     self as *mut Self::Abi
                 }
+                fn into_abi(self) -> Self::Abi {
+                    self
+                }
+                unsafe fn from_abi(abi: Self::Abi) -> Self {
+                    abi
+                }
             }
+            #date_time
+            #numerics
+            #graphics
         }
     }
 }
+
+// Structs may be serialized if all of their fields may be, which excludes any IReference<T> fields.
+fn serializable(reader: &TypeReader, kind: &TypeKind) -> bool {
+    match kind {
+        TypeKind::Struct(name) => Struct::from_type_def(reader, name.def).serializable,
+        TypeKind::Object
+        | TypeKind::Class(_)
+        | TypeKind::Interface(_)
+        | TypeKind::Delegate(_)
+        | TypeKind::Generic(_) => false,
+        _ => true,
+    }
+}
+
+// Structs may be Copy if all of their fields are plain values, which excludes strings and GUIDs.
+fn copyable(reader: &TypeReader, kind: &TypeKind) -> bool {
+    match kind {
+        TypeKind::Struct(name) => Struct::from_type_def(reader, name.def).copyable,
+        TypeKind::String
+        | TypeKind::Object
+        | TypeKind::Guid
+        | TypeKind::Class(_)
+        | TypeKind::Interface(_)
+        | TypeKind::Delegate(_)
+        | TypeKind::Generic(_) => false,
+        _ => true,
+    }
+}
+
+// Structs may be Eq and Hash if none of their fields are floating point numbers or references.
+fn hashable(reader: &TypeReader, kind: &TypeKind) -> bool {
+    match kind {
+        TypeKind::Struct(name) => Struct::from_type_def(reader, name.def).hashable,
+        TypeKind::F32
+        | TypeKind::F64
+        | TypeKind::Object
+        | TypeKind::Class(_)
+        | TypeKind::Interface(_)
+        | TypeKind::Delegate(_)
+        | TypeKind::Generic(_) => false,
+        _ => true,
+    }
+}
+
+// Returns the size and alignment of a struct with the given fields, which is precise enough to
+// tell which structs are too large to be returned in registers. Strings, objects, and other
+// references are pointers whose size the macro can't know, as it runs on the host rather than the
+// target, but structs containing them aren't plain data and so are never returned that way.
+fn layout(reader: &TypeReader, fields: &[(String, TypeKind)]) -> Option<(usize, usize)> {
+    let mut size: usize = 0;
+    let mut align = 1;
+
+    for (_, kind) in fields {
+        let (field_size, field_align) = match kind {
+            TypeKind::Bool | TypeKind::I8 | TypeKind::U8 => (1, 1),
+            TypeKind::Char | TypeKind::I16 | TypeKind::U16 => (2, 2),
+            TypeKind::I32 | TypeKind::U32 | TypeKind::F32 | TypeKind::Enum(_) => (4, 4),
+            TypeKind::I64 | TypeKind::U64 | TypeKind::F64 => (8, 8),
+            TypeKind::Guid => (16, 4),
+            TypeKind::Struct(name) => {
+                layout(reader, &Struct::from_type_def(reader, name.def).fields)?
+            }
+            TypeKind::String
+            | TypeKind::Object
+            | TypeKind::Class(_)
+            | TypeKind::Interface(_)
+            | TypeKind::Delegate(_)
+            | TypeKind::Generic(_) => return None,
+        };
+
+        size = size.next_multiple_of(field_align) + field_size;
+        align = align.max(field_align);
+    }
+
+    Some((size.next_multiple_of(align), align))
+}
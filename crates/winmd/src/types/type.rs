@@ -1,7 +1,7 @@
 use crate::flags::*;
 use crate::tables::*;
 use crate::types::*;
-use crate::TypeReader;
+use crate::{TypeOptions, TypeReader};
 
 use proc_macro2::TokenStream;
 
@@ -26,16 +26,26 @@ impl Type {
         }
     }
 
-    pub fn to_tokens(&self) -> TokenStream {
+    /// Generates the type's code with the given options.
+    pub fn to_tokens(&self, options: TypeOptions) -> TokenStream {
         match self {
-            Type::Class(t) => t.to_tokens(),
-            Type::Interface(t) => t.to_tokens(),
+            Type::Class(t) => t.to_tokens(options),
+            Type::Interface(t) => t.to_tokens(options),
             Type::Enum(t) => t.to_tokens(),
             Type::Struct(t) => t.to_tokens(),
             Type::Delegate(t) => t.to_tokens(),
         }
     }
 
+    /// Generates the type's vtable, if it has one, for the `__abi` module of its namespace.
+    pub fn to_abi_tokens(&self) -> TokenStream {
+        match self {
+            Type::Interface(t) => t.to_abi_tokens(),
+            Type::Delegate(t) => t.to_abi_tokens(),
+            _ => TokenStream::new(),
+        }
+    }
+
     pub fn name(&self) -> &TypeName {
         match self {
             Type::Class(t) => &t.name,
@@ -34,6 +34,14 @@ pub enum TypeKind {
 }
 
 impl TypeKind {
+    /// Returns true if this is a struct that is plain data too large to be returned in registers.
+    pub fn is_large(&self, reader: &TypeReader) -> bool {
+        match self {
+            Self::Struct(name) => Struct::from_type_def(reader, name.def).is_large(),
+            _ => false,
+        }
+    }
+
     pub fn signature(&self, reader: &TypeReader) -> String {
         match self {
             Self::Bool => "b1".to_owned(),
@@ -178,7 +186,7 @@ impl TypeKind {
     pub fn to_tokens(&self, calling_namespace: &str) -> TokenStream {
         match self {
             Self::Bool => quote! { bool },
-            Self::Char => quote! { u16 },
+            Self::Char => quote! { ::winrt::Char16 },
             Self::I8 => quote! { i8 },
             Self::U8 => quote! { u8 },
             Self::I16 => quote! { i16 },
@@ -204,10 +212,22 @@ impl TypeKind {
         }
     }
 
+    /// The type with each of its generic parameters replaced by `winrt::erased::Erased`
+    pub fn to_erased_tokens(&self, calling_namespace: &str) -> TokenStream {
+        match self {
+            Self::Class(name)
+            | Self::Interface(name)
+            | Self::Struct(name)
+            | Self::Delegate(name) => name.to_erased_tokens(calling_namespace),
+            Self::Generic(_) => quote! { ::winrt::erased::Erased },
+            _ => self.to_tokens(calling_namespace),
+        }
+    }
+
     pub fn to_abi_tokens(&self, calling_namespace: &str) -> TokenStream {
         match self {
             Self::Bool => quote! { bool, },
-            Self::Char => quote! { u16, },
+            Self::Char => quote! { ::winrt::Char16, },
             Self::I8 => quote! { i8, },
             Self::U8 => quote! { u8, },
             Self::I16 => quote! { i16, },
@@ -1,5 +1,6 @@
 use crate::blob::Blob;
 use crate::codes::*;
+use crate::interned::Interned;
 use crate::tables::*;
 use crate::types::*;
 use crate::*;
@@ -11,8 +12,8 @@ use std::iter::FromIterator;
 
 #[derive(Debug, Clone, PartialEq, PartialOrd, Eq, Ord)]
 pub struct TypeName {
-    pub namespace: String,
-    pub name: String,
+    pub namespace: Interned,
+    pub name: Interned,
     pub generics: Vec<TypeKind>,
     pub def: TypeDef,
 }
@@ -23,35 +24,21 @@ impl TypeName {
             return TypeGuid::from_type_def(reader, self.def);
         }
 
-        let mut data = vec![
-            0x11, 0xf4, 0x7a, 0xd5, 0x7b, 0x73, 0x42, 0xc0, 0xab, 0xae, 0x87, 0x8b, 0x1e, 0x16,
-            0xad, 0xee,
-        ];
-        data.extend_from_slice(self.interface_signature(reader).as_bytes());
-
-        let mut hash = sha1::Sha1::new();
-        hash.update(&data);
-        let bytes = hash.digest().bytes();
-
-        let first = u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
-        let second = u16::from_be_bytes([bytes[4], bytes[5]]);
-        let mut third = u16::from_be_bytes([bytes[6], bytes[7]]);
-
-        third = (third & 0x0fff) | (5 << 12);
-        let fourth = (bytes[8] & 0x3f) | 0x80;
+        let (first, second, third, fourth) =
+            winrt_sha1::signature_guid(&[self.interface_signature(reader).as_bytes()]);
 
         TypeGuid([
             GuidConstant::U32(first),
             GuidConstant::U16(second),
             GuidConstant::U16(third),
-            GuidConstant::U8(fourth),
-            GuidConstant::U8(bytes[9]),
-            GuidConstant::U8(bytes[10]),
-            GuidConstant::U8(bytes[11]),
-            GuidConstant::U8(bytes[12]),
-            GuidConstant::U8(bytes[13]),
-            GuidConstant::U8(bytes[14]),
-            GuidConstant::U8(bytes[15]),
+            GuidConstant::U8(fourth[0]),
+            GuidConstant::U8(fourth[1]),
+            GuidConstant::U8(fourth[2]),
+            GuidConstant::U8(fourth[3]),
+            GuidConstant::U8(fourth[4]),
+            GuidConstant::U8(fourth[5]),
+            GuidConstant::U8(fourth[6]),
+            GuidConstant::U8(fourth[7]),
         ])
     }
 
@@ -152,9 +139,7 @@ impl TypeName {
     }
 
     pub fn from_type_def(reader: &TypeReader, def: TypeDef) -> Self {
-        let (namespace, name) = def.name(reader);
-        let namespace = namespace.to_string();
-        let name = name.to_string();
+        let (namespace, name) = reader.type_def_name(def);
         let mut generics = Vec::new();
 
         for generic in def.generics(reader) {
@@ -178,9 +163,7 @@ impl TypeName {
         for _ in 0..args.capacity() {
             args.push(TypeKind::from_blob(blob, generics));
         }
-        let (namespace, name) = def.name(blob.reader);
-        let namespace = namespace.to_string();
-        let name = name.to_string();
+        let (namespace, name) = blob.reader.type_def_name(def);
         let generics = args;
 
         Self {
@@ -238,16 +221,32 @@ impl TypeName {
         }
     }
 
+    /// The type with each of its generic parameters replaced by `winrt::erased::Erased`
+    pub fn to_erased_tokens(&self, calling_namespace: &str) -> TokenStream {
+        if self.generics.is_empty() {
+            return self.to_tokens(calling_namespace);
+        }
+
+        let namespace = to_namespace_tokens(&self.namespace, calling_namespace);
+        let name = format_ident(&self.name[..self.name.len() - 2]);
+        let generics = self
+            .generics
+            .iter()
+            .map(|g| g.to_erased_tokens(calling_namespace));
+        quote! { #namespace#name::<#(#generics),*> }
+    }
+
+    /// The type's vtable, which is defined in the `__abi` module of the type's namespace
     pub fn to_abi_tokens(&self, calling_namespace: &str) -> TokenStream {
         let namespace = to_namespace_tokens(&self.namespace, calling_namespace);
 
         if self.generics.is_empty() {
             let name = format_abi_ident(&self.name);
-            quote! { #namespace#name }
+            quote! { #namespace __abi::#name }
         } else {
             let name = format_abi_ident(&self.name[..self.name.len() - 2]);
             let generics = self.generics.iter().map(|g| g.to_tokens(calling_namespace));
-            quote! { #namespace#name::<#(#generics),*> }
+            quote! { #namespace __abi::#name::<#(#generics),*> }
         }
     }
 
@@ -266,19 +265,23 @@ impl TypeName {
         }
     }
 
-    pub fn to_abi_definition_tokens(&self, calling_namespace: &str) -> TokenStream {
-        let namespace = to_namespace_tokens(&self.namespace, calling_namespace);
-
+    /// The definition of the type's vtable within the `__abi` module of the type's namespace
+    pub fn to_abi_definition_tokens(&self) -> TokenStream {
         if self.generics.is_empty() {
             let name = format_abi_ident(&self.name);
-            quote! { #namespace#name }
+            quote! { #name }
         } else {
             let name = format_abi_ident(&self.name[..self.name.len() - 2]);
-            let generics = self.generics.iter().map(|g| g.to_tokens(calling_namespace));
-            quote! { #namespace#name<#(#generics),*> }
+            let generics = self.generics.iter().map(|g| g.to_tokens(""));
+            quote! { #name<#(#generics),*> }
         }
     }
 
+    /// The namespace of the `__abi` module, which types within it are referred to relative to
+    pub fn abi_namespace(&self) -> String {
+        format!("{}.__abi", self.namespace)
+    }
+
     pub fn phantoms(&self) -> TokenStream {
         if self.generics.is_empty() {
             return TokenStream::new();
@@ -287,7 +290,7 @@ impl TypeName {
         let phantoms = self.generics.iter().enumerate().map(|(count, generic)| {
             let name = format_ident!("__{}", count);
             let generic = generic.to_tokens("");
-            quote! { #name: ::std::marker::PhantomData::<#generic>, }
+            quote! { #name: ::core::marker::PhantomData::<#generic>, }
         });
 
         TokenStream::from_iter(phantoms)
@@ -312,8 +315,8 @@ mod tests {
     #[test]
     fn runtime_name() {
         let mut type_name = TypeName {
-            name: String::from("MyType"),
-            namespace: String::from("Outer.Inner"),
+            name: Interned::from("MyType"),
+            namespace: Interned::from("Outer.Inner"),
             generics: vec![],
             def: TypeDef(Row {
                 index: 0,
@@ -339,6 +342,23 @@ mod tests {
         );
     }
 
+    #[test]
+    fn interned_names() {
+        let reader = &TypeReader::from_os();
+
+        let action = reader.resolve_type_def(("Windows.Foundation", "IAsyncAction"));
+        let uri = reader.resolve_type_def(("Windows.Foundation", "Uri"));
+        let action = TypeName::from_type_def(reader, action);
+        let uri = TypeName::from_type_def(reader, uri);
+
+        assert_eq!(action.namespace, "Windows.Foundation");
+        assert_eq!(action.name, "IAsyncAction");
+        assert!(std::ptr::eq(
+            action.namespace.as_str(),
+            uri.namespace.as_str()
+        ));
+    }
+
     #[test]
     fn guids() {
         let reader = &TypeReader::from_os();
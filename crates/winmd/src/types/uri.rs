@@ -0,0 +1,56 @@
+use crate::types::*;
+use proc_macro2::TokenStream;
+use quote::quote;
+
+// Provides conversions from strings to Uri, which parse through the runtime so that they agree
+// with `create_uri`, and conversions between Uri and url::Url, which are only available with the
+// winrt crate's `url` feature. A Url is created from the Uri's absolute form and a Uri from the
+// Url's serialization, and a string either side rejects is reported as an invalid argument.
+pub fn uri_tokens(name: &TypeName) -> TokenStream {
+    if name.namespace != "Windows.Foundation" || name.name != "Uri" {
+        return quote! {};
+    }
+
+    quote! {
+        impl ::core::convert::TryFrom<&str> for Uri {
+            type Error = ::winrt::Error;
+            fn try_from(value: &str) -> ::winrt::Result<Self> {
+                Ok(::winrt::nullable::or_null(Self::create_uri(value)?))
+            }
+        }
+        impl ::core::str::FromStr for Uri {
+            type Err = ::winrt::Error;
+            fn from_str(value: &str) -> ::winrt::Result<Self> {
+                ::core::convert::TryFrom::try_from(value)
+            }
+        }
+        ::winrt::__cfg_url! {
+            impl ::core::convert::TryFrom<&Uri> for ::winrt::url::Url {
+                type Error = ::winrt::Error;
+                fn try_from(value: &Uri) -> ::winrt::Result<Self> {
+                    let value = ::winrt::__alloc::string::String::from(&value.absolute_uri()?);
+                    ::winrt::url::Url::parse(&value)
+                        .map_err(|_| ::winrt::ErrorCode::INVALID_ARGUMENT.into())
+                }
+            }
+            impl ::core::convert::TryFrom<Uri> for ::winrt::url::Url {
+                type Error = ::winrt::Error;
+                fn try_from(value: Uri) -> ::winrt::Result<Self> {
+                    ::core::convert::TryFrom::try_from(&value)
+                }
+            }
+            impl ::core::convert::TryFrom<&::winrt::url::Url> for Uri {
+                type Error = ::winrt::Error;
+                fn try_from(value: &::winrt::url::Url) -> ::winrt::Result<Self> {
+                    ::core::convert::TryFrom::try_from(value.as_str())
+                }
+            }
+            impl ::core::convert::TryFrom<::winrt::url::Url> for Uri {
+                type Error = ::winrt::Error;
+                fn try_from(value: ::winrt::url::Url) -> ::winrt::Result<Self> {
+                    ::core::convert::TryFrom::try_from(value.as_str())
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,499 @@
+//! Checking of metadata files before they are read
+//!
+//! The reader indexes tables and heaps directly, so metadata that is corrupt or uses features that
+//! the reader doesn't support would otherwise panic with an index out of range somewhere in the
+//! middle of code generation. Validation walks every row of the supported tables instead and
+//! reports the first problem along with where it was found.
+
+use crate::file::{TableIndex, WinmdFile};
+
+/// A problem found while validating a metadata file
+#[derive(Debug)]
+pub struct ValidationError {
+    /// The name of the file containing the problem
+    pub file: String,
+    /// The table and row containing the problem, unless it was found in the file's layout
+    pub location: Option<(TableIndex, u32)>,
+    pub message: String,
+}
+
+impl std::fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.location {
+            Some((table, row)) => write!(
+                f,
+                "Invalid metadata in `{}` at {:?} row {}: {}",
+                self.file, table, row, self.message
+            ),
+            None => write!(f, "Invalid metadata in `{}`: {}", self.file, self.message),
+        }
+    }
+}
+
+impl std::error::Error for ValidationError {}
+
+#[derive(Copy, Clone)]
+enum Column {
+    Value,
+    String,
+    Blob(Signature),
+    Index(TableIndex),
+    List(TableIndex),
+    Coded(u32, &'static [Option<TableIndex>]),
+}
+
+#[derive(Copy, Clone)]
+enum Signature {
+    None,
+    Field,
+    Method,
+    MemberRef,
+    TypeSpec,
+}
+
+use TableIndex::*;
+
+// Targets of coded indices that aren't among the supported tables are left unchecked.
+const TYPE_DEF_OR_REF: Column = Column::Coded(2, &[Some(TypeDef), Some(TypeRef), Some(TypeSpec)]);
+const HAS_CONSTANT: Column = Column::Coded(2, &[Some(Field), Some(Param), None]);
+const TYPE_OR_METHOD_DEF: Column = Column::Coded(1, &[Some(TypeDef), Some(MethodDef)]);
+const RESOLUTION_SCOPE: Column = Column::Coded(2, &[None, None, None, Some(TypeRef)]);
+const ATTRIBUTE_TYPE: Column =
+    Column::Coded(3, &[None, None, Some(MethodDef), Some(MemberRef), None]);
+const MEMBER_REF_PARENT: Column = Column::Coded(
+    3,
+    &[
+        Some(TypeDef),
+        Some(TypeRef),
+        None,
+        Some(MethodDef),
+        Some(TypeSpec),
+    ],
+);
+const HAS_ATTRIBUTE: Column = Column::Coded(
+    5,
+    &[
+        Some(MethodDef),
+        Some(Field),
+        Some(TypeRef),
+        Some(TypeDef),
+        Some(Param),
+        Some(InterfaceImpl),
+        Some(MemberRef),
+        None,
+        None,
+        None,
+        None,
+        None,
+        None,
+        Some(TypeSpec),
+        None,
+        None,
+        None,
+        None,
+        None,
+        Some(GenericParam),
+        None,
+        None,
+    ],
+);
+
+fn columns(table: TableIndex) -> &'static [Column] {
+    match table {
+        Constant => &[Column::Value, HAS_CONSTANT, Column::Blob(Signature::None)],
+        CustomAttribute => &[HAS_ATTRIBUTE, ATTRIBUTE_TYPE, Column::Blob(Signature::None)],
+        Field => &[
+            Column::Value,
+            Column::String,
+            Column::Blob(Signature::Field),
+        ],
+        GenericParam => &[
+            Column::Value,
+            Column::Value,
+            TYPE_OR_METHOD_DEF,
+            Column::String,
+        ],
+        InterfaceImpl => &[Column::Index(TypeDef), TYPE_DEF_OR_REF],
+        MemberRef => &[
+            MEMBER_REF_PARENT,
+            Column::String,
+            Column::Blob(Signature::MemberRef),
+        ],
+        MethodDef => &[
+            Column::Value,
+            Column::Value,
+            Column::Value,
+            Column::String,
+            Column::Blob(Signature::Method),
+            Column::List(Param),
+        ],
+        Param => &[Column::Value, Column::Value, Column::String],
+        TypeDef => &[
+            Column::Value,
+            Column::String,
+            Column::String,
+            TYPE_DEF_OR_REF,
+            Column::List(Field),
+            Column::List(MethodDef),
+        ],
+        TypeRef => &[RESOLUTION_SCOPE, Column::String, Column::String],
+        TypeSpec => &[Column::Blob(Signature::TypeSpec)],
+    }
+}
+
+const TABLES: [TableIndex; 11] = [
+    Constant,
+    CustomAttribute,
+    Field,
+    GenericParam,
+    InterfaceImpl,
+    MemberRef,
+    MethodDef,
+    Param,
+    TypeDef,
+    TypeRef,
+    TypeSpec,
+];
+
+/// Check the tables, heaps and signatures of a metadata file
+pub fn validate_file(file: &WinmdFile) -> Result<(), ValidationError> {
+    let error = |location, message: String| ValidationError {
+        file: file.name.clone(),
+        location,
+        message,
+    };
+
+    if file.strings as u64 + file.strings_size as u64 > file.bytes.len() as u64 {
+        return Err(error(
+            None,
+            "the string heap extends beyond the file".into(),
+        ));
+    }
+
+    if file.blobs as u64 + file.blobs_size as u64 > file.bytes.len() as u64 {
+        return Err(error(None, "the blob heap extends beyond the file".into()));
+    }
+
+    for &table in TABLES.iter() {
+        for row in 0..file.tables[table as usize].row_count {
+            check_row(file, table, row).map_err(|message| error(Some((table, row)), message))?;
+        }
+    }
+
+    Ok(())
+}
+
+fn check_row(file: &WinmdFile, table: TableIndex, row: u32) -> Result<(), String> {
+    for (column, kind) in columns(table).iter().enumerate() {
+        let value = file.u32(table, row, column as u32);
+
+        match *kind {
+            Column::Value => {}
+            Column::String => {
+                string(file, value).map_err(|message| column_error(column, message))?;
+            }
+            Column::Blob(signature) => {
+                let bytes = blob(file, value).map_err(|message| column_error(column, message))?;
+                check_signature(file, signature, bytes)
+                    .map_err(|message| column_error(column, format!("signature {}", message)))?;
+            }
+            Column::Index(target) => {
+                if value == 0 || value > row_count(file, target) {
+                    return Err(column_error(
+                        column,
+                        format!("{:?} index {} is out of range", target, value),
+                    ));
+                }
+            }
+            Column::List(target) => {
+                // A list runs up to the start of the next row's list, so starts must not decrease
+                // and may be one past the last row when the list is empty.
+                if value == 0 || value > row_count(file, target) + 1 {
+                    return Err(column_error(
+                        column,
+                        format!("{:?} list start {} is out of range", target, value),
+                    ));
+                }
+
+                if row > 0 && value < file.u32(table, row - 1, column as u32) {
+                    return Err(column_error(
+                        column,
+                        format!(
+                            "{:?} list start {} precedes the previous row's",
+                            target, value
+                        ),
+                    ));
+                }
+            }
+            Column::Coded(bits, targets) => {
+                coded_index(file, bits, targets, value)
+                    .map_err(|message| column_error(column, message))?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn column_error(column: usize, message: String) -> String {
+    format!("column {}: {}", column, message)
+}
+
+fn row_count(file: &WinmdFile, table: TableIndex) -> u32 {
+    file.tables[table as usize].row_count
+}
+
+fn coded_index(
+    file: &WinmdFile,
+    bits: u32,
+    targets: &[Option<TableIndex>],
+    value: u32,
+) -> Result<(), String> {
+    let tag = (value & ((1 << bits) - 1)) as usize;
+    let index = value >> bits;
+
+    match targets.get(tag) {
+        None => Err(format!("coded index tag {} is invalid", tag)),
+        // A null index is used for optional references, such as the base type of an interface.
+        Some(_) if index == 0 => Ok(()),
+        Some(Some(target)) if index > row_count(file, *target) => {
+            Err(format!("{:?} index {} is out of range", target, index))
+        }
+        Some(_) => Ok(()),
+    }
+}
+
+fn string(file: &WinmdFile, offset: u32) -> Result<&str, String> {
+    if offset >= file.strings_size {
+        return Err(format!(
+            "string offset {} is beyond the string heap",
+            offset
+        ));
+    }
+
+    let start = (file.strings + offset) as usize;
+    let heap = &file.bytes[start..(file.strings + file.strings_size) as usize];
+    let len = heap
+        .iter()
+        .position(|c| *c == 0)
+        .ok_or_else(|| format!("string at offset {} is not terminated", offset))?;
+
+    std::str::from_utf8(&heap[..len])
+        .map_err(|_| format!("string at offset {} is not valid UTF-8", offset))
+}
+
+fn blob(file: &WinmdFile, offset: u32) -> Result<&[u8], String> {
+    if offset >= file.blobs_size {
+        return Err(format!("blob offset {} is beyond the blob heap", offset));
+    }
+
+    let start = (file.blobs + offset) as usize;
+    let mut heap = &file.bytes[start..(file.blobs + file.blobs_size) as usize];
+    let len = read_compressed(&mut heap)
+        .ok_or_else(|| format!("blob at offset {} has a truncated length", offset))?;
+
+    heap.get(..len as usize)
+        .ok_or_else(|| format!("blob at offset {} extends beyond the blob heap", offset))
+}
+
+fn read_compressed(bytes: &mut &[u8]) -> Option<u32> {
+    let first = *bytes.first()?;
+
+    let (value, len) = if first & 0x80 == 0 {
+        (first as u32, 1)
+    } else if first & 0xC0 == 0x80 {
+        let bytes = bytes.get(..2)?;
+        ((((bytes[0] & 0x3F) as u32) << 8) | bytes[1] as u32, 2)
+    } else if first & 0xE0 == 0xC0 {
+        let bytes = bytes.get(..4)?;
+        (
+            ((bytes[0] & 0x1F) as u32) << 24
+                | (bytes[1] as u32) << 16
+                | (bytes[2] as u32) << 8
+                | bytes[3] as u32,
+            4,
+        )
+    } else {
+        return None;
+    };
+
+    *bytes = &bytes[len..];
+    Some(value)
+}
+
+fn check_signature(file: &WinmdFile, signature: Signature, bytes: &[u8]) -> Result<(), String> {
+    let mut bytes = bytes;
+    let bytes = &mut bytes;
+
+    match signature {
+        Signature::None => return Ok(()),
+        Signature::Field => field_signature(file, bytes)?,
+        Signature::Method => method_signature(file, bytes)?,
+        Signature::MemberRef => match bytes.first() {
+            Some(0x06) => field_signature(file, bytes)?,
+            _ => method_signature(file, bytes)?,
+        },
+        Signature::TypeSpec => element_type(file, bytes)?,
+    }
+
+    if bytes.is_empty() {
+        Ok(())
+    } else {
+        Err(format!("has {} unexpected trailing bytes", bytes.len()))
+    }
+}
+
+fn field_signature(file: &WinmdFile, bytes: &mut &[u8]) -> Result<(), String> {
+    match read_compressed(bytes) {
+        Some(0x06) => element_type(file, bytes),
+        _ => Err("is not a field signature".into()),
+    }
+}
+
+fn method_signature(file: &WinmdFile, bytes: &mut &[u8]) -> Result<(), String> {
+    let calling_convention = read_compressed(bytes).ok_or("is empty")?;
+
+    if calling_convention & 0x10 != 0 {
+        read_compressed(bytes).ok_or("is truncated in the generic parameter count")?;
+    }
+
+    let count = read_compressed(bytes).ok_or("is truncated in the parameter count")?;
+
+    // The return type is followed by the parameters, either of which may be passed by reference.
+    for _ in 0..=count {
+        skip_modifiers(file, bytes)?;
+
+        if bytes.first() == Some(&0x10) {
+            *bytes = &bytes[1..];
+        }
+
+        element_type(file, bytes)?;
+    }
+
+    Ok(())
+}
+
+fn skip_modifiers(file: &WinmdFile, bytes: &mut &[u8]) -> Result<(), String> {
+    while let Some(0x1F) | Some(0x20) = bytes.first() {
+        *bytes = &bytes[1..];
+        type_def_or_ref(file, bytes)?;
+    }
+
+    Ok(())
+}
+
+fn type_def_or_ref(file: &WinmdFile, bytes: &mut &[u8]) -> Result<(), String> {
+    let value = read_compressed(bytes).ok_or("is truncated in a type reference")?;
+
+    let targets = match TYPE_DEF_OR_REF {
+        Column::Coded(_, targets) => targets,
+        _ => unreachable!(),
+    };
+
+    coded_index(file, 2, targets, value)
+}
+
+fn element_type(file: &WinmdFile, bytes: &mut &[u8]) -> Result<(), String> {
+    skip_modifiers(file, bytes)?;
+
+    match read_compressed(bytes).ok_or("is truncated in an element type")? {
+        0x01..=0x0E | 0x1C => Ok(()),
+        0x11 | 0x12 => type_def_or_ref(file, bytes),
+        0x13 | 0x1E => read_compressed(bytes)
+            .map(|_| ())
+            .ok_or_else(|| "is truncated in a generic parameter".into()),
+        0x15 => {
+            match read_compressed(bytes) {
+                Some(0x11) | Some(0x12) => {}
+                _ => return Err("has a generic instance that isn't a class or value type".into()),
+            }
+
+            type_def_or_ref(file, bytes)?;
+            let count = read_compressed(bytes).ok_or("is truncated in a generic argument count")?;
+
+            for _ in 0..count {
+                element_type(file, bytes)?;
+            }
+
+            Ok(())
+        }
+        0x1D => element_type(file, bytes),
+        element => Err(format!("has unsupported element type 0x{:X}", element)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn os() {
+        for file in crate::load_winmd::from_os().unwrap() {
+            validate_file(&file).unwrap();
+        }
+    }
+
+    // A file whose headers are intact but whose rows are corrupt, as a bad copy might leave it,
+    // is reported with the location of the problem rather than panicking.
+    #[test]
+    fn corrupt() {
+        let source = crate::load_winmd::from_os()
+            .unwrap()
+            .into_iter()
+            .find(|file| {
+                file.name
+                    .to_lowercase()
+                    .ends_with("windows.foundation.winmd")
+            })
+            .unwrap();
+
+        // Point the name of the first type definition far beyond the string heap.
+        let mut bytes = std::fs::read(&source.name).unwrap();
+        let table = &source.tables[TypeDef as usize];
+        let (offset, size) = table.columns[1];
+        let start = (table.data + offset) as usize;
+        bytes[start..start + size as usize]
+            .iter_mut()
+            .for_each(|byte| *byte = 0xFF);
+
+        let path = std::env::temp_dir().join(format!("winmd_corrupt_{}.winmd", std::process::id()));
+        std::fs::write(&path, &bytes).unwrap();
+
+        // The files are mapped, so they're closed before the file is removed.
+        let file = WinmdFile::new(&path).unwrap();
+        let error = validate_file(&file).unwrap_err();
+        drop(file);
+        let reader = crate::TypeReader::try_new(vec![WinmdFile::new(&path).unwrap()]);
+        let reader_error = reader.err().unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(error.location, Some((TypeDef, 0)));
+        assert!(error.message.contains("string"));
+        assert!(error.to_string().contains(&path.display().to_string()));
+        assert_eq!(reader_error.location, Some((TypeDef, 0)));
+    }
+
+    #[test]
+    fn compressed() {
+        let mut bytes: &[u8] = &[0x03, 0x80, 0x80, 0xC0, 0x00, 0x40, 0x00, 0xC0];
+        assert_eq!(read_compressed(&mut bytes), Some(0x03));
+        assert_eq!(read_compressed(&mut bytes), Some(0x80));
+        assert_eq!(read_compressed(&mut bytes), Some(0x4000));
+        assert_eq!(read_compressed(&mut bytes), None);
+        assert_eq!(bytes, &[0xC0]);
+    }
+
+    #[test]
+    fn error() {
+        let error = ValidationError {
+            file: "Windows.Foundation.winmd".into(),
+            location: Some((TableIndex::MethodDef, 12)),
+            message: "column 3: string offset 9 is beyond the string heap".into(),
+        };
+
+        assert_eq!(
+            error.to_string(),
+            "Invalid metadata in `Windows.Foundation.winmd` at MethodDef row 12: column 3: string offset 9 is beyond the string heap"
+        );
+    }
+}
@@ -4,26 +4,159 @@ use crate::*;
 // TODO: this should return `Result<&I>` e.g. a reference pointing to the factory cache.
 // So this function needs to be implemented as some sort of atomic/singleton where RoGetActivationFactory
 // is only called once and the result is then cached. Here's how I do it in C++ - it's critical
-// that this is super fast. Also, load RoGetActivationFactory dynamically and fall back to LoadLibrary
-// and implement DLL garbage collection for those. Version 0.1 can probably just pin everything.
+// that this is super fast. Also, fall back to LoadLibrary when RoGetActivationFactory fails and
+// implement DLL garbage collection for those. Version 0.1 can probably just pin everything.
 // https://github.com/microsoft/cppwinrt/blob/master/strings/base_activation.h
+//
+// Until then the factory is looked up on every call, so that no factory outlives the apartment
+// it was obtained in when a host, such as a test runner or plugin, uninitializes the runtime and
+// initializes it again.
 pub fn factory<C: RuntimeName, I: ComInterface>() -> Result<I> {
+    named_factory(C::NAME)
+}
+
+// Gets the factory of a class by its full name, for classes that are only known at runtime.
+pub(crate) fn named_factory<I: ComInterface>(class: &str) -> Result<I> {
     let mut ptr = std::ptr::null_mut();
     unsafe {
         let mut code =
-            runtime::RoGetActivationFactory(HString::from(C::NAME).abi(), &I::IID, &mut ptr);
+            runtime::RoGetActivationFactory(HString::from(class).abi(), &I::IID, &mut ptr);
 
+        // A thread that never initialized the runtime, or has since uninitialized it, fails with
+        // CO_E_NOTINITIALIZED. It joins the MTA, which is then kept alive for the rest of the
+        // process, and tries again.
         if code == ErrorCode::NOT_INITIALIZED {
             let mut _cookie = std::ptr::null_mut();
             runtime::CoIncrementMTAUsage(&mut _cookie);
 
-            code = runtime::RoGetActivationFactory(HString::from(C::NAME).abi(), &I::IID, &mut ptr);
+            code = runtime::RoGetActivationFactory(HString::from(class).abi(), &I::IID, &mut ptr);
+        }
+
+        // Classes that a process without package identity can't find may be provided by a DLL
+        // alongside the app.
+        if code == ErrorCode::CLASS_NOT_REGISTERED && !package::has_identity() {
+            if let Some(factory) = package::unpackaged_factory(class) {
+                return Ok(factory);
+            }
+        }
+
+        if code.is_err() {
+            trace::activation_failed(class, code);
+            return Err(ActivationError::new(class, code).into());
+        }
+
+        Ok(std::mem::transmute_copy(&ptr))
+    }
+}
+
+/// The error returned when the activation factory of a class can't be found or loaded
+///
+/// This names the class along with a hint at the likely cause of the most common failures, and
+/// is kept by the [`Error`] that it converts into so that printing the error describes the
+/// failure:
+///
+/// ```text
+/// cannot activate `Windows.ApplicationModel.Package` (HRESULT 0x80040154): the class isn't
+/// registered for processes without package identity
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct ActivationError {
+    code: ErrorCode,
+    class: String,
+    hint: Option<&'static str>,
+}
+
+impl ActivationError {
+    pub(crate) fn new(class: &str, code: ErrorCode) -> Self {
+        Self {
+            code,
+            class: class.to_string(),
+            hint: hint(class, code, package::has_identity),
         }
+    }
+
+    pub fn code(&self) -> ErrorCode {
+        self.code
+    }
+
+    /// The full name of the class, such as `Windows.Foundation.Uri`.
+    pub fn class_name(&self) -> &str {
+        &self.class
+    }
+
+    /// The likely cause of the failure, if the error code is one with a common cause.
+    pub fn hint(&self) -> Option<&'static str> {
+        self.hint
+    }
+}
+
+impl core::fmt::Display for ActivationError {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write!(
+            f,
+            "cannot activate `{}` (HRESULT 0x{:08X})",
+            self.class, self.code.0
+        )?;
+
+        if let Some(hint) = self.hint {
+            write!(f, ": {}", hint)?;
+        }
+
+        Ok(())
+    }
+}
 
-        code.and_then(|| std::mem::transmute_copy(&ptr))
+#[cfg(feature = "std")]
+impl std::error::Error for ActivationError {}
+
+impl From<ActivationError> for Error {
+    fn from(error: ActivationError) -> Self {
+        Error::from(error.code).with_activation(error)
     }
 }
 
+// Identity is only checked for the failures that it explains.
+fn hint(class: &str, code: ErrorCode, has_identity: fn() -> bool) -> Option<&'static str> {
+    match code {
+        ErrorCode::CLASS_NOT_REGISTERED if class.starts_with("Windows.") && !has_identity() => {
+            Some(
+                "the class isn't registered for processes without package identity, so it \
+                 requires package identity",
+            )
+        }
+        ErrorCode::CLASS_NOT_REGISTERED if !has_identity() => Some(
+            "the class isn't registered for processes without package identity, so it requires \
+             package identity or its DLL registered with `winrt::package::register_component`",
+        ),
+        ErrorCode::CLASS_NOT_REGISTERED => Some(
+            "the class isn't registered, so it may require a later version of Windows or an \
+             activatable class entry in the app's manifest",
+        ),
+        ErrorCode::NO_INTERFACE => Some(
+            "the class doesn't provide the requested factory or statics interface, so it may \
+             require a later version of Windows",
+        ),
+        ErrorCode::NOT_INITIALIZED => {
+            Some("the Windows Runtime isn't initialized on the calling thread")
+        }
+        ErrorCode::WRONG_THREAD | CHANGED_MODE => Some(
+            "the class can't be activated from the calling thread's apartment, such as a class \
+             that must be created on a UI thread",
+        ),
+        ErrorCode::MOD_NOT_FOUND | ErrorCode::PROC_NOT_FOUND => {
+            Some("the DLL that implements the class couldn't be loaded")
+        }
+        ACCESS_DENIED => Some("the calling process isn't allowed to activate the class"),
+        NOT_SUPPORTED => Some("the Windows Runtime isn't available on this platform"),
+        _ => None,
+    }
+}
+
+// The apartment was already initialized with a different concurrency model (RPC_E_CHANGED_MODE).
+const CHANGED_MODE: ErrorCode = ErrorCode(0x8001_0106_u32 as i32);
+const ACCESS_DENIED: ErrorCode = ErrorCode(0x8007_0005_u32 as i32);
+const NOT_SUPPORTED: ErrorCode = ErrorCode::from_win32(50);
+
 /// An [activation factory](https://docs.microsoft.com/en-us/windows/win32/api/activation/nn-activation-iactivationfactory) for activating WinRT types.
 #[repr(transparent)]
 #[derive(Default, Clone)]
@@ -63,3 +196,75 @@ pub struct abi_IActivationFactory {
         *mut <Object as RuntimeType>::Abi,
     ) -> ErrorCode,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn unpackaged() -> bool {
+        false
+    }
+
+    fn packaged() -> bool {
+        true
+    }
+
+    #[test]
+    fn hints() {
+        let code = ErrorCode::CLASS_NOT_REGISTERED;
+        let system = "Windows.ApplicationModel.Package";
+        let component = "Contoso.Widgets.Gadget";
+
+        assert!(hint(system, code, unpackaged)
+            .unwrap()
+            .ends_with("requires package identity"));
+        assert!(hint(component, code, unpackaged)
+            .unwrap()
+            .contains("register_component"));
+        assert!(hint(system, code, packaged).unwrap().contains("manifest"));
+        assert!(hint(component, ErrorCode::MOD_NOT_FOUND, packaged)
+            .unwrap()
+            .contains("DLL"));
+        assert!(hint(component, ErrorCode::FAIL, unpackaged).is_none());
+    }
+
+    #[test]
+    fn display() {
+        let error = ActivationError {
+            code: ErrorCode::CLASS_NOT_REGISTERED,
+            class: "Windows.Foundation.Uri".to_string(),
+            hint: hint(
+                "Windows.Foundation.Uri",
+                ErrorCode::CLASS_NOT_REGISTERED,
+                unpackaged,
+            ),
+        };
+
+        assert!(error
+            .to_string()
+            .starts_with("cannot activate `Windows.Foundation.Uri` (HRESULT 0x80040154): "));
+
+        let error: Error = error.into();
+        assert_eq!(error.code(), ErrorCode::CLASS_NOT_REGISTERED);
+        assert_eq!(
+            error.activation().unwrap().class_name(),
+            "Windows.Foundation.Uri"
+        );
+        assert!(error.to_string().contains("requires package identity"));
+    }
+
+    // The stand-in for the Windows Runtime on other platforms can't activate anything.
+    #[cfg(not(windows))]
+    #[test]
+    fn factory() {
+        let error = named_factory::<IActivationFactory>("Windows.Foundation.Uri")
+            .err()
+            .unwrap();
+        let activation = error.activation().unwrap();
+        assert_eq!(activation.class_name(), "Windows.Foundation.Uri");
+        assert_eq!(
+            activation.hint(),
+            hint("Windows.Foundation.Uri", NOT_SUPPORTED, packaged)
+        );
+    }
+}
@@ -0,0 +1,212 @@
+//! Support for the apartment checks generated for classes that aren't agile.
+//!
+//! In debug builds with the `std` feature, the apartment that such an object was created in is
+//! recorded, and each call asserts that it is made from the same apartment. The apartment is
+//! identified by its context token. Objects that were created elsewhere, such as those returned
+//! by other methods, are recorded when they are first called. The record holds a weak reference
+//! to the object, so that it is forgotten once the object is destroyed and its address is reused,
+//! and objects that don't support weak references aren't recorded.
+
+use crate::*;
+
+/// Checks the result of calling a method of a class that isn't agile. A call that failed with
+/// RPC_E_WRONG_THREAD because the object was used from an apartment other than the one that
+/// created it returns an error whose message names the class, the method, and the calling
+/// apartment.
+#[inline]
+pub fn check<T>(class: &str, method: &str, result: Result<T>) -> Result<T> {
+    match result {
+        Err(error) if error.code() == ErrorCode::WRONG_THREAD => {
+            Err(error.with_message(wrong_thread(class, method)))
+        }
+        result => result,
+    }
+}
+
+/// Records the current apartment as that of an object of a class that isn't agile, which was
+/// just created. This is called by the generated constructors in debug builds.
+pub fn record<T: ComInterface>(object: &T) {
+    #[cfg(feature = "std")]
+    homes::record(object, true);
+
+    #[cfg(not(feature = "std"))]
+    let _ = object;
+}
+
+/// Returns true unless the object of a class that isn't agile is known to have been created in
+/// another apartment. This is asserted by the generated methods in debug builds.
+pub fn is_home<T: ComInterface>(object: &T) -> bool {
+    #[cfg(feature = "std")]
+    return homes::record(object, false);
+
+    #[cfg(not(feature = "std"))]
+    {
+        let _ = object;
+        true
+    }
+}
+
+/// The message of the assertion made by the generated methods in debug builds.
+#[doc(hidden)]
+#[cold]
+pub fn wrong_apartment(class: &str, method: &str) -> String {
+    wrong_thread(class, method)
+}
+
+#[cfg(feature = "std")]
+mod homes {
+    use crate::authoring::{IWeakReference, IWeakReferenceSource};
+    use crate::*;
+
+    use std::sync::Mutex;
+
+    struct Home {
+        identity: usize,
+        token: usize,
+        object: IWeakReference,
+    }
+
+    // Weak references are agile, so they may be resolved from any apartment.
+    unsafe impl Send for Home {}
+
+    static HOMES: Mutex<Vec<Home>> = Mutex::new(Vec::new());
+
+    // Records the current apartment as the object's if it is new, or if `replace` is true, and
+    // returns whether the object's apartment is the current one.
+    pub fn record<T: ComInterface>(object: &T, replace: bool) -> bool {
+        let mut token = 0;
+
+        if unsafe { runtime::CoGetContextToken(&mut token) }.is_err() || object.is_null() {
+            return true;
+        }
+
+        let identity: IUnknown = object.query();
+        let key = identity.as_raw() as usize;
+
+        // The objects resolved from the weak references are released after the lock, since
+        // releasing the last reference to one may call back into this module.
+        let mut resolved = Vec::new();
+        let mut homes = HOMES.lock().unwrap();
+
+        if let Some(index) = homes.iter().position(|home| home.identity == key) {
+            // The record is of this object, rather than of one destroyed before it that had the
+            // same address.
+            if !replace && resolve(&homes[index], &mut resolved) == Some(key) {
+                return homes[index].token == token;
+            }
+
+            homes.swap_remove(index);
+        }
+
+        let source: IWeakReferenceSource = identity.query();
+
+        if let Ok(object) = source.get_weak_reference() {
+            homes.retain(|home| resolve(home, &mut resolved).is_some());
+            homes.push(Home {
+                identity: key,
+                token,
+                object,
+            });
+        }
+
+        true
+    }
+
+    // Returns the identity of the recorded object if it is still alive.
+    fn resolve(home: &Home, resolved: &mut Vec<IUnknown>) -> Option<usize> {
+        let object = home.object.resolve::<IUnknown>().ok()??;
+        let identity = object.as_raw() as usize;
+        resolved.push(object);
+        Some(identity)
+    }
+}
+
+#[cold]
+fn wrong_thread(class: &str, method: &str) -> String {
+    format!(
+        "`{}::{}` was called from {}, but `{}` isn't agile and may only be used from the apartment that created it. Use `winrt::Marshaled` to use it from another apartment.",
+        class,
+        method,
+        apartment(),
+        class
+    )
+}
+
+fn apartment() -> String {
+    let mut apartment_type = 0;
+    let mut qualifier = 0;
+    let code = unsafe { runtime::CoGetApartmentType(&mut apartment_type, &mut qualifier) };
+
+    let apartment = match apartment_type {
+        _ if code.is_err() => "an uninitialized apartment",
+        runtime::APTTYPE_STA => "a single-threaded apartment",
+        runtime::APTTYPE_MAINSTA => "the main single-threaded apartment",
+        runtime::APTTYPE_MTA => "the multithreaded apartment",
+        runtime::APTTYPE_NA => "the neutral apartment",
+        _ => "an unknown apartment",
+    };
+
+    #[cfg(feature = "std")]
+    return format!("{} on thread {:?}", apartment, std::thread::current().id());
+
+    #[cfg(not(feature = "std"))]
+    return apartment.to_string();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn other_errors() {
+        assert_eq!(check("Class", "method", Ok(1)).unwrap(), 1);
+
+        let error = check::<()>("Class", "method", Err(ErrorCode::FAIL.into())).unwrap_err();
+        assert_eq!(error.code(), ErrorCode::FAIL);
+        assert!(error.message().is_none());
+    }
+
+    #[test]
+    fn wrong_thread() {
+        let error =
+            check::<()>("Class", "method", Err(ErrorCode::WRONG_THREAD.into())).unwrap_err();
+        assert_eq!(error.code(), ErrorCode::WRONG_THREAD);
+        assert!(error
+            .message()
+            .unwrap()
+            .starts_with("`Class::method` was called from"));
+        assert!(error.to_string().ends_with("(HRESULT 0x8001010E)"));
+    }
+
+    // Each thread of other platforms is its own apartment.
+    #[cfg(all(feature = "std", not(windows)))]
+    #[test]
+    fn recorded() {
+        use crate::authoring::tests::{widget, LOCK};
+
+        fn is_home_on_other_thread(widget: &IUnknown) -> bool {
+            let raw = widget.as_raw() as usize;
+
+            std::thread::spawn(move || {
+                let widget = unsafe { &*(&raw as *const usize as *const IUnknown) };
+                is_home(widget)
+            })
+            .join()
+            .unwrap()
+        }
+
+        let _lock = LOCK.lock().unwrap();
+
+        let created = widget();
+        record(&created);
+        assert!(is_home(&created));
+        assert!(!is_home_on_other_thread(&created));
+        drop(created);
+
+        // An object that wasn't recorded when it was created is recorded when it is first
+        // called, even if a destroyed object had the same address.
+        let returned = widget();
+        assert!(is_home_on_other_thread(&returned));
+        assert!(!is_home(&returned));
+    }
+}
@@ -0,0 +1,194 @@
+//! Detecting the APIs available on the running version of Windows
+//!
+//! Apps that support several versions of Windows can guard calls to newer APIs with these
+//! functions, which wrap `Windows.Foundation.Metadata.ApiInformation` and take the type to test
+//! as a type parameter rather than as its name:
+//!
+//! ```rust,ignore
+//! if winrt::api_information::is_method_present::<Uri>("CreateWithRelativeUri")? {
+//!     // ...
+//! }
+//! ```
+//!
+//! Member names are given as they appear in metadata, which is in Pascal case rather than the
+//! snake case of the generated methods.
+
+use crate::activation::factory;
+use crate::*;
+
+use core::sync::atomic::{AtomicU8, Ordering};
+
+/// Returns true if the type is present on the running version of Windows.
+pub fn is_type_present<T: RuntimeName>() -> Result<bool> {
+    let statics = statics()?;
+    let (this, vtable) = statics.ptr.vtable();
+    let mut present = false;
+
+    (vtable.is_type_present)(this, HString::from(T::NAME).abi(), &mut present).and_then(|| present)
+}
+
+/// Returns true if the type has a method with the given name.
+pub fn is_method_present<T: RuntimeName>(method: &str) -> Result<bool> {
+    member_present::<T>(method, |statics| statics.is_method_present)
+}
+
+/// Returns true if the type has a method with the given name and number of input parameters.
+pub fn is_method_present_with_arity<T: RuntimeName>(method: &str, arity: u32) -> Result<bool> {
+    let statics = statics()?;
+    let (this, vtable) = statics.ptr.vtable();
+    let mut present = false;
+
+    (vtable.is_method_present_with_arity)(
+        this,
+        HString::from(T::NAME).abi(),
+        HString::from(method).abi(),
+        arity,
+        &mut present,
+    )
+    .and_then(|| present)
+}
+
+/// Returns true if the type has an event with the given name.
+pub fn is_event_present<T: RuntimeName>(event: &str) -> Result<bool> {
+    member_present::<T>(event, |statics| statics.is_event_present)
+}
+
+/// Returns true if the type has a property with the given name.
+pub fn is_property_present<T: RuntimeName>(property: &str) -> Result<bool> {
+    member_present::<T>(property, |statics| statics.is_property_present)
+}
+
+/// Returns true if the type has a property with the given name that can be read but not written.
+pub fn is_read_only_property_present<T: RuntimeName>(property: &str) -> Result<bool> {
+    member_present::<T>(property, |statics| statics.is_read_only_property_present)
+}
+
+/// Returns true if the type has a property with the given name that can be written.
+pub fn is_writeable_property_present<T: RuntimeName>(property: &str) -> Result<bool> {
+    member_present::<T>(property, |statics| statics.is_writeable_property_present)
+}
+
+/// Returns true if the enum has a value with the given name.
+pub fn is_enum_named_value_present<T: RuntimeName>(value: &str) -> Result<bool> {
+    member_present::<T>(value, |statics| statics.is_enum_named_value_present)
+}
+
+/// Returns true if the API contract, such as `Windows.Foundation.UniversalApiContract`, is
+/// present with at least the given version.
+pub fn is_api_contract_present(contract: &str, major: u16, minor: u16) -> Result<bool> {
+    let statics = statics()?;
+    let (this, vtable) = statics.ptr.vtable();
+    let mut present = false;
+
+    (vtable.is_api_contract_present_by_major_and_minor)(
+        this,
+        HString::from(contract).abi(),
+        major,
+        minor,
+        &mut present,
+    )
+    .and_then(|| present)
+}
+
+// Called by the methods generated with the `version_checks` option before calling the methods of
+// interfaces introduced by a later version of a contract. The answer is cached in `present`,
+// which is 0 until the contract has been checked, 1 if it's present and 2 if it isn't.
+#[doc(hidden)]
+pub fn require_contract(present: &AtomicU8, contract: &str, major: u16, minor: u16) -> Result<()> {
+    match present.load(Ordering::Relaxed) {
+        1 => return Ok(()),
+        2 => return Err(ErrorCode::OLD_WIN_VERSION.into()),
+        _ => {}
+    }
+
+    if is_api_contract_present(contract, major, minor)? {
+        present.store(1, Ordering::Relaxed);
+        Ok(())
+    } else {
+        present.store(2, Ordering::Relaxed);
+        Err(ErrorCode::OLD_WIN_VERSION.into())
+    }
+}
+
+type MemberPresent = extern "system" fn(
+    RawComPtr<IApiInformationStatics>,
+    <HString as RuntimeType>::Abi,
+    <HString as RuntimeType>::Abi,
+    *mut bool,
+) -> ErrorCode;
+
+// The methods that test for a member by name share a signature.
+fn member_present<T: RuntimeName>(
+    member: &str,
+    method: fn(&abi_IApiInformationStatics) -> MemberPresent,
+) -> Result<bool> {
+    let statics = statics()?;
+    let (this, vtable) = statics.ptr.vtable();
+    let mut present = false;
+
+    method(vtable)(
+        this,
+        HString::from(T::NAME).abi(),
+        HString::from(member).abi(),
+        &mut present,
+    )
+    .and_then(|| present)
+}
+
+fn statics() -> Result<IApiInformationStatics> {
+    factory::<ApiInformation, IApiInformationStatics>()
+}
+
+struct ApiInformation;
+
+impl RuntimeName for ApiInformation {
+    const NAME: &'static str = "Windows.Foundation.Metadata.ApiInformation";
+}
+
+#[repr(transparent)]
+#[derive(Default, Clone)]
+struct IApiInformationStatics {
+    ptr: ComPtr<IApiInformationStatics>,
+}
+
+unsafe impl ComInterface for IApiInformationStatics {
+    type VTable = abi_IApiInformationStatics;
+    const IID: Guid = Guid::from_values(
+        0x9974_39FE,
+        0xF681,
+        0x4A11,
+        [0xB4, 0x16, 0xC1, 0x3A, 0x47, 0xE8, 0xBA, 0x36],
+    );
+}
+
+#[repr(C)]
+#[allow(non_camel_case_types)]
+struct abi_IApiInformationStatics {
+    __base: [usize; 6],
+    is_type_present: extern "system" fn(
+        RawComPtr<IApiInformationStatics>,
+        <HString as RuntimeType>::Abi,
+        *mut bool,
+    ) -> ErrorCode,
+    is_method_present: MemberPresent,
+    is_method_present_with_arity: extern "system" fn(
+        RawComPtr<IApiInformationStatics>,
+        <HString as RuntimeType>::Abi,
+        <HString as RuntimeType>::Abi,
+        u32,
+        *mut bool,
+    ) -> ErrorCode,
+    is_event_present: MemberPresent,
+    is_property_present: MemberPresent,
+    is_read_only_property_present: MemberPresent,
+    is_writeable_property_present: MemberPresent,
+    is_enum_named_value_present: MemberPresent,
+    __is_api_contract_present_by_major: usize,
+    is_api_contract_present_by_major_and_minor: extern "system" fn(
+        RawComPtr<IApiInformationStatics>,
+        <HString as RuntimeType>::Abi,
+        u16,
+        u16,
+        *mut bool,
+    ) -> ErrorCode,
+}
@@ -0,0 +1,460 @@
+//! Running a [CoreApplication](https://docs.microsoft.com/en-us/uwp/api/windows.applicationmodel.core.coreapplication) from Rust
+//!
+//! A UWP app that draws directly to its `CoreWindow`, such as with a swap chain, provides the
+//! `IFrameworkView` that the `CoreApplication` drives. The app implements [`View`] and passes a
+//! closure that creates it to [`run`] from its `main` function:
+//!
+//! ```ignore
+//! struct App;
+//!
+//! impl app::View for App {
+//!     fn set_window(&mut self, window: &Object) -> Result<()> {
+//!         let window: CoreWindow = window.try_into()?;
+//!         window.activate()
+//!     }
+//!
+//!     fn run(&mut self) -> Result<()> {
+//!         let window = CoreWindow::get_for_current_thread()?;
+//!         window.dispatcher()?.process_events(CoreProcessEventsOption::ProcessUntilQuit)
+//!     }
+//! }
+//!
+//! fn main() -> Result<()> {
+//!     app::run(|| App)
+//! }
+//! ```
+//!
+//! The view receives the `CoreApplicationView` and `CoreWindow` as objects that may be converted
+//! to whichever projected types the app has imported from `Windows.ApplicationModel.Core` and
+//! `Windows.UI.Core`.
+
+use crate::activation::factory;
+use crate::ref_count::RefCount;
+use crate::*;
+
+use std::cell::RefCell;
+
+/// The callbacks of an app's view, which correspond to the methods of `IFrameworkView`
+///
+/// The `CoreApplication` calls these on the app's UI thread. An error returned by any of them
+/// is returned to the `CoreApplication`, which terminates the app.
+pub trait View {
+    /// Called when the app is launched with the `CoreApplicationView`.
+    fn initialize(&mut self, _application_view: &Object) -> Result<()> {
+        Ok(())
+    }
+
+    /// Called with the app's `CoreWindow`.
+    fn set_window(&mut self, _window: &Object) -> Result<()> {
+        Ok(())
+    }
+
+    /// Called with the entry point of the app before `run` to load any resources.
+    fn load(&mut self, _entry_point: &HString) -> Result<()> {
+        Ok(())
+    }
+
+    /// Runs the app, typically by processing the window's events until the app quits.
+    fn run(&mut self) -> Result<()>;
+
+    /// Called when the app is closed.
+    fn uninitialize(&mut self) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// Runs the `CoreApplication` with views created by the closure, returning once the app quits.
+///
+/// This must be called from the app's main thread.
+pub fn run<V, F>(create: F) -> Result<()>
+where
+    V: View + 'static,
+    F: FnMut() -> V + 'static,
+{
+    let mut create = create;
+    let source = ViewSource::new(Box::new(move || Box::new(create())));
+    let statics = factory::<CoreApplication, ICoreApplication>()?;
+    let this = statics.ptr.as_raw();
+
+    unsafe { ((*(*(this))).run)(this, source.as_raw()).ok() }
+}
+
+struct CoreApplication;
+
+impl RuntimeName for CoreApplication {
+    const NAME: &'static str = "Windows.ApplicationModel.Core.CoreApplication";
+}
+
+#[repr(transparent)]
+#[derive(Default, Clone)]
+struct ICoreApplication {
+    ptr: ComPtr<ICoreApplication>,
+}
+
+unsafe impl ComInterface for ICoreApplication {
+    type VTable = abi_ICoreApplication;
+    const IID: Guid = Guid::from_values(
+        0x0AAC_F7A4,
+        0x5E1D,
+        0x49DF,
+        [0x80, 0x34, 0xFB, 0x6A, 0x68, 0xBC, 0x5E, 0xD1],
+    );
+}
+
+#[repr(C)]
+#[allow(non_camel_case_types)]
+struct abi_ICoreApplication {
+    __base: [usize; 13],
+    run: extern "system" fn(RawComPtr<ICoreApplication>, RawComPtr<ViewSource>) -> ErrorCode,
+}
+
+#[repr(transparent)]
+#[derive(Default, Clone)]
+struct ViewSource {
+    ptr: ComPtr<ViewSource>,
+}
+
+unsafe impl ComInterface for ViewSource {
+    type VTable = ViewSourceVtable;
+    const IID: Guid = Guid::from_values(
+        0xCD77_0614,
+        0x65C4,
+        0x426C,
+        [0x94, 0x94, 0x34, 0xFC, 0x43, 0x55, 0x48, 0x62],
+    );
+}
+
+#[repr(transparent)]
+#[derive(Default, Clone)]
+struct FrameworkView {
+    ptr: ComPtr<FrameworkView>,
+}
+
+unsafe impl ComInterface for FrameworkView {
+    type VTable = FrameworkViewVtable;
+    const IID: Guid = Guid::from_values(
+        0xFAAB_5CD0,
+        0x8924,
+        0x45AC,
+        [0xAD, 0x0F, 0xA0, 0x8F, 0xAE, 0x5D, 0x03, 0x24],
+    );
+}
+
+// An IFrameworkViewSource that creates a view with the boxed closure whenever it is asked for
+// one. Neither it nor the views are agile since the CoreApplication only calls them on the
+// thread of the view.
+#[repr(C)]
+struct Source {
+    vtable: *const ViewSourceVtable,
+    count: RefCount,
+    create: RefCell<Box<dyn FnMut() -> Box<dyn View>>>,
+}
+
+#[repr(C)]
+struct Frame {
+    vtable: *const FrameworkViewVtable,
+    count: RefCount,
+    view: RefCell<Box<dyn View>>,
+}
+
+impl ViewSource {
+    fn new(create: Box<dyn FnMut() -> Box<dyn View>>) -> Self {
+        let source = Box::new(Source {
+            vtable: &SOURCE_VTABLE,
+            count: RefCount::new(1),
+            create: RefCell::new(create),
+        });
+
+        let ptr = Box::into_raw(source);
+        unsafe { std::mem::transmute_copy(&ptr) }
+    }
+
+    fn as_raw(&self) -> RawComPtr<ViewSource> {
+        self.ptr.as_raw()
+    }
+}
+
+#[repr(C)]
+struct ViewSourceVtable {
+    query_interface: extern "system" fn(*mut Source, &Guid, *mut RawPtr) -> ErrorCode,
+    add_ref: extern "system" fn(*mut Source) -> u32,
+    release: extern "system" fn(*mut Source) -> u32,
+    get_iids: extern "system" fn(RawPtr, *mut u32, *mut *mut Guid) -> ErrorCode,
+    get_runtime_class_name: extern "system" fn(RawPtr, *mut RawPtr) -> ErrorCode,
+    get_trust_level: extern "system" fn(RawPtr, *mut i32) -> ErrorCode,
+    create_view: extern "system" fn(*mut Source, *mut RawPtr) -> ErrorCode,
+}
+
+#[repr(C)]
+struct FrameworkViewVtable {
+    query_interface: extern "system" fn(*mut Frame, &Guid, *mut RawPtr) -> ErrorCode,
+    add_ref: extern "system" fn(*mut Frame) -> u32,
+    release: extern "system" fn(*mut Frame) -> u32,
+    get_iids: extern "system" fn(RawPtr, *mut u32, *mut *mut Guid) -> ErrorCode,
+    get_runtime_class_name: extern "system" fn(RawPtr, *mut RawPtr) -> ErrorCode,
+    get_trust_level: extern "system" fn(RawPtr, *mut i32) -> ErrorCode,
+    initialize: extern "system" fn(*mut Frame, RawPtr) -> ErrorCode,
+    set_window: extern "system" fn(*mut Frame, RawPtr) -> ErrorCode,
+    load: extern "system" fn(*mut Frame, RawPtr) -> ErrorCode,
+    run: extern "system" fn(*mut Frame) -> ErrorCode,
+    uninitialize: extern "system" fn(*mut Frame) -> ErrorCode,
+}
+
+static SOURCE_VTABLE: ViewSourceVtable = ViewSourceVtable {
+    query_interface: source_query_interface,
+    add_ref: source_add_ref,
+    release: source_release,
+    get_iids,
+    get_runtime_class_name,
+    get_trust_level,
+    create_view,
+};
+
+static FRAME_VTABLE: FrameworkViewVtable = FrameworkViewVtable {
+    query_interface: frame_query_interface,
+    add_ref: frame_add_ref,
+    release: frame_release,
+    get_iids,
+    get_runtime_class_name,
+    get_trust_level,
+    initialize,
+    set_window,
+    load,
+    run: frame_run,
+    uninitialize,
+};
+
+extern "system" fn source_query_interface(
+    this: *mut Source,
+    iid: &Guid,
+    interface: *mut RawPtr,
+) -> ErrorCode {
+    unsafe {
+        if *iid == ViewSource::IID || *iid == IUnknown::IID || *iid == Object::IID {
+            *interface = this as RawPtr;
+            (*this).count.addref();
+            ErrorCode(0)
+        } else {
+            *interface = std::ptr::null_mut();
+            ErrorCode::NO_INTERFACE
+        }
+    }
+}
+
+extern "system" fn source_add_ref(this: *mut Source) -> u32 {
+    unsafe { (*this).count.addref() }
+}
+
+extern "system" fn source_release(this: *mut Source) -> u32 {
+    unsafe {
+        let remaining = (*this).count.release();
+
+        if remaining == 0 {
+            std::mem::drop(Box::from_raw(this));
+        }
+
+        remaining
+    }
+}
+
+extern "system" fn create_view(this: *mut Source, view: *mut RawPtr) -> ErrorCode {
+    unsafe {
+        let created = ((*this).create.borrow_mut())();
+
+        let frame = Box::new(Frame {
+            vtable: &FRAME_VTABLE,
+            count: RefCount::new(1),
+            view: RefCell::new(created),
+        });
+
+        *view = Box::into_raw(frame) as RawPtr;
+    }
+
+    ErrorCode(0)
+}
+
+extern "system" fn frame_query_interface(
+    this: *mut Frame,
+    iid: &Guid,
+    interface: *mut RawPtr,
+) -> ErrorCode {
+    unsafe {
+        if *iid == FrameworkView::IID || *iid == IUnknown::IID || *iid == Object::IID {
+            *interface = this as RawPtr;
+            (*this).count.addref();
+            ErrorCode(0)
+        } else {
+            *interface = std::ptr::null_mut();
+            ErrorCode::NO_INTERFACE
+        }
+    }
+}
+
+extern "system" fn frame_add_ref(this: *mut Frame) -> u32 {
+    unsafe { (*this).count.addref() }
+}
+
+extern "system" fn frame_release(this: *mut Frame) -> u32 {
+    unsafe {
+        let remaining = (*this).count.release();
+
+        if remaining == 0 {
+            std::mem::drop(Box::from_raw(this));
+        }
+
+        remaining
+    }
+}
+
+// Panics can't unwind into the CoreApplication so, with the `std` feature, they are caught and
+// returned as E_UNEXPECTED.
+fn invoke<F: FnOnce(&mut dyn View) -> Result<()>>(this: *mut Frame, callback: F) -> ErrorCode {
+    let call = || unsafe { callback(&mut **(*this).view.borrow_mut()) };
+
+    #[cfg(feature = "std")]
+    return authoring::invoke(call);
+
+    #[cfg(not(feature = "std"))]
+    call().into()
+}
+
+// The arguments are borrowed for the duration of the call, and `Object` and `HString` have the
+// same representation as the raw pointers.
+extern "system" fn initialize(this: *mut Frame, application_view: RawPtr) -> ErrorCode {
+    invoke(this, |view| unsafe {
+        view.initialize(&*(&application_view as *const RawPtr as *const Object))
+    })
+}
+
+extern "system" fn set_window(this: *mut Frame, window: RawPtr) -> ErrorCode {
+    invoke(this, |view| unsafe {
+        view.set_window(&*(&window as *const RawPtr as *const Object))
+    })
+}
+
+extern "system" fn load(this: *mut Frame, entry_point: RawPtr) -> ErrorCode {
+    invoke(this, |view| unsafe {
+        view.load(&*(&entry_point as *const RawPtr as *const HString))
+    })
+}
+
+extern "system" fn frame_run(this: *mut Frame) -> ErrorCode {
+    invoke(this, |view| view.run())
+}
+
+extern "system" fn uninitialize(this: *mut Frame) -> ErrorCode {
+    invoke(this, |view| view.uninitialize())
+}
+
+extern "system" fn get_iids(_this: RawPtr, count: *mut u32, iids: *mut *mut Guid) -> ErrorCode {
+    unsafe {
+        *count = 0;
+        *iids = std::ptr::null_mut();
+    }
+
+    ErrorCode(0)
+}
+
+extern "system" fn get_runtime_class_name(_this: RawPtr, name: *mut RawPtr) -> ErrorCode {
+    unsafe {
+        *name = std::ptr::null_mut();
+    }
+
+    ErrorCode(0)
+}
+
+extern "system" fn get_trust_level(_this: RawPtr, level: *mut i32) -> ErrorCode {
+    unsafe {
+        *level = 0;
+    }
+
+    ErrorCode(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::mem::size_of;
+    use std::rc::Rc;
+
+    // The offset of a vtable's slot in units of pointers.
+    fn slot<V, T>(vtable: &V, slot: &T) -> usize {
+        (slot as *const T as usize - vtable as *const V as usize) / size_of::<usize>()
+    }
+
+    #[test]
+    fn iids() {
+        assert_eq!(
+            ICoreApplication::IID,
+            Guid::from("0AACF7A4-5E1D-49DF-8034-FB6A68BC5ED1")
+        );
+        assert_eq!(
+            ViewSource::IID,
+            Guid::from("CD770614-65C4-426C-9494-34FC43554862")
+        );
+        assert_eq!(
+            FrameworkView::IID,
+            Guid::from("FAAB5CD0-8924-45AC-AD0F-A08FAE5D0324")
+        );
+    }
+
+    #[test]
+    fn vtables() {
+        // `Run` follows the six methods of IInspectable and the seven members of
+        // ICoreApplication that precede it.
+        assert_eq!(size_of::<abi_ICoreApplication>(), 14 * size_of::<usize>());
+
+        assert_eq!(slot(&SOURCE_VTABLE, &SOURCE_VTABLE.create_view), 6);
+        assert_eq!(size_of::<ViewSourceVtable>(), 7 * size_of::<usize>());
+
+        assert_eq!(slot(&FRAME_VTABLE, &FRAME_VTABLE.initialize), 6);
+        assert_eq!(slot(&FRAME_VTABLE, &FRAME_VTABLE.set_window), 7);
+        assert_eq!(slot(&FRAME_VTABLE, &FRAME_VTABLE.load), 8);
+        assert_eq!(slot(&FRAME_VTABLE, &FRAME_VTABLE.run), 9);
+        assert_eq!(slot(&FRAME_VTABLE, &FRAME_VTABLE.uninitialize), 10);
+        assert_eq!(size_of::<FrameworkViewVtable>(), 11 * size_of::<usize>());
+    }
+
+    struct Counter(Rc<RefCell<u32>>);
+
+    impl View for Counter {
+        fn run(&mut self) -> Result<()> {
+            *self.0.borrow_mut() += 1;
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn create_view() {
+        let runs = Rc::new(RefCell::new(0));
+        let counted = runs.clone();
+        let source = ViewSource::new(Box::new(move || Box::new(Counter(counted.clone()))));
+        let this = source.as_raw() as *mut Source;
+
+        unsafe {
+            let mut interface = std::ptr::null_mut();
+            let vtable = &*(*this).vtable;
+            assert_eq!(
+                (vtable.query_interface)(this, &FrameworkView::IID, &mut interface),
+                ErrorCode::NO_INTERFACE
+            );
+            assert!(interface.is_null());
+
+            let mut view = std::ptr::null_mut();
+            assert_eq!((vtable.create_view)(this, &mut view), ErrorCode(0));
+
+            let frame = view as *mut Frame;
+            let vtable = &*(*frame).vtable;
+            assert_eq!(
+                (vtable.query_interface)(frame, &FrameworkView::IID, &mut interface),
+                ErrorCode(0)
+            );
+            assert_eq!(interface, view);
+            assert_eq!((vtable.run)(frame), ErrorCode(0));
+            assert_eq!(*runs.borrow(), 1);
+
+            assert_eq!((vtable.release)(frame), 1);
+            assert_eq!((vtable.release)(frame), 0);
+        }
+    }
+}
@@ -20,8 +20,22 @@ impl<T: RuntimeType> Array<T> {
         Self::default()
     }
 
+    /// Drops the elements and frees the buffer, which is owned by the COM task allocator
     pub fn clear(&mut self) {
-        // TODO: drop members, CoTastkMemFree, zero members
+        if self.data.is_null() {
+            return;
+        }
+
+        unsafe {
+            std::ptr::drop_in_place(std::ptr::slice_from_raw_parts_mut(
+                self.data,
+                self.len as usize,
+            ));
+            heap::co_task_mem_free(self.data as RawPtr);
+        }
+
+        self.data = std::ptr::null_mut();
+        self.len = 0;
     }
 
     pub fn as_slice(&self) -> &[T] {
@@ -43,6 +57,14 @@ impl<T: RuntimeType> Array<T> {
 
 impl<T> Drop for Array<T> {
     fn drop(&mut self) {
-        // TODO: CoTaskMemFree
+        if !self.data.is_null() {
+            unsafe {
+                std::ptr::drop_in_place(std::ptr::slice_from_raw_parts_mut(
+                    self.data,
+                    self.len as usize,
+                ));
+                heap::co_task_mem_free(self.data as RawPtr);
+            }
+        }
     }
 }
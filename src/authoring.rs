@@ -0,0 +1,731 @@
+//! Helpers for objects implemented in Rust that hold event handlers
+//!
+//! An object that raises events holds a strong reference to each handler, and a handler, such as
+//! one registered by a XAML page, commonly holds the page that in turn holds the object. Neither
+//! side is ever released, so a component that isn't careful keeps the whole tree alive. The
+//! helpers here break such cycles from both ends:
+//!
+//! * An [`EventSource`] holds the handlers of one event and releases them all when the object is
+//!   closed, as well as when it is dropped, and drops handlers whose apartment has gone away.
+//! * A [`WeakReferenceSource`] takes the place of an object's reference count so that it can
+//!   implement `IWeakReferenceSource`, which XAML and other callers use to hold the object
+//!   without keeping it alive.
+//!
+//!
+//! The methods of such an object are `extern "system"` functions that a panic must not unwind out
+//! of, so their bodies are run with [`invoke`], which returns their result as an HRESULT:
+//!
+//! ```rust,ignore
+//! extern "system" fn close(this: *mut Widget) -> ErrorCode {
+//!     authoring::invoke(|| {
+//!         unsafe { (*this).changed.clear() };
+//!         Ok(())
+//!     })
+//! }
+//! ```
+
+use crate::unknown::abi_IUnknown;
+use crate::*;
+
+use std::sync::atomic::{AtomicI64, AtomicPtr, AtomicU32, Ordering};
+use std::sync::Mutex;
+
+/// Runs the body of an ABI method implemented in Rust and returns its result as an HRESULT.
+///
+/// A panic can't unwind into the caller, so it is caught and returned as `E_UNEXPECTED`. Both
+/// the panic and any error are logged by the `tracelogging` feature.
+pub fn invoke<F: FnOnce() -> Result<()>>(body: F) -> ErrorCode {
+    match std::panic::catch_unwind(std::panic::AssertUnwindSafe(body)) {
+        Ok(result) => result.into(),
+        Err(panic) => {
+            let message = match panic.downcast_ref::<&str>() {
+                Some(message) => message,
+                None => panic.downcast_ref::<String>().map_or("", |message| message),
+            };
+
+            trace::panic_converted(message, ErrorCode::UNEXPECTED);
+            ErrorCode::UNEXPECTED
+        }
+    }
+}
+
+// The handler's apartment has shut down, so it can no longer be called (RPC_S_SERVER_UNAVAILABLE).
+const SERVER_UNAVAILABLE: ErrorCode = ErrorCode::from_win32(1722);
+
+/// The handlers registered for an event of an object implemented in Rust
+///
+/// Handlers are invoked in the order they were added, from a snapshot of the list, so a handler
+/// may add or remove handlers, including itself, while the event is being raised.
+pub struct EventSource<T: ComInterface> {
+    handlers: Mutex<Vec<(i64, T)>>,
+    next: AtomicI64,
+}
+
+impl<T: ComInterface + Clone> EventSource<T> {
+    pub fn new() -> Self {
+        Self {
+            handlers: Mutex::new(Vec::new()),
+            next: AtomicI64::new(1),
+        }
+    }
+
+    /// Registers a handler, returning the token to remove it with. This is what an event's
+    /// `add` method calls.
+    pub fn add(&self, handler: &T) -> Result<EventRegistrationToken> {
+        if handler.is_null() {
+            return Err(ErrorCode::INVALID_ARGUMENT.into());
+        }
+
+        let token = self.next.fetch_add(1, Ordering::Relaxed);
+        self.handlers.lock().unwrap().push((token, handler.clone()));
+        Ok(EventRegistrationToken::new(token))
+    }
+
+    /// Removes the handler registered with the token. This is what an event's `remove` method
+    /// calls, and unknown tokens are ignored as they are by the event sources of Windows.
+    pub fn remove(&self, token: EventRegistrationToken) {
+        let removed = {
+            let mut handlers = self.handlers.lock().unwrap();
+            handlers
+                .iter()
+                .position(|(value, _)| *value == token.value)
+                .map(|index| handlers.remove(index))
+        };
+
+        // The handler is released outside of the lock as releasing it may run arbitrary code.
+        drop(removed);
+    }
+
+    /// Releases every handler, breaking any cycles through them. This is what an object's
+    /// `Close` method should call, and it is called when the source is dropped.
+    pub fn clear(&self) {
+        let handlers = std::mem::take(&mut *self.handlers.lock().unwrap());
+        drop(handlers);
+    }
+
+    /// Returns the number of registered handlers.
+    pub fn len(&self) -> usize {
+        self.handlers.lock().unwrap().len()
+    }
+
+    /// Returns true if no handlers are registered.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Raises the event by calling `invoke` with each handler.
+    ///
+    /// Every handler is called even if one fails. Handlers that fail because their apartment
+    /// has shut down are removed, and otherwise the first error is returned.
+    pub fn invoke<F: FnMut(&T) -> Result<()>>(&self, mut invoke: F) -> Result<()> {
+        let handlers = self.handlers.lock().unwrap().clone();
+        let mut result = Ok(());
+
+        for (token, handler) in &handlers {
+            if let Err(error) = invoke(handler) {
+                match error.code() {
+                    ErrorCode::DISCONNECTED | SERVER_UNAVAILABLE => {
+                        self.remove(EventRegistrationToken::new(*token))
+                    }
+                    _ if result.is_ok() => result = Err(error),
+                    _ => {}
+                }
+            }
+        }
+
+        result
+    }
+}
+
+impl<T: ComInterface + Clone> Default for EventSource<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The IID of `IWeakReferenceSource`.
+pub const IWEAK_REFERENCE_SOURCE: Guid = Guid::from_values(
+    0x0000_0038,
+    0x0000,
+    0x0000,
+    [0xC0, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x46],
+);
+
+/// The reference count of an object implemented in Rust that supports weak references
+///
+/// The object uses this in place of a plain reference count, calling [`addref`] and [`release`]
+/// from its own `AddRef` and `Release`. Once the object is in its final place in memory it calls
+/// [`set_owner`] with its `IUnknown` pointer, and then answers queries for
+/// [`IWEAK_REFERENCE_SOURCE`] with [`as_raw`]. The weak references handed out share the count,
+/// so they resolve to the object only while it is alive.
+///
+/// [`addref`]: Self::addref
+/// [`release`]: Self::release
+/// [`set_owner`]: Self::set_owner
+/// [`as_raw`]: Self::as_raw
+#[repr(C)]
+pub struct WeakReferenceSource {
+    vtable: *const SourceVtable,
+    reference: *mut WeakReference,
+}
+
+unsafe impl Send for WeakReferenceSource {}
+unsafe impl Sync for WeakReferenceSource {}
+
+impl WeakReferenceSource {
+    /// Creates the count of a new object, which starts with a single strong reference.
+    pub fn new() -> Self {
+        let reference = Box::new(WeakReference {
+            vtable: &REFERENCE_VTABLE,
+            count: AtomicU32::new(1),
+            strong: AtomicU32::new(1),
+            owner: AtomicPtr::new(std::ptr::null_mut()),
+        });
+
+        Self {
+            vtable: &SOURCE_VTABLE,
+            reference: Box::into_raw(reference),
+        }
+    }
+
+    /// Sets the `IUnknown` pointer of the object, which the weak references query when they are
+    /// resolved and which the `IWeakReferenceSource` interface forwards its `IUnknown` methods to.
+    /// Until then, weak references don't resolve.
+    ///
+    /// # Safety
+    /// The pointer must be the object that owns this count, and must remain valid until its
+    /// strong count reaches zero.
+    pub unsafe fn set_owner(&self, owner: RawPtr) {
+        (*self.reference).owner.store(owner, Ordering::Release);
+    }
+
+    /// Adds a strong reference, returning the new count.
+    pub fn addref(&self) -> u32 {
+        unsafe { (*self.reference).strong.fetch_add(1, Ordering::Relaxed) + 1 }
+    }
+
+    /// Removes a strong reference, returning the remaining count. The object should destroy
+    /// itself once this reaches zero, and weak references no longer resolve from then on.
+    pub fn release(&self) -> u32 {
+        unsafe {
+            let remaining = (*self.reference).strong.fetch_sub(1, Ordering::Release) - 1;
+
+            if remaining == 0 {
+                std::sync::atomic::fence(Ordering::Acquire);
+            }
+
+            remaining
+        }
+    }
+
+    /// Returns the `IWeakReferenceSource` interface pointer, without adding a reference.
+    pub fn as_raw(&self) -> RawPtr {
+        self as *const Self as RawPtr
+    }
+
+    unsafe fn owner(&self) -> RawComPtr<IUnknown> {
+        (*self.reference).owner.load(Ordering::Acquire) as RawComPtr<IUnknown>
+    }
+}
+
+impl Default for WeakReferenceSource {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for WeakReferenceSource {
+    fn drop(&mut self) {
+        reference_release(self.reference);
+    }
+}
+
+/// A weak reference to an object, as returned by `IWeakReferenceSource::GetWeakReference`
+#[repr(transparent)]
+#[derive(Default, Clone)]
+pub struct IWeakReference {
+    ptr: ComPtr<IWeakReference>,
+}
+
+impl IWeakReference {
+    /// Returns the object as the interface `T` if it is still alive, and `None` otherwise.
+    pub fn resolve<T: ComInterface>(&self) -> Result<Option<T>> {
+        let (this, vtable) = self.ptr.vtable();
+        let mut object: T = unsafe { std::mem::zeroed() };
+
+        (vtable.resolve)(
+            this as *mut WeakReference,
+            &T::IID,
+            &mut object as *mut T as *mut RawPtr,
+        )
+        .ok()?;
+
+        Ok(nullable::from_abi(object))
+    }
+}
+
+unsafe impl ComInterface for IWeakReference {
+    type VTable = ReferenceVtable;
+    const IID: Guid = Guid::from_values(
+        0x0000_0037,
+        0x0000,
+        0x0000,
+        [0xC0, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x46],
+    );
+}
+
+/// The interface that objects supporting weak references implement
+#[repr(transparent)]
+#[derive(Default, Clone)]
+pub struct IWeakReferenceSource {
+    ptr: ComPtr<IWeakReferenceSource>,
+}
+
+impl IWeakReferenceSource {
+    /// Returns a weak reference to the object.
+    pub fn get_weak_reference(&self) -> Result<IWeakReference> {
+        let (this, vtable) = self.ptr.vtable();
+        let mut reference = IWeakReference::default();
+
+        (vtable.get_weak_reference)(
+            this as *mut WeakReferenceSource,
+            reference.ptr.set_abi() as *mut *mut WeakReference,
+        )
+        .and_then(|| reference)
+    }
+}
+
+unsafe impl ComInterface for IWeakReferenceSource {
+    type VTable = SourceVtable;
+    const IID: Guid = IWEAK_REFERENCE_SOURCE;
+}
+
+// The weak reference is a separate object so that it can outlive the object it refers to. It
+// holds the strong count of the object, along with its own count of the weak references and of
+// the WeakReferenceSource that created it.
+#[repr(C)]
+struct WeakReference {
+    vtable: *const ReferenceVtable,
+    count: AtomicU32,
+    strong: AtomicU32,
+    owner: AtomicPtr<std::ffi::c_void>,
+}
+
+#[doc(hidden)]
+#[repr(C)]
+pub struct SourceVtable {
+    query_interface: extern "system" fn(*mut WeakReferenceSource, &Guid, *mut RawPtr) -> ErrorCode,
+    add_ref: extern "system" fn(*mut WeakReferenceSource) -> u32,
+    release: extern "system" fn(*mut WeakReferenceSource) -> u32,
+    get_weak_reference:
+        extern "system" fn(*mut WeakReferenceSource, *mut *mut WeakReference) -> ErrorCode,
+}
+
+#[doc(hidden)]
+#[repr(C)]
+pub struct ReferenceVtable {
+    query_interface: extern "system" fn(*mut WeakReference, &Guid, *mut RawPtr) -> ErrorCode,
+    add_ref: extern "system" fn(*mut WeakReference) -> u32,
+    release: extern "system" fn(*mut WeakReference) -> u32,
+    resolve: extern "system" fn(*mut WeakReference, &Guid, *mut RawPtr) -> ErrorCode,
+}
+
+static SOURCE_VTABLE: SourceVtable = SourceVtable {
+    query_interface: source_query_interface,
+    add_ref: source_add_ref,
+    release: source_release,
+    get_weak_reference,
+};
+
+static REFERENCE_VTABLE: ReferenceVtable = ReferenceVtable {
+    query_interface: reference_query_interface,
+    add_ref: reference_add_ref,
+    release: reference_release,
+    resolve,
+};
+
+// The IUnknown methods of IWeakReferenceSource are those of the object that implements it.
+fn owner_vtable(this: *mut WeakReferenceSource) -> (RawComPtr<IUnknown>, &'static abi_IUnknown) {
+    unsafe {
+        let owner = (*this).owner();
+        (owner, &**owner)
+    }
+}
+
+extern "system" fn source_query_interface(
+    this: *mut WeakReferenceSource,
+    iid: &Guid,
+    interface: *mut RawPtr,
+) -> ErrorCode {
+    let (owner, vtable) = owner_vtable(this);
+    (vtable.unknown_query_interface)(owner, iid, interface)
+}
+
+extern "system" fn source_add_ref(this: *mut WeakReferenceSource) -> u32 {
+    let (owner, vtable) = owner_vtable(this);
+    (vtable.unknown_add_ref)(owner)
+}
+
+extern "system" fn source_release(this: *mut WeakReferenceSource) -> u32 {
+    let (owner, vtable) = owner_vtable(this);
+    (vtable.unknown_release)(owner)
+}
+
+extern "system" fn get_weak_reference(
+    this: *mut WeakReferenceSource,
+    reference: *mut *mut WeakReference,
+) -> ErrorCode {
+    unsafe {
+        let value = (*this).reference;
+        reference_add_ref(value);
+        *reference = value;
+    }
+
+    ErrorCode(0)
+}
+
+extern "system" fn reference_query_interface(
+    this: *mut WeakReference,
+    iid: &Guid,
+    interface: *mut RawPtr,
+) -> ErrorCode {
+    unsafe {
+        if *iid == IWeakReference::IID || *iid == IUnknown::IID {
+            *interface = this as RawPtr;
+            reference_add_ref(this);
+            ErrorCode(0)
+        } else {
+            *interface = std::ptr::null_mut();
+            ErrorCode::NO_INTERFACE
+        }
+    }
+}
+
+extern "system" fn reference_add_ref(this: *mut WeakReference) -> u32 {
+    unsafe { (*this).count.fetch_add(1, Ordering::Relaxed) + 1 }
+}
+
+extern "system" fn reference_release(this: *mut WeakReference) -> u32 {
+    unsafe {
+        let remaining = (*this).count.fetch_sub(1, Ordering::Release) - 1;
+
+        if remaining == 0 {
+            std::sync::atomic::fence(Ordering::Acquire);
+            drop(Box::from_raw(this));
+        }
+
+        remaining
+    }
+}
+
+extern "system" fn resolve(
+    this: *mut WeakReference,
+    iid: &Guid,
+    interface: *mut RawPtr,
+) -> ErrorCode {
+    unsafe {
+        *interface = std::ptr::null_mut();
+
+        // The weak references of an object that hasn't called `set_owner` yet have nothing to
+        // resolve to.
+        let owner = (*this).owner.load(Ordering::Acquire) as RawComPtr<IUnknown>;
+
+        if owner.is_null() {
+            return ErrorCode(0);
+        }
+
+        // A strong reference is only taken while there is still one, as an object whose count
+        // has reached zero is already being destroyed.
+        let strong = &(*this).strong;
+        let mut count = strong.load(Ordering::Relaxed);
+
+        loop {
+            if count == 0 {
+                return ErrorCode(0);
+            }
+
+            match strong.compare_exchange_weak(
+                count,
+                count + 1,
+                Ordering::Acquire,
+                Ordering::Relaxed,
+            ) {
+                Ok(_) => break,
+                Err(actual) => count = actual,
+            }
+        }
+
+        let result = ((**owner).unknown_query_interface)(owner, iid, interface);
+        ((**owner).unknown_release)(owner);
+        result
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use std::sync::atomic::AtomicUsize;
+
+    // The number of widgets and handlers that haven't been destroyed, for detecting leaks.
+    static WIDGETS: AtomicUsize = AtomicUsize::new(0);
+    static HANDLERS: AtomicUsize = AtomicUsize::new(0);
+
+    // The tests share the counts above.
+    pub(crate) static LOCK: Mutex<()> = Mutex::new(());
+
+    // An authored object with an event, and a handler that holds a strong reference to it, as
+    // the handler of a XAML page holds the page that holds the object.
+    #[repr(C)]
+    struct Widget {
+        vtable: *const abi_IUnknown,
+        count: WeakReferenceSource,
+        changed: EventSource<IUnknown>,
+    }
+
+    #[repr(C)]
+    struct Handler {
+        vtable: *const abi_IUnknown,
+        count: AtomicU32,
+        widget: IUnknown,
+    }
+
+    static WIDGET_VTABLE: abi_IUnknown = abi_IUnknown {
+        unknown_query_interface: widget_query_interface,
+        unknown_add_ref: widget_add_ref,
+        unknown_release: widget_release,
+    };
+
+    static HANDLER_VTABLE: abi_IUnknown = abi_IUnknown {
+        unknown_query_interface: handler_query_interface,
+        unknown_add_ref: handler_add_ref,
+        unknown_release: handler_release,
+    };
+
+    pub(crate) fn widget() -> IUnknown {
+        let widget = Box::into_raw(Box::new(Widget {
+            vtable: &WIDGET_VTABLE,
+            count: WeakReferenceSource::new(),
+            changed: EventSource::new(),
+        }));
+
+        WIDGETS.fetch_add(1, Ordering::SeqCst);
+
+        unsafe {
+            (*widget).count.set_owner(widget as RawPtr);
+            std::mem::transmute(widget)
+        }
+    }
+
+    fn handler(widget: &IUnknown) -> IUnknown {
+        let handler = Box::into_raw(Box::new(Handler {
+            vtable: &HANDLER_VTABLE,
+            count: AtomicU32::new(1),
+            widget: widget.clone(),
+        }));
+
+        HANDLERS.fetch_add(1, Ordering::SeqCst);
+        unsafe { std::mem::transmute(handler) }
+    }
+
+    fn changed(widget: &IUnknown) -> &EventSource<IUnknown> {
+        unsafe { &(*(widget.as_raw() as *const Widget)).changed }
+    }
+
+    extern "system" fn widget_query_interface(
+        this: RawComPtr<IUnknown>,
+        iid: &Guid,
+        interface: *mut RawPtr,
+    ) -> ErrorCode {
+        unsafe {
+            let widget = this as *mut Widget;
+
+            if *iid == IUnknown::IID {
+                *interface = widget as RawPtr;
+            } else if *iid == IWEAK_REFERENCE_SOURCE {
+                *interface = (*widget).count.as_raw();
+            } else {
+                *interface = std::ptr::null_mut();
+                return ErrorCode::NO_INTERFACE;
+            }
+
+            (*widget).count.addref();
+            ErrorCode(0)
+        }
+    }
+
+    extern "system" fn widget_add_ref(this: RawComPtr<IUnknown>) -> u32 {
+        unsafe { (*(this as *mut Widget)).count.addref() }
+    }
+
+    extern "system" fn widget_release(this: RawComPtr<IUnknown>) -> u32 {
+        unsafe {
+            let remaining = (*(this as *mut Widget)).count.release();
+
+            if remaining == 0 {
+                drop(Box::from_raw(this as *mut Widget));
+                WIDGETS.fetch_sub(1, Ordering::SeqCst);
+            }
+
+            remaining
+        }
+    }
+
+    extern "system" fn handler_query_interface(
+        this: RawComPtr<IUnknown>,
+        iid: &Guid,
+        interface: *mut RawPtr,
+    ) -> ErrorCode {
+        unsafe {
+            if *iid == IUnknown::IID {
+                *interface = this as RawPtr;
+                handler_add_ref(this);
+                ErrorCode(0)
+            } else {
+                *interface = std::ptr::null_mut();
+                ErrorCode::NO_INTERFACE
+            }
+        }
+    }
+
+    extern "system" fn handler_add_ref(this: RawComPtr<IUnknown>) -> u32 {
+        unsafe {
+            (*(this as *mut Handler))
+                .count
+                .fetch_add(1, Ordering::Relaxed)
+                + 1
+        }
+    }
+
+    extern "system" fn handler_release(this: RawComPtr<IUnknown>) -> u32 {
+        unsafe {
+            let remaining = (*(this as *mut Handler))
+                .count
+                .fetch_sub(1, Ordering::Release)
+                - 1;
+
+            if remaining == 0 {
+                drop(Box::from_raw(this as *mut Handler));
+                HANDLERS.fetch_sub(1, Ordering::SeqCst);
+            }
+
+            remaining
+        }
+    }
+
+    fn live() -> (usize, usize) {
+        (
+            WIDGETS.load(Ordering::SeqCst),
+            HANDLERS.load(Ordering::SeqCst),
+        )
+    }
+
+    #[test]
+    fn cycle_leaks_without_close() {
+        let _lock = LOCK.lock().unwrap();
+        let widget = widget();
+        changed(&widget).add(&handler(&widget)).unwrap();
+
+        // The handler keeps the widget alive, and the widget keeps the handler alive.
+        drop(widget);
+        assert_eq!(live(), (1, 1));
+
+        WIDGETS.store(0, Ordering::SeqCst);
+        HANDLERS.store(0, Ordering::SeqCst);
+    }
+
+    #[test]
+    fn close_breaks_cycle() {
+        let _lock = LOCK.lock().unwrap();
+        let widget = widget();
+        changed(&widget).add(&handler(&widget)).unwrap();
+        assert_eq!(changed(&widget).len(), 1);
+
+        changed(&widget).clear();
+        assert_eq!(live(), (1, 0));
+
+        drop(widget);
+        assert_eq!(live(), (0, 0));
+    }
+
+    #[test]
+    fn remove() {
+        let _lock = LOCK.lock().unwrap();
+        let widget = widget();
+        let first = changed(&widget).add(&handler(&widget)).unwrap();
+        let second = changed(&widget).add(&handler(&widget)).unwrap();
+        assert!(first != second);
+
+        changed(&widget).remove(first);
+        changed(&widget).remove(first);
+        assert_eq!(live(), (1, 1));
+
+        let mut calls = 0;
+        changed(&widget)
+            .invoke(|_| {
+                calls += 1;
+                Ok(())
+            })
+            .unwrap();
+        assert_eq!(calls, 1);
+
+        changed(&widget).remove(second);
+        assert!(changed(&widget).is_empty());
+
+        drop(widget);
+        assert_eq!(live(), (0, 0));
+    }
+
+    #[test]
+    fn disconnected_handlers_are_removed() {
+        let _lock = LOCK.lock().unwrap();
+        let widget = widget();
+        changed(&widget).add(&handler(&widget)).unwrap();
+
+        let result = changed(&widget).invoke(|_| Err(ErrorCode::DISCONNECTED.into()));
+        assert!(result.is_ok());
+        assert!(changed(&widget).is_empty());
+
+        drop(widget);
+        assert_eq!(live(), (0, 0));
+    }
+
+    #[test]
+    fn weak_reference() {
+        let _lock = LOCK.lock().unwrap();
+        let widget = widget();
+
+        let source: IWeakReferenceSource = widget.query();
+        assert!(!source.is_null());
+        let reference = source.get_weak_reference().unwrap();
+        drop(source);
+
+        let resolved: IUnknown = reference.resolve().unwrap().unwrap();
+        assert!(resolved == widget);
+        drop(resolved);
+
+        // The weak reference neither keeps the widget alive nor outlives its usefulness.
+        drop(widget);
+        assert_eq!(live(), (0, 0));
+        assert!(reference.resolve::<IUnknown>().unwrap().is_none());
+    }
+
+    #[test]
+    fn unowned_weak_reference() {
+        let count = WeakReferenceSource::new();
+
+        // Releasing the source would forward to its owner, which it doesn't have yet.
+        let source = std::mem::ManuallyDrop::new(unsafe {
+            std::mem::transmute::<RawPtr, IWeakReferenceSource>(count.as_raw())
+        });
+
+        let reference = source.get_weak_reference().unwrap();
+        assert!(reference.resolve::<IUnknown>().unwrap().is_none());
+    }
+
+    #[test]
+    fn invoke_abi() {
+        assert_eq!(invoke(|| Ok(())), ErrorCode(0));
+        assert_eq!(invoke(|| Err(ErrorCode::BOUNDS.into())), ErrorCode::BOUNDS);
+        assert_eq!(invoke(|| panic!("unwound")), ErrorCode::UNEXPECTED);
+    }
+}
@@ -0,0 +1,136 @@
+use crate::runtime;
+use crate::*;
+
+use alloc::sync::Arc;
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll, RawWaker, RawWakerVTable, Waker};
+
+/// Runs a future to completion on the current thread, blocking until it is ready.
+///
+/// If the calling thread is a single-threaded apartment (STA), such as a UI thread, the wait
+/// is performed with `CoWaitForMultipleHandles` so that window messages and COM calls continue
+/// to be dispatched. Blindly waiting on an STA would otherwise deadlock any WinRT async operation
+/// that needs to call back into the apartment in order to complete.
+pub fn block_on<F: Future>(future: F) -> F::Output {
+    let signal = Arc::new(Signal::new());
+    let waker = signal.waker();
+    let mut context = Context::from_waker(&waker);
+
+    let mut future = future;
+    // The future is shadowed so that it can never be moved again once pinned.
+    let mut future = unsafe { Pin::new_unchecked(&mut future) };
+
+    loop {
+        if let Poll::Ready(output) = future.as_mut().poll(&mut context) {
+            return output;
+        }
+
+        signal.wait();
+    }
+}
+
+/// An auto-reset event that wakes the blocked thread.
+struct Signal {
+    event: RawPtr,
+}
+
+// The event handle may be signaled from any thread.
+unsafe impl Send for Signal {}
+unsafe impl Sync for Signal {}
+
+impl Signal {
+    fn new() -> Self {
+        let event = unsafe { runtime::CreateEventW(std::ptr::null_mut(), 0, 0, std::ptr::null()) };
+
+        if event.is_null() {
+            panic!("Could not successfully create an event for block_on");
+        }
+
+        Self { event }
+    }
+
+    fn set(&self) {
+        unsafe {
+            runtime::SetEvent(self.event);
+        }
+    }
+
+    // The wait is only pumped on an STA. Should `CoWaitForMultipleHandles` fail, for example
+    // because the apartment is being torn down, the thread waits without pumping rather than
+    // panicking on a thread that the caller owns. A wait that times out is simply retried.
+    fn wait(&self) {
+        if is_sta() {
+            loop {
+                let mut index = 0;
+
+                let code = unsafe {
+                    runtime::CoWaitForMultipleHandles(
+                        runtime::COWAIT_DISPATCH_CALLS | runtime::COWAIT_DISPATCH_WINDOW_MESSAGES,
+                        runtime::INFINITE,
+                        1,
+                        &self.event,
+                        &mut index,
+                    )
+                };
+
+                if code.is_ok() {
+                    return;
+                }
+
+                if code != runtime::RPC_S_CALLPENDING {
+                    break;
+                }
+            }
+        }
+
+        unsafe {
+            runtime::WaitForSingleObject(self.event, runtime::INFINITE);
+        }
+    }
+
+    fn waker(self: &Arc<Self>) -> Waker {
+        let raw = Arc::into_raw(self.clone()) as *const ();
+        unsafe { Waker::from_raw(RawWaker::new(raw, &VTABLE)) }
+    }
+}
+
+impl Drop for Signal {
+    fn drop(&mut self) {
+        unsafe {
+            runtime::CloseHandle(self.event);
+        }
+    }
+}
+
+fn is_sta() -> bool {
+    let mut apartment_type = 0;
+    let mut qualifier = 0;
+
+    let code = unsafe { runtime::CoGetApartmentType(&mut apartment_type, &mut qualifier) };
+
+    code.is_ok()
+        && (apartment_type == runtime::APTTYPE_STA || apartment_type == runtime::APTTYPE_MAINSTA)
+}
+
+static VTABLE: RawWakerVTable = RawWakerVTable::new(clone, wake, wake_by_ref, drop);
+
+unsafe fn clone(data: *const ()) -> RawWaker {
+    let signal = std::mem::ManuallyDrop::new(Arc::from_raw(data as *const Signal));
+    let raw = Arc::into_raw(Arc::clone(&signal)) as *const ();
+    RawWaker::new(raw, &VTABLE)
+}
+
+unsafe fn wake(data: *const ()) {
+    let signal = Arc::from_raw(data as *const Signal);
+    signal.set();
+}
+
+unsafe fn wake_by_ref(data: *const ()) {
+    let signal = &*(data as *const Signal);
+    signal.set();
+}
+
+unsafe fn drop(data: *const ()) {
+    std::mem::drop(Arc::from_raw(data as *const Signal));
+}
@@ -0,0 +1,229 @@
+use crate::delegate::{add_ref, release, Counted};
+use crate::interop::IBufferByteAccess;
+use crate::ref_count::RefCount;
+use crate::unknown::IAGILE_OBJECT;
+use crate::*;
+
+use std::sync::atomic::{AtomicU32, Ordering};
+
+// A fixed capacity IBuffer backed by Rust memory. The Windows.Storage.Streams.Buffer class isn't
+// available to the runtime, and may not have been imported alongside IInputStream, so readers
+// provide their own buffer. It also implements IBufferByteAccess, as every IBuffer is expected
+// to, so the bytes read into it can be accessed directly. The bytes are only ever freed by the
+// buffer itself, so they come from the allocator installed with `heap::set_allocator`.
+
+/// Creates an empty buffer of the given capacity as the interface `T`, which must be `IBuffer`.
+pub(crate) fn create_buffer<T: ComInterface>(capacity: u32) -> T {
+    // The allocator isn't asked for zero bytes, and a buffer without capacity still needs a
+    // pointer to hand out through IBufferByteAccess.
+    let data = unsafe { heap::alloc(capacity.max(1) as usize) as *mut u8 };
+
+    if data.is_null() {
+        panic!("Could not successfully allocate for IBuffer");
+    }
+
+    unsafe { std::ptr::write_bytes(data, 0, capacity as usize) };
+
+    let buffer = Box::new(Buffer {
+        vtable: &VTABLE,
+        byte_access: &BYTE_ACCESS_VTABLE,
+        count: RefCount::new(1),
+        iid: T::IID,
+        length: AtomicU32::new(0),
+        capacity,
+        data,
+    });
+
+    let ptr = Box::into_raw(buffer);
+    unsafe { std::mem::transmute_copy(&ptr) }
+}
+
+/// Creates a buffer holding a copy of the given bytes as the interface `T`, which must be
+/// `IBuffer`.
+pub(crate) fn create_buffer_from<T: ComInterface>(bytes: &[u8]) -> T {
+    let buffer: T = create_buffer(bytes.len() as u32);
+
+    unsafe {
+        let this = buffer.as_raw() as *mut Buffer;
+        std::ptr::copy_nonoverlapping(bytes.as_ptr(), (*this).data, bytes.len());
+        (*this).length.store(bytes.len() as u32, Ordering::Release);
+    }
+
+    buffer
+}
+
+#[repr(C)]
+struct Buffer {
+    vtable: *const BufferVtable,
+    byte_access: *const ByteAccessVtable,
+    count: RefCount,
+    iid: Guid,
+    length: AtomicU32,
+    capacity: u32,
+    data: *mut u8,
+}
+
+impl Buffer {
+    // The IBufferByteAccess interface pointer immediately follows the IBuffer vtable pointer.
+    unsafe fn from_byte_access(this: *mut *const ByteAccessVtable) -> *mut Buffer {
+        this.offset(-1) as *mut Buffer
+    }
+}
+
+impl Counted for Buffer {
+    fn count(&self) -> &RefCount {
+        &self.count
+    }
+}
+
+impl Drop for Buffer {
+    fn drop(&mut self) {
+        unsafe { heap::free(self.data as RawPtr) };
+    }
+}
+
+#[repr(C)]
+struct BufferVtable {
+    query_interface: extern "system" fn(*mut Buffer, &Guid, *mut RawPtr) -> ErrorCode,
+    add_ref: extern "system" fn(*mut Buffer) -> u32,
+    release: extern "system" fn(*mut Buffer) -> u32,
+    get_iids: extern "system" fn(*mut Buffer, *mut u32, *mut *mut Guid) -> ErrorCode,
+    get_runtime_class_name: extern "system" fn(*mut Buffer, *mut RawPtr) -> ErrorCode,
+    get_trust_level: extern "system" fn(*mut Buffer, *mut i32) -> ErrorCode,
+    capacity: extern "system" fn(*mut Buffer, *mut u32) -> ErrorCode,
+    length: extern "system" fn(*mut Buffer, *mut u32) -> ErrorCode,
+    set_length: extern "system" fn(*mut Buffer, u32) -> ErrorCode,
+}
+
+#[repr(C)]
+struct ByteAccessVtable {
+    query_interface:
+        extern "system" fn(*mut *const ByteAccessVtable, &Guid, *mut RawPtr) -> ErrorCode,
+    add_ref: extern "system" fn(*mut *const ByteAccessVtable) -> u32,
+    release: extern "system" fn(*mut *const ByteAccessVtable) -> u32,
+    buffer: extern "system" fn(*mut *const ByteAccessVtable, *mut *mut u8) -> ErrorCode,
+}
+
+static VTABLE: BufferVtable = BufferVtable {
+    query_interface,
+    add_ref: add_ref::<Buffer>,
+    release: release::<Buffer>,
+    get_iids,
+    get_runtime_class_name,
+    get_trust_level,
+    capacity,
+    length,
+    set_length,
+};
+
+static BYTE_ACCESS_VTABLE: ByteAccessVtable = ByteAccessVtable {
+    query_interface: byte_access_query_interface,
+    add_ref: byte_access_add_ref,
+    release: byte_access_release,
+    buffer,
+};
+
+extern "system" fn query_interface(
+    this: *mut Buffer,
+    iid: &Guid,
+    interface: *mut RawPtr,
+) -> ErrorCode {
+    unsafe {
+        if *iid == (*this).iid
+            || *iid == IUnknown::IID
+            || *iid == Object::IID
+            || *iid == IAGILE_OBJECT
+        {
+            *interface = this as RawPtr;
+        } else if *iid == IBufferByteAccess::IID {
+            *interface = &mut (*this).byte_access as *mut _ as RawPtr;
+        } else {
+            *interface = std::ptr::null_mut();
+            return ErrorCode::NO_INTERFACE;
+        }
+
+        (*this).count.addref();
+        ErrorCode(0)
+    }
+}
+
+extern "system" fn get_iids(
+    _this: *mut Buffer,
+    count: *mut u32,
+    iids: *mut *mut Guid,
+) -> ErrorCode {
+    unsafe {
+        *count = 0;
+        *iids = std::ptr::null_mut();
+    }
+
+    ErrorCode(0)
+}
+
+extern "system" fn get_runtime_class_name(_this: *mut Buffer, name: *mut RawPtr) -> ErrorCode {
+    unsafe {
+        *name = std::ptr::null_mut();
+    }
+
+    ErrorCode(0)
+}
+
+extern "system" fn get_trust_level(_this: *mut Buffer, level: *mut i32) -> ErrorCode {
+    unsafe {
+        *level = 0;
+    }
+
+    ErrorCode(0)
+}
+
+extern "system" fn capacity(this: *mut Buffer, value: *mut u32) -> ErrorCode {
+    unsafe {
+        *value = (*this).capacity;
+    }
+
+    ErrorCode(0)
+}
+
+extern "system" fn length(this: *mut Buffer, value: *mut u32) -> ErrorCode {
+    unsafe {
+        *value = (*this).length.load(Ordering::Acquire);
+    }
+
+    ErrorCode(0)
+}
+
+extern "system" fn set_length(this: *mut Buffer, value: u32) -> ErrorCode {
+    unsafe {
+        if value > (*this).capacity {
+            return ErrorCode::INVALID_ARGUMENT;
+        }
+
+        (*this).length.store(value, Ordering::Release);
+    }
+
+    ErrorCode(0)
+}
+
+extern "system" fn byte_access_query_interface(
+    this: *mut *const ByteAccessVtable,
+    iid: &Guid,
+    interface: *mut RawPtr,
+) -> ErrorCode {
+    unsafe { query_interface(Buffer::from_byte_access(this), iid, interface) }
+}
+
+extern "system" fn byte_access_add_ref(this: *mut *const ByteAccessVtable) -> u32 {
+    unsafe { add_ref(Buffer::from_byte_access(this)) }
+}
+
+extern "system" fn byte_access_release(this: *mut *const ByteAccessVtable) -> u32 {
+    unsafe { release(Buffer::from_byte_access(this)) }
+}
+
+extern "system" fn buffer(this: *mut *const ByteAccessVtable, value: *mut *mut u8) -> ErrorCode {
+    unsafe {
+        *value = (*Buffer::from_byte_access(this)).data;
+    }
+
+    ErrorCode(0)
+}
@@ -0,0 +1,244 @@
+use crate::runtime;
+use crate::*;
+
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::task::{Context, Poll, Waker};
+use std::time::Duration;
+
+/// A WinRT async type whose underlying operation can be canceled
+///
+/// This is implemented for the WinRT async interfaces and any classes that implement them.
+/// Dropping a WinRT future does not stop the operation so the combinators provided here
+/// explicitly cancel the operation via `IAsyncInfo::Cancel` when it is no longer needed.
+pub trait Cancelable: Future + Unpin + Sized {
+    /// Requests cancellation of the underlying async operation
+    fn cancel(&self) -> Result<()>;
+
+    /// Completes with a timeout error, and cancels the operation, if the operation does not
+    /// complete within the given duration.
+    fn with_timeout(self, duration: Duration) -> Timeout<Self> {
+        Timeout {
+            operation: self,
+            duration,
+            timer: None,
+            complete: false,
+        }
+    }
+
+    /// Completes with whichever operation completes first and cancels the other.
+    fn race<Other: Cancelable<Output = Self::Output>>(self, other: Other) -> Race<Self, Other> {
+        Race {
+            first: self,
+            second: other,
+            complete: false,
+        }
+    }
+}
+
+/// The state of a WinRT async operation, corresponding to `AsyncStatus`
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum AsyncState {
+    /// The operation is still running
+    Started,
+    /// The operation completed successfully
+    Completed,
+    /// The operation was canceled
+    Canceled,
+    /// The operation failed, as described by [`AsyncInfo::error_code`]
+    Error,
+}
+
+impl AsyncState {
+    /// Converts from the `AsyncStatus` ABI value.
+    pub fn from_abi(value: i32) -> Self {
+        match value {
+            0 => Self::Started,
+            1 => Self::Completed,
+            2 => Self::Canceled,
+            _ => Self::Error,
+        }
+    }
+}
+
+/// The state of a WinRT async operation, as provided by `IAsyncInfo`
+///
+/// This is implemented for the WinRT async interfaces and any classes that implement them so
+/// that callers can inspect an operation without polling it, for example to orchestrate several
+/// operations or to report progress. The projected types provide the same methods directly, but
+/// with the `AsyncStatus` and `HResult` types of `Windows.Foundation`.
+pub trait AsyncInfo: Cancelable {
+    /// Returns the current state of the operation.
+    fn status(&self) -> Result<AsyncState>;
+
+    /// Returns the error that the operation failed with, or success if it hasn't failed.
+    fn error_code(&self) -> Result<ErrorCode>;
+
+    /// Returns the identifier of the operation.
+    fn id(&self) -> Result<u32>;
+}
+
+/// A future returned by [`Cancelable::with_timeout`]
+///
+/// Dropping the future before it completes cancels the operation.
+pub struct Timeout<F: Cancelable> {
+    operation: F,
+    duration: Duration,
+    timer: Option<Timer>,
+    complete: bool,
+}
+
+impl<F, T> Future for Timeout<F>
+where
+    F: Cancelable<Output = Result<T>>,
+{
+    type Output = Result<T>;
+
+    fn poll(mut self: Pin<&mut Self>, context: &mut Context<'_>) -> Poll<Self::Output> {
+        if let Poll::Ready(output) = Pin::new(&mut self.operation).poll(context) {
+            self.complete = true;
+            return Poll::Ready(output);
+        }
+
+        if self.timer.is_none() {
+            self.timer = Some(Timer::new(self.duration)?);
+        }
+
+        if self.timer.as_ref().unwrap().set_waker(context.waker()) {
+            self.complete = true;
+            self.operation.cancel()?;
+            return Poll::Ready(Err(ErrorCode::TIMEOUT.into()));
+        }
+
+        Poll::Pending
+    }
+}
+
+impl<F: Cancelable> Drop for Timeout<F> {
+    fn drop(&mut self) {
+        if !self.complete {
+            let _ = self.operation.cancel();
+        }
+    }
+}
+
+/// A future returned by [`Cancelable::race`]
+///
+/// Dropping the future before it completes cancels both operations.
+pub struct Race<A: Cancelable, B: Cancelable> {
+    first: A,
+    second: B,
+    complete: bool,
+}
+
+impl<A, B> Future for Race<A, B>
+where
+    A: Cancelable,
+    B: Cancelable<Output = A::Output>,
+{
+    type Output = A::Output;
+
+    fn poll(mut self: Pin<&mut Self>, context: &mut Context<'_>) -> Poll<Self::Output> {
+        if let Poll::Ready(output) = Pin::new(&mut self.first).poll(context) {
+            self.complete = true;
+            let _ = self.second.cancel();
+            return Poll::Ready(output);
+        }
+
+        if let Poll::Ready(output) = Pin::new(&mut self.second).poll(context) {
+            self.complete = true;
+            let _ = self.first.cancel();
+            return Poll::Ready(output);
+        }
+
+        Poll::Pending
+    }
+}
+
+impl<A: Cancelable, B: Cancelable> Drop for Race<A, B> {
+    fn drop(&mut self) {
+        if !self.complete {
+            let _ = self.first.cancel();
+            let _ = self.second.cancel();
+        }
+    }
+}
+
+/// A one-shot timer that wakes the most recent waker when it fires.
+struct Timer {
+    handle: RawPtr,
+    state: *mut TimerState,
+}
+
+struct TimerState {
+    fired: AtomicBool,
+    waker: Mutex<Option<Waker>>,
+}
+
+impl Timer {
+    fn new(duration: Duration) -> Result<Self> {
+        let state = Box::into_raw(Box::new(TimerState {
+            fired: AtomicBool::new(false),
+            waker: Mutex::new(None),
+        }));
+
+        let mut handle = std::ptr::null_mut();
+        let milliseconds = duration.as_millis().min(runtime::INFINITE as u128 - 1) as u32;
+
+        let created = unsafe {
+            runtime::CreateTimerQueueTimer(
+                &mut handle,
+                std::ptr::null_mut(),
+                fire,
+                state as RawPtr,
+                milliseconds,
+                0,
+                runtime::WT_EXECUTEONLYONCE,
+            )
+        };
+
+        if created == 0 {
+            let error = std::io::Error::last_os_error();
+            unsafe { std::mem::drop(Box::from_raw(state)) };
+            return Err(error.into());
+        }
+
+        Ok(Self { handle, state })
+    }
+
+    /// Registers the waker and returns whether the timer has already fired.
+    fn set_waker(&self, waker: &Waker) -> bool {
+        let state = unsafe { &*self.state };
+        *state.waker.lock().unwrap() = Some(waker.clone());
+        state.fired.load(Ordering::Acquire)
+    }
+}
+
+impl Drop for Timer {
+    fn drop(&mut self) {
+        unsafe {
+            // Waits for any running callback to complete before releasing the shared state.
+            runtime::DeleteTimerQueueTimer(
+                std::ptr::null_mut(),
+                self.handle,
+                runtime::INVALID_HANDLE_VALUE,
+            );
+            std::mem::drop(Box::from_raw(self.state));
+        }
+    }
+}
+
+// The shared state is synchronized so the timer may move between threads.
+unsafe impl Send for Timer {}
+unsafe impl Sync for Timer {}
+
+extern "system" fn fire(parameter: RawPtr, _fired: u8) {
+    let state = unsafe { &*(parameter as *const TimerState) };
+    state.fired.store(true, Ordering::Release);
+
+    if let Some(waker) = state.waker.lock().unwrap().take() {
+        waker.wake();
+    }
+}
@@ -0,0 +1,88 @@
+use crate::*;
+
+use std::fmt;
+
+/// Extends COM interfaces with a checked conversion to another interface or class
+///
+/// Unlike [`TryInto`], whose error only names the requested interface, the error returned by a
+/// failed cast names both the runtime class of the source object and the requested type.
+pub trait Cast: ComInterface {
+    fn cast<T: ComInterface>(&self) -> std::result::Result<T, CastError>;
+}
+
+impl<S: ComInterface> Cast for S {
+    fn cast<T: ComInterface>(&self) -> std::result::Result<T, CastError> {
+        TryInto::<T>::try_into(self).map_err(|error| CastError {
+            code: error.code(),
+            source: runtime_class_name(self),
+            target: std::any::type_name::<T>(),
+        })
+    }
+}
+
+/// The error returned when an object does not implement the requested interface
+#[derive(Clone, Debug, PartialEq)]
+pub struct CastError {
+    code: ErrorCode,
+    source: String,
+    target: &'static str,
+}
+
+impl CastError {
+    pub fn code(&self) -> ErrorCode {
+        self.code
+    }
+
+    /// The runtime class name of the object that was cast, or an empty string if the object
+    /// does not provide one.
+    pub fn source_name(&self) -> &str {
+        &self.source
+    }
+
+    /// The name of the type the object was cast to.
+    pub fn target_name(&self) -> &'static str {
+        self.target
+    }
+}
+
+impl CastError {
+    // Describes the cast without the error code, which `Error` displays alongside its message.
+    fn description(&self) -> String {
+        if self.source.is_empty() {
+            format!("cannot cast object to `{}`", self.target)
+        } else {
+            format!("cannot cast `{}` to `{}`", self.source, self.target)
+        }
+    }
+}
+
+impl fmt::Display for CastError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} (HRESULT 0x{:08X})", self.description(), self.code.0)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for CastError {}
+
+// The names of the types are kept as the error's message.
+impl From<CastError> for Error {
+    fn from(error: CastError) -> Self {
+        Error::from(error.code).with_message(error.description())
+    }
+}
+
+fn runtime_class_name<S: ComInterface>(source: &S) -> String {
+    // Not every COM interface derives from IInspectable, so the name is only read from an
+    // IInspectable pointer obtained through QueryInterface.
+    let object: Object = source.query();
+
+    if object.is_null() {
+        return String::new();
+    }
+
+    object
+        .type_name()
+        .map(|name| name.to_string())
+        .unwrap_or_default()
+}
@@ -0,0 +1,109 @@
+use crate::*;
+
+use std::convert::TryFrom;
+use std::fmt;
+
+/// A UTF-16 code unit, the WinRT `Char` type
+///
+/// WinRT characters are single UTF-16 code units so, unlike a Rust `char`, they may hold half of
+/// a surrogate pair and can't represent characters outside of the Basic Multilingual Plane.
+#[repr(transparent)]
+#[derive(Copy, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Char16(pub u16);
+
+impl Char16 {
+    /// Returns true if the code unit is half of a surrogate pair, and so is not a character on
+    /// its own.
+    pub fn is_surrogate(self) -> bool {
+        self.0 >= 0xD800 && self.0 <= 0xDFFF
+    }
+
+    /// Returns the character, or `None` if the code unit is half of a surrogate pair.
+    pub fn to_char(self) -> Option<char> {
+        std::char::from_u32(self.0 as u32)
+    }
+}
+
+impl From<u16> for Char16 {
+    fn from(value: u16) -> Self {
+        Char16(value)
+    }
+}
+
+impl From<Char16> for u16 {
+    fn from(value: Char16) -> Self {
+        value.0
+    }
+}
+
+/// Converts a code unit into a character, replacing half of a surrogate pair with
+/// `char::REPLACEMENT_CHARACTER`. Use `Char16::to_char` to detect surrogates instead.
+impl From<Char16> for char {
+    fn from(value: Char16) -> Self {
+        value.to_char().unwrap_or(std::char::REPLACEMENT_CHARACTER)
+    }
+}
+
+impl TryFrom<char> for Char16 {
+    type Error = Char16Error;
+
+    fn try_from(value: char) -> std::result::Result<Self, Self::Error> {
+        let value = value as u32;
+
+        if value > 0xFFFF {
+            Err(Char16Error(value))
+        } else {
+            Ok(Char16(value as u16))
+        }
+    }
+}
+
+impl PartialEq<char> for Char16 {
+    fn eq(&self, other: &char) -> bool {
+        self.0 as u32 == *other as u32
+    }
+}
+
+impl fmt::Display for Char16 {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(&char::from(*self), f)
+    }
+}
+
+impl fmt::Debug for Char16 {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.to_char() {
+            Some(value) => fmt::Debug::fmt(&value, f),
+            None => write!(f, "Char16(0x{:04X})", self.0),
+        }
+    }
+}
+
+unsafe impl RuntimeType for Char16 {
+    type Abi = Self;
+
+    fn abi(&self) -> Self::Abi {
+        *self
+    }
+
+    fn set_abi(&mut self) -> *mut Self::Abi {
+        self as *mut Self::Abi
+    }
+}
+
+/// The error returned when a character can't be represented by a single UTF-16 code unit
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Char16Error(u32);
+
+impl fmt::Display for Char16Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "U+{:04X} is outside of the Basic Multilingual Plane and can't be represented by a single UTF-16 code unit",
+            self.0
+        )
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Char16Error {}
@@ -0,0 +1,69 @@
+use crate::*;
+
+// Support for the generated `to_vec_snapshot` and `checked_iter` methods of the WinRT collection
+// interfaces.
+
+// The number of times a snapshot is attempted before the collection is considered to be changing
+// too often to copy.
+const ATTEMPTS: usize = 3;
+
+/// Calls the closure to copy a collection with a new iterator, calling it again if the
+/// collection changed while it was being copied.
+///
+/// A change is indicated by the iterator failing with `E_CHANGED_STATE`. If the collection keeps
+/// changing the error is of kind [`ErrorKind::CollectionChanged`].
+pub fn snapshot<T, F: FnMut() -> Result<Vec<T>>>(mut copy: F) -> Result<Vec<T>> {
+    for _ in 1..ATTEMPTS {
+        match copy() {
+            Err(error) if error.code() == ErrorCode::CHANGED_STATE => continue,
+            result => return result,
+        }
+    }
+
+    copy().map_err(checked)
+}
+
+/// Classifies an error from an iterator as being of kind [`ErrorKind::CollectionChanged`] if it
+/// is because the collection changed.
+pub fn checked(error: Error) -> Error {
+    if error.code() == ErrorCode::CHANGED_STATE {
+        error.with_kind(ErrorKind::CollectionChanged)
+    } else {
+        error
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn retries() {
+        let mut calls = 0;
+        let items = snapshot(|| {
+            calls += 1;
+            match calls {
+                1 | 2 => Err(ErrorCode::CHANGED_STATE.into()),
+                _ => Ok(vec![1, 2, 3]),
+            }
+        });
+        assert_eq!(items.unwrap(), [1, 2, 3]);
+
+        let error = snapshot::<u32, _>(|| Err(ErrorCode::CHANGED_STATE.into())).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::CollectionChanged);
+        assert_eq!(error.code(), ErrorCode::CHANGED_STATE);
+
+        let mut calls = 0;
+        let error = snapshot::<u32, _>(|| {
+            calls += 1;
+            Err(ErrorCode::BOUNDS.into())
+        })
+        .unwrap_err();
+        assert_eq!(error.code(), ErrorCode::BOUNDS);
+        assert_eq!(calls, 1);
+
+        let error: Error = ErrorCode::CHANGED_STATE.into();
+        assert_eq!(error.kind(), ErrorKind::Closed);
+        assert_eq!(checked(error).kind(), ErrorKind::CollectionChanged);
+    }
+}
@@ -17,6 +17,10 @@ pub unsafe trait ComInterface: Sized {
     const IID: Guid;
     type VTable;
 
+    /// Whether `IID` is that of a generic type's definition rather than of this specialization.
+    #[doc(hidden)]
+    const GENERIC: bool = false;
+
     #[inline(always)]
     fn as_raw(&self) -> RawComPtr<Self> {
         unsafe { std::mem::transmute_copy(self) }
@@ -53,7 +57,18 @@ pub unsafe trait ComInterface: Sized {
     /// rely on ComInterface to calculate the guid for all types.
     unsafe fn query_with_iid<Into: ComInterface>(&self, guid: &Guid) -> Into {
         let mut into: Into = std::mem::zeroed();
-        self.raw_query(guid, &mut into);
+
+        if is_same_interface::<Self>(guid) {
+            let from = self.as_iunknown();
+
+            if !from.is_null() {
+                ((*(*(from))).unknown_add_ref)(from);
+                std::ptr::write(&mut into as *mut Into as *mut RawComPtr<IUnknown>, from);
+            }
+        } else {
+            self.raw_query(guid, &mut into);
+        }
+
         into
     }
 
@@ -65,5 +80,31 @@ pub unsafe trait ComInterface: Sized {
     }
 }
 
+/// Returns true if a pointer to `T` is already a pointer to the interface with the given IID, so
+/// that casting to it only requires an AddRef rather than a call to QueryInterface. Queries for
+/// IUnknown always go through QueryInterface since they must return the object's identity.
+#[inline(always)]
+pub(crate) fn is_same_interface<T: ComInterface>(iid: &Guid) -> bool {
+    !T::GENERIC && *iid == T::IID && *iid != IUnknown::IID
+}
+
+/// Returns true if both interfaces refer to the same object.
+///
+/// COM objects may hand out different pointers for each interface they implement, so the
+/// interfaces are compared by their canonical IUnknown pointers. Two null interfaces are equal.
+pub fn is_same_object<A: ComInterface, B: ComInterface>(a: &A, b: &B) -> bool {
+    if a.as_raw() as RawPtr == b.as_raw() as RawPtr {
+        return true;
+    }
+
+    if a.is_null() || b.is_null() {
+        return false;
+    }
+
+    let a: IUnknown = a.query();
+    let b: IUnknown = b.query();
+    a.as_raw() == b.as_raw()
+}
+
 /// A non-reference-counted pointer to a COM interface
 pub type RawComPtr<T> = *const *const <T as ComInterface>::VTable;
@@ -16,11 +16,26 @@ impl<T: ComInterface> ComPtr<T> {
         }
         &mut self.ptr as *mut _ as _
     }
+
+    /// Returns the raw pointer along with its vtable for the generated methods to call through,
+    /// panicking if the pointer is null.
+    #[doc(hidden)]
+    #[inline(always)]
+    pub fn vtable(&self) -> (RawComPtr<T>, &T::VTable) {
+        if self.ptr.is_null() {
+            panic!("The `this` pointer was null when calling method");
+        }
+
+        // A non-null pointer refers to an object that is kept alive by this reference, and every
+        // COM object starts with a pointer to its vtable.
+        unsafe { (self.ptr, &**self.ptr) }
+    }
 }
 
 unsafe impl<T: ComInterface> ComInterface for ComPtr<T> {
     const IID: Guid = T::IID;
     type VTable = T::VTable;
+    const GENERIC: bool = T::GENERIC;
 }
 
 impl<T: ComInterface> Clone for ComPtr<T> {
@@ -0,0 +1,113 @@
+// Support for the generated conversions between Windows.Foundation.DateTime and TimeSpan and the
+// chrono and time crates, enabled by the `chrono` and `time` features respectively. WinRT counts
+// time in 100ns ticks and DateTime counts them from the FILETIME epoch of January 1, 1601 (UTC).
+// Conversions that could overflow the range of the destination type are fallible.
+
+#[cfg(any(feature = "chrono", feature = "time"))]
+use crate::*;
+
+/// The number of ticks between January 1, 1601 and the Unix epoch of January 1, 1970
+#[cfg(any(feature = "chrono", feature = "time"))]
+const UNIX_EPOCH: i64 = 116_444_736_000_000_000;
+
+#[cfg(any(feature = "chrono", feature = "time"))]
+const TICKS_PER_SECOND: i64 = 10_000_000;
+
+#[cfg(any(feature = "chrono", feature = "time"))]
+fn overflow() -> Error {
+    ErrorCode::INVALID_ARGUMENT.into()
+}
+
+#[cfg(feature = "chrono")]
+pub fn ticks_to_chrono(ticks: i64) -> chrono::DateTime<chrono::Utc> {
+    use chrono::TimeZone;
+    let ticks = i128::from(ticks) - i128::from(UNIX_EPOCH);
+    let seconds = ticks.div_euclid(TICKS_PER_SECOND.into()) as i64;
+    let nanoseconds = ticks.rem_euclid(TICKS_PER_SECOND.into()) as u32 * 100;
+    chrono::Utc.timestamp_opt(seconds, nanoseconds).unwrap()
+}
+
+#[cfg(feature = "chrono")]
+pub fn chrono_to_ticks(value: &chrono::DateTime<chrono::Utc>) -> Result<i64> {
+    let ticks = i128::from(value.timestamp()) * i128::from(TICKS_PER_SECOND)
+        + i128::from(value.timestamp_subsec_nanos() / 100)
+        + i128::from(UNIX_EPOCH);
+    std::convert::TryFrom::try_from(ticks).map_err(|_| overflow())
+}
+
+#[cfg(feature = "chrono")]
+pub fn ticks_to_chrono_duration(ticks: i64) -> chrono::Duration {
+    chrono::Duration::seconds(ticks / TICKS_PER_SECOND)
+        + chrono::Duration::nanoseconds(ticks % TICKS_PER_SECOND * 100)
+}
+
+#[cfg(feature = "chrono")]
+pub fn chrono_duration_to_ticks(value: &chrono::Duration) -> Result<i64> {
+    let seconds = value.num_seconds();
+    let nanoseconds = (*value - chrono::Duration::seconds(seconds))
+        .num_nanoseconds()
+        .ok_or_else(overflow)?;
+    seconds
+        .checked_mul(TICKS_PER_SECOND)
+        .and_then(|ticks| ticks.checked_add(nanoseconds / 100))
+        .ok_or_else(overflow)
+}
+
+#[cfg(feature = "time")]
+pub fn ticks_to_time(ticks: i64) -> Result<time::OffsetDateTime> {
+    let nanoseconds = (i128::from(ticks) - i128::from(UNIX_EPOCH)) * 100;
+    time::OffsetDateTime::from_unix_timestamp_nanos(nanoseconds).map_err(|_| overflow())
+}
+
+#[cfg(feature = "time")]
+pub fn time_to_ticks(value: &time::OffsetDateTime) -> Result<i64> {
+    // The range of OffsetDateTime exceeds that of DateTime when the time crate's `large-dates`
+    // feature is enabled, which any crate in the dependency graph may do.
+    let ticks = value.unix_timestamp_nanos().div_euclid(100) + i128::from(UNIX_EPOCH);
+    std::convert::TryFrom::try_from(ticks).map_err(|_| overflow())
+}
+
+#[cfg(feature = "time")]
+pub fn ticks_to_time_duration(ticks: i64) -> time::Duration {
+    time::Duration::new(
+        ticks / TICKS_PER_SECOND,
+        (ticks % TICKS_PER_SECOND * 100) as i32,
+    )
+}
+
+#[cfg(feature = "time")]
+pub fn time_duration_to_ticks(value: &time::Duration) -> Result<i64> {
+    std::convert::TryFrom::try_from(value.whole_nanoseconds() / 100).map_err(|_| overflow())
+}
+
+#[cfg(feature = "chrono")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __cfg_chrono {
+    ($($item:tt)*) => {
+        $($item)*
+    };
+}
+
+#[cfg(not(feature = "chrono"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __cfg_chrono {
+    ($($item:tt)*) => {};
+}
+
+#[cfg(feature = "time")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __cfg_time {
+    ($($item:tt)*) => {
+        $($item)*
+    };
+}
+
+#[cfg(not(feature = "time"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __cfg_time {
+    ($($item:tt)*) => {};
+}
@@ -0,0 +1,26 @@
+//! Support for the `Debug` implementations generated for runtime classes.
+
+use crate::*;
+
+use std::fmt;
+
+/// Formats a runtime class as its name followed by its string representation, if it implements
+/// `IStringable`, or else by its address.
+pub fn fmt_class(
+    f: &mut fmt::Formatter,
+    name: &str,
+    ptr: RawPtr,
+    string: Option<HString>,
+) -> fmt::Result {
+    let mut tuple = f.debug_tuple(name);
+
+    if ptr.is_null() {
+        tuple.field(&format_args!("null"));
+    } else if let Some(string) = string {
+        tuple.field(&string.to_string());
+    } else {
+        tuple.field(&ptr);
+    }
+
+    tuple.finish()
+}
@@ -0,0 +1,68 @@
+use crate::*;
+
+use std::future::Future;
+
+/// A WinRT deferral, such as `Deferral` or `SuspendingDeferral`
+///
+/// This is implemented for any WinRT class or interface that provides a parameterless `Complete`
+/// method returning nothing.
+pub trait Deferral {
+    /// Informs the event source that the deferred work has completed
+    fn complete(&self) -> Result<()>;
+}
+
+/// Event args that hand out a [`Deferral`] via a parameterless `GetDeferral` method
+pub trait GetDeferral {
+    type Deferral: Deferral;
+
+    fn get_deferral(&self) -> Result<Self::Deferral>;
+}
+
+/// Completes a deferral when dropped
+///
+/// An event source waits for every deferral it has handed out to complete before continuing so a
+/// deferral that is never completed can stall app suspension or background activation
+/// indefinitely. The guard ensures that the deferral is completed on every path out of a handler,
+/// including early returns and panics.
+pub struct DeferralGuard<T: Deferral> {
+    deferral: Option<T>,
+}
+
+impl<T: Deferral> DeferralGuard<T> {
+    /// Obtains a deferral from the event args
+    pub fn new<A: GetDeferral<Deferral = T>>(args: &A) -> Result<Self> {
+        Ok(Self::from_deferral(args.get_deferral()?))
+    }
+
+    /// Guards a deferral that has already been obtained
+    pub fn from_deferral(deferral: T) -> Self {
+        Self {
+            deferral: Some(deferral),
+        }
+    }
+
+    /// Completes the deferral, returning any error rather than ignoring it as `drop` does.
+    pub fn complete(mut self) -> Result<()> {
+        match self.deferral.take() {
+            Some(deferral) => deferral.complete(),
+            None => Ok(()),
+        }
+    }
+
+    /// Completes the deferral once the future has completed, or is dropped.
+    ///
+    /// This is useful for async event handlers where the deferred work is itself a future.
+    pub async fn complete_after<F: Future>(self, future: F) -> F::Output {
+        let output = future.await;
+        drop(self);
+        output
+    }
+}
+
+impl<T: Deferral> Drop for DeferralGuard<T> {
+    fn drop(&mut self) {
+        if let Some(deferral) = self.deferral.take() {
+            let _ = deferral.complete();
+        }
+    }
+}
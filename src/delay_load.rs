@@ -0,0 +1,163 @@
+//! Loading of functions that may not be available on every version of Windows
+//!
+//! The runtime calls anything beyond kernel32 through here so that a missing library or function
+//! is reported as an error from the call rather than preventing the process from loading.
+
+use crate::*;
+use std::sync::atomic::{AtomicPtr, Ordering};
+
+/// Returns the address of the named function in the library, converted to the function pointer
+/// type `F`
+///
+/// Libraries are only loaded from the system directory. The result, including a missing
+/// function, is cached so later calls for the same function don't load the library again.
+///
+/// ```rust,ignore
+/// type RoGetActivationFactory = extern "system" fn(RawPtr, &Guid, *mut RawPtr) -> ErrorCode;
+/// let function = get_proc::<RoGetActivationFactory>("api-ms-win-core-winrt-l1-1-0", "RoGetActivationFactory")?;
+/// ```
+///
+/// # Safety
+///
+/// `F` must be a function pointer type matching the function's signature and calling convention.
+pub unsafe fn get_proc<F: Copy>(library: &'static str, function: &'static str) -> Result<F> {
+    assert!(
+        std::mem::size_of::<F>() == std::mem::size_of::<usize>(),
+        "`get_proc` requires a function pointer type"
+    );
+
+    let address = match find(library, function) {
+        Some(address) => address,
+        None => insert(library, function, load(library, function)),
+    };
+
+    match address {
+        Address::Found(address) => Ok(std::mem::transmute_copy(&address)),
+        Address::MissingLibrary => Err(ErrorCode::MOD_NOT_FOUND.into()),
+        Address::MissingFunction => Err(ErrorCode::PROC_NOT_FOUND.into()),
+    }
+}
+
+/// Returns true if the named function is available, loading and caching it as `get_proc` would.
+pub fn is_available(library: &'static str, function: &'static str) -> bool {
+    unsafe { get_proc::<usize>(library, function).is_ok() }
+}
+
+// Only a missing library is reported where there are no libraries to load.
+#[cfg_attr(not(windows), allow(dead_code))]
+#[derive(Copy, Clone)]
+enum Address {
+    Found(usize),
+    MissingLibrary,
+    MissingFunction,
+}
+
+// The cache is a list that only grows, so it can be searched without a lock while other threads
+// add entries. Two threads may race to load the same function, which is harmless.
+struct Entry {
+    library: &'static str,
+    function: &'static str,
+    address: Address,
+    next: *mut Entry,
+}
+
+static CACHE: AtomicPtr<Entry> = AtomicPtr::new(std::ptr::null_mut());
+
+fn find(library: &str, function: &str) -> Option<Address> {
+    let mut entry = CACHE.load(Ordering::Acquire);
+
+    while let Some(current) = unsafe { entry.as_ref() } {
+        if current.library == library && current.function == function {
+            return Some(current.address);
+        }
+
+        entry = current.next;
+    }
+
+    None
+}
+
+fn insert(library: &'static str, function: &'static str, address: Address) -> Address {
+    let entry = Box::into_raw(Box::new(Entry {
+        library,
+        function,
+        address,
+        next: CACHE.load(Ordering::Acquire),
+    }));
+
+    loop {
+        let next = unsafe { (*entry).next };
+
+        match CACHE.compare_exchange_weak(next, entry, Ordering::AcqRel, Ordering::Acquire) {
+            Ok(_) => return address,
+            Err(head) => unsafe { (*entry).next = head },
+        }
+    }
+}
+
+#[cfg(windows)]
+fn load(library: &str, function: &str) -> Address {
+    const LOAD_LIBRARY_SEARCH_SYSTEM32: u32 = 0x800;
+    const ERROR_INVALID_PARAMETER: u32 = 87;
+
+    let name: Vec<u16> = library.encode_utf16().chain(std::iter::once(0)).collect();
+    let function: Vec<u8> = function.bytes().chain(std::iter::once(0)).collect();
+
+    unsafe {
+        let mut module = runtime::LoadLibraryExW(
+            name.as_ptr(),
+            std::ptr::null_mut(),
+            LOAD_LIBRARY_SEARCH_SYSTEM32,
+        );
+
+        // Windows 7 without KB2533623 doesn't support the search flags, so the library is loaded
+        // by its full path in the system directory instead.
+        if module.is_null() && runtime::GetLastError() == ERROR_INVALID_PARAMETER {
+            if let Some(path) = system_path(library) {
+                module = runtime::LoadLibraryW(path.as_ptr());
+            }
+        }
+
+        if module.is_null() {
+            return Address::MissingLibrary;
+        }
+
+        let address = runtime::GetProcAddress(module, function.as_ptr());
+
+        if address.is_null() {
+            Address::MissingFunction
+        } else {
+            Address::Found(address as usize)
+        }
+    }
+}
+
+// The null-terminated path of the library in the system directory.
+#[cfg(windows)]
+fn system_path(library: &str) -> Option<Vec<u16>> {
+    let mut path = vec![0u16; 260];
+
+    loop {
+        let len = unsafe { runtime::GetSystemDirectoryW(path.as_mut_ptr(), path.len() as u32) };
+
+        match len as usize {
+            0 => return None,
+            // The buffer was too small, and the length includes the terminator.
+            len if len > path.len() => path.resize(len, 0),
+            len => {
+                path.truncate(len);
+                break;
+            }
+        }
+    }
+
+    path.extend(format!("\\{}.dll", library).encode_utf16());
+    path.push(0);
+    Some(path)
+}
+
+// There are no Windows libraries to load elsewhere.
+#[cfg(not(windows))]
+fn load(_library: &str, _function: &str) -> Address {
+    Address::MissingLibrary
+}
@@ -0,0 +1,145 @@
+use crate::ref_count::RefCount;
+use crate::unknown::IAGILE_OBJECT;
+use crate::*;
+
+// The COM plumbing shared by the objects that the runtime implements itself, such as the
+// completed handlers awaited by futures, the work items run on the thread pool, and buffers. A
+// `Delegate` holds some state for its `Invoke` method, which is the only slot that differs
+// between delegates, and is agile so that it may be invoked from whichever thread the caller
+// chooses.
+
+/// A heap allocated COM object that frees itself once its last reference is released.
+pub(crate) trait Counted {
+    fn count(&self) -> &RefCount;
+}
+
+pub(crate) extern "system" fn add_ref<T: Counted>(this: *mut T) -> u32 {
+    unsafe { (*this).count().addref() }
+}
+
+pub(crate) extern "system" fn release<T: Counted>(this: *mut T) -> u32 {
+    unsafe {
+        let remaining = (*this).count().release();
+
+        if remaining == 0 {
+            std::mem::drop(Box::from_raw(this));
+        }
+
+        remaining
+    }
+}
+
+/// A delegate of whichever interface it was created as, holding the state for its `Invoke`
+/// method.
+#[repr(C)]
+pub(crate) struct Delegate<T> {
+    vtable: *const std::ffi::c_void,
+    count: RefCount,
+    iid: Guid,
+    pub state: T,
+}
+
+/// The vtable of a delegate whose `Invoke` method is of type `I`.
+#[repr(C)]
+pub(crate) struct DelegateVtable<T, I> {
+    query_interface: extern "system" fn(*mut Delegate<T>, &Guid, *mut RawPtr) -> ErrorCode,
+    add_ref: extern "system" fn(*mut Delegate<T>) -> u32,
+    release: extern "system" fn(*mut Delegate<T>) -> u32,
+    invoke: I,
+}
+
+impl<T, I> DelegateVtable<T, I> {
+    pub const fn new(invoke: I) -> Self {
+        Self {
+            query_interface: query_interface::<T>,
+            add_ref: add_ref::<Delegate<T>>,
+            release: release::<Delegate<T>>,
+            invoke,
+        }
+    }
+}
+
+impl<T> Delegate<T> {
+    /// Creates a delegate of type `D` with the given vtable and state.
+    #[allow(clippy::new_ret_no_self)]
+    pub fn new<D: ComInterface, I>(vtable: &'static DelegateVtable<T, I>, state: T) -> D {
+        let delegate = Box::new(Self {
+            vtable: vtable as *const _ as _,
+            count: RefCount::new(1),
+            iid: D::IID,
+            state,
+        });
+
+        let ptr = Box::into_raw(delegate);
+        unsafe { std::mem::transmute_copy(&ptr) }
+    }
+
+    /// Returns the state of the delegate if it was created with the given vtable.
+    pub fn state<'a, D: ComInterface, I>(
+        delegate: &'a D,
+        vtable: &'static DelegateVtable<T, I>,
+    ) -> Option<&'a T> {
+        let this = delegate.as_raw() as *const Self;
+
+        if this.is_null() || !std::ptr::eq(unsafe { (*this).vtable }, vtable as *const _ as _) {
+            None
+        } else {
+            Some(unsafe { &(*this).state })
+        }
+    }
+}
+
+impl<T> Counted for Delegate<T> {
+    fn count(&self) -> &RefCount {
+        &self.count
+    }
+}
+
+extern "system" fn query_interface<T>(
+    this: *mut Delegate<T>,
+    iid: &Guid,
+    interface: *mut RawPtr,
+) -> ErrorCode {
+    unsafe {
+        if *iid == (*this).iid || *iid == IUnknown::IID || *iid == IAGILE_OBJECT {
+            *interface = this as RawPtr;
+            (*this).count.addref();
+            ErrorCode(0)
+        } else {
+            *interface = std::ptr::null_mut();
+            ErrorCode::NO_INTERFACE
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    type Counter = Delegate<Arc<()>>;
+    type CounterVtable = DelegateVtable<Arc<()>, extern "system" fn(*mut Counter) -> ErrorCode>;
+
+    static VTABLE: CounterVtable = DelegateVtable::new(invoke);
+    static OTHER: CounterVtable = DelegateVtable::new(invoke);
+
+    extern "system" fn invoke(_this: *mut Counter) -> ErrorCode {
+        ErrorCode(0)
+    }
+
+    #[test]
+    fn released() {
+        let state = Arc::new(());
+        let delegate: IUnknown = Delegate::new(&VTABLE, state.clone());
+        let copy = delegate.clone();
+
+        assert!(Delegate::state(&copy, &VTABLE).is_some());
+        assert!(Delegate::state(&copy, &OTHER).is_none());
+        assert_eq!(Arc::strong_count(&state), 2);
+
+        drop(delegate);
+        assert_eq!(Arc::strong_count(&state), 2);
+        drop(copy);
+        assert_eq!(Arc::strong_count(&state), 1);
+    }
+}
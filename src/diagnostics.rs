@@ -0,0 +1,79 @@
+//! Naming interfaces by their IIDs in diagnostics
+//!
+//! A failed `QueryInterface` only reports the IID that was requested. When the `import!` macro is
+//! given the `"interface_names"` option it generates a `register_interface_names` function
+//! alongside the imported modules that, once called, lets [`interface_name`] name the imported
+//! interfaces and delegates. The runtime then names the interface, rather than printing its
+//! GUID, in the events it logs for failed conversions. Generic interfaces aren't registered as
+//! each specialization has its own IID.
+
+use crate::*;
+
+use std::sync::RwLock;
+
+static NAMES: RwLock<Vec<&'static [(Guid, &'static str)]>> = RwLock::new(Vec::new());
+
+/// Registers a table of interface names. This is called by the generated
+/// `register_interface_names` function.
+#[doc(hidden)]
+pub fn register_interface_names(names: &'static [(Guid, &'static str)]) {
+    let mut tables = NAMES.write().unwrap();
+
+    if !tables.iter().any(|table| std::ptr::eq(*table, names)) {
+        tables.push(names);
+    }
+}
+
+/// Returns the name of the interface with the given IID, if it has been registered.
+pub fn interface_name(iid: &Guid) -> Option<&'static str> {
+    NAMES
+        .read()
+        .unwrap()
+        .iter()
+        .flat_map(|table| table.iter())
+        .find(|(guid, _)| guid == iid)
+        .map(|(_, name)| *name)
+}
+
+/// Returns the name of the interface with the given IID, or the IID itself if it hasn't been
+/// registered.
+pub fn describe_interface(iid: &Guid) -> String {
+    match interface_name(iid) {
+        Some(name) => name.to_string(),
+        None => format!("{:?}", iid),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn names() {
+        static NAMES: [(Guid, &str); 1] = [(
+            Guid::from_values(
+                0x9636_9F54,
+                0x8EB6,
+                0x48F0,
+                [0xAB, 0xCE, 0xC1, 0xB2, 0x11, 0xE6, 0x27, 0xC3],
+            ),
+            "Windows.Foundation.IStringable",
+        )];
+
+        let unknown = Guid::from_values(1, 2, 3, [4, 5, 6, 7, 8, 9, 10, 11]);
+        assert_eq!(interface_name(&NAMES[0].0), None);
+
+        register_interface_names(&NAMES);
+        register_interface_names(&NAMES);
+        assert_eq!(
+            interface_name(&NAMES[0].0),
+            Some("Windows.Foundation.IStringable")
+        );
+        assert_eq!(
+            describe_interface(&NAMES[0].0),
+            "Windows.Foundation.IStringable"
+        );
+        assert_eq!(interface_name(&unknown), None);
+        assert_eq!(describe_interface(&unknown), format!("{:?}", unknown));
+    }
+}
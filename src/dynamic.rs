@@ -0,0 +1,585 @@
+//! Calling methods and properties by name at runtime
+//!
+//! A [`DynamicObject`] pairs an object with the metadata of its runtime class, as resolved by the
+//! [`reflection`](crate::reflection) module, so that its methods may be called and its properties
+//! read and written by name with arguments boxed as a [`Value`]. This is late binding for plugin
+//! systems, scripting bridges, and REPL-like tools that can't import the types they use at
+//! compile time.
+//!
+//! ```no_run
+//! use winrt::dynamic::{DynamicObject, Value};
+//!
+//! let statics = DynamicObject::statics("Windows.Foundation.Uri")?;
+//! let uri = statics.call("CreateUri", &[Value::from("http://kennykerr.ca/")])?;
+//!
+//! if let Value::Object(uri) = uri {
+//!     let uri = DynamicObject::new(&uri)?;
+//!     assert!(uri.get("Domain")? == Value::from("kennykerr.ca"));
+//! }
+//! # Ok::<(), winrt::Error>(())
+//! ```
+//!
+//! Methods are found among the non-generic interfaces of the class, and of its base classes, by
+//! their name in metadata and their number of parameters. Overloads with the same number of
+//! parameters are told apart by the kinds of the arguments. Parameters may be of any primitive
+//! type other than a floating point number, a string, an enum, or an object, and values of any of
+//! these kinds as well as floating point numbers may be returned. Calling a method with other
+//! parameters, such as structs, arrays, and out parameters, fails with E_NOTIMPL.
+
+use crate::reflection::{self, TypeInfo};
+use crate::unknown::abi_IUnknown;
+use crate::*;
+
+use winmd::codes::TypeDefOrRef;
+use winmd::flags::TypeCategory;
+use winmd::signature::{ElementType, ParamSig};
+use winmd::tables::{AttributeArg, TypeDef};
+use winmd::TypeReader;
+
+// No method with the given name and number of parameters was found (DISP_E_MEMBERNOTFOUND).
+const MEMBER_NOT_FOUND: ErrorCode = ErrorCode(0x8002_0003_u32 as i32);
+// The arguments don't match the parameters of the method (DISP_E_TYPEMISMATCH).
+const TYPE_MISMATCH: ErrorCode = ErrorCode(0x8002_0005_u32 as i32);
+// The method's signature can't be called dynamically (E_NOTIMPL).
+const NOT_IMPLEMENTED: ErrorCode = ErrorCode::NOT_IMPLEMENTED;
+
+// The most arguments, including the pointer to the return value, that may be passed.
+const MAX_ARGS: usize = 8;
+
+/// A boxed argument or return value of a method called through a [`DynamicObject`]
+#[derive(Clone, PartialEq)]
+pub enum Value {
+    /// The return value of a method that returns nothing, or a null object
+    Empty,
+    Bool(bool),
+    Char(Char16),
+    I8(i8),
+    U8(u8),
+    I16(i16),
+    U16(u16),
+    /// A signed 32-bit integer, or the value of an enum whose underlying type is `i32`
+    I32(i32),
+    /// An unsigned 32-bit integer, or the value of a flags enum
+    U32(u32),
+    I64(i64),
+    U64(u64),
+    F32(f32),
+    F64(f64),
+    String(HString),
+    /// An object, which is queried for the interface a parameter expects
+    Object(Object),
+}
+
+impl std::fmt::Debug for Value {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Value::Empty => f.write_str("Empty"),
+            Value::Bool(value) => f.debug_tuple("Bool").field(value).finish(),
+            Value::Char(value) => f.debug_tuple("Char").field(value).finish(),
+            Value::I8(value) => f.debug_tuple("I8").field(value).finish(),
+            Value::U8(value) => f.debug_tuple("U8").field(value).finish(),
+            Value::I16(value) => f.debug_tuple("I16").field(value).finish(),
+            Value::U16(value) => f.debug_tuple("U16").field(value).finish(),
+            Value::I32(value) => f.debug_tuple("I32").field(value).finish(),
+            Value::U32(value) => f.debug_tuple("U32").field(value).finish(),
+            Value::I64(value) => f.debug_tuple("I64").field(value).finish(),
+            Value::U64(value) => f.debug_tuple("U64").field(value).finish(),
+            Value::F32(value) => f.debug_tuple("F32").field(value).finish(),
+            Value::F64(value) => f.debug_tuple("F64").field(value).finish(),
+            Value::String(value) => f.debug_tuple("String").field(value).finish(),
+            Value::Object(value) => {
+                let name = value.type_name().map(|name| name.to_string());
+                f.debug_tuple("Object")
+                    .field(&name.as_deref().unwrap_or("?"))
+                    .finish()
+            }
+        }
+    }
+}
+
+macro_rules! from_primitive {
+    ($($variant:ident($type:ty)),*) => {
+        $(
+            impl From<$type> for Value {
+                fn from(value: $type) -> Self {
+                    Value::$variant(value)
+                }
+            }
+        )*
+    };
+}
+
+from_primitive!(
+    Bool(bool),
+    Char(Char16),
+    I8(i8),
+    U8(u8),
+    I16(i16),
+    U16(u16),
+    I32(i32),
+    U32(u32),
+    I64(i64),
+    U64(u64),
+    F32(f32),
+    F64(f64),
+    String(HString),
+    Object(Object)
+);
+
+impl From<&str> for Value {
+    fn from(value: &str) -> Self {
+        Value::String(value.into())
+    }
+}
+
+impl From<String> for Value {
+    fn from(value: String) -> Self {
+        Value::String(value.into())
+    }
+}
+
+/// An object whose methods and properties are called by name
+#[derive(Clone)]
+pub struct DynamicObject {
+    object: Object,
+    info: TypeInfo,
+    interfaces: Vec<TypeInfo>,
+}
+
+impl DynamicObject {
+    /// Resolves the runtime class of an object so that its members may be called by name.
+    pub fn new<T: ComInterface>(object: &T) -> Result<Self> {
+        let info = TypeInfo::of(object)?;
+        let mut defs = Vec::new();
+        let mut class = Some(info.def());
+
+        while let Some(def) = class {
+            required(info.reader(), def, &mut defs);
+            class = base(info.reader(), def);
+        }
+
+        Ok(Self {
+            object: object.query(),
+            interfaces: defs.into_iter().map(|def| info.with_def(def)).collect(),
+            info,
+        })
+    }
+
+    /// Creates an instance of a class with its default constructor.
+    pub fn activate(class: &str) -> Result<Self> {
+        let factory: IActivationFactory = activation::named_factory(class)?;
+        Self::new(&factory.activate_instance::<Object>()?)
+    }
+
+    /// Gets the activation factory of a class so that its static methods and constructors that
+    /// take parameters, such as `CreateUri`, may be called by name.
+    pub fn statics(class: &str) -> Result<Self> {
+        let info = TypeInfo::resolve(class)?;
+        let object: Object = activation::named_factory(class)?;
+        let reader = info.reader();
+        let mut defs = Vec::new();
+
+        for attribute in info.def().attributes(reader) {
+            match attribute.name(reader) {
+                ("Windows.Foundation.Metadata", "StaticAttribute")
+                | ("Windows.Foundation.Metadata", "ActivatableAttribute")
+                | ("Windows.Foundation.Metadata", "ComposableAttribute") => {}
+                _ => continue,
+            }
+
+            for (_, arg) in attribute.args(reader) {
+                if let AttributeArg::TypeDef(def) = arg {
+                    if !defs.contains(&def) {
+                        defs.push(def);
+                        required(reader, def, &mut defs);
+                    }
+                }
+            }
+        }
+
+        Ok(Self {
+            object,
+            interfaces: defs.into_iter().map(|def| info.with_def(def)).collect(),
+            info,
+        })
+    }
+
+    /// Returns the object whose members are called.
+    pub fn object(&self) -> &Object {
+        &self.object
+    }
+
+    /// Returns the metadata of the object's runtime class.
+    pub fn type_info(&self) -> &TypeInfo {
+        &self.info
+    }
+
+    /// Calls the method with the given name in metadata, such as `ToString`, with the arguments.
+    pub fn call(&self, method: &str, args: &[Value]) -> Result<Value> {
+        let mut error = MEMBER_NOT_FOUND;
+
+        for interface in &self.interfaces {
+            let reader = interface.reader();
+
+            for info in interface.methods() {
+                if info.name != method || info.sig.params.len() != args.len() {
+                    continue;
+                }
+
+                let kinds: Option<Vec<Kind>> = info
+                    .sig
+                    .params
+                    .iter()
+                    .map(|param| Kind::from_param(reader, param).filter(Kind::is_param))
+                    .collect();
+
+                let (kinds, result_kind) =
+                    match (kinds, Kind::from_param(reader, &info.sig.return_type)) {
+                        (Some(kinds), Some(result_kind)) => (kinds, result_kind),
+                        _ => {
+                            error = NOT_IMPLEMENTED;
+                            continue;
+                        }
+                    };
+
+                // Holds the interfaces that the arguments are queried for until the call returns.
+                let mut objects = Vec::new();
+                let mut slots = Vec::new();
+
+                if let Err(code) = marshal(&kinds, args, &mut slots, &mut objects) {
+                    error = code;
+                    continue;
+                }
+
+                let iid = match interface.iid() {
+                    Some(iid) => iid,
+                    None => continue,
+                };
+
+                let this = query(&self.object, &iid)?;
+                let mut result = 0u64;
+
+                if result_kind != Kind::Void {
+                    slots.push(&mut result as *mut u64 as usize);
+                }
+
+                if slots.len() > MAX_ARGS {
+                    error = NOT_IMPLEMENTED;
+                    continue;
+                }
+
+                unsafe {
+                    invoke(this.abi() as RawPtr, info.slot, &slots).ok()?;
+                    return Ok(read(result_kind, &result));
+                }
+            }
+        }
+
+        Err(error.into())
+    }
+
+    /// Gets the value of a property.
+    pub fn get(&self, property: &str) -> Result<Value> {
+        self.call(&format!("get_{}", property), &[])
+    }
+
+    /// Sets the value of a property.
+    pub fn set<V: Into<Value>>(&self, property: &str, value: V) -> Result<()> {
+        self.call(&format!("put_{}", property), &[value.into()])
+            .map(|_| ())
+    }
+}
+
+impl std::fmt::Debug for DynamicObject {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_tuple("DynamicObject")
+            .field(&self.info.full_name())
+            .finish()
+    }
+}
+
+// The kinds of parameters and return values that may be passed dynamically.
+#[derive(Clone, PartialEq)]
+enum Kind {
+    Void,
+    Bool,
+    Char,
+    I8,
+    U8,
+    I16,
+    U16,
+    I32,
+    U32,
+    I64,
+    U64,
+    F32,
+    F64,
+    String,
+    // An object and the IID of the interface it's passed as, which is unknown for the
+    // specializations of generic interfaces.
+    Object(Option<Guid>),
+}
+
+impl Kind {
+    fn from_param(reader: &TypeReader, param: &ParamSig) -> Option<Self> {
+        if param.by_ref {
+            return None;
+        }
+
+        Self::from_element_type(reader, &param.kind)
+    }
+
+    fn from_element_type(reader: &TypeReader, kind: &ElementType) -> Option<Self> {
+        Some(match kind {
+            ElementType::Void => Kind::Void,
+            ElementType::Bool => Kind::Bool,
+            ElementType::Char => Kind::Char,
+            ElementType::I8 => Kind::I8,
+            ElementType::U8 => Kind::U8,
+            ElementType::I16 => Kind::I16,
+            ElementType::U16 => Kind::U16,
+            ElementType::I32 => Kind::I32,
+            ElementType::U32 => Kind::U32,
+            ElementType::I64 => Kind::I64,
+            ElementType::U64 => Kind::U64,
+            ElementType::F32 => Kind::F32,
+            ElementType::F64 => Kind::F64,
+            ElementType::String => Kind::String,
+            ElementType::Object => Kind::Object(Some(Object::IID)),
+            ElementType::GenericInstance(..) => Kind::Object(None),
+            ElementType::TypeDefOrRef(TypeDefOrRef::TypeSpec(_)) => return None,
+            ElementType::TypeDefOrRef(def) => {
+                let (namespace, name) = def.name(reader);
+                let def = reflection::find(reader, namespace, name)?;
+
+                match def.category(reader) {
+                    TypeCategory::Enum => {
+                        let field = def.fields(reader).next()?;
+                        Self::from_element_type(reader, &ElementType::from_field(reader, field))?
+                    }
+                    TypeCategory::Interface | TypeCategory::Delegate => {
+                        Kind::Object(Some(reflection::iid(reader, def)?))
+                    }
+                    TypeCategory::Class => Kind::Object(default_interface(reader, def)),
+                    TypeCategory::Struct => return None,
+                }
+            }
+            _ => return None,
+        })
+    }
+
+    // Floating point arguments are passed in separate registers on 64-bit targets, and the
+    // interfaces of generic specializations can't be queried for without their IIDs.
+    fn is_param(&self) -> bool {
+        !matches!(
+            self,
+            Kind::Void | Kind::F32 | Kind::F64 | Kind::Object(None)
+        )
+    }
+}
+
+// Converts the arguments to the pointer-sized values that they're passed as.
+fn marshal(
+    kinds: &[Kind],
+    args: &[Value],
+    slots: &mut Vec<usize>,
+    objects: &mut Vec<Object>,
+) -> std::result::Result<(), ErrorCode> {
+    for (kind, arg) in kinds.iter().zip(args) {
+        match (kind, arg) {
+            (Kind::Bool, Value::Bool(value)) => slots.push(*value as usize),
+            (Kind::Char, Value::Char(value)) => slots.push(value.0 as usize),
+            (Kind::I8, Value::I8(value)) => slots.push(*value as usize),
+            (Kind::U8, Value::U8(value)) => slots.push(*value as usize),
+            (Kind::I16, Value::I16(value)) => slots.push(*value as usize),
+            (Kind::U16, Value::U16(value)) => slots.push(*value as usize),
+            (Kind::I32, Value::I32(value)) => slots.push(*value as usize),
+            (Kind::U32, Value::U32(value)) => slots.push(*value as usize),
+            (Kind::I64, Value::I64(value)) => push_u64(slots, *value as u64),
+            (Kind::U64, Value::U64(value)) => push_u64(slots, *value),
+            (Kind::String, Value::String(value)) => slots.push(value.abi() as usize),
+            (Kind::Object(_), Value::Empty) => slots.push(0),
+            (Kind::Object(Some(iid)), Value::Object(value)) => {
+                let value = query(value, iid).map_err(|_| TYPE_MISMATCH)?;
+                slots.push(value.abi() as usize);
+                objects.push(value);
+            }
+            _ => return Err(TYPE_MISMATCH),
+        }
+    }
+
+    Ok(())
+}
+
+// A 64-bit integer takes two stack slots on 32-bit targets, with the low half first.
+#[cfg(target_pointer_width = "64")]
+fn push_u64(slots: &mut Vec<usize>, value: u64) {
+    slots.push(value as usize);
+}
+
+#[cfg(not(target_pointer_width = "64"))]
+fn push_u64(slots: &mut Vec<usize>, value: u64) {
+    slots.push(value as usize);
+    slots.push((value >> 32) as usize);
+}
+
+// Reads a return value, which is no larger than 64 bits, and takes ownership of any string or
+// object it refers to.
+unsafe fn read(kind: Kind, result: &u64) -> Value {
+    let ptr = result as *const u64;
+
+    match kind {
+        Kind::Void => Value::Empty,
+        Kind::Bool => Value::Bool(*(ptr as *const u8) != 0),
+        Kind::Char => Value::Char(Char16(*(ptr as *const u16))),
+        Kind::I8 => Value::I8(*(ptr as *const i8)),
+        Kind::U8 => Value::U8(*(ptr as *const u8)),
+        Kind::I16 => Value::I16(*(ptr as *const i16)),
+        Kind::U16 => Value::U16(*(ptr as *const u16)),
+        Kind::I32 => Value::I32(*(ptr as *const i32)),
+        Kind::U32 => Value::U32(*(ptr as *const u32)),
+        Kind::I64 => Value::I64(*(ptr as *const i64)),
+        Kind::U64 => Value::U64(*ptr),
+        Kind::F32 => Value::F32(*(ptr as *const f32)),
+        Kind::F64 => Value::F64(*(ptr as *const f64)),
+        Kind::String => Value::String(std::mem::transmute_copy(&*(ptr as *const RawPtr))),
+        Kind::Object(_) => {
+            let object: Object = std::mem::transmute_copy(&*(ptr as *const RawPtr));
+
+            if object.is_null() {
+                Value::Empty
+            } else {
+                Value::Object(object)
+            }
+        }
+    }
+}
+
+macro_rules! invoke {
+    ($function:expr, $this:expr, $slots:expr, $($count:literal => ($($index:literal)*))*) => {
+        match $slots.len() {
+            $(
+                $count => {
+                    let function: extern "system" fn(RawPtr $(, invoke!(@usize $index))*) -> ErrorCode =
+                        std::mem::transmute($function);
+                    function($this $(, $slots[$index])*)
+                }
+            )*
+            _ => NOT_IMPLEMENTED,
+        }
+    };
+    (@usize $index:literal) => { usize };
+}
+
+// Calls the method in the given vtable slot, passing each argument as a pointer-sized value.
+unsafe fn invoke(this: RawPtr, slot: usize, slots: &[usize]) -> ErrorCode {
+    let vtable = *(this as *const *const usize);
+    let function = *vtable.add(slot);
+
+    invoke!(function, this, slots,
+        0 => ()
+        1 => (0)
+        2 => (0 1)
+        3 => (0 1 2)
+        4 => (0 1 2 3)
+        5 => (0 1 2 3 4)
+        6 => (0 1 2 3 4 5)
+        7 => (0 1 2 3 4 5 6)
+        8 => (0 1 2 3 4 5 6 7)
+    )
+}
+
+fn query(object: &Object, iid: &Guid) -> Result<Object> {
+    let mut result = Object::default();
+
+    if object.is_null() {
+        return Ok(result);
+    }
+
+    unsafe {
+        let from = object.abi() as *const *const abi_IUnknown;
+        let code = ((*(*from)).unknown_query_interface)(from, iid, result.set_abi() as *mut RawPtr);
+
+        if code.is_err() {
+            trace::query_failed(iid, code);
+            return Err(code.into());
+        }
+    }
+
+    Ok(result)
+}
+
+// Adds the non-generic interfaces that a class implements, or an interface requires, and those
+// that they require in turn.
+fn required(reader: &TypeReader, def: TypeDef, defs: &mut Vec<TypeDef>) {
+    for interface in def.interfaces(reader) {
+        let interface = match interface.interface(reader) {
+            TypeDefOrRef::TypeSpec(_) => continue,
+            interface => interface,
+        };
+
+        let (namespace, name) = interface.name(reader);
+
+        if let Some(def) = reflection::find(reader, namespace, name) {
+            if !defs.contains(&def) {
+                defs.push(def);
+                required(reader, def, defs);
+            }
+        }
+    }
+}
+
+fn base(reader: &TypeReader, def: TypeDef) -> Option<TypeDef> {
+    match def.extends(reader) {
+        TypeDefOrRef::TypeSpec(_) => None,
+        base => {
+            let (namespace, name) = base.name(reader);
+            reflection::find(reader, namespace, name)
+        }
+    }
+}
+
+fn default_interface(reader: &TypeReader, def: TypeDef) -> Option<Guid> {
+    let interface = def.interfaces(reader).find(|interface| {
+        interface.has_attribute(reader, ("Windows.Foundation.Metadata", "DefaultAttribute"))
+    })?;
+
+    match interface.interface(reader) {
+        TypeDefOrRef::TypeSpec(_) => None,
+        interface => {
+            let (namespace, name) = interface.name(reader);
+            reflection::iid(reader, reflection::find(reader, namespace, name)?)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn values() {
+        assert_eq!(Value::from("hello"), Value::String("hello".into()));
+        assert_eq!(Value::from(7u32), Value::U32(7));
+        assert_eq!(format!("{:?}", Value::from(true)), "Bool(true)");
+    }
+
+    #[test]
+    fn slots() {
+        let mut slots = Vec::new();
+        let mut objects = Vec::new();
+
+        marshal(
+            &[Kind::Bool, Kind::I32, Kind::Object(Some(Object::IID))],
+            &[Value::Bool(true), Value::I32(-1), Value::Empty],
+            &mut slots,
+            &mut objects,
+        )
+        .unwrap();
+
+        assert_eq!(slots, [1, -1i32 as usize, 0]);
+
+        let error = marshal(&[Kind::U32], &[Value::I32(1)], &mut slots, &mut objects);
+        assert_eq!(error, Err(TYPE_MISMATCH));
+    }
+}
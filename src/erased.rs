@@ -0,0 +1,240 @@
+//! Support for the code generated by the `"erased"` import option.
+//!
+//! With this option the methods of generic interfaces, such as `IVector<T>`, are thin wrappers
+//! that forward to a single specialization, `IVector<Erased>`, whenever the type arguments are
+//! strings, classes, interfaces or delegates. Since all of these are passed as a pointer at the
+//! ABI level, the wrappers only reinterpret their arguments and return values, and the code that
+//! calls through the vtable is only compiled once.
+//!
+//! The choice between the erased and typed bodies is made by the type arguments' implementations
+//! of [`RuntimeType::select`], so each instantiation of a wrapper only compiles the body it uses.
+
+use crate::*;
+use core::marker::PhantomData;
+
+/// A type argument standing in for any type whose ABI is an owned pointer
+///
+/// An `Erased` value never releases the pointer it holds. The typed wrappers remain responsible
+/// for the ownership of their arguments and take ownership of any values that are returned.
+#[repr(transparent)]
+pub struct Erased(RawPtr);
+
+impl Default for Erased {
+    fn default() -> Self {
+        Erased(std::ptr::null_mut())
+    }
+}
+
+unsafe impl RuntimeType for Erased {
+    type Abi = RawPtr;
+
+    fn abi(&self) -> Self::Abi {
+        self.0
+    }
+
+    fn set_abi(&mut self) -> *mut Self::Abi {
+        &mut self.0
+    }
+}
+
+/// Reinterprets a reference to a value as a reference to its erased equivalent.
+///
+/// # Safety
+///
+/// `U` must have the same layout as `T`, differing only in type arguments that are erasable.
+#[inline(always)]
+pub unsafe fn erase<T, U>(value: &T) -> &U {
+    debug_assert_eq!(std::mem::size_of::<T>(), std::mem::size_of::<U>());
+    &*(value as *const T as *const U)
+}
+
+/// Reinterprets a mutable reference to a value as a reference to its erased equivalent.
+///
+/// # Safety
+///
+/// See [`erase`].
+#[inline(always)]
+pub unsafe fn erase_mut<T, U>(value: &mut T) -> &mut U {
+    debug_assert_eq!(std::mem::size_of::<T>(), std::mem::size_of::<U>());
+    &mut *(value as *mut T as *mut U)
+}
+
+/// Reinterprets a slice as a slice of its erased equivalent.
+///
+/// # Safety
+///
+/// See [`erase`].
+#[inline(always)]
+pub unsafe fn erase_slice<T, U>(value: &[T]) -> &[U] {
+    debug_assert_eq!(std::mem::size_of::<T>(), std::mem::size_of::<U>());
+    std::slice::from_raw_parts(value.as_ptr() as *const U, value.len())
+}
+
+/// Reinterprets a mutable slice as a slice of its erased equivalent.
+///
+/// # Safety
+///
+/// See [`erase`].
+#[inline(always)]
+pub unsafe fn erase_slice_mut<T, U>(value: &mut [T]) -> &mut [U] {
+    debug_assert_eq!(std::mem::size_of::<T>(), std::mem::size_of::<U>());
+    std::slice::from_raw_parts_mut(value.as_mut_ptr() as *mut U, value.len())
+}
+
+/// Takes ownership of an erased value returned by the erased specialization.
+///
+/// # Safety
+///
+/// `T` must have the same layout as `U`, differing only in type arguments that are erasable.
+#[inline(always)]
+pub unsafe fn restore<U, T>(value: U) -> T {
+    debug_assert_eq!(std::mem::size_of::<T>(), std::mem::size_of::<U>());
+    let result = std::mem::transmute_copy(&value);
+    std::mem::forget(value);
+    result
+}
+
+/// Returns a reference to the value of a parameter, whether it is borrowed or owned.
+#[inline(always)]
+pub fn param_ref<'a, T: RuntimeType>(param: &'a Param<'_, T>) -> &'a T {
+    match param {
+        Param::Borrowed(value) => value,
+        Param::Owned(value) => value,
+    }
+}
+
+/// The erased and typed bodies of a wrapper, one of which is called by [`RuntimeType::select`]
+pub trait Selector {
+    /// The value returned by either body
+    type Output;
+
+    /// Calls the body that forwards to the erased specialization.
+    fn erased(self) -> Self::Output;
+
+    /// Calls the body that calls the typed specialization directly.
+    fn typed(self) -> Self::Output;
+}
+
+struct Select<A, E, T> {
+    args: A,
+    erased: E,
+    typed: T,
+}
+
+impl<A, R, E: FnOnce(A) -> R, T: FnOnce(A) -> R> Selector for Select<A, E, T> {
+    type Output = R;
+
+    #[inline(always)]
+    fn erased(self) -> R {
+        (self.erased)(self.args)
+    }
+
+    #[inline(always)]
+    fn typed(self) -> R {
+        (self.typed)(self.args)
+    }
+}
+
+// Selects the erased body only if `U` is also erasable.
+struct Then<U, S>(S, PhantomData<U>);
+
+impl<U: RuntimeType, S: Selector> Selector for Then<U, S> {
+    type Output = S::Output;
+
+    #[inline(always)]
+    fn erased(self) -> S::Output {
+        U::select(self.0)
+    }
+
+    #[inline(always)]
+    fn typed(self) -> S::Output {
+        self.0.typed()
+    }
+}
+
+/// The type arguments of a generic interface, given as a tuple
+pub trait TypeArgs {
+    /// Calls `erased` if every type argument is erasable and `typed` otherwise, passing `args`
+    /// to the one that is called. The other closure is never instantiated.
+    fn select<A, R, E: FnOnce(A) -> R, T: FnOnce(A) -> R>(args: A, erased: E, typed: T) -> R;
+}
+
+impl<T1: RuntimeType> TypeArgs for (T1,) {
+    #[inline(always)]
+    fn select<A, R, E: FnOnce(A) -> R, T: FnOnce(A) -> R>(args: A, erased: E, typed: T) -> R {
+        T1::select(Select {
+            args,
+            erased,
+            typed,
+        })
+    }
+}
+
+impl<T1: RuntimeType, T2: RuntimeType> TypeArgs for (T1, T2) {
+    #[inline(always)]
+    fn select<A, R, E: FnOnce(A) -> R, T: FnOnce(A) -> R>(args: A, erased: E, typed: T) -> R {
+        let select = Select {
+            args,
+            erased,
+            typed,
+        };
+
+        T1::select(Then::<T2, _>(select, PhantomData))
+    }
+}
+
+impl<T1: RuntimeType, T2: RuntimeType, T3: RuntimeType> TypeArgs for (T1, T2, T3) {
+    #[inline(always)]
+    fn select<A, R, E: FnOnce(A) -> R, T: FnOnce(A) -> R>(args: A, erased: E, typed: T) -> R {
+        let select = Select {
+            args,
+            erased,
+            typed,
+        };
+
+        T1::select(Then::<T2, _>(
+            Then::<T3, _>(select, PhantomData),
+            PhantomData,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Fails to compile if it is evaluated, which only happens when a function using it is
+    // instantiated.
+    struct Instantiated<T>(PhantomData<T>);
+
+    impl<T> Instantiated<T> {
+        const ERROR: bool = panic!("a body that isn't selected was instantiated");
+    }
+
+    fn erased<Args: TypeArgs>() -> bool {
+        Args::select((), |()| true, |()| Instantiated::<Args>::ERROR)
+    }
+
+    fn typed<Args: TypeArgs>() -> bool {
+        Args::select((), |()| Instantiated::<Args>::ERROR, |()| false)
+    }
+
+    #[test]
+    fn only_the_selected_body_is_instantiated() {
+        assert!(erased::<(HString,)>());
+        assert!(erased::<(HString, Object)>());
+        assert!(erased::<(Object, HString, Object)>());
+
+        assert!(!typed::<(i32,)>());
+        assert!(!typed::<(HString, i32)>());
+        assert!(!typed::<(i32, HString)>());
+        assert!(!typed::<(Erased,)>());
+    }
+
+    #[test]
+    fn args() {
+        let value = HString::from("value");
+        let len = <(HString,)>::select((value, 1), |(value, extra)| value.len() + extra, |_| 0);
+        assert_eq!(len, 6);
+    }
+}
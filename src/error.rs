@@ -1,6 +1,16 @@
 #![allow(overflowing_literals)]
 
+use crate::activation::ActivationError;
+use alloc::boxed::Box;
+use alloc::string::String;
+
 /// An alias for `std::result::Result<T, winrt::Error>`
+///
+/// This is the result of every generated method and of the runtime's own functions, so that
+/// errors propagate with `?` regardless of where they come from. An [`ErrorCode`] converts into
+/// an [`Error`], so that a failed HRESULT can be returned with `code.ok()?` and a missing value
+/// with `option.ok_or(ErrorCode::BOUNDS)?`, while implementations of ABI functions turn a result
+/// back into the HRESULT they return with `result.into()`.
 #[must_use]
 pub type Result<T> = std::result::Result<T, Error>;
 
@@ -8,6 +18,9 @@ pub type Result<T> = std::result::Result<T, Error>;
 #[derive(Debug)]
 pub struct Error {
     code: ErrorCode,
+    activation: Option<Box<ActivationError>>,
+    message: Option<String>,
+    kind: Option<ErrorKind>,
     // TODO: add `info: IErrorInfo`
 }
 
@@ -15,6 +28,154 @@ impl Error {
     pub fn code(&self) -> ErrorCode {
         self.code
     }
+
+    /// The class that couldn't be activated, if this error is from getting its activation
+    /// factory.
+    pub fn activation(&self) -> Option<&ActivationError> {
+        self.activation.as_deref()
+    }
+
+    pub(crate) fn with_activation(mut self, activation: ActivationError) -> Self {
+        self.activation = Some(Box::new(activation));
+        self
+    }
+
+    /// A description of the failure from the runtime, such as for a call from the wrong
+    /// apartment.
+    pub fn message(&self) -> Option<&str> {
+        self.message.as_deref()
+    }
+
+    pub(crate) fn with_message(mut self, message: String) -> Self {
+        self.message = Some(message);
+        self
+    }
+
+    pub(crate) fn with_kind(mut self, kind: ErrorKind) -> Self {
+        self.kind = Some(kind);
+        self
+    }
+
+    /// Classifies the error by its code, unless the runtime knows more about where it came from
+    pub fn kind(&self) -> ErrorKind {
+        if let Some(kind) = self.kind {
+            return kind;
+        }
+
+        match self.code {
+            ErrorCode::CLOSED | ErrorCode::CHANGED_STATE | ErrorCode::DISCONNECTED => {
+                ErrorKind::Closed
+            }
+            ErrorCode::NO_PACKAGE => ErrorKind::NoPackageIdentity,
+            ErrorCode::OLD_WIN_VERSION => ErrorKind::NotSupportedOnThisVersion,
+            _ => ErrorKind::Other,
+        }
+    }
+}
+
+/// The kind of an [`Error`], as returned by [`Error::kind`]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum ErrorKind {
+    /// The object was closed or disconnected, or its state changed so that it can no longer be
+    /// used. This is common for brokered objects after the app is suspended and resumed, and
+    /// the object may need to be reacquired, such as with [`Reacquire`](crate::Reacquire).
+    Closed,
+    /// The collection changed while it was being iterated, as reported by the generated
+    /// `checked_iter` and `to_vec_snapshot` methods of the collection interfaces. The iteration
+    /// may be started over with a new iterator.
+    CollectionChanged,
+    /// The API requires package identity, which the calling process doesn't have. The
+    /// [`package`](crate::package) module describes running unpackaged.
+    NoPackageIdentity,
+    /// The API was introduced by a later version of Windows than the one that is running, as
+    /// reported by the methods generated with the `version_checks` option of `import!`. The
+    /// [`api_information`](crate::api_information) module can test for APIs ahead of time.
+    NotSupportedOnThisVersion,
+    /// Any other error
+    Other,
+}
+
+impl From<ErrorCode> for Error {
+    fn from(code: ErrorCode) -> Self {
+        Self {
+            code,
+            activation: None,
+            message: None,
+            kind: None,
+        }
+    }
+}
+
+// Turning an error back into an HRESULT is how an implementation of an ABI function returns it
+// to its caller, so this is where errors originating in Rust are logged.
+impl From<Error> for ErrorCode {
+    fn from(error: Error) -> Self {
+        crate::trace::error_originated(error.code);
+        error.code
+    }
+}
+
+impl From<Result<()>> for ErrorCode {
+    fn from(result: Result<()>) -> Self {
+        match result {
+            Ok(()) => ErrorCode(0),
+            Err(error) => error.into(),
+        }
+    }
+}
+
+impl core::fmt::Display for Error {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        match (&self.activation, &self.message) {
+            (Some(activation), _) => core::fmt::Display::fmt(activation, f),
+            (None, Some(message)) => write!(f, "{} ({})", message, self.code),
+            (None, None) => core::fmt::Display::fmt(&self.code, f),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Error {}
+
+// Errors that didn't come from the operating system are classified by their kind, and the
+// description is kept as the message. An error that was itself converted from an `Error` is
+// unwrapped again.
+#[cfg(feature = "std")]
+impl From<std::io::Error> for Error {
+    fn from(error: std::io::Error) -> Self {
+        const ERROR_FILE_NOT_FOUND: u32 = 2;
+
+        if error.get_ref().is_some_and(|inner| inner.is::<Error>()) {
+            return *error.into_inner().unwrap().downcast::<Error>().unwrap();
+        }
+
+        let code = match error.raw_os_error() {
+            Some(code) if code > 0 => ErrorCode::from_win32(code as u32),
+            Some(code) if code < 0 => ErrorCode(code),
+            _ => match error.kind() {
+                std::io::ErrorKind::NotFound => ErrorCode::from_win32(ERROR_FILE_NOT_FOUND),
+                std::io::ErrorKind::InvalidInput => ErrorCode::INVALID_ARGUMENT,
+                _ => ErrorCode::FAIL,
+            },
+        };
+
+        Error::from(code).with_message(error.to_string())
+    }
+}
+
+// An HRESULT in the Win32 facility maps back to its Win32 error code, which `std::io::Error`
+// classifies by kind. Other errors are wrapped, keeping their code and message.
+#[cfg(feature = "std")]
+impl From<Error> for std::io::Error {
+    fn from(error: Error) -> Self {
+        let code = error.code().0 as u32;
+
+        if code & 0xFFFF_0000 == 0x8007_0000 {
+            std::io::Error::from_raw_os_error((code & 0xFFFF) as i32)
+        } else {
+            std::io::Error::other(error)
+        }
+    }
 }
 
 type HRESULT = i32;
@@ -24,7 +185,35 @@ type HRESULT = i32;
 #[derive(Copy, Clone, Debug, PartialEq)]
 pub struct ErrorCode(pub HRESULT);
 
+impl core::fmt::Display for ErrorCode {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write!(f, "HRESULT 0x{:08X}", self.0)
+    }
+}
+
+impl From<HRESULT> for ErrorCode {
+    fn from(value: HRESULT) -> ErrorCode {
+        ErrorCode(value)
+    }
+}
+
+impl From<ErrorCode> for HRESULT {
+    fn from(value: ErrorCode) -> HRESULT {
+        value.0
+    }
+}
+
 impl ErrorCode {
+    /// Maps a Win32 error code to an HRESULT as with HRESULT_FROM_WIN32.
+    #[inline]
+    pub const fn from_win32(code: u32) -> ErrorCode {
+        if code as i32 <= 0 {
+            ErrorCode(code as i32)
+        } else {
+            ErrorCode(((code & 0xFFFF) | 0x8007_0000) as i32)
+        }
+    }
+
     #[inline]
     pub fn is_ok(self) -> bool {
         self.0 >= 0
@@ -45,7 +234,7 @@ impl ErrorCode {
         if self.is_ok() {
             Ok(())
         } else {
-            Err(Error { code: self })
+            Err(self.into())
         }
     }
 
@@ -58,5 +247,43 @@ impl ErrorCode {
         Ok(value())
     }
 
+    /// The object has been closed (RO_E_CLOSED).
+    pub const CLOSED: ErrorCode = ErrorCode(0x8000_0013);
+    /// A concurrent or interleaved operation changed the state of the object (E_CHANGED_STATE).
+    pub const CHANGED_STATE: ErrorCode = ErrorCode(0x8000_000C);
+    /// The object has disconnected from its clients (RPC_E_DISCONNECTED).
+    pub const DISCONNECTED: ErrorCode = ErrorCode(0x8001_0108);
+
+    /// The object was called from an apartment other than the one that created it
+    /// (RPC_E_WRONG_THREAD).
+    pub const WRONG_THREAD: ErrorCode = ErrorCode(0x8001_010E);
+    /// The process has no package identity (APPMODEL_ERROR_NO_PACKAGE).
+    pub const NO_PACKAGE: ErrorCode = ErrorCode(0x8007_3D54);
+    /// The API requires a later version of Windows (ERROR_OLD_WIN_VERSION).
+    pub const OLD_WIN_VERSION: ErrorCode = ErrorCode(0x8007_047E);
+    /// The class isn't registered (REGDB_E_CLASSNOTREG).
+    pub const CLASS_NOT_REGISTERED: ErrorCode = ErrorCode(0x8004_0154);
+
+    /// A catastrophic failure (E_UNEXPECTED).
+    pub const UNEXPECTED: ErrorCode = ErrorCode(0x8000_FFFF);
+    /// An unspecified failure (E_FAIL).
+    pub const FAIL: ErrorCode = ErrorCode(0x8000_4005);
+    /// The method isn't implemented (E_NOTIMPL).
+    pub const NOT_IMPLEMENTED: ErrorCode = ErrorCode(0x8000_4001);
+    /// The object doesn't implement the requested interface (E_NOINTERFACE).
+    pub const NO_INTERFACE: ErrorCode = ErrorCode(0x8000_4002);
+    /// A method was called at an unexpected time (E_ILLEGAL_METHOD_CALL).
+    pub const ILLEGAL_METHOD_CALL: ErrorCode = ErrorCode(0x8000_000E);
+    /// An index is out of bounds (E_BOUNDS).
+    pub const BOUNDS: ErrorCode = ErrorCode(0x8000_000B);
+    /// One or more arguments are invalid (E_INVALIDARG).
+    pub const INVALID_ARGUMENT: ErrorCode = ErrorCode(0x8007_0057);
+
     pub(crate) const NOT_INITIALIZED: ErrorCode = ErrorCode(0x8004_01F0);
+    #[cfg(feature = "std")]
+    pub(crate) const ILLEGAL_DELEGATE_ASSIGNMENT: ErrorCode = ErrorCode(0x8000_0018);
+    #[cfg(feature = "std")]
+    pub(crate) const TIMEOUT: ErrorCode = ErrorCode(0x8007_05B4);
+    pub(crate) const MOD_NOT_FOUND: ErrorCode = ErrorCode(0x8007_007E);
+    pub(crate) const PROC_NOT_FOUND: ErrorCode = ErrorCode(0x8007_007F);
 }
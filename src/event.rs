@@ -0,0 +1,52 @@
+use crate::*;
+
+/// A token identifying a registered event handler
+///
+/// Event sources hand out a token from each `add` method, and the handler remains registered
+/// until the token is passed to the matching `remove` method.
+#[repr(C)]
+#[derive(Copy, Clone, Default, Debug, PartialEq, Eq, Hash)]
+pub struct EventRegistrationToken {
+    pub value: i64,
+}
+
+impl EventRegistrationToken {
+    pub const fn new(value: i64) -> Self {
+        Self { value }
+    }
+
+    /// Returns true if the token identifies a registration. Event sources never hand out a zero
+    /// token, so this is false for a default token and one that has already been removed.
+    pub fn is_valid(&self) -> bool {
+        self.value != 0
+    }
+
+    /// Revokes the registration by passing the token to an event's `remove` method, unless it
+    /// isn't valid, and resets the token so that the handler is only ever removed once.
+    ///
+    /// ```rust,ignore
+    /// token.remove(|token| button.remove_click(token))?;
+    /// ```
+    pub fn remove<F: FnOnce(EventRegistrationToken) -> Result<()>>(
+        &mut self,
+        remove: F,
+    ) -> Result<()> {
+        if !self.is_valid() {
+            return Ok(());
+        }
+
+        remove(std::mem::take(self))
+    }
+}
+
+unsafe impl RuntimeType for EventRegistrationToken {
+    type Abi = Self;
+
+    fn abi(&self) -> Self::Abi {
+        *self
+    }
+
+    fn set_abi(&mut self) -> *mut Self::Abi {
+        self as *mut Self::Abi
+    }
+}
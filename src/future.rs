@@ -0,0 +1,51 @@
+use crate::delegate::{Delegate, DelegateVtable};
+use crate::*;
+
+use std::sync::Mutex;
+use std::task::Waker;
+
+// Support for the generated `Future` implementations of the WinRT async interfaces. The four
+// completed handlers (AsyncActionCompletedHandler, AsyncOperationCompletedHandler<T> and their
+// progress variants) share the same ABI so a single implementation serves them all. The handler
+// is agile so that it may be invoked from whatever thread completes the operation and simply
+// wakes the most recent waker, leaving it to the executor to decide where the future is polled.
+
+/// Creates a completed handler of type `T` that wakes the given waker when invoked.
+pub fn completed_handler<T: ComInterface>(waker: &Waker) -> T {
+    Delegate::new(&VTABLE, Mutex::new(Some(waker.clone())))
+}
+
+/// Replaces the waker of a completed handler previously created by `completed_handler`.
+///
+/// Fails with `E_ILLEGAL_DELEGATE_ASSIGNMENT` if the handler was assigned by someone else, as
+/// an async operation only supports a single completed handler.
+pub fn set_waker<T: ComInterface>(handler: &T, waker: &Waker) -> Result<()> {
+    match Delegate::state(handler, &VTABLE) {
+        Some(state) => {
+            *state.lock().unwrap() = Some(waker.clone());
+            Ok(())
+        }
+        None => ErrorCode::ILLEGAL_DELEGATE_ASSIGNMENT.ok(),
+    }
+}
+
+type Completed = Delegate<Mutex<Option<Waker>>>;
+type CompletedVtable = DelegateVtable<
+    Mutex<Option<Waker>>,
+    extern "system" fn(*mut Completed, RawPtr, i32) -> ErrorCode,
+>;
+
+static VTABLE: CompletedVtable = DelegateVtable::new(invoke);
+
+// Waking runs the executor's code, which mustn't unwind into the async operation.
+extern "system" fn invoke(this: *mut Completed, _sender: RawPtr, _status: i32) -> ErrorCode {
+    let waker = unsafe { (*this).state.lock().unwrap().take() };
+
+    authoring::invoke(|| {
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+
+        Ok(())
+    })
+}
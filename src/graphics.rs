@@ -0,0 +1,35 @@
+// Support for the `euclid` and `palette` features. The generated Windows.Foundation geometry
+// structs and Windows.UI.Color invoke these macros regardless of the features and they expand to
+// nothing when the features are disabled.
+
+#[cfg(feature = "euclid")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __cfg_euclid {
+    ($($item:tt)*) => {
+        $($item)*
+    };
+}
+
+#[cfg(not(feature = "euclid"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __cfg_euclid {
+    ($($item:tt)*) => {};
+}
+
+#[cfg(feature = "palette")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __cfg_palette {
+    ($($item:tt)*) => {
+        $($item)*
+    };
+}
+
+#[cfg(not(feature = "palette"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __cfg_palette {
+    ($($item:tt)*) => {};
+}
@@ -2,12 +2,12 @@ use super::RuntimeType;
 
 /// A globally unique identifier [(GUID)](https://docs.microsoft.com/en-us/dotnet/api/system.guid?view=netcore-3.1)
 #[repr(C)]
-#[derive(Clone, Default, PartialEq)]
+#[derive(Clone, Default, PartialEq, Eq, Hash)]
 pub struct Guid {
-    data1: u32,
-    data2: u16,
-    data3: u16,
-    data4: [u8; 8],
+    pub(crate) data1: u32,
+    pub(crate) data2: u16,
+    pub(crate) data3: u16,
+    pub(crate) data4: [u8; 8],
 }
 
 impl Guid {
@@ -19,6 +19,122 @@ impl Guid {
             data4,
         }
     }
+
+    /// Computes the IID of a parameterized type from its signature, such as
+    /// `pinterface({913337e9-11a1-4345-a3a2-4e7f956e222d};string)` for `IVector<HString>`.
+    ///
+    /// This is the name-based (version 5) GUID of the signature in the Windows Runtime's
+    /// namespace for parameterized types. Being a `const fn`, it can compute the IID of a generic
+    /// interface implemented in Rust without a hashing dependency at run time.
+    pub const fn from_signature(signature: &[u8]) -> Guid {
+        Guid::from_signature_parts(&[signature])
+    }
+
+    /// Computes the IID of a parameterized type from its signature given in parts, which are
+    /// hashed as if they were concatenated.
+    ///
+    /// A `const` can't build a signature at compile time, so this lets the signature of an
+    /// interface or delegate, as returned by [`Guid::signature`], be combined with the rest:
+    ///
+    /// ```ignore
+    /// // IVector<IMyInterface>
+    /// const IID: Guid = Guid::from_signature_parts(&[
+    ///     b"pinterface({913337e9-11a1-4345-a3a2-4e7f956e222d};",
+    ///     &IMyInterface::IID.signature(),
+    ///     b")",
+    /// ]);
+    /// ```
+    pub const fn from_signature_parts(parts: &[&[u8]]) -> Guid {
+        let (data1, data2, data3, data4) = winrt_sha1::signature_guid(parts);
+
+        Guid {
+            data1,
+            data2,
+            data3,
+            data4,
+        }
+    }
+
+    /// Returns the GUID as it appears in signatures, in lowercase and braces, such as
+    /// `{96369f54-8eb6-48f0-abce-c1b211e627c3}`, which is the signature of a non-generic
+    /// interface or delegate with that IID.
+    pub const fn signature(&self) -> [u8; 38] {
+        const HEX: &[u8; 16] = b"0123456789abcdef";
+
+        let bytes = [
+            (self.data1 >> 24) as u8,
+            (self.data1 >> 16) as u8,
+            (self.data1 >> 8) as u8,
+            self.data1 as u8,
+            (self.data2 >> 8) as u8,
+            self.data2 as u8,
+            (self.data3 >> 8) as u8,
+            self.data3 as u8,
+            self.data4[0],
+            self.data4[1],
+            self.data4[2],
+            self.data4[3],
+            self.data4[4],
+            self.data4[5],
+            self.data4[6],
+            self.data4[7],
+        ];
+
+        let mut signature = [b'-'; 38];
+        signature[0] = b'{';
+        signature[37] = b'}';
+        let mut offset = 1;
+        let mut i = 0;
+
+        while i < 16 {
+            // Dashes follow the 4th, 6th, 8th and 10th bytes.
+            if i == 4 || i == 6 || i == 8 || i == 10 {
+                offset += 1;
+            }
+
+            signature[offset] = HEX[(bytes[i] >> 4) as usize];
+            signature[offset + 1] = HEX[(bytes[i] & 0xf) as usize];
+            offset += 2;
+            i += 1;
+        }
+
+        signature
+    }
+}
+
+#[cfg(feature = "uuid")]
+impl Guid {
+    /// Creates a random (version 4) GUID.
+    pub fn new_v4() -> Guid {
+        uuid::Uuid::new_v4().into()
+    }
+
+    /// Creates a name-based (version 5) GUID that is always the same for a given namespace and name.
+    pub fn new_v5(namespace: &Guid, name: &[u8]) -> Guid {
+        uuid::Uuid::new_v5(&namespace.into(), name).into()
+    }
+}
+
+#[cfg(feature = "uuid")]
+impl From<uuid::Uuid> for Guid {
+    fn from(value: uuid::Uuid) -> Guid {
+        let (data1, data2, data3, data4) = value.as_fields();
+        Guid::from_values(data1, data2, data3, *data4)
+    }
+}
+
+#[cfg(feature = "uuid")]
+impl From<&Guid> for uuid::Uuid {
+    fn from(value: &Guid) -> uuid::Uuid {
+        uuid::Uuid::from_fields(value.data1, value.data2, value.data3, &value.data4)
+    }
+}
+
+#[cfg(feature = "uuid")]
+impl From<Guid> for uuid::Uuid {
+    fn from(value: Guid) -> uuid::Uuid {
+        (&value).into()
+    }
 }
 
 unsafe impl RuntimeType for Guid {
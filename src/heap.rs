@@ -0,0 +1,179 @@
+//! Memory allocation for the runtime's own buffers and for buffers owned across the ABI
+//!
+//! Buffers that the runtime both allocates and frees, such as the backing store of the `IBuffer`
+//! that stream readers fill, are allocated through the [`Allocator`] installed with
+//! [`set_allocator`], which is the process heap unless an embedder provides its own, such as
+//! [`GlobalHeap`] to use the application's global allocator.
+//!
+//! Strings are always allocated on the process heap, whatever the allocator. An HSTRING may be
+//! created by the operating system and released by the runtime, or created by the runtime and
+//! released by the operating system, so both must agree on where it lives. Buffers that cross the
+//! ABI, such as receive arrays and strings returned by Win32 APIs, are owned by the COM task
+//! allocator and must be freed with [`co_task_mem_free`].
+
+use crate::*;
+use std::sync::atomic::{AtomicPtr, Ordering};
+
+/// An allocator for the runtime's own buffers
+///
+/// # Safety
+///
+/// `alloc` must return either null or a pointer to at least `bytes` bytes, aligned to at least
+/// twice the size of a pointer as the process heap is, which remains valid until it is passed to
+/// `free`. Both may be called from any thread.
+pub unsafe trait Allocator: Sync {
+    /// Allocates `bytes` bytes, returning null on failure
+    ///
+    /// # Safety
+    ///
+    /// `bytes` must not be zero.
+    unsafe fn alloc(&self, bytes: usize) -> RawPtr;
+
+    /// Frees memory returned by `alloc`
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must have been returned by `alloc` on this allocator and not already freed.
+    unsafe fn free(&self, ptr: RawPtr);
+}
+
+/// The default allocator, which uses the process heap
+pub struct ProcessHeap;
+
+unsafe impl Allocator for ProcessHeap {
+    unsafe fn alloc(&self, bytes: usize) -> RawPtr {
+        runtime::HeapAlloc(runtime::GetProcessHeap(), 0, bytes)
+    }
+
+    unsafe fn free(&self, ptr: RawPtr) {
+        runtime::HeapFree(runtime::GetProcessHeap(), 0, ptr);
+    }
+}
+
+/// An allocator that uses Rust's global allocator
+///
+/// This routes the runtime's buffers through the `#[global_allocator]` of the embedding
+/// application, which is installed with `heap::set_allocator(&GlobalHeap)`.
+pub struct GlobalHeap;
+
+// Each allocation is prefixed with its size, which `free` needs to rebuild the layout. The prefix
+// also keeps the memory aligned to twice the size of a pointer.
+const PREFIX: usize = 2 * std::mem::size_of::<usize>();
+
+unsafe impl Allocator for GlobalHeap {
+    unsafe fn alloc(&self, bytes: usize) -> RawPtr {
+        let layout = match bytes
+            .checked_add(PREFIX)
+            .and_then(|size| alloc::alloc::Layout::from_size_align(size, PREFIX).ok())
+        {
+            Some(layout) => layout,
+            None => return std::ptr::null_mut(),
+        };
+
+        let ptr = alloc::alloc::alloc(layout);
+
+        if ptr.is_null() {
+            return std::ptr::null_mut();
+        }
+
+        *(ptr as *mut usize) = bytes;
+        ptr.add(PREFIX) as RawPtr
+    }
+
+    unsafe fn free(&self, ptr: RawPtr) {
+        let ptr = (ptr as *mut u8).sub(PREFIX);
+        let bytes = *(ptr as *mut usize);
+        alloc::alloc::dealloc(
+            ptr,
+            alloc::alloc::Layout::from_size_align_unchecked(bytes + PREFIX, PREFIX),
+        );
+    }
+}
+
+#[cfg(feature = "std")]
+static DEFAULT: &dyn Allocator = &ProcessHeap;
+static ALLOCATOR: AtomicPtr<&'static dyn Allocator> = AtomicPtr::new(std::ptr::null_mut());
+
+/// Routes the runtime's allocations through `allocator`
+///
+/// The allocator can only be set before the runtime first allocates a buffer, since memory must
+/// be freed by the allocator it came from, and it is never used for strings. Returns false,
+/// leaving the current allocator in place, if an allocator was already set or the runtime has
+/// already allocated a buffer from the process heap.
+pub fn set_allocator(allocator: &'static dyn Allocator) -> bool {
+    let ptr = Box::into_raw(Box::new(allocator));
+
+    match ALLOCATOR.compare_exchange(
+        std::ptr::null_mut(),
+        ptr,
+        Ordering::AcqRel,
+        Ordering::Acquire,
+    ) {
+        Ok(_) => true,
+        Err(_) => {
+            unsafe { std::mem::drop(Box::from_raw(ptr)) };
+            false
+        }
+    }
+}
+
+// The first buffer pins the process heap unless an allocator was set.
+#[cfg(feature = "std")]
+fn allocator() -> &'static dyn Allocator {
+    let mut ptr = ALLOCATOR.load(Ordering::Acquire);
+
+    if ptr.is_null() {
+        let default = &DEFAULT as *const _ as *mut _;
+
+        ptr = match ALLOCATOR.compare_exchange(
+            std::ptr::null_mut(),
+            default,
+            Ordering::AcqRel,
+            Ordering::Acquire,
+        ) {
+            Ok(_) => default,
+            Err(current) => current,
+        };
+    }
+
+    unsafe { *ptr }
+}
+
+#[cfg(feature = "std")]
+pub(crate) unsafe fn alloc(bytes: usize) -> RawPtr {
+    allocator().alloc(bytes)
+}
+
+#[cfg(feature = "std")]
+pub(crate) unsafe fn free(ptr: RawPtr) {
+    allocator().free(ptr)
+}
+
+// HSTRING headers are shared with the operating system, which may free them, and so always live
+// on the process heap.
+pub(crate) unsafe fn string_alloc(bytes: usize) -> RawPtr {
+    ProcessHeap.alloc(bytes)
+}
+
+pub(crate) unsafe fn string_free(ptr: RawPtr) {
+    ProcessHeap.free(ptr)
+}
+
+/// Allocates `bytes` bytes with the COM task allocator, returning null on failure
+///
+/// This is the allocator that owns buffers passed across the ABI, such as those filled in for
+/// receive arrays.
+pub fn co_task_mem_alloc(bytes: usize) -> RawPtr {
+    unsafe { runtime::CoTaskMemAlloc(bytes) }
+}
+
+/// Frees memory owned by the COM task allocator, such as strings returned by Win32 APIs
+///
+/// # Safety
+///
+/// `ptr` must be null or have been allocated by the COM task allocator and not already freed.
+pub unsafe fn co_task_mem_free(ptr: RawPtr) {
+    if !ptr.is_null() {
+        runtime::CoTaskMemFree(ptr);
+    }
+}
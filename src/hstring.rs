@@ -1,5 +1,4 @@
 use crate::ref_count::RefCount;
-use crate::runtime;
 use crate::*;
 use std::ptr;
 
@@ -36,6 +35,10 @@ impl HString {
     }
 
     /// Get the string as 16-bit wide characters (wchars)
+    ///
+    /// This borrows the string data without a copy. The slice excludes the null terminator and
+    /// includes any embedded nulls, since an HSTRING's length is stored rather than implied by
+    /// its first null.
     pub fn as_wide(&self) -> &[u16] {
         if self.is_empty() {
             return &[];
@@ -45,6 +48,38 @@ impl HString {
         unsafe { std::slice::from_raw_parts((*header).data, (*header).len as usize) }
     }
 
+    /// Get a pointer to the null-terminated string data, as expected by Win32 W-functions
+    ///
+    /// The pointer is valid for as long as the string is borrowed. Functions reading up to the
+    /// first null will see only the part of the string before any embedded null.
+    pub fn as_ptr(&self) -> *const u16 {
+        static EMPTY: u16 = 0;
+
+        if self.is_empty() {
+            return &EMPTY;
+        }
+
+        unsafe { (*self.ptr).data }
+    }
+
+    /// Create a string from 16-bit wide characters
+    ///
+    /// The characters are copied as they are, including any embedded nulls.
+    pub fn from_wide(value: &[u16]) -> HString {
+        if value.is_empty() {
+            return HString::new();
+        }
+
+        let ptr = Header::alloc(value.len() as u32);
+
+        unsafe {
+            ptr::copy_nonoverlapping(value.as_ptr(), (*ptr).data, value.len());
+            ptr::write((*ptr).data.add(value.len()), 0);
+        }
+
+        Self { ptr }
+    }
+
     /// Clear the contents of the string and free the memory if the last handle to the string data
     pub fn clear(&mut self) {
         if self.is_empty() {
@@ -56,17 +91,94 @@ impl HString {
             debug_assert!((*header).flags & REFERENCE_FLAG == 0);
 
             if (*((*header).shared.as_mut_ptr())).count.release() == 0 {
-                runtime::HeapFree(runtime::GetProcessHeap(), 0, self.ptr as RawPtr);
+                heap::string_free(self.ptr as RawPtr);
             }
         }
 
         self.ptr = std::ptr::null_mut();
     }
+
+    /// Check whether the string begins with `prefix`, comparing UTF-16 code units
+    pub fn starts_with(&self, prefix: &str) -> bool {
+        let mut wide = self.as_wide().iter().copied();
+        prefix.encode_utf16().all(|c| wide.next() == Some(c))
+    }
+
+    /// Check whether the string ends with `suffix`, comparing UTF-16 code units
+    pub fn ends_with(&self, suffix: &str) -> bool {
+        let suffix: Vec<u16> = suffix.encode_utf16().collect();
+        self.as_wide().ends_with(&suffix)
+    }
+
+    /// Create a lowercase copy of the string
+    ///
+    /// The characters are mapped as with `char::to_lowercase` directly from the UTF-16 data, and
+    /// any unpaired surrogates are copied as they are.
+    pub fn to_lowercase(&self) -> HString {
+        self.map_chars(|c, wide| c.to_lowercase().for_each(|c| push_char(c, wide)))
+    }
+
+    /// Create an uppercase copy of the string
+    ///
+    /// The characters are mapped as with `char::to_uppercase` directly from the UTF-16 data, and
+    /// any unpaired surrogates are copied as they are.
+    pub fn to_uppercase(&self) -> HString {
+        self.map_chars(|c, wide| c.to_uppercase().for_each(|c| push_char(c, wide)))
+    }
+
+    fn map_chars<F: Fn(char, &mut Vec<u16>)>(&self, f: F) -> HString {
+        let mut wide = Vec::with_capacity(self.len());
+
+        for c in std::char::decode_utf16(self.as_wide().iter().copied()) {
+            match c {
+                Ok(c) => f(c, &mut wide),
+                Err(error) => wide.push(error.unpaired_surrogate()),
+            }
+        }
+
+        HString::from_wide(&wide)
+    }
+
+    // Concatenates the strings into a single allocation of exactly `left.len() + right_len`
+    // characters. Like every other `HString`, the result is allocated with `heap::string_alloc`
+    // rather than the pluggable allocator, so it can be freed by whichever side releases it.
+    fn concat<I: Iterator<Item = u16>>(left: &[u16], right_len: usize, right: I) -> HString {
+        let len = left.len() + right_len;
+
+        if len == 0 {
+            return HString::new();
+        }
+
+        assert!(len <= u32::MAX as usize, "HString length exceeds u32");
+        let ptr = Header::alloc(len as u32);
+
+        unsafe {
+            ptr::copy_nonoverlapping(left.as_ptr(), (*ptr).data, left.len());
+            let data = (*ptr).data.add(left.len());
+
+            for (offset, wide) in right.take(right_len).enumerate() {
+                ptr::write(data.add(offset), wide);
+            }
+
+            ptr::write((*ptr).data.add(len), 0);
+        }
+
+        Self { ptr }
+    }
+}
+
+fn push_char(c: char, wide: &mut Vec<u16>) {
+    let mut buffer = [0; 2];
+    wide.extend_from_slice(c.encode_utf16(&mut buffer));
 }
 
 unsafe impl RuntimeType for HString {
     type Abi = *mut Header;
 
+    fn select<S: crate::erased::Selector>(selector: S) -> S::Output {
+        selector.erased()
+    }
+
     fn abi(&self) -> Self::Abi {
         self.ptr
     }
@@ -90,7 +202,7 @@ impl Clone for HString {
         }
 
         Self {
-            ptr: unsafe { (*self.ptr).duplicate() },
+            ptr: unsafe { Header::duplicate(self.ptr) },
         }
     }
 }
@@ -140,6 +252,58 @@ impl From<&str> for HString {
     }
 }
 
+#[cfg(feature = "std")]
+impl From<&std::ffi::OsStr> for HString {
+    fn from(value: &std::ffi::OsStr) -> HString {
+        #[cfg(windows)]
+        {
+            use std::os::windows::ffi::OsStrExt;
+            let wide: Vec<u16> = value.encode_wide().collect();
+            HString::from_wide(&wide)
+        }
+        #[cfg(not(windows))]
+        {
+            value.to_string_lossy().as_ref().into()
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<&std::path::Path> for HString {
+    fn from(value: &std::path::Path) -> HString {
+        value.as_os_str().into()
+    }
+}
+
+#[cfg(feature = "widestring")]
+impl HString {
+    /// Borrow the string as a `widestring::U16Str` without a copy
+    pub fn as_u16str(&self) -> &widestring::U16Str {
+        widestring::U16Str::from_slice(self.as_wide())
+    }
+}
+
+#[cfg(feature = "widestring")]
+impl From<&widestring::U16Str> for HString {
+    fn from(value: &widestring::U16Str) -> HString {
+        HString::from_wide(value.as_slice())
+    }
+}
+
+#[cfg(feature = "widestring")]
+impl From<&widestring::U16CStr> for HString {
+    fn from(value: &widestring::U16CStr) -> HString {
+        HString::from_wide(value.as_slice())
+    }
+}
+
+#[cfg(feature = "widestring")]
+impl From<&HString> for widestring::U16String {
+    fn from(hstring: &HString) -> Self {
+        widestring::U16String::from_vec(hstring.as_wide())
+    }
+}
+
 impl From<String> for HString {
     fn from(value: String) -> HString {
         value.as_str().into()
@@ -158,6 +322,14 @@ impl PartialEq for HString {
     }
 }
 
+impl Eq for HString {}
+
+impl std::hash::Hash for HString {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.as_wide().hash(state)
+    }
+}
+
 impl PartialEq<String> for HString {
     fn eq(&self, other: &String) -> bool {
         self == other.as_str()
@@ -176,6 +348,85 @@ impl PartialEq<&str> for HString {
     }
 }
 
+impl std::ops::Add<&HString> for &HString {
+    type Output = HString;
+
+    fn add(self, other: &HString) -> HString {
+        if self.is_empty() {
+            return other.clone();
+        }
+
+        if other.is_empty() {
+            return self.clone();
+        }
+
+        HString::concat(self.as_wide(), other.len(), other.as_wide().iter().copied())
+    }
+}
+
+impl std::ops::Add<&str> for &HString {
+    type Output = HString;
+
+    fn add(self, other: &str) -> HString {
+        if other.is_empty() {
+            return self.clone();
+        }
+
+        let len = other.chars().map(char::len_utf16).sum();
+        HString::concat(self.as_wide(), len, other.encode_utf16())
+    }
+}
+
+impl std::ops::Add<&HString> for HString {
+    type Output = HString;
+
+    fn add(self, other: &HString) -> HString {
+        &self + other
+    }
+}
+
+impl std::ops::Add<&str> for HString {
+    type Output = HString;
+
+    fn add(self, other: &str) -> HString {
+        &self + other
+    }
+}
+
+impl std::ops::AddAssign<&HString> for HString {
+    fn add_assign(&mut self, other: &HString) {
+        *self = &*self + other;
+    }
+}
+
+impl std::ops::AddAssign<&str> for HString {
+    fn add_assign(&mut self, other: &str) {
+        *self = &*self + other;
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<&HString> for std::ffi::OsString {
+    fn from(hstring: &HString) -> Self {
+        #[cfg(windows)]
+        {
+            use std::os::windows::ffi::OsStringExt;
+            std::ffi::OsString::from_wide(hstring.as_wide())
+        }
+        #[cfg(not(windows))]
+        {
+            String::from_utf16_lossy(hstring.as_wide()).into()
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<&HString> for std::path::PathBuf {
+    fn from(hstring: &HString) -> Self {
+        std::ffi::OsString::from(hstring).into()
+    }
+}
+
 impl<'a> From<&'a HString> for String {
     fn from(hstring: &HString) -> Self {
         String::from_utf16(hstring.as_wide()).unwrap()
@@ -188,6 +439,186 @@ impl From<HString> for String {
     }
 }
 
+/// A preallocated buffer that becomes an [`HString`](struct.HString.html) without a copy
+///
+/// The buffer is zero-initialized and may be written directly, for example from `encode_utf16` or
+/// by a Win32 API filling a `PWSTR`. Space for the null terminator is reserved beyond the end of
+/// the slice.
+///
+/// ```rust
+/// # use winrt::{HString, HStringBuilder};
+/// let mut builder = HStringBuilder::new(5);
+///
+/// for (slot, wide) in builder.iter_mut().zip("hello".encode_utf16()) {
+///     *slot = wide;
+/// }
+///
+/// let value: HString = builder.into();
+/// assert_eq!(value, "hello");
+/// ```
+pub struct HStringBuilder {
+    ptr: *mut Header,
+}
+
+impl HStringBuilder {
+    /// Allocate a buffer for a string of `len` UTF-16 code units
+    pub fn new(len: usize) -> Self {
+        if len == 0 {
+            return Self {
+                ptr: std::ptr::null_mut(),
+            };
+        }
+
+        assert!(len <= u32::MAX as usize, "HString length exceeds u32");
+        let ptr = Header::alloc(len as u32);
+
+        unsafe {
+            ptr::write_bytes((*ptr).data, 0, len + 1);
+        }
+
+        Self { ptr }
+    }
+
+    /// Shorten the string to `len` code units, such as when an API writes fewer than expected
+    ///
+    /// This has no effect if `len` is not less than the current length.
+    pub fn truncate(&mut self, len: usize) {
+        if len < self.len() {
+            unsafe { (*self.ptr).len = len as u32 };
+        }
+    }
+
+    fn len(&self) -> usize {
+        if self.ptr.is_null() {
+            0
+        } else {
+            unsafe { (*self.ptr).len as usize }
+        }
+    }
+}
+
+impl std::ops::Deref for HStringBuilder {
+    type Target = [u16];
+
+    fn deref(&self) -> &[u16] {
+        if self.ptr.is_null() {
+            return &[];
+        }
+
+        unsafe { std::slice::from_raw_parts((*self.ptr).data, self.len()) }
+    }
+}
+
+impl std::ops::DerefMut for HStringBuilder {
+    fn deref_mut(&mut self) -> &mut [u16] {
+        if self.ptr.is_null() {
+            return &mut [];
+        }
+
+        unsafe { std::slice::from_raw_parts_mut((*self.ptr).data, self.len()) }
+    }
+}
+
+impl From<HStringBuilder> for HString {
+    fn from(builder: HStringBuilder) -> HString {
+        let builder = std::mem::ManuallyDrop::new(builder);
+        let mut value = HString { ptr: builder.ptr };
+
+        if !value.ptr.is_null() {
+            let len = builder.len();
+
+            // The buffer may have been truncated since the terminator was written.
+            unsafe { ptr::write((*value.ptr).data.add(len), 0) };
+
+            if len == 0 {
+                value.clear();
+            }
+        }
+
+        value
+    }
+}
+
+impl Drop for HStringBuilder {
+    fn drop(&mut self) {
+        if !self.ptr.is_null() {
+            unsafe { heap::string_free(self.ptr as RawPtr) };
+        }
+    }
+}
+
+/// A string literal with its UTF-16 data and fast-pass HSTRING header computed at compile time
+///
+/// These are created with the [`h!`](macro.h.html) macro. Passing one to a WinRT method borrows
+/// the static data, so the call performs no allocation and no UTF-8 to UTF-16 conversion.
+#[repr(transparent)]
+pub struct StaticHString {
+    #[doc(hidden)]
+    pub header: *const StaticHeader,
+}
+
+// The header and string data are immutable statics.
+unsafe impl Sync for StaticHString {}
+
+impl StaticHString {
+    /// Borrow the literal as an `HString`
+    pub fn as_hstring(&self) -> &HString {
+        unsafe { &*(self as *const StaticHString as *const HString) }
+    }
+}
+
+impl std::ops::Deref for StaticHString {
+    type Target = HString;
+
+    fn deref(&self) -> &HString {
+        self.as_hstring()
+    }
+}
+
+impl std::fmt::Debug for StaticHString {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_hstring())
+    }
+}
+
+impl<'a> From<&'a StaticHString> for Param<'a, HString> {
+    fn from(value: &'a StaticHString) -> Param<'a, HString> {
+        Param::Borrowed(value.as_hstring())
+    }
+}
+
+/// The leading fields of `Header`, which is all that a reference string requires.
+#[doc(hidden)]
+#[repr(C)]
+pub struct StaticHeader {
+    pub flags: u32,
+    pub len: u32,
+    pub _0: u32,
+    pub _1: u32,
+    pub data: *const u16,
+}
+
+unsafe impl Sync for StaticHeader {}
+
+/// Creates a [`StaticHString`](struct.StaticHString.html) from a string literal
+///
+/// The literal is converted to UTF-16 at compile time and given a fast-pass HSTRING header, so
+/// the result may be passed to any WinRT method expecting a string without allocating.
+///
+/// ```ignore
+/// let uri = Uri::create_uri(winrt::h!("http://kennykerr.ca"))?;
+/// ```
+#[macro_export]
+macro_rules! h {
+    ($value:literal) => {{
+        // Procedural macros may only be expanded in item position on older compilers.
+        mod __h {
+            $crate::__hstring!($value);
+        }
+        &__h::HSTRING
+    }};
+}
+
 const REFERENCE_FLAG: u32 = 1;
 
 #[repr(C)]
@@ -211,8 +642,7 @@ impl Header {
         debug_assert!(len != 0);
         // alloc enough space for header and two bytes per character
         let alloc_size = std::mem::size_of::<Header>() + 2 * len as usize;
-        let header =
-            unsafe { runtime::HeapAlloc(runtime::GetProcessHeap(), 0, alloc_size) as *mut Header };
+        let header = unsafe { heap::string_alloc(alloc_size) as *mut Header };
 
         if header.is_null() {
             panic!("Could not successfully allocate for HString");
@@ -227,17 +657,18 @@ impl Header {
         header
     }
 
-    fn duplicate(&mut self) -> *mut Header {
-        if self.flags & REFERENCE_FLAG == 0 {
-            unsafe {
-                (*self.shared.as_ptr()).count.addref();
-                self
-            }
+    // A reference header may be the `StaticHeader` of an `h!` literal, which is smaller than a
+    // `Header` and immutable, so only its leading fields are read and it is never borrowed as a
+    // whole.
+    unsafe fn duplicate(header: *const Header) -> *mut Header {
+        if (*header).flags & REFERENCE_FLAG == 0 {
+            (*(*header).shared.as_ptr()).count.addref();
+            header as *mut Header
         } else {
-            let copy = Header::alloc(self.len);
-            unsafe {
-                std::ptr::copy_nonoverlapping(self.data, (*copy).data, self.len as usize + 1);
-            }
+            let len = (*header).len;
+            let data = (*header).data;
+            let copy = Header::alloc(len);
+            ptr::copy_nonoverlapping(data, (*copy).data, len as usize + 1);
             copy
         }
     }
@@ -308,9 +739,88 @@ mod tests {
         assert!(format!("{}", to) == "Hello");
     }
 
+    #[test]
+    fn builder() {
+        let mut builder = HStringBuilder::new(8);
+        assert!(builder.iter().all(|wide| *wide == 0));
+
+        for (slot, wide) in builder.iter_mut().zip("Hello".encode_utf16()) {
+            *slot = wide;
+        }
+
+        builder.truncate(5);
+        let value: HString = builder.into();
+        assert!(value == "Hello");
+        assert!(unsafe { *(*value.ptr).data.add(5) } == 0);
+
+        let mut builder = HStringBuilder::new(3);
+        builder.truncate(0);
+        assert!(HString::from(builder).is_empty());
+        assert!(HString::from(HStringBuilder::new(0)).is_empty());
+    }
+
+    #[test]
+    fn concat() {
+        let hello = HString::from("Hello");
+        let mut value = &hello + " wörld";
+        assert!(value == "Hello wörld");
+        assert!(value.len() == 11);
+        assert!(unsafe { *(*value.ptr).data.add(11) } == 0);
+
+        value += &HString::from("!");
+        assert!(value == "Hello wörld!");
+
+        let empty = HString::new();
+        assert!(&empty + &hello == "Hello");
+        assert!((&empty + "").is_empty());
+        assert!(hello + "" == "Hello");
+
+        let value = &HString::from("a") + "é😀";
+        assert!(value == "aé😀");
+        assert!(value.len() == 4);
+        assert!(unsafe { *(*value.ptr).data.add(4) } == 0);
+    }
+
+    #[test]
+    fn operations() {
+        let value = HString::from("Hello World");
+        assert!(value.starts_with("Hello"));
+        assert!(value.starts_with(""));
+        assert!(!value.starts_with("World"));
+        assert!(value.ends_with("World"));
+        assert!(!value.ends_with("Hello World!"));
+        assert!(value.to_lowercase() == "hello world");
+        assert!(value.to_uppercase() == "HELLO WORLD");
+        assert!(HString::from("ÄÖ").to_lowercase() == "äö");
+        assert!(HString::new().to_lowercase().is_empty());
+    }
+
     #[test]
     fn from_empty_string() {
         let h = HString::from("");
         assert!(format!("{}", h) == "");
     }
+
+    #[test]
+    fn clone_literal() {
+        // The same statics that `h!` expands to, which are read-only and whose header is smaller
+        // than `Header`. Cloning must only read them, which `cargo miri test` also checks.
+        static DATA: [u16; 6] = [0x68, 0x65, 0x6C, 0x6C, 0x6F, 0];
+        static HEADER: StaticHeader = StaticHeader {
+            flags: 1,
+            len: 5,
+            _0: 0,
+            _1: 0,
+            data: &DATA as *const [u16; 6] as *const u16,
+        };
+        static HSTRING: StaticHString = StaticHString { header: &HEADER };
+
+        let value = HString::clone(&HSTRING);
+        assert!(value == "hello");
+        assert!(value.ptr as *const StaticHeader != &HEADER as *const StaticHeader);
+        assert!(HSTRING.len() == 5);
+
+        let copy = value.clone();
+        assert!(copy.ptr == value.ptr);
+    }
 }
@@ -0,0 +1,254 @@
+//! Support for moving pixel data between `SoftwareBitmap` and Rust imaging code.
+//!
+//! A `SoftwareBitmap` stores each plane of its pixel data with a stride that is often wider than
+//! the visible pixels. The generated `SoftwareBitmap::to_pixels` and `SoftwareBitmap::from_pixels`
+//! methods copy to and from a tightly packed [`Pixels`] buffer so that the data may be handed to
+//! code that expects a plain `(width, height, format, &[u8])` image. With the `image` feature
+//! enabled, `Pixels` may also be converted to and from `image::DynamicImage`.
+
+use crate::*;
+
+/// The layout of the pixels in a [`Pixels`] buffer, corresponding to `BitmapPixelFormat`
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum PixelFormat {
+    /// Four bytes per pixel in blue, green, red, alpha order
+    Bgra8,
+    /// Four bytes per pixel in red, green, blue, alpha order
+    Rgba8,
+    /// One byte per pixel
+    Gray8,
+    /// Two little-endian bytes per pixel
+    Gray16,
+    /// A full resolution plane of luma bytes followed by a half resolution plane of interleaved
+    /// chroma (U, V) byte pairs
+    Nv12,
+    /// Any other `BitmapPixelFormat` value, which cannot be packed or unpacked
+    Other(i32),
+}
+
+impl PixelFormat {
+    /// Converts from the `BitmapPixelFormat` ABI value.
+    pub fn from_abi(value: i32) -> Self {
+        match value {
+            87 => Self::Bgra8,
+            30 => Self::Rgba8,
+            62 => Self::Gray8,
+            57 => Self::Gray16,
+            103 => Self::Nv12,
+            value => Self::Other(value),
+        }
+    }
+
+    /// Converts to the `BitmapPixelFormat` ABI value.
+    pub fn abi(self) -> i32 {
+        match self {
+            Self::Bgra8 => 87,
+            Self::Rgba8 => 30,
+            Self::Gray8 => 62,
+            Self::Gray16 => 57,
+            Self::Nv12 => 103,
+            Self::Other(value) => value,
+        }
+    }
+
+    // Returns the number of rows and the number of bytes per row of each plane.
+    fn planes(self, width: usize, height: usize) -> Result<Vec<(usize, usize)>> {
+        match self {
+            Self::Bgra8 | Self::Rgba8 => Ok(vec![(height, width * 4)]),
+            Self::Gray8 => Ok(vec![(height, width)]),
+            Self::Gray16 => Ok(vec![(height, width * 2)]),
+            Self::Nv12 => Ok(vec![
+                (height, width),
+                (height / 2 + height % 2, width + width % 2),
+            ]),
+            Self::Other(_) => Err(ErrorCode::INVALID_ARGUMENT.into()),
+        }
+    }
+
+    // Returns the length of a tightly packed image of this format.
+    fn len(self, width: u32, height: u32) -> Result<usize> {
+        Ok(self
+            .planes(width as usize, height as usize)?
+            .iter()
+            .map(|(rows, row)| rows * row)
+            .sum())
+    }
+}
+
+/// A tightly packed image with no padding between rows or planes
+#[derive(Clone, Debug, PartialEq)]
+pub struct Pixels {
+    pub width: u32,
+    pub height: u32,
+    pub format: PixelFormat,
+    pub data: Vec<u8>,
+}
+
+impl Pixels {
+    /// Creates an image, failing if the length of the data does not match the dimensions.
+    pub fn new(width: u32, height: u32, format: PixelFormat, data: Vec<u8>) -> Result<Self> {
+        if data.len() != format.len(width, height)? {
+            return Err(ErrorCode::INVALID_ARGUMENT.into());
+        }
+
+        Ok(Self {
+            width,
+            height,
+            format,
+            data,
+        })
+    }
+
+    pub fn as_tuple(&self) -> (u32, u32, PixelFormat, &[u8]) {
+        (self.width, self.height, self.format, &self.data)
+    }
+}
+
+/// The location of a plane within a bitmap buffer, as given by `BitmapPlaneDescription`
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Plane {
+    pub start: usize,
+    pub stride: usize,
+}
+
+/// Copies the planes out of a bitmap buffer, dropping any padding.
+#[doc(hidden)]
+pub fn pack(
+    width: u32,
+    height: u32,
+    format: PixelFormat,
+    planes: &[Plane],
+    buffer: &[u8],
+) -> Result<Pixels> {
+    let mut data = Vec::new();
+
+    for_each_row(
+        width,
+        height,
+        format,
+        planes,
+        buffer.len(),
+        |offset, len| {
+            data.extend_from_slice(&buffer[offset..offset + len]);
+        },
+    )?;
+
+    Ok(Pixels {
+        width,
+        height,
+        format,
+        data,
+    })
+}
+
+/// Copies packed pixels into the planes of a bitmap buffer, failing if the length of the data
+/// does not match the dimensions.
+#[doc(hidden)]
+pub fn unpack(pixels: &Pixels, planes: &[Plane], buffer: &mut [u8]) -> Result<()> {
+    // The fields of `Pixels` are public, so the data may not be the length that `new` checked.
+    if pixels.data.len() != pixels.format.len(pixels.width, pixels.height)? {
+        return Err(ErrorCode::INVALID_ARGUMENT.into());
+    }
+
+    let mut source = 0;
+    let length = buffer.len();
+
+    for_each_row(
+        pixels.width,
+        pixels.height,
+        pixels.format,
+        planes,
+        length,
+        |offset, len| {
+            buffer[offset..offset + len].copy_from_slice(&pixels.data[source..source + len]);
+            source += len;
+        },
+    )
+}
+
+// Calls `f` with the offset and length of every row, having first checked that they all fit.
+fn for_each_row<F: FnMut(usize, usize)>(
+    width: u32,
+    height: u32,
+    format: PixelFormat,
+    planes: &[Plane],
+    length: usize,
+    mut f: F,
+) -> Result<()> {
+    let layout = format.planes(width as usize, height as usize)?;
+
+    if layout.len() != planes.len() {
+        return Err(ErrorCode::INVALID_ARGUMENT.into());
+    }
+
+    for ((rows, row), plane) in layout.iter().zip(planes) {
+        if *rows > 0
+            && (plane.stride < *row || plane.start + plane.stride * (rows - 1) + row > length)
+        {
+            return Err(ErrorCode::INVALID_ARGUMENT.into());
+        }
+    }
+
+    for ((rows, row), plane) in layout.iter().zip(planes) {
+        for index in 0..*rows {
+            f(plane.start + plane.stride * index, *row);
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(feature = "image")]
+impl std::convert::TryFrom<Pixels> for image::DynamicImage {
+    type Error = Error;
+
+    fn try_from(pixels: Pixels) -> Result<Self> {
+        let (width, height) = (pixels.width, pixels.height);
+        let invalid = || Error::from(ErrorCode::INVALID_ARGUMENT);
+
+        match pixels.format {
+            PixelFormat::Bgra8 => {
+                let mut data = pixels.data;
+                data.chunks_exact_mut(4).for_each(|pixel| pixel.swap(0, 2));
+                image::RgbaImage::from_raw(width, height, data)
+                    .map(Self::ImageRgba8)
+                    .ok_or_else(invalid)
+            }
+            PixelFormat::Rgba8 => image::RgbaImage::from_raw(width, height, pixels.data)
+                .map(Self::ImageRgba8)
+                .ok_or_else(invalid),
+            PixelFormat::Gray8 => image::GrayImage::from_raw(width, height, pixels.data)
+                .map(Self::ImageLuma8)
+                .ok_or_else(invalid),
+            PixelFormat::Gray16 => {
+                let data = pixels
+                    .data
+                    .chunks_exact(2)
+                    .map(|pixel| u16::from_le_bytes([pixel[0], pixel[1]]))
+                    .collect();
+                image::ImageBuffer::from_raw(width, height, data)
+                    .map(Self::ImageLuma16)
+                    .ok_or_else(invalid)
+            }
+            PixelFormat::Nv12 | PixelFormat::Other(_) => Err(invalid()),
+        }
+    }
+}
+
+/// Converts the image to `Bgra8`, the format expected by most WinRT imaging APIs.
+#[cfg(feature = "image")]
+impl From<&image::DynamicImage> for Pixels {
+    fn from(image: &image::DynamicImage) -> Self {
+        let image = image.to_rgba8();
+        let (width, height) = image.dimensions();
+        let mut data = image.into_raw();
+        data.chunks_exact_mut(4).for_each(|pixel| pixel.swap(0, 2));
+
+        Self {
+            width,
+            height,
+            format: PixelFormat::Bgra8,
+            data,
+        }
+    }
+}
@@ -0,0 +1,66 @@
+//! Sharing one HSTRING for each frequently used string
+//!
+//! Code that passes the same strings to WinRT over and over, such as the property names and
+//! class names that a data binding bridge looks up in property bags, would otherwise allocate and
+//! convert a new HSTRING for every call. [`intern()`] converts each distinct string once and
+//! returns a handle to the same immutable string data from then on, which costs only a reference
+//! count increment. Interned strings stay allocated until [`clear()`] is called, so the table is
+//! meant for a bounded set of strings rather than arbitrary user data. For string literals the
+//! [`h!`](../macro.h.html) macro avoids even the first allocation.
+
+use crate::*;
+
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+
+// An HString isn't `Send` since it holds a raw pointer, but interned strings are allocated on the
+// heap, never modified, and reference counted atomically.
+struct Interned(HString);
+
+unsafe impl Send for Interned {}
+
+static TABLE: Mutex<BTreeMap<String, Interned>> = Mutex::new(BTreeMap::new());
+
+/// Returns the shared HSTRING for the string, creating it the first time the string is interned.
+pub fn intern(value: &str) -> HString {
+    let mut table = TABLE.lock().unwrap();
+
+    if let Some(interned) = table.get(value) {
+        return interned.0.clone();
+    }
+
+    let interned = HString::from(value);
+    table.insert(value.to_string(), Interned(interned.clone()));
+    interned
+}
+
+/// Returns the number of strings that have been interned.
+pub fn len() -> usize {
+    TABLE.lock().unwrap().len()
+}
+
+/// Releases the table's references to the interned strings. Strings still held elsewhere remain
+/// valid, and interning them again creates new copies.
+pub fn clear() {
+    TABLE.lock().unwrap().clear();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shared() {
+        let first = intern("Windows.Foundation.Uri");
+        let second = intern("Windows.Foundation.Uri");
+        assert_eq!(first, "Windows.Foundation.Uri");
+        assert_eq!(first.as_ptr(), second.as_ptr());
+        assert!(intern("").is_empty());
+        assert!(len() >= 2);
+
+        clear();
+        let third = intern("Windows.Foundation.Uri");
+        assert_eq!(first, third);
+        assert_ne!(first.as_ptr(), third.as_ptr());
+    }
+}
@@ -0,0 +1,469 @@
+//! Interop with the classic COM interfaces that WinRT types implement to integrate with Win32,
+//! DirectX, and other native code.
+
+use crate::runtime;
+use crate::*;
+
+/// A Win32 window handle (HWND)
+///
+/// With the `raw-window-handle` feature enabled this implements `HasRawWindowHandle` so that a
+/// `CoreWindow` may be handed directly to Rust graphics crates such as wgpu.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct WindowHandle(pub RawPtr);
+
+impl WindowHandle {
+    /// Retrieves the window handle of a `CoreWindow` via `ICoreWindowInterop`.
+    pub fn from_core_window<T: ComInterface>(window: &T) -> Result<Self> {
+        let interop: ICoreWindowInterop = window.try_into()?;
+        interop.window_handle()
+    }
+}
+
+#[cfg(feature = "raw-window-handle")]
+unsafe impl raw_window_handle::HasRawWindowHandle for WindowHandle {
+    fn raw_window_handle(&self) -> raw_window_handle::RawWindowHandle {
+        let mut handle = raw_window_handle::Win32WindowHandle::empty();
+        handle.hwnd = self.0;
+        raw_window_handle::RawWindowHandle::Win32(handle)
+    }
+}
+
+#[cfg(feature = "raw-window-handle")]
+unsafe impl raw_window_handle::HasRawDisplayHandle for WindowHandle {
+    fn raw_display_handle(&self) -> raw_window_handle::RawDisplayHandle {
+        raw_window_handle::RawDisplayHandle::Windows(
+            raw_window_handle::WindowsDisplayHandle::empty(),
+        )
+    }
+}
+
+/// The [ICoreWindowInterop interface](https://docs.microsoft.com/en-us/windows/win32/api/corewindow/nn-corewindow-icorewindowinterop)
+/// implemented by `CoreWindow`
+#[repr(transparent)]
+#[derive(Default, Clone)]
+pub struct ICoreWindowInterop {
+    ptr: ComPtr<ICoreWindowInterop>,
+}
+
+impl ICoreWindowInterop {
+    pub fn window_handle(&self) -> Result<WindowHandle> {
+        let this = self.ptr.as_raw();
+        if this.is_null() {
+            panic!("The `this` pointer was null when calling method");
+        }
+        let mut hwnd = std::ptr::null_mut();
+        unsafe { ((*(*(this))).window_handle)(this, &mut hwnd).and_then(|| WindowHandle(hwnd)) }
+    }
+
+    pub fn set_message_handled(&self, value: bool) -> Result<()> {
+        let this = self.ptr.as_raw();
+        if this.is_null() {
+            panic!("The `this` pointer was null when calling method");
+        }
+        unsafe { ((*(*(this))).set_message_handled)(this, value as u8).ok() }
+    }
+}
+
+unsafe impl ComInterface for ICoreWindowInterop {
+    type VTable = abi_ICoreWindowInterop;
+    const IID: Guid = Guid::from_values(
+        0x45D6_4A29,
+        0xA63E,
+        0x4CB6,
+        [0xB4, 0x98, 0x57, 0x81, 0xD2, 0x98, 0xCB, 0x4F],
+    );
+}
+
+#[repr(C)]
+pub struct abi_ICoreWindowInterop {
+    __base: [usize; 3],
+    window_handle: extern "system" fn(RawComPtr<ICoreWindowInterop>, *mut RawPtr) -> ErrorCode,
+    set_message_handled: extern "system" fn(RawComPtr<ICoreWindowInterop>, u8) -> ErrorCode,
+}
+
+/// The [ICompositorDesktopInterop interface](https://docs.microsoft.com/en-us/windows/win32/api/windows.ui.composition.interop/nn-windows-ui-composition-interop-icompositordesktopinterop)
+/// implemented by `Compositor`
+#[repr(transparent)]
+#[derive(Default, Clone)]
+pub struct ICompositorDesktopInterop {
+    ptr: ComPtr<ICompositorDesktopInterop>,
+}
+
+impl ICompositorDesktopInterop {
+    /// Creates a composition target for the window, typically returned as a `DesktopWindowTarget`.
+    pub fn create_desktop_window_target<T: ComInterface>(
+        &self,
+        window: WindowHandle,
+        topmost: bool,
+    ) -> Result<T> {
+        let this = self.ptr.as_raw();
+        if this.is_null() {
+            panic!("The `this` pointer was null when calling method");
+        }
+        let mut target = IUnknown::default();
+        unsafe {
+            ((*(*(this))).create_desktop_window_target)(
+                this,
+                window.0,
+                topmost as i32,
+                target.set_abi() as _,
+            )
+            .ok()?;
+        }
+        TryInto::<T>::try_into(&target)
+    }
+
+    pub fn ensure_on_thread(&self, thread_id: u32) -> Result<()> {
+        let this = self.ptr.as_raw();
+        if this.is_null() {
+            panic!("The `this` pointer was null when calling method");
+        }
+        unsafe { ((*(*(this))).ensure_on_thread)(this, thread_id).ok() }
+    }
+}
+
+unsafe impl ComInterface for ICompositorDesktopInterop {
+    type VTable = abi_ICompositorDesktopInterop;
+    const IID: Guid = Guid::from_values(
+        0x29E6_91FA,
+        0x4567,
+        0x4DCA,
+        [0xB3, 0x19, 0xD0, 0xF2, 0x07, 0xEB, 0x68, 0x07],
+    );
+}
+
+#[repr(C)]
+pub struct abi_ICompositorDesktopInterop {
+    __base: [usize; 3],
+    create_desktop_window_target: extern "system" fn(
+        RawComPtr<ICompositorDesktopInterop>,
+        RawPtr,
+        i32,
+        *mut RawPtr,
+    ) -> ErrorCode,
+    ensure_on_thread: extern "system" fn(RawComPtr<ICompositorDesktopInterop>, u32) -> ErrorCode,
+}
+
+/// The [ISwapChainPanelNative interface](https://docs.microsoft.com/en-us/windows/win32/api/windows.ui.xaml.media.dxinterop/nn-windows-ui-xaml-media-dxinterop-iswapchainpanelnative)
+/// implemented by `SwapChainPanel`
+#[repr(transparent)]
+#[derive(Default, Clone)]
+pub struct ISwapChainPanelNative {
+    ptr: ComPtr<ISwapChainPanelNative>,
+}
+
+impl ISwapChainPanelNative {
+    /// Sets the DirectX swap chain presented by the panel.
+    ///
+    /// # Safety
+    /// The `swap_chain` must be a valid `IDXGISwapChain` pointer, or null to clear the panel.
+    pub unsafe fn set_swap_chain(&self, swap_chain: RawPtr) -> Result<()> {
+        let this = self.ptr.as_raw();
+        if this.is_null() {
+            panic!("The `this` pointer was null when calling method");
+        }
+        ((*(*(this))).set_swap_chain)(this, swap_chain).ok()
+    }
+}
+
+unsafe impl ComInterface for ISwapChainPanelNative {
+    type VTable = abi_ISwapChainPanelNative;
+    const IID: Guid = Guid::from_values(
+        0xF92F_19D2,
+        0x3ADE,
+        0x45A6,
+        [0xA2, 0x0C, 0xF6, 0xF1, 0xEA, 0x90, 0x55, 0x4B],
+    );
+}
+
+#[repr(C)]
+pub struct abi_ISwapChainPanelNative {
+    __base: [usize; 3],
+    set_swap_chain: extern "system" fn(RawComPtr<ISwapChainPanelNative>, RawPtr) -> ErrorCode,
+}
+
+/// Wraps a DXGI device as a WinRT `IDirect3DDevice` using `CreateDirect3D11DeviceFromDXGIDevice`.
+///
+/// # Safety
+/// The `dxgi_device` must be a valid `IDXGIDevice` pointer.
+pub unsafe fn create_direct3d_device<T: ComInterface>(dxgi_device: RawPtr) -> Result<T> {
+    let mut device = Object::default();
+    runtime::CreateDirect3D11DeviceFromDXGIDevice(dxgi_device, device.set_abi()).ok()?;
+    TryInto::<T>::try_into(&device)
+}
+
+/// Wraps a DXGI surface as a WinRT `IDirect3DSurface` using `CreateDirect3D11SurfaceFromDXGISurface`.
+///
+/// # Safety
+/// The `dxgi_surface` must be a valid `IDXGISurface` pointer.
+pub unsafe fn create_direct3d_surface<T: ComInterface>(dxgi_surface: RawPtr) -> Result<T> {
+    let mut surface = Object::default();
+    runtime::CreateDirect3D11SurfaceFromDXGISurface(dxgi_surface, surface.set_abi()).ok()?;
+    TryInto::<T>::try_into(&surface)
+}
+
+/// Retrieves the DXGI or D3D11 interface underlying a WinRT `IDirect3DDevice` or `IDirect3DSurface`.
+///
+/// The `iid` identifies the requested native interface, such as `ID3D11Device` or `IDXGISurface`.
+/// The returned pointer carries a reference that the caller is responsible for releasing.
+pub fn get_dxgi_interface<T: ComInterface>(object: &T, iid: &Guid) -> Result<RawPtr> {
+    let access: IDirect3DDxgiInterfaceAccess = object.try_into()?;
+    access.get_interface(iid)
+}
+
+/// The [IDirect3DDxgiInterfaceAccess interface](https://docs.microsoft.com/en-us/windows/win32/api/windows.graphics.directx.direct3d11.interop/ns-windows-graphics-directx-direct3d11-interop-idirect3ddxgiinterfaceaccess)
+/// implemented by `IDirect3DDevice` and `IDirect3DSurface`
+#[repr(transparent)]
+#[derive(Default, Clone)]
+pub struct IDirect3DDxgiInterfaceAccess {
+    ptr: ComPtr<IDirect3DDxgiInterfaceAccess>,
+}
+
+impl IDirect3DDxgiInterfaceAccess {
+    pub fn get_interface(&self, iid: &Guid) -> Result<RawPtr> {
+        let this = self.ptr.as_raw();
+        if this.is_null() {
+            panic!("The `this` pointer was null when calling method");
+        }
+        let mut interface = std::ptr::null_mut();
+        unsafe { ((*(*(this))).get_interface)(this, iid, &mut interface).and_then(|| interface) }
+    }
+}
+
+unsafe impl ComInterface for IDirect3DDxgiInterfaceAccess {
+    type VTable = abi_IDirect3DDxgiInterfaceAccess;
+    const IID: Guid = Guid::from_values(
+        0xA9B3_D012,
+        0x3DF2,
+        0x4EE3,
+        [0xB8, 0xD1, 0x86, 0x95, 0xF4, 0x57, 0xD3, 0xC1],
+    );
+}
+
+#[repr(C)]
+pub struct abi_IDirect3DDxgiInterfaceAccess {
+    __base: [usize; 3],
+    get_interface: extern "system" fn(
+        RawComPtr<IDirect3DDxgiInterfaceAccess>,
+        &Guid,
+        *mut RawPtr,
+    ) -> ErrorCode,
+}
+
+/// Provides access to the bytes of an `IMemoryBufferReference`, such as the one returned by
+/// `BitmapBuffer::create_reference`.
+///
+/// The bytes are only valid while the reference is open so they are lent to the closure rather
+/// than returned.
+pub fn with_bytes<T: ComInterface, R, F: FnOnce(&mut [u8]) -> R>(reference: &T, f: F) -> Result<R> {
+    let (data, len) = memory_buffer_bytes(reference)?;
+
+    unsafe {
+        if data.is_null() {
+            return Ok(f(&mut []));
+        }
+
+        Ok(f(std::slice::from_raw_parts_mut(data, len)))
+    }
+}
+
+/// Lends the bytes of an `IMemoryBufferReference` for as long as the guard lives, and closes the
+/// reference when the guard is dropped
+///
+/// This provides zero-copy access to buffers such as those of media frames and bitmaps, which
+/// would otherwise have to be lent to a closure by [`with_bytes`]. The bytes are only valid while
+/// the reference is open, so closing it is tied to the guard rather than left to the caller.
+///
+/// ```no_run
+/// winrt::import!(
+///     dependencies
+///         "os"
+///     types
+///         "windows.foundation.memorybuffer"
+/// );
+///
+/// fn main() -> winrt::Result<()> {
+///     use windows::foundation::MemoryBuffer;
+///
+///     let buffer = MemoryBuffer::create(1024)?;
+///     let mut bytes = winrt::interop::MemoryBufferGuard::new(buffer.create_reference()?)?;
+///     bytes[0] = 255;
+///     bytes.close()?;
+///     Ok(())
+/// }
+/// ```
+pub struct MemoryBufferGuard<T: ComInterface> {
+    reference: T,
+    data: *mut u8,
+    len: usize,
+}
+
+impl<T: ComInterface> MemoryBufferGuard<T> {
+    /// Takes ownership of the reference and gets its bytes.
+    pub fn new(reference: T) -> Result<Self> {
+        let (data, len) = memory_buffer_bytes(&reference)?;
+        Ok(Self {
+            reference,
+            data,
+            len,
+        })
+    }
+
+    /// Returns the reference whose bytes are lent.
+    pub fn reference(&self) -> &T {
+        &self.reference
+    }
+
+    /// Returns the bytes of the buffer.
+    pub fn as_slice(&self) -> &[u8] {
+        if self.data.is_null() {
+            return &[];
+        }
+
+        unsafe { std::slice::from_raw_parts(self.data, self.len) }
+    }
+
+    /// Returns the bytes of the buffer for writing.
+    pub fn as_mut_slice(&mut self) -> &mut [u8] {
+        if self.data.is_null() {
+            return &mut [];
+        }
+
+        unsafe { std::slice::from_raw_parts_mut(self.data, self.len) }
+    }
+
+    /// Closes the reference, returning any error rather than ignoring it as `drop` does.
+    pub fn close(self) -> Result<()> {
+        let result = close(&self.reference);
+        std::mem::forget(self);
+        result
+    }
+}
+
+impl<T: ComInterface> std::ops::Deref for MemoryBufferGuard<T> {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        self.as_slice()
+    }
+}
+
+impl<T: ComInterface> std::ops::DerefMut for MemoryBufferGuard<T> {
+    fn deref_mut(&mut self) -> &mut [u8] {
+        self.as_mut_slice()
+    }
+}
+
+impl<T: ComInterface> Drop for MemoryBufferGuard<T> {
+    fn drop(&mut self) {
+        let _ = close(&self.reference);
+    }
+}
+
+fn memory_buffer_bytes<T: ComInterface>(reference: &T) -> Result<(*mut u8, usize)> {
+    let access: IMemoryBufferByteAccess = reference.try_into()?;
+    let this = access.ptr.as_raw();
+    let mut data = std::ptr::null_mut();
+    let mut capacity = 0;
+
+    unsafe {
+        ((*(*(this))).buffer)(this, &mut data, &mut capacity).ok()?;
+    }
+
+    Ok((data, capacity as usize))
+}
+
+fn close<T: ComInterface>(object: &T) -> Result<()> {
+    let closable: IClosable = object.try_into()?;
+    let this = closable.ptr.as_raw();
+
+    unsafe { ((*(*(this))).close)(this).ok() }
+}
+
+// The Windows.Foundation.IClosable interface, declared here so that the runtime can close the
+// references it lends without depending on generated code.
+#[repr(transparent)]
+#[derive(Default, Clone)]
+struct IClosable {
+    ptr: ComPtr<IClosable>,
+}
+
+unsafe impl ComInterface for IClosable {
+    type VTable = abi_IClosable;
+    const IID: Guid = Guid::from_values(
+        0x30D5_A829,
+        0x7FA4,
+        0x4026,
+        [0x83, 0xBB, 0xD7, 0x5B, 0xAE, 0x4E, 0xA9, 0x9E],
+    );
+}
+
+#[repr(C)]
+struct abi_IClosable {
+    __base: [usize; 6],
+    close: extern "system" fn(RawComPtr<IClosable>) -> ErrorCode,
+}
+
+/// The [IMemoryBufferByteAccess interface](https://docs.microsoft.com/en-us/windows/win32/winrt/imemorybufferbyteaccess)
+/// implemented by `IMemoryBufferReference`
+#[repr(transparent)]
+#[derive(Default, Clone)]
+pub struct IMemoryBufferByteAccess {
+    ptr: ComPtr<IMemoryBufferByteAccess>,
+}
+
+unsafe impl ComInterface for IMemoryBufferByteAccess {
+    type VTable = abi_IMemoryBufferByteAccess;
+    const IID: Guid = Guid::from_values(
+        0x5B0D_3235,
+        0x4DBA,
+        0x4D44,
+        [0x86, 0x5E, 0x8F, 0x1D, 0x0E, 0x4F, 0xD0, 0x4D],
+    );
+}
+
+#[repr(C)]
+pub struct abi_IMemoryBufferByteAccess {
+    __base: [usize; 3],
+    buffer:
+        extern "system" fn(RawComPtr<IMemoryBufferByteAccess>, *mut *mut u8, *mut u32) -> ErrorCode,
+}
+
+/// The [IBufferByteAccess interface](https://docs.microsoft.com/en-us/windows/win32/api/robuffer/nn-robuffer-ibufferbyteaccess)
+/// implemented by `IBuffer`
+#[repr(transparent)]
+#[derive(Default, Clone)]
+pub struct IBufferByteAccess {
+    ptr: ComPtr<IBufferByteAccess>,
+}
+
+impl IBufferByteAccess {
+    /// Returns a pointer to the buffer's bytes, which remain valid for as long as the buffer.
+    pub fn buffer(&self) -> Result<*mut u8> {
+        let this = self.ptr.as_raw();
+        if this.is_null() {
+            panic!("The `this` pointer was null when calling method");
+        }
+        let mut data = std::ptr::null_mut();
+        unsafe {
+            ((*(*(this))).buffer)(this, &mut data).ok()?;
+        }
+        Ok(data)
+    }
+}
+
+unsafe impl ComInterface for IBufferByteAccess {
+    type VTable = abi_IBufferByteAccess;
+    const IID: Guid = Guid::from_values(
+        0x905A_0FEF,
+        0xBC53,
+        0x11DF,
+        [0x8C, 0x49, 0x00, 0x1E, 0x4F, 0xC6, 0x86, 0xDA],
+    );
+}
+
+#[repr(C)]
+pub struct abi_IBufferByteAccess {
+    __base: [usize; 3],
+    buffer: extern "system" fn(RawComPtr<IBufferByteAccess>, *mut *mut u8) -> ErrorCode,
+}
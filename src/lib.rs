@@ -35,42 +35,245 @@
 //! port: 80
 //! string: http://kennykerr.ca/
 //! ```
+//!
+//! The default `std` feature may be disabled to use the core of the runtime in `#![no_std]`
+//! environments that provide `alloc`. This leaves out the executor integrations, such as the
+//! `Future` implementations of the async interfaces, and the conversions to `std` types.
+//!
+//! The `reflection` feature adds the [`reflection`] module, which resolves types to their
+//! metadata at runtime for dynamic scenarios that can't import them at compile time, and the
+//! [`dynamic`] module, which builds on it to call methods and properties by name.
+//!
+//! The `tracelogging` feature registers a TraceLogging provider named `Rust.WinRT` that logs
+//! errors and panics returned to callers as HRESULTs, as well as activation failures, so they
+//! appear in standard Windows diagnostics tooling.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+// The generated code reaches `alloc` and `std` through these since the importing crate may be
+// `#![no_std]` even when this crate is built with `std`.
+#[doc(hidden)]
+pub extern crate alloc as __alloc;
+#[cfg(feature = "std")]
+#[doc(hidden)]
+pub extern crate std as __std;
+
+// Without `std`, paths to the `core` items that `std` re-exports still resolve and the `alloc`
+// items that its prelude provides are imported by the modules using `crate::*`.
+#[cfg(not(feature = "std"))]
+extern crate core as std;
+#[cfg(not(feature = "std"))]
+#[allow(unused_imports)]
+pub(crate) use alloc::{
+    borrow::ToOwned,
+    boxed::Box,
+    format,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
 
 #[cfg(doctest)]
 doc_comment::doctest!("../README.md");
 
 #[doc(hidden)]
 pub mod activation;
+#[doc(hidden)]
+pub mod apartment;
+pub mod api_information;
+pub mod app;
 mod array;
+#[cfg(feature = "std")]
+pub mod authoring;
+mod block_on;
+#[cfg(feature = "std")]
+mod buffer;
+#[cfg(feature = "std")]
+mod cancelable;
+mod cast;
+mod char16;
+#[doc(hidden)]
+pub mod collections;
 mod com_interface;
 mod com_ptr;
+#[doc(hidden)]
+pub mod date_time;
+#[doc(hidden)]
+pub mod debug;
+mod deferral;
+pub mod delay_load;
+#[cfg(feature = "std")]
+mod delegate;
+#[cfg(feature = "std")]
+pub mod diagnostics;
+#[cfg(feature = "reflection")]
+pub mod dynamic;
+#[doc(hidden)]
+pub mod erased;
 mod error;
+mod event;
+#[cfg(feature = "std")]
+#[doc(hidden)]
+pub mod future;
+mod graphics;
 mod guid;
+pub mod heap;
 mod hstring;
+pub mod imaging;
+#[cfg(feature = "std")]
+pub mod intern;
+pub mod interop;
+mod marshaled;
+#[doc(hidden)]
+pub mod nullable;
+mod numerics;
 mod object;
+pub mod package;
 mod param;
+pub mod prelude;
+#[doc(hidden)]
+pub mod property_set;
+mod property_value;
+#[cfg(feature = "std")]
+mod reacquire;
 mod ref_count;
+#[cfg(feature = "reflection")]
+pub mod reflection;
 mod runtime;
 mod runtime_name;
 mod runtime_type;
+mod serialization;
+#[cfg(feature = "std")]
+#[doc(hidden)]
+pub mod streams;
+#[cfg(not(windows))]
+mod stub;
+pub mod sys;
+#[cfg(feature = "std")]
+mod thread_pool;
+mod trace;
 mod try_into;
 mod unknown;
+mod weak;
+pub mod xaml_islands;
 
 #[doc(inline)]
-pub use activation::IActivationFactory;
+pub use activation::{ActivationError, IActivationFactory};
 pub use array::Array;
-pub use com_interface::{ComInterface, RawComPtr};
+pub use block_on::block_on;
+#[cfg(feature = "std")]
+pub use cancelable::{AsyncInfo, AsyncState, Cancelable, Race, Timeout};
+pub use cast::{Cast, CastError};
+pub use char16::{Char16, Char16Error};
+pub use com_interface::{is_same_object, ComInterface, RawComPtr};
 pub use com_ptr::ComPtr;
+pub use deferral::{Deferral, DeferralGuard, GetDeferral};
 pub use error::*;
+pub use event::EventRegistrationToken;
 pub use guid::Guid;
-pub use hstring::HString;
+#[doc(hidden)]
+pub use hstring::StaticHeader;
+pub use hstring::{HString, HStringBuilder, StaticHString};
+pub use marshaled::Marshaled;
 pub use object::Object;
 pub use param::Param;
+pub use property_set::IntoPropertyValue;
+pub use property_value::PropertyTypeError;
+#[cfg(feature = "std")]
+pub use reacquire::Reacquire;
 pub use runtime_name::RuntimeName;
 pub use runtime_type::RuntimeType;
+#[cfg(feature = "futures")]
+pub use streams::AsyncWriter;
+#[cfg(feature = "std")]
+pub use streams::{AsyncReader, InputStream, NextChunk, OutputStream};
+#[cfg(feature = "std")]
+pub use thread_pool::{spawn, JoinHandle};
 pub use try_into::TryInto;
 pub use unknown::IUnknown;
+pub use weak::{Downgrade, Upgrade};
+#[doc(hidden)]
+pub use winrt_macros::__hstring;
 pub use winrt_macros::import;
 
+#[cfg(feature = "chrono")]
+#[doc(hidden)]
+pub use chrono;
+#[cfg(feature = "euclid")]
+#[doc(hidden)]
+pub use euclid;
+#[cfg(feature = "mint")]
+#[doc(hidden)]
+pub use mint;
+#[cfg(feature = "palette")]
+#[doc(hidden)]
+pub use palette;
+#[cfg(feature = "serde")]
+#[doc(hidden)]
+pub use serde;
+#[cfg(feature = "serde_json")]
+#[doc(hidden)]
+pub use serde_json;
+#[cfg(feature = "time")]
+#[doc(hidden)]
+pub use time;
+#[cfg(feature = "url")]
+#[doc(hidden)]
+pub use url;
+
 /// A convenient alias of a void pointer
 pub type RawPtr = *mut std::ffi::c_void;
+
+// The generated code invokes `__cfg_std` regardless of the feature for the conveniences that rely
+// on `std`, and it expands to nothing when the feature is disabled.
+#[cfg(feature = "std")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __cfg_std {
+    ($($item:tt)*) => {
+        $($item)*
+    };
+}
+
+#[cfg(not(feature = "std"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __cfg_std {
+    ($($item:tt)*) => {};
+}
+
+// Likewise for the conveniences that rely on the `futures` feature.
+#[cfg(feature = "futures")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __cfg_futures {
+    ($($item:tt)*) => {
+        $($item)*
+    };
+}
+
+#[cfg(not(feature = "futures"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __cfg_futures {
+    ($($item:tt)*) => {};
+}
+
+// Likewise for the conversions between Windows.Foundation.Uri and `url::Url`.
+#[cfg(feature = "url")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __cfg_url {
+    ($($item:tt)*) => {
+        $($item)*
+    };
+}
+
+#[cfg(not(feature = "url"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __cfg_url {
+    ($($item:tt)*) => {};
+}
@@ -0,0 +1,65 @@
+use crate::runtime;
+use crate::*;
+
+use std::marker::PhantomData;
+
+/// A COM interface marshaled for use by another apartment
+///
+/// Non-agile objects may only be called from the apartment that created them. `Marshaled<T>`
+/// captures such an object via `CoMarshalInterThreadInterfaceInStream` so that it may be sent to
+/// another thread and then resolved to a proxy that is safe to call from that thread. Agile
+/// objects may simply be used from any thread and don't need to be marshaled.
+pub struct Marshaled<T: ComInterface> {
+    stream: IUnknown,
+    phantom: PhantomData<T>,
+}
+
+impl<T: ComInterface> Marshaled<T> {
+    /// Marshals the interface from the calling apartment
+    pub fn new(interface: &T) -> Result<Self> {
+        let mut stream = IUnknown::default();
+
+        unsafe {
+            runtime::CoMarshalInterThreadInterfaceInStream(
+                &T::IID,
+                interface.as_iunknown(),
+                stream.set_abi() as _,
+            )
+            .ok()?;
+        }
+
+        Ok(Self {
+            stream,
+            phantom: PhantomData,
+        })
+    }
+
+    /// Resolves the interface for use by the calling apartment
+    ///
+    /// The marshaled interface may only be resolved once.
+    pub fn resolve(mut self) -> Result<T> {
+        let stream = std::mem::take(&mut self.stream);
+        let mut ptr = std::ptr::null_mut();
+
+        unsafe {
+            // The stream is released whether or not unmarshaling succeeds.
+            let code = runtime::CoGetInterfaceAndReleaseStream(stream.as_raw(), &T::IID, &mut ptr);
+            std::mem::forget(stream);
+            code.and_then(|| std::mem::transmute_copy(&ptr))
+        }
+    }
+}
+
+impl<T: ComInterface> Drop for Marshaled<T> {
+    fn drop(&mut self) {
+        if !self.stream.is_null() {
+            unsafe {
+                runtime::CoReleaseMarshalData(self.stream.as_raw());
+            }
+        }
+    }
+}
+
+// The stream holds no reference to the original object's apartment and is designed to be
+// handed to another thread.
+unsafe impl<T: ComInterface> Send for Marshaled<T> {}
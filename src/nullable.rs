@@ -0,0 +1,59 @@
+//! Support for the code generated by the `"nullable"` import option.
+//!
+//! With this option, methods returning classes, interfaces, delegates or objects return
+//! `Result<Option<T>>`. Code generated alongside the methods, such as the `Iterator` and `Future`
+//! implementations, uses [`or_null`] so that it works with or without the option.
+
+use crate::*;
+
+/// A value returned by a method, which may be wrapped in an `Option`
+pub trait Nullable {
+    type Value;
+
+    /// Returns the value, or a null value for `None`.
+    fn or_null(self) -> Self::Value;
+
+    /// Returns the value, or `None` if it is null.
+    fn into_option(self) -> Option<Self::Value>;
+}
+
+impl<T: ComInterface> Nullable for T {
+    type Value = T;
+
+    fn or_null(self) -> T {
+        self
+    }
+
+    fn into_option(self) -> Option<T> {
+        from_abi(self)
+    }
+}
+
+impl<T: ComInterface> Nullable for Option<T> {
+    type Value = T;
+
+    fn or_null(self) -> T {
+        // A zero initialized interface is null.
+        self.unwrap_or_else(|| unsafe { std::mem::zeroed() })
+    }
+
+    fn into_option(self) -> Option<T> {
+        self
+    }
+}
+
+/// Returns the value returned by a method, or a null value for `None`.
+#[inline(always)]
+pub fn or_null<T: Nullable>(value: T) -> T::Value {
+    value.or_null()
+}
+
+/// Converts a value returned through the ABI into `None` if it is null.
+#[inline(always)]
+pub fn from_abi<T: ComInterface>(value: T) -> Option<T> {
+    if value.is_null() {
+        None
+    } else {
+        Some(value)
+    }
+}
@@ -0,0 +1,18 @@
+// Support for the `mint` feature. The generated Windows.Foundation.Numerics structs invoke
+// `__cfg_mint` regardless of the feature and it expands to nothing when the feature is disabled.
+
+#[cfg(feature = "mint")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __cfg_mint {
+    ($($item:tt)*) => {
+        $($item)*
+    };
+}
+
+#[cfg(not(feature = "mint"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __cfg_mint {
+    ($($item:tt)*) => {};
+}
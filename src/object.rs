@@ -23,6 +23,14 @@ impl Object {
     }
 }
 
+impl PartialEq for Object {
+    fn eq(&self, other: &Self) -> bool {
+        is_same_object(self, other)
+    }
+}
+
+impl Eq for Object {}
+
 unsafe impl ComInterface for Object {
     type VTable = abi_IInspectable;
     const IID: Guid = Guid::from_values(
@@ -36,6 +44,10 @@ unsafe impl ComInterface for Object {
 unsafe impl RuntimeType for Object {
     type Abi = RawComPtr<Object>;
 
+    fn select<S: crate::erased::Selector>(selector: S) -> S::Output {
+        selector.erased()
+    }
+
     fn abi(&self) -> Self::Abi {
         self.ptr.as_raw()
     }
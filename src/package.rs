@@ -0,0 +1,213 @@
+//! Detecting [package identity](https://docs.microsoft.com/en-us/windows/apps/desktop/modernize/package-identity-overview) and running unpackaged
+//!
+//! Some APIs, such as notifications and background tasks, require the calling process to have
+//! package identity and fail with an error of kind [`ErrorKind::NoPackageIdentity`] otherwise.
+//! Other classes are only registered by a package's manifest. A process without package identity
+//! may instead register the DLLs of such components with [`register_component`]. When it then
+//! activates a class that isn't registered, the runtime falls back to registration-free
+//! activation: it loads the DLLs registered for the namespaces of the class, from the most
+//! specific, and asks each for the activation factory. If no DLL provides the class, or none was
+//! registered, the activation fails with the original error.
+//!
+//! No other DLLs are loaded, and classes in the `Windows` namespace are never loaded this way,
+//! since the system classes that aren't registered for unpackaged processes are unavailable to
+//! them on purpose.
+
+use crate::*;
+
+use std::sync::atomic::{AtomicU8, Ordering};
+
+const ERROR_SUCCESS: i32 = 0;
+const ERROR_INSUFFICIENT_BUFFER: i32 = 122;
+const APPMODEL_ERROR_NO_PACKAGE: i32 = 15700;
+
+/// Returns the full name of the calling process's package, or `None` if it has no package
+/// identity.
+pub fn full_name() -> Result<Option<String>> {
+    let mut len = 0;
+
+    match unsafe { runtime::GetCurrentPackageFullName(&mut len, std::ptr::null_mut()) } {
+        ErrorCode(ERROR_INSUFFICIENT_BUFFER) => {}
+        ErrorCode(APPMODEL_ERROR_NO_PACKAGE) => return Ok(None),
+        // Versions of Windows without the function don't have packages either.
+        ErrorCode::MOD_NOT_FOUND | ErrorCode::PROC_NOT_FOUND => return Ok(None),
+        ErrorCode(code) => return Err(ErrorCode::from_win32(code as u32).into()),
+    }
+
+    let mut name = vec![0u16; len as usize];
+
+    match unsafe { runtime::GetCurrentPackageFullName(&mut len, name.as_mut_ptr()) } {
+        ErrorCode(ERROR_SUCCESS) => {}
+        ErrorCode(code) => return Err(ErrorCode::from_win32(code as u32).into()),
+    }
+
+    // The length includes the terminating null character.
+    name.truncate(len.saturating_sub(1) as usize);
+    Ok(Some(String::from_utf16_lossy(&name)))
+}
+
+/// Returns true if the calling process has package identity.
+///
+/// The result is cached since identity can't change while the process is running.
+pub fn has_identity() -> bool {
+    const UNKNOWN: u8 = 0;
+    const PACKAGED: u8 = 1;
+    const UNPACKAGED: u8 = 2;
+    static IDENTITY: AtomicU8 = AtomicU8::new(UNKNOWN);
+
+    match IDENTITY.load(Ordering::Relaxed) {
+        PACKAGED => true,
+        UNPACKAGED => false,
+        _ => {
+            let packaged = matches!(full_name(), Ok(Some(_)));
+            let identity = if packaged { PACKAGED } else { UNPACKAGED };
+            IDENTITY.store(identity, Ordering::Relaxed);
+            packaged
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+static COMPONENTS: std::sync::Mutex<Vec<(String, String)>> = std::sync::Mutex::new(Vec::new());
+
+/// Registers the DLL at `path` as the provider of the classes in `namespace`, and in the
+/// namespaces nested within it, for when the process has no package identity.
+///
+/// A relative path is searched for in the app's directory. The DLL is only loaded once a class
+/// that it may provide fails to activate otherwise.
+///
+/// ```no_run
+/// winrt::package::register_component("Contoso.Widgets", "Contoso.Widgets.dll");
+/// ```
+#[cfg(feature = "std")]
+pub fn register_component(namespace: &str, path: &str) {
+    COMPONENTS
+        .lock()
+        .unwrap()
+        .push((namespace.to_string(), path.to_string()));
+}
+
+/// Gets the activation factory for the class from one of the DLLs registered for its namespaces.
+/// This is used by `factory` when the process has no package identity.
+pub(crate) fn unpackaged_factory<I: ComInterface>(class: &str) -> Option<I> {
+    if class.starts_with("Windows.") {
+        return None;
+    }
+
+    components(class)
+        .iter()
+        .find_map(|path| library_factory(path, class))
+        .and_then(|factory| TryInto::<I>::try_into(&factory).ok())
+}
+
+// The paths of the components registered for the namespaces of the class, from the most
+// specific namespace.
+#[cfg(feature = "std")]
+fn components(class: &str) -> Vec<String> {
+    let components = COMPONENTS.lock().unwrap();
+
+    let mut matching: Vec<&(String, String)> = components
+        .iter()
+        .filter(|(namespace, _)| {
+            class.len() > namespace.len()
+                && class.starts_with(namespace.as_str())
+                && class.as_bytes()[namespace.len()] == b'.'
+        })
+        .collect();
+
+    matching.sort_by_key(|(namespace, _)| std::cmp::Reverse(namespace.len()));
+    matching.into_iter().map(|(_, path)| path.clone()).collect()
+}
+
+// Components can only be registered with the `std` feature.
+#[cfg(not(feature = "std"))]
+fn components(_class: &str) -> Vec<String> {
+    Vec::new()
+}
+
+#[cfg(windows)]
+fn library_factory(path: &str, class: &str) -> Option<Object> {
+    type DllGetActivationFactory =
+        extern "system" fn(*mut hstring::Header, *mut RawPtr) -> ErrorCode;
+
+    const LOAD_LIBRARY_SEARCH_APPLICATION_DIR: u32 = 0x200;
+
+    let path: Vec<u16> = path.encode_utf16().chain(std::iter::once(0)).collect();
+
+    // A library that exports the function stays loaded since the factory and the objects it
+    // creates may outlive any handle to it.
+    unsafe {
+        let module = runtime::LoadLibraryExW(
+            path.as_ptr(),
+            std::ptr::null_mut(),
+            LOAD_LIBRARY_SEARCH_APPLICATION_DIR,
+        );
+
+        if module.is_null() {
+            return None;
+        }
+
+        let address = runtime::GetProcAddress(module, b"DllGetActivationFactory\0".as_ptr());
+
+        if address.is_null() {
+            runtime::FreeLibrary(module);
+            return None;
+        }
+
+        let function: DllGetActivationFactory = std::mem::transmute(address);
+        let mut factory = Object::default();
+
+        let code = function(
+            HString::from(class).abi(),
+            factory.set_abi() as *mut _ as *mut RawPtr,
+        );
+
+        if code.is_ok() {
+            Some(factory)
+        } else {
+            None
+        }
+    }
+}
+
+// There are no libraries to load elsewhere.
+#[cfg(not(windows))]
+fn library_factory(_path: &str, _class: &str) -> Option<Object> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(not(windows))]
+    #[test]
+    fn unpackaged() {
+        assert_eq!(full_name().unwrap(), None);
+        assert!(!has_identity());
+        assert!(unpackaged_factory::<Object>("Contoso.Widgets.Gadget").is_none());
+        assert!(unpackaged_factory::<Object>("Windows.Foundation.Uri").is_none());
+
+        let error: Error = ErrorCode::NO_PACKAGE.into();
+        assert_eq!(error.kind(), ErrorKind::NoPackageIdentity);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn components() {
+        register_component("Fabrikam", "Fabrikam.dll");
+        register_component("Fabrikam.Widgets", "widgets\\Fabrikam.Widgets.dll");
+        register_component("Windows.Foundation", "Foundation.dll");
+
+        assert_eq!(
+            super::components("Fabrikam.Widgets.Gadget"),
+            ["widgets\\Fabrikam.Widgets.dll", "Fabrikam.dll"]
+        );
+        assert_eq!(super::components("Fabrikam.Gadget"), ["Fabrikam.dll"]);
+        assert!(super::components("FabrikamX.Gadget").is_empty());
+        assert!(super::components("Fabrikam").is_empty());
+
+        // Nothing is loaded for the system's classes, even if registered.
+        assert!(unpackaged_factory::<Object>("Windows.Foundation.Uri").is_none());
+    }
+}
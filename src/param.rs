@@ -1,6 +1,14 @@
 use crate::*;
 
 /// A WinRT method parameter
+///
+/// Borrowed values are passed by their ABI pointer without an AddRef/Release pair, so prefer
+/// passing references to objects when the caller keeps ownership. A generated method accepts a
+/// value or a reference wherever it takes a class, interface, delegate, struct or string, and an
+/// `Option` of a reference for a class, interface or delegate, so that callers never need to
+/// clone an argument just to pass it.
+/// A class passed to a parameter of one of its other interfaces is queried for that interface
+/// for the duration of the call.
 pub enum Param<'a, T: RuntimeType> {
     Borrowed(&'a T),
     Owned(T),
@@ -27,6 +35,17 @@ impl<'a, T: RuntimeType> From<&'a T> for Param<'a, T> {
     }
 }
 
+/// `None` is passed as null. Only classes, interfaces and delegates are nullable, so structs and
+/// strings don't accept an `Option`.
+impl<'a, T: RuntimeType + ComInterface + Default> From<Option<&'a T>> for Param<'a, T> {
+    fn from(value: Option<&'a T>) -> Param<'a, T> {
+        match value {
+            Some(value) => Param::Borrowed(value),
+            None => Param::Owned(T::default()),
+        }
+    }
+}
+
 impl<'a> From<&'a str> for Param<'a, HString> {
     fn from(value: &'a str) -> Param<'a, HString> {
         Param::Owned(value.into())
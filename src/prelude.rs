@@ -0,0 +1,14 @@
+//! The most commonly used types, traits and macros, for glob importing.
+//!
+//! ```rust
+//! use winrt::prelude::*;
+//!
+//! fn greeting() -> Result<HString> {
+//!     Ok("hello".into())
+//! }
+//! ```
+
+pub use crate::{
+    h, import, Cast, CastError, ComInterface, Error, ErrorCode, Guid, HString, Object, Param,
+    Result, RuntimeName, RuntimeType, TryInto,
+};
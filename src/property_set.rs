@@ -0,0 +1,322 @@
+//! Boxing values and building property sets
+//!
+//! App services, background task triggers, and the media APIs take their payloads as a
+//! `ValueSet` or `PropertySet`, which map strings to objects. Scalars and strings are stored in
+//! them by boxing the value with `Windows.Foundation.PropertyValue`, as [`IntoPropertyValue`]
+//! does, and the [`property_set!`](../macro.property_set.html) macro boxes each value and inserts
+//! it into a new set in one expression:
+//!
+//! ```ignore
+//! let message: ValueSet = winrt::property_set! {
+//!     "command" => "resize",
+//!     "width" => 640,
+//!     "height" => 480,
+//! }?;
+//! ```
+//!
+//! Slices and vectors of the same types are boxed as arrays, which are read back with
+//! `IReferenceArray<T>`, and an `Option` is boxed as its value or as a null object for `None`,
+//! which is how APIs represent missing values.
+//!
+//! The set is activated and filled through the `IMap<String, Object>` interface that both
+//! classes implement, so the macro works with whichever of them the app has imported.
+
+use crate::activation::named_factory;
+use crate::*;
+
+/// A value that may be boxed as an [`Object`], as with the factory methods of
+/// `Windows.Foundation.PropertyValue`
+///
+/// Objects are stored as they are, so an object converted with `Object::from` may be mixed with
+/// boxed values in a [`property_set!`](macro.property_set.html).
+pub trait IntoPropertyValue {
+    fn into_property_value(self) -> Result<Object>;
+}
+
+macro_rules! into_property_value {
+    ($($type:ty => $method:ident,)*) => {
+        $(
+            impl IntoPropertyValue for $type {
+                fn into_property_value(self) -> Result<Object> {
+                    let statics = named_factory::<IPropertyValueStatics>(PROPERTY_VALUE)?;
+                    let (this, vtable) = statics.ptr.vtable();
+                    let mut object = Object::default();
+                    (vtable.$method)(this, RuntimeType::abi(&self), object.set_abi()).and_then(|| object)
+                }
+            }
+        )*
+    };
+}
+
+into_property_value! {
+    u8 => create_uint8,
+    i16 => create_int16,
+    u16 => create_uint16,
+    i32 => create_int32,
+    u32 => create_uint32,
+    i64 => create_int64,
+    u64 => create_uint64,
+    f32 => create_single,
+    f64 => create_double,
+    Char16 => create_char16,
+    bool => create_boolean,
+    HString => create_string,
+    Guid => create_guid,
+}
+
+macro_rules! into_property_value_array {
+    ($($type:ty => $method:ident,)*) => {
+        $(
+            impl IntoPropertyValue for &[$type] {
+                fn into_property_value(self) -> Result<Object> {
+                    let statics = named_factory::<IPropertyValueStatics>(PROPERTY_VALUE)?;
+                    let (this, vtable) = statics.ptr.vtable();
+                    let mut object = Object::default();
+                    (vtable.$method)(
+                        this,
+                        self.len() as u32,
+                        self.as_ptr() as *const <$type as RuntimeType>::Abi,
+                        object.set_abi(),
+                    )
+                    .and_then(|| object)
+                }
+            }
+
+            impl IntoPropertyValue for Vec<$type> {
+                fn into_property_value(self) -> Result<Object> {
+                    self.as_slice().into_property_value()
+                }
+            }
+        )*
+    };
+}
+
+into_property_value_array! {
+    u8 => create_uint8_array,
+    i16 => create_int16_array,
+    u16 => create_uint16_array,
+    i32 => create_int32_array,
+    u32 => create_uint32_array,
+    i64 => create_int64_array,
+    u64 => create_uint64_array,
+    f32 => create_single_array,
+    f64 => create_double_array,
+    Char16 => create_char16_array,
+    bool => create_boolean_array,
+    HString => create_string_array,
+    Object => create_inspectable_array,
+    Guid => create_guid_array,
+}
+
+impl<T: IntoPropertyValue> IntoPropertyValue for Option<T> {
+    fn into_property_value(self) -> Result<Object> {
+        match self {
+            Some(value) => value.into_property_value(),
+            None => Ok(Object::default()),
+        }
+    }
+}
+
+impl IntoPropertyValue for &HString {
+    fn into_property_value(self) -> Result<Object> {
+        self.clone().into_property_value()
+    }
+}
+
+impl IntoPropertyValue for &str {
+    fn into_property_value(self) -> Result<Object> {
+        HString::from(self).into_property_value()
+    }
+}
+
+impl IntoPropertyValue for String {
+    fn into_property_value(self) -> Result<Object> {
+        HString::from(self).into_property_value()
+    }
+}
+
+impl IntoPropertyValue for &String {
+    fn into_property_value(self) -> Result<Object> {
+        HString::from(self).into_property_value()
+    }
+}
+
+impl IntoPropertyValue for Object {
+    fn into_property_value(self) -> Result<Object> {
+        Ok(self)
+    }
+}
+
+impl IntoPropertyValue for &Object {
+    fn into_property_value(self) -> Result<Object> {
+        Ok(self.clone())
+    }
+}
+
+/// Activates a set and inserts the boxed values. This is called by the `property_set!` macro.
+#[doc(hidden)]
+pub fn build<C, I>(values: I) -> Result<C>
+where
+    C: RuntimeName + ComInterface,
+    I: IntoIterator<Item = (HString, Result<Object>)>,
+{
+    let factory = named_factory::<IActivationFactory>(C::NAME)?;
+    let set: IPropertyMap = factory.activate_instance()?;
+
+    if set.is_null() {
+        return Err(ErrorCode::NO_INTERFACE.into());
+    }
+
+    let (this, vtable) = set.ptr.vtable();
+
+    for (key, value) in values {
+        let value = value?;
+        let mut replaced = false;
+        (vtable.insert)(this, key.abi(), value.abi(), &mut replaced).ok()?;
+    }
+
+    TryInto::<C>::try_into(&set)
+}
+
+/// Creates a `PropertySet` or `ValueSet` from pairs of keys and values, boxing each value with
+/// [`IntoPropertyValue`](trait.IntoPropertyValue.html)
+///
+/// The expression is a `Result` of whichever set it is assigned to, and fails with the first
+/// value that couldn't be boxed or inserted.
+///
+/// ```ignore
+/// let payload: ValueSet = winrt::property_set! { "key" => 42, "name" => "foo" }?;
+/// ```
+#[macro_export]
+macro_rules! property_set {
+    ($($key:expr => $value:expr),* $(,)?) => {
+        $crate::property_set::build([
+            $((
+                $crate::HString::from($key),
+                $crate::IntoPropertyValue::into_property_value($value),
+            ),)*
+        ])
+    };
+}
+
+const PROPERTY_VALUE: &str = "Windows.Foundation.PropertyValue";
+
+#[repr(transparent)]
+#[derive(Default, Clone)]
+struct IPropertyValueStatics {
+    ptr: ComPtr<IPropertyValueStatics>,
+}
+
+unsafe impl ComInterface for IPropertyValueStatics {
+    type VTable = abi_IPropertyValueStatics;
+    const IID: Guid = Guid::from_values(
+        0x629B_DBC8,
+        0xD932,
+        0x4FF4,
+        [0x96, 0xB9, 0x8D, 0x96, 0xC5, 0xC1, 0xE8, 0x58],
+    );
+}
+
+type Create<T> = extern "system" fn(
+    RawComPtr<IPropertyValueStatics>,
+    <T as RuntimeType>::Abi,
+    *mut RawComPtr<Object>,
+) -> ErrorCode;
+
+type CreateArray<T> = extern "system" fn(
+    RawComPtr<IPropertyValueStatics>,
+    u32,
+    *const <T as RuntimeType>::Abi,
+    *mut RawComPtr<Object>,
+) -> ErrorCode;
+
+// Only the methods up to CreateGuidArray are declared.
+#[repr(C)]
+#[allow(non_camel_case_types)]
+struct abi_IPropertyValueStatics {
+    __base: [usize; 6],
+    create_empty:
+        extern "system" fn(RawComPtr<IPropertyValueStatics>, *mut RawComPtr<Object>) -> ErrorCode,
+    create_uint8: Create<u8>,
+    create_int16: Create<i16>,
+    create_uint16: Create<u16>,
+    create_int32: Create<i32>,
+    create_uint32: Create<u32>,
+    create_int64: Create<i64>,
+    create_uint64: Create<u64>,
+    create_single: Create<f32>,
+    create_double: Create<f64>,
+    create_char16: Create<Char16>,
+    create_boolean: Create<bool>,
+    create_string: Create<HString>,
+    create_inspectable: Create<Object>,
+    create_guid: Create<Guid>,
+    __create_date_time: usize,
+    __create_time_span: usize,
+    __create_point: usize,
+    __create_size: usize,
+    __create_rect: usize,
+    create_uint8_array: CreateArray<u8>,
+    create_int16_array: CreateArray<i16>,
+    create_uint16_array: CreateArray<u16>,
+    create_int32_array: CreateArray<i32>,
+    create_uint32_array: CreateArray<u32>,
+    create_int64_array: CreateArray<i64>,
+    create_uint64_array: CreateArray<u64>,
+    create_single_array: CreateArray<f32>,
+    create_double_array: CreateArray<f64>,
+    create_char16_array: CreateArray<Char16>,
+    create_boolean_array: CreateArray<bool>,
+    create_string_array: CreateArray<HString>,
+    create_inspectable_array: CreateArray<Object>,
+    create_guid_array: CreateArray<Guid>,
+}
+
+// The IMap<String, Object> interface implemented by PropertySet and ValueSet.
+#[repr(transparent)]
+#[derive(Default, Clone)]
+struct IPropertyMap {
+    ptr: ComPtr<IPropertyMap>,
+}
+
+unsafe impl ComInterface for IPropertyMap {
+    type VTable = abi_IPropertyMap;
+    const IID: Guid = Guid::from_signature(
+        b"pinterface({3c2925fe-8519-45c1-aa79-197b6718c1c1};string;cinterface(IInspectable))",
+    );
+}
+
+// Only the methods up to Insert are declared.
+#[repr(C)]
+#[allow(non_camel_case_types)]
+struct abi_IPropertyMap {
+    __base: [usize; 6],
+    lookup: usize,
+    size: usize,
+    has_key: usize,
+    get_view: usize,
+    insert: extern "system" fn(
+        RawComPtr<IPropertyMap>,
+        <HString as RuntimeType>::Abi,
+        RawComPtr<Object>,
+        *mut bool,
+    ) -> ErrorCode,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn map_iid() {
+        assert_eq!(
+            IPropertyMap::IID,
+            Guid::from_values(
+                0x1B0D_3570,
+                0x0877,
+                0x5EC2,
+                [0x8A, 0x2C, 0x3B, 0x95, 0x39, 0x50, 0x6A, 0xCA],
+            )
+        );
+    }
+}
@@ -0,0 +1,210 @@
+use crate::*;
+
+use std::fmt;
+
+// The type of a property value doesn't match the requested type (TYPE_E_TYPEMISMATCH), as
+// returned by the IPropertyValue getters themselves.
+const TYPE_MISMATCH: ErrorCode = ErrorCode(0x8002_8CA0_u32 as i32);
+
+// The PropertyType of an array is that of its elements plus this offset.
+const ARRAY: i32 = 1024;
+
+const NAMES: [&str; 21] = [
+    "Empty",
+    "UInt8",
+    "Int16",
+    "UInt16",
+    "Int32",
+    "UInt32",
+    "Int64",
+    "UInt64",
+    "Single",
+    "Double",
+    "Char16",
+    "Boolean",
+    "String",
+    "Inspectable",
+    "DateTime",
+    "TimeSpan",
+    "Guid",
+    "Point",
+    "Size",
+    "Rect",
+    "OtherType",
+];
+
+/// The error returned by the typed getters of `IPropertyValue` and `Object`, such as `as_i32`,
+/// when the value is of another type or can't be read
+///
+/// Unlike the error returned by a getter such as `get_int32`, this names both the expected type
+/// and the type of the value.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PropertyTypeError {
+    code: ErrorCode,
+    expected: i32,
+    actual: Option<i32>,
+}
+
+impl PropertyTypeError {
+    /// Checks the `PropertyType` of a value, given as its underlying value, against the type
+    /// expected by a getter. This is called by the generated getters.
+    #[doc(hidden)]
+    pub fn check(actual: i32, expected: i32) -> std::result::Result<(), Self> {
+        if actual == expected {
+            Ok(())
+        } else {
+            Err(Self {
+                code: TYPE_MISMATCH,
+                expected,
+                actual: Some(actual),
+            })
+        }
+    }
+
+    /// Creates the error for a getter that failed for another reason, such as a disconnected
+    /// object. This is called by the generated getters.
+    #[doc(hidden)]
+    pub fn failed(error: Error, expected: i32) -> Self {
+        Self {
+            code: error.code(),
+            expected,
+            actual: None,
+        }
+    }
+
+    pub fn code(&self) -> ErrorCode {
+        self.code
+    }
+
+    /// The name of the `PropertyType` that the getter expected, such as `Int32`.
+    pub fn expected_name(&self) -> &'static str {
+        type_name(self.expected)
+    }
+
+    /// The name of the `PropertyType` of the value, or `None` if the getter failed before the
+    /// type was read.
+    pub fn actual_name(&self) -> Option<&'static str> {
+        self.actual.map(type_name)
+    }
+}
+
+impl PropertyTypeError {
+    // Describes the mismatch without the error code, which `Error` displays alongside its
+    // message.
+    fn description(&self) -> String {
+        match self.actual_name() {
+            Some(actual) => format!(
+                "expected a property value of type `{}` but found `{}`",
+                self.expected_name(),
+                actual
+            ),
+            None => format!(
+                "cannot read a property value of type `{}`",
+                self.expected_name()
+            ),
+        }
+    }
+}
+
+impl fmt::Display for PropertyTypeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} (HRESULT 0x{:08X})", self.description(), self.code.0)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for PropertyTypeError {}
+
+// The names of the types are kept as the error's message.
+impl From<PropertyTypeError> for Error {
+    fn from(error: PropertyTypeError) -> Self {
+        Error::from(error.code).with_message(error.description())
+    }
+}
+
+fn type_name(value: i32) -> &'static str {
+    const ARRAY_NAMES: [&str; 21] = [
+        "EmptyArray",
+        "UInt8Array",
+        "Int16Array",
+        "UInt16Array",
+        "Int32Array",
+        "UInt32Array",
+        "Int64Array",
+        "UInt64Array",
+        "SingleArray",
+        "DoubleArray",
+        "Char16Array",
+        "BooleanArray",
+        "StringArray",
+        "InspectableArray",
+        "DateTimeArray",
+        "TimeSpanArray",
+        "GuidArray",
+        "PointArray",
+        "SizeArray",
+        "RectArray",
+        "OtherTypeArray",
+    ];
+
+    // Empty has no array type.
+    let (names, index) = if value > ARRAY {
+        (&ARRAY_NAMES, value - ARRAY)
+    } else {
+        (&NAMES, value)
+    };
+
+    names.get(index as usize).copied().unwrap_or("Unknown")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn names() {
+        assert_eq!(type_name(0), "Empty");
+        assert_eq!(type_name(4), "Int32");
+        assert_eq!(type_name(20), "OtherType");
+        assert_eq!(type_name(1024 + 9), "DoubleArray");
+        assert_eq!(type_name(1024 + 20), "OtherTypeArray");
+        assert_eq!(type_name(1024), "Unknown");
+        assert_eq!(type_name(21), "Unknown");
+        assert_eq!(type_name(-1), "Unknown");
+    }
+
+    #[test]
+    fn check() {
+        assert_eq!(PropertyTypeError::check(4, 4), Ok(()));
+
+        let error = PropertyTypeError::check(12, 4).unwrap_err();
+        assert_eq!(error.code(), TYPE_MISMATCH);
+        assert_eq!(error.expected_name(), "Int32");
+        assert_eq!(error.actual_name(), Some("String"));
+        assert_eq!(
+            error.to_string(),
+            "expected a property value of type `Int32` but found `String` (HRESULT 0x80028CA0)"
+        );
+
+        let error = PropertyTypeError::failed(ErrorCode::CLOSED.into(), 1024 + 9);
+        assert_eq!(error.actual_name(), None);
+        assert_eq!(
+            error.to_string(),
+            "cannot read a property value of type `DoubleArray` (HRESULT 0x80000013)"
+        );
+    }
+
+    #[test]
+    fn into_error() {
+        let error: Error = PropertyTypeError::check(12, 4).unwrap_err().into();
+        assert_eq!(error.code(), TYPE_MISMATCH);
+        assert_eq!(
+            error.message(),
+            Some("expected a property value of type `Int32` but found `String`")
+        );
+        assert_eq!(
+            error.to_string(),
+            "expected a property value of type `Int32` but found `String` (HRESULT 0x80028CA0)"
+        );
+    }
+}
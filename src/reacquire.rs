@@ -0,0 +1,122 @@
+use crate::*;
+
+use std::future::Future;
+use std::sync::Mutex;
+
+/// A long-lived handle that is reacquired when it goes stale
+///
+/// Handles to brokered objects, such as devices, sensors, and app services, are routinely closed
+/// or disconnected when the app is suspended and resumed. The handle is acquired by calling the
+/// given closure, and again whenever a call fails with an error of kind [`ErrorKind::Closed`],
+/// after which the call is retried once with the new handle.
+///
+/// ```no_run
+/// winrt::import!(
+///     dependencies
+///         "os"
+///     types
+///         "windows.devices.geolocation.geolocator"
+/// );
+///
+/// fn main() -> winrt::Result<()> {
+///     use windows::devices::geolocation::Geolocator;
+///
+///     let handle = winrt::Reacquire::new(Geolocator::new)?;
+///     let status = handle.call(|geolocator| geolocator.location_status())?;
+///     Ok(())
+/// }
+/// ```
+pub struct Reacquire<T> {
+    value: Mutex<T>,
+    acquire: Box<dyn Fn() -> Result<T> + Send + Sync>,
+}
+
+impl<T: Clone> Reacquire<T> {
+    /// Acquires the handle by calling the closure, which is called again whenever the handle
+    /// needs to be reacquired.
+    pub fn new<F: Fn() -> Result<T> + Send + Sync + 'static>(acquire: F) -> Result<Self> {
+        Ok(Self {
+            value: Mutex::new(acquire()?),
+            acquire: Box::new(acquire),
+        })
+    }
+
+    /// Returns the current handle.
+    pub fn get(&self) -> T {
+        self.value.lock().unwrap().clone()
+    }
+
+    /// Acquires a new handle, replacing the current one, and returns it.
+    pub fn reacquire(&self) -> Result<T> {
+        let value = (self.acquire)()?;
+        *self.value.lock().unwrap() = value.clone();
+        Ok(value)
+    }
+
+    /// Calls the closure with the current handle, and calls it again with a new handle if it
+    /// fails because the handle was closed.
+    pub fn call<R, F: Fn(&T) -> Result<R>>(&self, call: F) -> Result<R> {
+        match call(&self.get()) {
+            Err(error) if error.kind() == ErrorKind::Closed => call(&self.reacquire()?),
+            result => result,
+        }
+    }
+
+    /// Calls the closure and awaits the future it returns with the current handle, and does so
+    /// again with a new handle if it fails because the handle was closed.
+    pub async fn call_async<R, O, F>(&self, call: F) -> Result<R>
+    where
+        O: Future<Output = Result<R>>,
+        F: Fn(T) -> O,
+    {
+        match call(self.get()).await {
+            Err(error) if error.kind() == ErrorKind::Closed => call(self.reacquire()?).await,
+            result => result,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use std::sync::Arc;
+
+    #[test]
+    fn reacquire() -> Result<()> {
+        let count = Arc::new(AtomicU32::new(0));
+        let acquired = count.clone();
+        let handle = Reacquire::new(move || Ok(acquired.fetch_add(1, Ordering::SeqCst)))?;
+        assert_eq!(handle.get(), 0);
+
+        let value = handle.call(|value| match value {
+            0 => Err(ErrorCode::CLOSED.into()),
+            value => Ok(*value),
+        })?;
+        assert_eq!(value, 1);
+        assert_eq!(handle.get(), 1);
+
+        let error = handle
+            .call(|_| Err::<(), _>(ErrorCode::CHANGED_STATE.into()))
+            .unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::Closed);
+        assert_eq!(handle.get(), 2);
+
+        let error = handle
+            .call(|_| Err::<(), _>(ErrorCode::FAIL.into()))
+            .unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::Other);
+        assert_eq!(count.load(Ordering::SeqCst), 3);
+
+        let value = block_on(handle.call_async(|value| async move {
+            if value == 2 {
+                Err(ErrorCode::DISCONNECTED.into())
+            } else {
+                Ok(value)
+            }
+        }))?;
+        assert_eq!(value, 3);
+
+        Ok(())
+    }
+}
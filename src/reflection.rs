@@ -0,0 +1,355 @@
+//! Resolving WinRT types to their metadata at runtime
+//!
+//! With the `reflection` feature a type's metadata may be looked up by name, or from an object's
+//! runtime class name, without importing it at compile time. This is the basis for dynamic
+//! scenarios such as scripting bridges and generic object inspectors. The metadata file defining
+//! a type is found with `RoGetMetaDataFile`, which knows about the system's metadata as well as
+//! that of the calling app's package, and is read with the `winmd` crate. A directory's metadata
+//! is read once and shared by every type resolved from it.
+//!
+//! ```no_run
+//! let info = winrt::reflection::TypeInfo::resolve("Windows.Foundation.Uri")?;
+//!
+//! for interface in info.interfaces() {
+//!     println!("{}", interface.name);
+//! }
+//! # Ok::<(), winrt::Error>(())
+//! ```
+
+use crate::*;
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use winmd::codes::TypeDefOrRef;
+use winmd::flags::TypeCategory;
+use winmd::signature::{ElementType, MethodSig};
+use winmd::tables::{AttributeArg, MethodDef, TypeDef};
+use winmd::TypeReader;
+
+// The name couldn't be resolved to any metadata file (RO_E_METADATA_NAME_NOT_FOUND).
+const METADATA_NAME_NOT_FOUND: ErrorCode = ErrorCode(0x8000_000F_u32 as i32);
+// The metadata is malformed, as with HRESULT_FROM_WIN32(ERROR_BAD_FORMAT).
+const BAD_FORMAT: ErrorCode = ErrorCode(0x8007_000B_u32 as i32);
+
+// The number of IUnknown and IInspectable methods preceding an interface's own methods.
+const INSPECTABLE_SLOTS: usize = 6;
+
+static READERS: Mutex<BTreeMap<PathBuf, Arc<TypeReader>>> = Mutex::new(BTreeMap::new());
+
+/// The metadata of a WinRT type
+#[derive(Clone)]
+pub struct TypeInfo {
+    reader: Arc<TypeReader>,
+    def: TypeDef,
+    path: PathBuf,
+}
+
+/// An interface implemented or required by a type, as returned by [`TypeInfo::interfaces`]
+#[derive(Clone, Debug, PartialEq)]
+pub struct InterfaceInfo {
+    /// The full name of the interface, including the arguments of a generic interface, such as
+    /// ``Windows.Foundation.Collections.IIterable`1<String>``
+    pub name: String,
+    /// Whether this is the class's default interface
+    pub default: bool,
+    /// Whether the interface is a specialization of a generic interface, which can't be resolved
+    /// on its own
+    pub generic: bool,
+}
+
+/// A method of an interface or delegate, as returned by [`TypeInfo::methods`]
+#[derive(Clone, Debug)]
+pub struct MethodInfo {
+    /// The name of the method in metadata, such as `get_Domain` for a property getter
+    pub name: String,
+    /// The names of the method's parameters
+    pub params: Vec<String>,
+    /// The method's position in the interface's vtable
+    pub slot: usize,
+    /// The method's signature
+    pub sig: MethodSig,
+}
+
+impl TypeInfo {
+    /// Resolves the full name of a type, such as `Windows.Foundation.Uri`, to its metadata.
+    pub fn resolve(name: &str) -> Result<Self> {
+        let mut path = HString::new();
+
+        // Only the path is needed, as the file is read with the `winmd` crate rather than through
+        // a metadata import, and the type's token is only returned along with an import.
+        unsafe {
+            runtime::RoGetMetaDataFile(
+                HString::from(name).abi(),
+                std::ptr::null_mut(),
+                path.set_abi(),
+                std::ptr::null_mut(),
+                std::ptr::null_mut(),
+            )
+            .ok()?;
+        }
+
+        let path = PathBuf::from(path.to_string());
+        let reader = reader(&path)?;
+
+        let (namespace, name) = match name.rfind('.') {
+            Some(dot) => (&name[..dot], &name[dot + 1..]),
+            None => return Err(METADATA_NAME_NOT_FOUND.into()),
+        };
+
+        match find(&reader, namespace, name) {
+            Some(def) => Ok(Self { reader, def, path }),
+            None => Err(METADATA_NAME_NOT_FOUND.into()),
+        }
+    }
+
+    /// Resolves the runtime class of an object to its metadata.
+    pub fn of<T: ComInterface>(object: &T) -> Result<Self> {
+        let object: Object = object.query();
+
+        if object.is_null() {
+            return Err(ErrorCode::NO_INTERFACE.into());
+        }
+
+        Self::resolve(&object.type_name()?.to_string())
+    }
+
+    /// Returns the namespace and name of the type.
+    pub fn name(&self) -> (&str, &str) {
+        self.def.name(&self.reader)
+    }
+
+    /// Returns the full name of the type.
+    pub fn full_name(&self) -> String {
+        let (namespace, name) = self.name();
+        format!("{}.{}", namespace, name)
+    }
+
+    /// Returns the kind of the type.
+    pub fn category(&self) -> TypeCategory {
+        self.def.category(&self.reader)
+    }
+
+    /// Returns the path of the metadata file defining the type.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Returns the reader of the metadata, for use with the `winmd` crate.
+    pub fn reader(&self) -> &TypeReader {
+        &self.reader
+    }
+
+    /// Returns the type's definition, for use with the `winmd` crate.
+    pub fn def(&self) -> TypeDef {
+        self.def
+    }
+
+    /// Returns the IID of an interface or delegate.
+    pub fn iid(&self) -> Option<Guid> {
+        iid(&self.reader, self.def)
+    }
+
+    /// Returns the interfaces that a class implements or that an interface requires.
+    pub fn interfaces(&self) -> Vec<InterfaceInfo> {
+        self.def
+            .interfaces(&self.reader)
+            .map(|interface| {
+                let default = interface.has_attribute(
+                    &self.reader,
+                    ("Windows.Foundation.Metadata", "DefaultAttribute"),
+                );
+
+                match interface.interface(&self.reader) {
+                    TypeDefOrRef::TypeSpec(spec) => InterfaceInfo {
+                        name: type_name(
+                            &self.reader,
+                            &ElementType::from_type_spec(&self.reader, spec),
+                        ),
+                        default,
+                        generic: true,
+                    },
+                    interface => {
+                        let (namespace, name) = interface.name(&self.reader);
+                        InterfaceInfo {
+                            name: format!("{}.{}", namespace, name),
+                            default,
+                            generic: false,
+                        }
+                    }
+                }
+            })
+            .collect()
+    }
+
+    /// Returns the methods of an interface or delegate in vtable order.
+    pub fn methods(&self) -> Vec<MethodInfo> {
+        self.def
+            .methods(&self.reader)
+            .enumerate()
+            .map(|(index, method)| MethodInfo {
+                name: method.name(&self.reader).to_string(),
+                params: params(&self.reader, method),
+                slot: INSPECTABLE_SLOTS + index,
+                sig: MethodSig::from_method_def(&self.reader, method),
+            })
+            .collect()
+    }
+
+    /// Returns the metadata of one of the interfaces returned by [`TypeInfo::interfaces`].
+    pub fn interface(&self, interface: &InterfaceInfo) -> Result<Self> {
+        if interface.generic {
+            return Err(METADATA_NAME_NOT_FOUND.into());
+        }
+
+        Self::resolve(&interface.name)
+    }
+
+    // Returns the metadata of another type read by the same reader.
+    pub(crate) fn with_def(&self, def: TypeDef) -> Self {
+        Self {
+            reader: self.reader.clone(),
+            def,
+            path: self.path.clone(),
+        }
+    }
+}
+
+impl std::fmt::Debug for TypeInfo {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_tuple("TypeInfo").field(&self.full_name()).finish()
+    }
+}
+
+/// Returns the name of a type in a signature, with the arguments of a generic instance.
+pub fn type_name(reader: &TypeReader, kind: &ElementType) -> String {
+    match kind {
+        ElementType::TypeDefOrRef(def) => {
+            let (namespace, name) = def.name(reader);
+            format!("{}.{}", namespace, name)
+        }
+        ElementType::GenericInstance(def, args) => {
+            let args: Vec<String> = args.iter().map(|arg| type_name(reader, arg)).collect();
+            format!(
+                "{}<{}>",
+                type_name(reader, &ElementType::TypeDefOrRef(*def)),
+                args.join(", ")
+            )
+        }
+        ElementType::Array(kind) => format!("{}[]", type_name(reader, kind)),
+        ElementType::GenericParam(index) | ElementType::MethodGenericParam(index) => {
+            format!("T{}", index)
+        }
+        kind => format!("{:?}", kind),
+    }
+}
+
+// Finds a type by name without panicking if it isn't defined, as with `System.Guid`.
+pub(crate) fn find(reader: &TypeReader, namespace: &str, name: &str) -> Option<TypeDef> {
+    reader
+        .types
+        .get(namespace)
+        .and_then(|types| types.get(name))
+        .copied()
+}
+
+// Reads the IID from the type's GuidAttribute.
+pub(crate) fn iid(reader: &TypeReader, def: TypeDef) -> Option<Guid> {
+    let attribute = def.attributes(reader).find(|attribute| {
+        attribute.name(reader) == ("Windows.Foundation.Metadata", "GuidAttribute")
+    })?;
+
+    let args: Vec<AttributeArg> = attribute
+        .args(reader)
+        .into_iter()
+        .map(|(_, arg)| arg)
+        .collect();
+
+    match args.as_slice() {
+        [AttributeArg::U32(data1), AttributeArg::U16(data2), AttributeArg::U16(data3), rest @ ..]
+            if rest.len() == 8 =>
+        {
+            let mut data4 = [0; 8];
+
+            for (byte, arg) in data4.iter_mut().zip(rest) {
+                if let AttributeArg::U8(value) = arg {
+                    *byte = *value;
+                }
+            }
+
+            Some(Guid::from_values(*data1, *data2, *data3, data4))
+        }
+        _ => None,
+    }
+}
+
+// The parameter rows also describe the return value, at sequence zero.
+fn params(reader: &TypeReader, method: MethodDef) -> Vec<String> {
+    method
+        .params(reader)
+        .filter(|param| param.sequence(reader) != 0)
+        .map(|param| param.name(reader).to_string())
+        .collect()
+}
+
+// Reads the metadata in the same directory as the file, along with the system's metadata that it
+// may refer to, once per directory.
+fn reader(path: &Path) -> Result<Arc<TypeReader>> {
+    let dir = path.parent().unwrap_or(path).to_path_buf();
+    let mut readers = READERS.lock().unwrap();
+
+    if let Some(reader) = readers.get(&dir) {
+        return Ok(reader.clone());
+    }
+
+    let os_dir = winmd::load_winmd::os_dir();
+    let mut files = winmd::load_winmd::from_dir(&dir)?;
+
+    if dir != os_dir {
+        files.append(&mut winmd::load_winmd::from_dir(&os_dir)?);
+    }
+
+    let reader = Arc::new(TypeReader::try_new(files).map_err(|_| Error::from(BAD_FORMAT))?);
+    readers.insert(dir, reader.clone());
+    Ok(reader)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Elsewhere the name is never found, but the arguments are still checked.
+    #[cfg(not(windows))]
+    #[test]
+    fn not_found() {
+        let error = TypeInfo::resolve("Windows.Foundation.Uri").unwrap_err();
+        assert_eq!(error.code(), METADATA_NAME_NOT_FOUND);
+
+        let _lock = crate::authoring::tests::LOCK.lock().unwrap();
+        let error = TypeInfo::of(&crate::authoring::tests::widget()).unwrap_err();
+        assert_eq!(error.code(), ErrorCode::NO_INTERFACE);
+    }
+
+    // The system's metadata resolves, and so do the interfaces of the type.
+    #[cfg(windows)]
+    #[test]
+    fn resolve() -> Result<()> {
+        let info = TypeInfo::resolve("Windows.Foundation.Uri")?;
+        assert_eq!(info.name(), ("Windows.Foundation", "Uri"));
+        assert!(info.category() == TypeCategory::Class);
+        assert!(info.path().ends_with("Windows.Foundation.winmd"));
+
+        let default = info
+            .interfaces()
+            .into_iter()
+            .find(|interface| interface.default)
+            .unwrap();
+        assert_eq!(default.name, "Windows.Foundation.IUriRuntimeClass");
+        assert!(info.interface(&default)?.category() == TypeCategory::Interface);
+
+        let error = TypeInfo::resolve("Windows.Foundation.Missing").unwrap_err();
+        assert_eq!(error.code(), METADATA_NAME_NOT_FOUND);
+        Ok(())
+    }
+}
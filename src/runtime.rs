@@ -1,19 +1,170 @@
-use crate::{hstring, ErrorCode, Guid, RawPtr};
+#[cfg(any(windows, feature = "std"))]
+use crate::RawPtr;
+#[cfg(windows)]
+use crate::{delay_load, hstring, ErrorCode, Guid, IUnknown, Object, RawComPtr};
 
+#[cfg(windows)]
 #[link(name = "kernel32")]
 extern "system" {
     pub fn GetProcessHeap() -> RawPtr;
     pub fn HeapAlloc(heap: RawPtr, flags: u32, bytes: usize) -> RawPtr;
     pub fn HeapFree(heap: RawPtr, flags: u32, ptr: RawPtr) -> i32;
+    pub fn CreateEventW(
+        attributes: RawPtr,
+        manual_reset: i32,
+        initial_state: i32,
+        name: *const u16,
+    ) -> RawPtr;
+    pub fn SetEvent(handle: RawPtr) -> i32;
+    pub fn CloseHandle(handle: RawPtr) -> i32;
+    pub fn WaitForSingleObject(handle: RawPtr, milliseconds: u32) -> u32;
+    pub fn CreateTimerQueueTimer(
+        timer: *mut RawPtr,
+        queue: RawPtr,
+        callback: extern "system" fn(RawPtr, u8),
+        parameter: RawPtr,
+        due_time: u32,
+        period: u32,
+        flags: u32,
+    ) -> i32;
+    pub fn DeleteTimerQueueTimer(queue: RawPtr, timer: RawPtr, event: RawPtr) -> i32;
+    pub fn LoadLibraryExW(name: *const u16, file: RawPtr, flags: u32) -> RawPtr;
+    pub fn LoadLibraryW(name: *const u16) -> RawPtr;
+    pub fn GetSystemDirectoryW(buffer: *mut u16, size: u32) -> u32;
+    pub fn GetLastError() -> u32;
+    pub fn GetProcAddress(module: RawPtr, name: *const u8) -> RawPtr;
+    pub fn FreeLibrary(module: RawPtr) -> i32;
 }
 
-#[link(name = "onecore")]
-extern "system" {
-    // TODO: get rid of these (not available on Windows 7) - we'll load these dynamically
-    pub fn CoIncrementMTAUsage(cookie: *mut RawPtr) -> ErrorCode;
-    pub fn RoGetActivationFactory(
+// Everything beyond kernel32 is loaded on first use so that a function missing from an older
+// version of Windows fails the call with its error code rather than preventing the process from
+// loading.
+#[cfg(windows)]
+macro_rules! delay_load {
+    ($($library:literal fn $name:ident($($param:ident: $type:ty),*);)*) => {
+        $(
+            #[allow(non_snake_case)]
+            pub unsafe fn $name($($param: $type),*) -> ErrorCode {
+                type Function = extern "system" fn($($type),*) -> ErrorCode;
+
+                match delay_load::get_proc::<Function>($library, stringify!($name)) {
+                    Ok(function) => function($($param),*),
+                    Err(error) => error.code(),
+                }
+            }
+        )*
+    };
+}
+
+#[cfg(windows)]
+delay_load! {
+    "api-ms-win-core-com-l1-1-0" fn CoIncrementMTAUsage(cookie: *mut RawPtr);
+    "api-ms-win-core-winrt-l1-1-0" fn RoGetActivationFactory(
         hstring: *mut hstring::Header,
         interface: &Guid,
-        result: *mut RawPtr,
-    ) -> ErrorCode;
+        result: *mut RawPtr
+    );
+    // This returns a Win32 error rather than an HRESULT, and is missing before Windows 8.
+    "kernel32" fn GetCurrentPackageFullName(length: *mut u32, name: *mut u16);
+    "ole32" fn CoGetApartmentType(apartment_type: *mut i32, qualifier: *mut i32);
+    "ole32" fn CoWaitForMultipleHandles(
+        flags: u32,
+        timeout: u32,
+        count: u32,
+        handles: *const RawPtr,
+        index: *mut u32
+    );
+    "ole32" fn CoMarshalInterThreadInterfaceInStream(
+        iid: &Guid,
+        unknown: RawComPtr<IUnknown>,
+        stream: *mut RawPtr
+    );
+    "ole32" fn CoGetInterfaceAndReleaseStream(
+        stream: RawComPtr<IUnknown>,
+        iid: &Guid,
+        interface: *mut RawPtr
+    );
+    "ole32" fn CoReleaseMarshalData(stream: RawComPtr<IUnknown>);
+    "d3d11" fn CreateDirect3D11DeviceFromDXGIDevice(
+        dxgi_device: RawPtr,
+        graphics_device: *mut RawComPtr<Object>
+    );
+    "d3d11" fn CreateDirect3D11SurfaceFromDXGISurface(
+        dxgi_surface: RawPtr,
+        graphics_surface: *mut RawComPtr<Object>
+    );
+}
+
+// Only the apartment checks of debug builds, which need `std`, use this.
+#[cfg(all(windows, feature = "std"))]
+delay_load! {
+    "ole32" fn CoGetContextToken(token: *mut usize);
+}
+
+// These return Win32 errors rather than HRESULTs.
+#[cfg(all(windows, feature = "tracelogging"))]
+delay_load! {
+    "advapi32" fn EventRegister(
+        provider: &Guid,
+        callback: RawPtr,
+        context: RawPtr,
+        handle: *mut u64
+    );
+    "advapi32" fn EventWriteTransfer(
+        handle: u64,
+        descriptor: RawPtr,
+        activity: *const Guid,
+        related: *const Guid,
+        count: u32,
+        data: RawPtr
+    );
+}
+
+#[cfg(all(windows, feature = "reflection"))]
+delay_load! {
+    "api-ms-win-ro-typeresolution-l1-1-0" fn RoGetMetaDataFile(
+        name: *mut hstring::Header,
+        dispenser: RawPtr,
+        path: *mut *mut hstring::Header,
+        import: RawPtr,
+        token: *mut u32
+    );
 }
+
+// The task allocator has no error code to return, so a missing function fails the allocation.
+#[cfg(windows)]
+#[allow(non_snake_case)]
+pub unsafe fn CoTaskMemAlloc(bytes: usize) -> RawPtr {
+    type Function = extern "system" fn(usize) -> RawPtr;
+
+    match delay_load::get_proc::<Function>("ole32", "CoTaskMemAlloc") {
+        Ok(function) => function(bytes),
+        Err(_) => std::ptr::null_mut(),
+    }
+}
+
+#[cfg(windows)]
+#[allow(non_snake_case)]
+pub unsafe fn CoTaskMemFree(ptr: RawPtr) {
+    type Function = extern "system" fn(RawPtr);
+
+    if let Ok(function) = delay_load::get_proc::<Function>("ole32", "CoTaskMemFree") {
+        function(ptr);
+    }
+}
+
+#[cfg(not(windows))]
+pub use crate::stub::*;
+
+pub const INFINITE: u32 = 0xFFFF_FFFF;
+#[cfg(feature = "std")]
+pub const INVALID_HANDLE_VALUE: RawPtr = -1isize as RawPtr;
+#[cfg(feature = "std")]
+pub const WT_EXECUTEONLYONCE: u32 = 8;
+pub const COWAIT_DISPATCH_CALLS: u32 = 8;
+pub const COWAIT_DISPATCH_WINDOW_MESSAGES: u32 = 0x10;
+pub const RPC_S_CALLPENDING: crate::ErrorCode = crate::ErrorCode(0x8001_0115u32 as i32);
+pub const APTTYPE_STA: i32 = 0;
+pub const APTTYPE_MTA: i32 = 1;
+pub const APTTYPE_NA: i32 = 2;
+pub const APTTYPE_MAINSTA: i32 = 3;
@@ -1,7 +1,10 @@
 /// RuntimeType is used to constrain WinRT generic types to WinRT types.
 ///
 /// It is highly unlikely that users of winrt will ever need to implement this
-/// trait for themselves
+/// trait for themselves but it is also the stable way to exchange values with other
+/// code, such as C++ or C bindings, at the ABI level. Every projected type implements it:
+/// strings, classes, interfaces and delegates have a raw pointer as their ABI, while
+/// primitives, enums and structs are passed by value.
 ///
 /// # Safety
 ///
@@ -10,10 +13,67 @@
 /// The type itself must also be zero initializable and safe to drop if all bits are zeroable.
 /// RuntimeTypes must be safe to use in WinRT generics.
 pub unsafe trait RuntimeType {
+    /// The type's representation at the ABI level
     type Abi;
 
+    /// Calls the selector's erased body if the ABI is an owned pointer, such as a string or COM
+    /// pointer, that may be passed through the type-erased code generated by the `"erased"` import
+    /// option, and its typed body otherwise.
+    #[doc(hidden)]
+    #[inline(always)]
+    fn select<S: crate::erased::Selector>(selector: S) -> S::Output
+    where
+        Self: Sized,
+    {
+        selector.typed()
+    }
+
+    /// Returns the ABI representation of the value without affecting its ownership.
+    ///
+    /// A returned pointer is only valid for as long as the value is alive and unchanged.
     fn abi(&self) -> Self::Abi;
+
+    /// Clears the value and returns a pointer to its ABI representation so that it may be
+    /// used as an out parameter.
     fn set_abi(&mut self) -> *mut Self::Abi;
+
+    /// Transfers ownership of the value to the returned ABI representation.
+    ///
+    /// A returned reference, such as a string or COM pointer, must eventually be released by
+    /// the receiver or passed back to `from_abi`.
+    fn into_abi(self) -> Self::Abi
+    where
+        Self: Sized,
+    {
+        let abi = self.abi();
+        std::mem::forget(self);
+        abi
+    }
+
+    /// Returns a value with all of its bits zeroed, which the trait's safety requirements make
+    /// valid, for the generated methods to receive return values into.
+    #[doc(hidden)]
+    fn zeroed() -> Self
+    where
+        Self: Sized,
+    {
+        unsafe { std::mem::zeroed() }
+    }
+
+    /// Takes ownership of an ABI representation, releasing any reference it holds when the
+    /// returned value is dropped.
+    ///
+    /// # Safety
+    ///
+    /// The ABI value must be valid for the type and, if it is a reference, owned by the caller.
+    unsafe fn from_abi(abi: Self::Abi) -> Self
+    where
+        Self: Sized,
+    {
+        let mut value: Self = std::mem::zeroed();
+        std::ptr::write(value.set_abi(), abi);
+        value
+    }
 }
 
 macro_rules! primitive_runtime_type {
@@ -0,0 +1,168 @@
+// Support for the `serde` feature. HString, Char16, Guid and EventRegistrationToken are
+// implemented here while projected enums and structs rely on the `__serde_enum` and
+// `__serde_struct` macros, which are invoked by the generated code regardless of the feature and
+// expand to nothing when it is disabled. Enums are serialized as their underlying integer value so
+// that flags combinations round-trip, Guids as their hyphenated string form, characters as their
+// UTF-16 code unit, and structs field by field. The `serde_json` feature similarly gates the
+// generated conversions for the Windows.Data.Json types behind `__cfg_serde_json`.
+
+#[cfg(feature = "serde")]
+mod implementation {
+    use crate::*;
+    use serde::de::{Error, Unexpected};
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    impl Serialize for HString {
+        fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+            // An HString may hold unpaired surrogates, which have no UTF-8 representation.
+            let value = String::from_utf16(self.as_wide()).map_err(serde::ser::Error::custom)?;
+            serializer.serialize_str(&value)
+        }
+    }
+
+    impl<'de> Deserialize<'de> for HString {
+        fn deserialize<D: Deserializer<'de>>(
+            deserializer: D,
+        ) -> std::result::Result<Self, D::Error> {
+            String::deserialize(deserializer).map(HString::from)
+        }
+    }
+
+    impl Serialize for Char16 {
+        fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+            serializer.serialize_u16(self.0)
+        }
+    }
+
+    impl<'de> Deserialize<'de> for Char16 {
+        fn deserialize<D: Deserializer<'de>>(
+            deserializer: D,
+        ) -> std::result::Result<Self, D::Error> {
+            u16::deserialize(deserializer).map(Char16)
+        }
+    }
+
+    // Serialized as the generated structs are, field by field.
+    #[derive(Serialize, Deserialize)]
+    #[serde(rename = "EventRegistrationToken")]
+    struct Token {
+        value: i64,
+    }
+
+    impl Serialize for EventRegistrationToken {
+        fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+            Token { value: self.value }.serialize(serializer)
+        }
+    }
+
+    impl<'de> Deserialize<'de> for EventRegistrationToken {
+        fn deserialize<D: Deserializer<'de>>(
+            deserializer: D,
+        ) -> std::result::Result<Self, D::Error> {
+            Token::deserialize(deserializer).map(|token| EventRegistrationToken::new(token.value))
+        }
+    }
+
+    impl Serialize for Guid {
+        fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+            serializer.serialize_str(&format!("{:?}", self))
+        }
+    }
+
+    impl<'de> Deserialize<'de> for Guid {
+        fn deserialize<D: Deserializer<'de>>(
+            deserializer: D,
+        ) -> std::result::Result<Self, D::Error> {
+            let value = String::deserialize(deserializer)?;
+
+            // `Guid::from` panics on malformed input so the format is checked up front.
+            let valid = value.len() == 36
+                && value.bytes().enumerate().all(|(index, byte)| match index {
+                    8 | 13 | 18 | 23 => byte == b'-',
+                    _ => byte.is_ascii_hexdigit(),
+                });
+
+            if !valid {
+                return Err(D::Error::invalid_value(
+                    Unexpected::Str(&value),
+                    &"a hyphenated GUID string",
+                ));
+            }
+
+            Ok(Guid::from(value.as_str()))
+        }
+    }
+
+    #[doc(hidden)]
+    #[macro_export]
+    macro_rules! __serde_enum {
+        ($name:ident) => {
+            impl ::winrt::serde::Serialize for $name {
+                fn serialize<S: ::winrt::serde::Serializer>(
+                    &self,
+                    serializer: S,
+                ) -> ::std::result::Result<S::Ok, S::Error> {
+                    ::winrt::serde::Serialize::serialize(
+                        &::winrt::RuntimeType::abi(self),
+                        serializer,
+                    )
+                }
+            }
+            impl<'de> ::winrt::serde::Deserialize<'de> for $name {
+                fn deserialize<D: ::winrt::serde::Deserializer<'de>>(
+                    deserializer: D,
+                ) -> ::std::result::Result<Self, D::Error> {
+                    let mut value = Self::default();
+                    unsafe {
+                        *::winrt::RuntimeType::set_abi(&mut value) =
+                            ::winrt::serde::Deserialize::deserialize(deserializer)?;
+                    }
+                    Ok(value)
+                }
+            }
+        };
+    }
+
+    #[doc(hidden)]
+    #[macro_export]
+    macro_rules! __serde_struct {
+        ($($item:tt)*) => {
+            #[derive(::winrt::serde::Serialize, ::winrt::serde::Deserialize)]
+            #[serde(crate = "::winrt::serde")]
+            $($item)*
+        };
+    }
+}
+
+#[cfg(not(feature = "serde"))]
+mod implementation {
+    #[doc(hidden)]
+    #[macro_export]
+    macro_rules! __serde_enum {
+        ($name:ident) => {};
+    }
+
+    #[doc(hidden)]
+    #[macro_export]
+    macro_rules! __serde_struct {
+        ($($item:tt)*) => {
+            $($item)*
+        };
+    }
+}
+
+#[cfg(feature = "serde_json")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __cfg_serde_json {
+    ($($item:tt)*) => {
+        $($item)*
+    };
+}
+
+#[cfg(not(feature = "serde_json"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __cfg_serde_json {
+    ($($item:tt)*) => {};
+}
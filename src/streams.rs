@@ -0,0 +1,405 @@
+use crate::buffer::{create_buffer, create_buffer_from};
+use crate::interop::IBufferByteAccess;
+use crate::*;
+
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+/// A WinRT input stream that can be read incrementally
+///
+/// This is implemented for `IInputStream` and any classes or interfaces that implement it, such
+/// as the stream returned by `IHttpContent::read_as_input_stream_async`. The data is read one
+/// chunk at a time, rather than buffering the whole stream, so it may be handed to the async Rust
+/// ecosystem. With the `futures` feature enabled the reader returned by `into_async_read`
+/// implements both `futures::io::AsyncRead` and `futures::Stream`.
+pub trait InputStream: Sized {
+    #[doc(hidden)]
+    type Buffer: ComInterface;
+    #[doc(hidden)]
+    type Read: Future<Output = Result<Self::Buffer>> + Cancelable;
+
+    #[doc(hidden)]
+    fn read_buffer(&self, buffer: &Self::Buffer, capacity: u32) -> Result<Self::Read>;
+
+    #[doc(hidden)]
+    fn buffer_len(buffer: &Self::Buffer) -> Result<u32>;
+
+    /// Returns a reader that reads the stream in chunks of at most 64 KiB.
+    fn into_async_read(self) -> AsyncReader<Self> {
+        AsyncReader::with_capacity(self, 64 * 1024)
+    }
+}
+
+/// A WinRT output stream that can be written incrementally
+///
+/// This is implemented for `IOutputStream` and any classes or interfaces that implement it. With
+/// the `futures` feature enabled the writer returned by `into_async_write` implements
+/// `futures::io::AsyncWrite`.
+pub trait OutputStream: Sized {
+    #[doc(hidden)]
+    type Buffer: ComInterface;
+    #[doc(hidden)]
+    type Write: Future<Output = Result<u32>> + Cancelable;
+    #[doc(hidden)]
+    type Flush: Future<Output = Result<bool>> + Cancelable;
+
+    #[doc(hidden)]
+    fn write_buffer(&self, buffer: &Self::Buffer) -> Result<Self::Write>;
+
+    #[doc(hidden)]
+    fn flush_buffers(&self) -> Result<Self::Flush>;
+
+    /// Returns a writer that writes to the stream asynchronously.
+    #[cfg(feature = "futures")]
+    fn into_async_write(self) -> AsyncWriter<Self> {
+        AsyncWriter {
+            stream: self,
+            pending: None,
+            flushing: None,
+        }
+    }
+}
+
+/// A reader returned by [`InputStream::into_async_read`]
+///
+/// Dropping the reader cancels any pending read.
+pub struct AsyncReader<S: InputStream> {
+    stream: S,
+    capacity: u32,
+    pending: Option<S::Read>,
+    chunk: Vec<u8>,
+    position: usize,
+    done: bool,
+}
+
+impl<S: InputStream> AsyncReader<S> {
+    /// Creates a reader that reads the stream in chunks of at most `capacity` bytes.
+    pub fn with_capacity(stream: S, capacity: u32) -> Self {
+        Self {
+            stream,
+            capacity: capacity.max(1),
+            pending: None,
+            chunk: Vec::new(),
+            position: 0,
+            done: false,
+        }
+    }
+
+    /// Returns the underlying stream.
+    pub fn get_ref(&self) -> &S {
+        &self.stream
+    }
+
+    /// Reads the next chunk of the stream, returning `None` at the end of the stream.
+    pub fn next_chunk(&mut self) -> NextChunk<'_, S> {
+        NextChunk { reader: self }
+    }
+
+    /// Writes the rest of the stream to the writer, returning the number of bytes written.
+    pub async fn copy_to<W: std::io::Write>(&mut self, writer: &mut W) -> Result<u64> {
+        let mut written = 0;
+
+        while let Some(chunk) = self.next_chunk().await? {
+            writer.write_all(&chunk)?;
+            written += chunk.len() as u64;
+        }
+
+        writer.flush()?;
+        Ok(written)
+    }
+
+    fn poll_next_chunk(&mut self, context: &mut Context) -> Poll<Result<Option<Vec<u8>>>> {
+        match self.poll_fill(context) {
+            Poll::Ready(Ok(true)) => {
+                let chunk = std::mem::take(&mut self.chunk);
+                let position = std::mem::replace(&mut self.position, 0);
+
+                if position == 0 {
+                    Poll::Ready(Ok(Some(chunk)))
+                } else {
+                    Poll::Ready(Ok(Some(chunk[position..].to_vec())))
+                }
+            }
+            Poll::Ready(Ok(false)) => Poll::Ready(Ok(None)),
+            Poll::Ready(Err(error)) => Poll::Ready(Err(error)),
+            Poll::Pending => Poll::Pending,
+        }
+    }
+
+    /// Ensures that there are unread bytes in the current chunk, returning false at the end of
+    /// the stream.
+    fn poll_fill(&mut self, context: &mut Context) -> Poll<Result<bool>> {
+        while self.position == self.chunk.len() {
+            if self.done {
+                return Poll::Ready(Ok(false));
+            }
+
+            if self.pending.is_none() {
+                let buffer = create_buffer(self.capacity);
+                self.pending = Some(self.stream.read_buffer(&buffer, self.capacity)?);
+            }
+
+            let pending = self.pending.as_mut().unwrap();
+            let buffer = match Pin::new(pending).poll(context) {
+                Poll::Ready(buffer) => buffer,
+                Poll::Pending => return Poll::Pending,
+            };
+
+            self.pending = None;
+            let buffer = buffer?;
+            let len = S::buffer_len(&buffer)? as usize;
+
+            // A partial read only returns an empty buffer at the end of the stream.
+            if len == 0 {
+                self.done = true;
+                continue;
+            }
+
+            let access: IBufferByteAccess = TryInto::try_into(&buffer)?;
+            let data = access.buffer()?;
+
+            self.chunk.clear();
+            self.chunk
+                .extend_from_slice(unsafe { std::slice::from_raw_parts(data, len) });
+            self.position = 0;
+        }
+
+        Poll::Ready(Ok(true))
+    }
+}
+
+impl<S: InputStream> Drop for AsyncReader<S> {
+    fn drop(&mut self) {
+        if let Some(pending) = &self.pending {
+            let _ = pending.cancel();
+        }
+    }
+}
+
+// The reader never pins its fields so it may always be moved.
+impl<S: InputStream> Unpin for AsyncReader<S> {}
+
+/// A future returned by [`AsyncReader::next_chunk`]
+pub struct NextChunk<'a, S: InputStream> {
+    reader: &'a mut AsyncReader<S>,
+}
+
+impl<S: InputStream> Future for NextChunk<'_, S> {
+    type Output = Result<Option<Vec<u8>>>;
+
+    fn poll(mut self: Pin<&mut Self>, context: &mut Context) -> Poll<Self::Output> {
+        self.reader.poll_next_chunk(context)
+    }
+}
+
+#[cfg(feature = "futures")]
+impl<S: InputStream> futures_io::AsyncRead for AsyncReader<S> {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        context: &mut Context,
+        buf: &mut [u8],
+    ) -> Poll<std::io::Result<usize>> {
+        if buf.is_empty() {
+            return Poll::Ready(Ok(0));
+        }
+
+        match self.poll_fill(context) {
+            Poll::Ready(Ok(true)) => {
+                let available = &self.chunk[self.position..];
+                let len = available.len().min(buf.len());
+                buf[..len].copy_from_slice(&available[..len]);
+                self.position += len;
+                Poll::Ready(Ok(len))
+            }
+            Poll::Ready(Ok(false)) => Poll::Ready(Ok(0)),
+            Poll::Ready(Err(error)) => Poll::Ready(Err(error.into())),
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+#[cfg(feature = "futures")]
+impl<S: InputStream> futures_core::Stream for AsyncReader<S> {
+    type Item = Result<Vec<u8>>;
+
+    fn poll_next(mut self: Pin<&mut Self>, context: &mut Context) -> Poll<Option<Self::Item>> {
+        match self.poll_next_chunk(context) {
+            Poll::Ready(Ok(Some(chunk))) => Poll::Ready(Some(Ok(chunk))),
+            Poll::Ready(Ok(None)) => Poll::Ready(None),
+            Poll::Ready(Err(error)) => Poll::Ready(Some(Err(error))),
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+/// A writer returned by [`OutputStream::into_async_write`]
+///
+/// Each write is copied and started immediately, and any error is reported by the next write or
+/// flush. Dropping the writer cancels any pending write.
+#[cfg(feature = "futures")]
+pub struct AsyncWriter<S: OutputStream> {
+    stream: S,
+    pending: Option<(S::Write, Vec<u8>)>,
+    flushing: Option<S::Flush>,
+}
+
+#[cfg(feature = "futures")]
+impl<S: OutputStream> AsyncWriter<S> {
+    /// Returns the underlying stream.
+    pub fn get_ref(&self) -> &S {
+        &self.stream
+    }
+
+    /// Waits for the pending write to complete, writing the rest of its bytes if only some of
+    /// them were written.
+    fn poll_pending(&mut self, context: &mut Context) -> Poll<Result<()>> {
+        while let Some((write, _)) = &mut self.pending {
+            let count = match Pin::new(write).poll(context) {
+                Poll::Ready(count) => count,
+                Poll::Pending => return Poll::Pending,
+            };
+
+            let (_, mut data) = self.pending.take().unwrap();
+            let count = count? as usize;
+
+            if count == 0 {
+                return Poll::Ready(Err(ErrorCode::FAIL.into()));
+            }
+
+            if count < data.len() {
+                data.drain(..count);
+                let buffer = create_buffer_from(&data);
+                self.pending = Some((self.stream.write_buffer(&buffer)?, data));
+            }
+        }
+
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_flush_buffers(&mut self, context: &mut Context) -> Poll<Result<()>> {
+        match self.poll_pending(context) {
+            Poll::Ready(Ok(())) => {}
+            Poll::Ready(Err(error)) => return Poll::Ready(Err(error)),
+            Poll::Pending => return Poll::Pending,
+        }
+
+        if self.flushing.is_none() {
+            self.flushing = Some(self.stream.flush_buffers()?);
+        }
+
+        let flushing = self.flushing.as_mut().unwrap();
+
+        match Pin::new(flushing).poll(context) {
+            Poll::Ready(result) => {
+                self.flushing = None;
+                Poll::Ready(result.map(|_| ()))
+            }
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+#[cfg(feature = "futures")]
+impl<S: OutputStream> Drop for AsyncWriter<S> {
+    fn drop(&mut self) {
+        if let Some((pending, _)) = &self.pending {
+            let _ = pending.cancel();
+        }
+
+        if let Some(flushing) = &self.flushing {
+            let _ = flushing.cancel();
+        }
+    }
+}
+
+// The writer never pins its fields so it may always be moved.
+#[cfg(feature = "futures")]
+impl<S: OutputStream> Unpin for AsyncWriter<S> {}
+
+#[cfg(feature = "futures")]
+impl<S: OutputStream> futures_io::AsyncWrite for AsyncWriter<S> {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        context: &mut Context,
+        buf: &[u8],
+    ) -> Poll<std::io::Result<usize>> {
+        match self.poll_pending(context) {
+            Poll::Ready(Ok(())) => {}
+            Poll::Ready(Err(error)) => return Poll::Ready(Err(error.into())),
+            Poll::Pending => return Poll::Pending,
+        }
+
+        if buf.is_empty() {
+            return Poll::Ready(Ok(0));
+        }
+
+        let buffer = create_buffer_from(buf);
+        let write = self.stream.write_buffer(&buffer)?;
+        self.pending = Some((write, buf.to_vec()));
+        Poll::Ready(Ok(buf.len()))
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, context: &mut Context) -> Poll<std::io::Result<()>> {
+        self.poll_flush_buffers(context).map_err(From::from)
+    }
+
+    fn poll_close(self: Pin<&mut Self>, context: &mut Context) -> Poll<std::io::Result<()>> {
+        self.poll_flush(context)
+    }
+}
+
+/// Opens a stream and copies it to the writer. This is used by the generated
+/// `StorageFile::copy_to_file`.
+#[doc(hidden)]
+pub async fn copy_to_writer<O, S, W>(open: O, writer: &mut W) -> Result<u64>
+where
+    O: Future<Output = Result<S>>,
+    S: InputStream,
+    W: std::io::Write,
+{
+    open.await?.into_async_read().copy_to(writer).await
+}
+
+/// Opens a stream, copies the reader to it, and then truncates it to the number of bytes
+/// written. This is used by the generated `StorageFile::copy_from_file`.
+#[doc(hidden)]
+pub async fn copy_from_reader<O, S, R, T>(open: O, reader: &mut R, truncate: T) -> Result<u64>
+where
+    O: Future<Output = Result<S>>,
+    S: OutputStream,
+    R: std::io::Read,
+    T: FnOnce(&S, u64) -> Result<()>,
+{
+    let stream = open.await?;
+    let mut chunk = vec![0; 64 * 1024];
+    let mut written = 0;
+
+    loop {
+        let len = match reader.read(&mut chunk) {
+            Ok(0) => break,
+            Ok(len) => len,
+            Err(error) if error.kind() == std::io::ErrorKind::Interrupted => continue,
+            Err(error) => return Err(error.into()),
+        };
+
+        let mut remaining = &chunk[..len];
+
+        while !remaining.is_empty() {
+            let buffer = create_buffer_from(remaining);
+            let count = stream.write_buffer(&buffer)?.await? as usize;
+
+            if count == 0 {
+                return Err(ErrorCode::FAIL.into());
+            }
+
+            remaining = &remaining[count.min(remaining.len())..];
+        }
+
+        written += len as u64;
+    }
+
+    truncate(&stream, written)?;
+    stream.flush_buffers()?.await?;
+    Ok(written)
+}
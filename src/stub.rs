@@ -0,0 +1,332 @@
+// A stand-in for the operating system functions used by the runtime, for targets other than
+// Windows. Strings, events and timers work in-process so that crates which only use WinRT on
+// Windows can still build and unit test the rest of their logic elsewhere, while activation and
+// anything else that needs the Windows Runtime fails with ERROR_NOT_SUPPORTED.
+
+#![allow(non_snake_case)]
+
+use crate::{hstring, ErrorCode, Guid, IUnknown, Object, RawComPtr, RawPtr};
+use alloc::alloc::{alloc, dealloc, Layout};
+use alloc::boxed::Box;
+#[cfg(not(feature = "std"))]
+use core::sync::atomic::{AtomicBool, Ordering};
+#[cfg(feature = "std")]
+use std::sync::{Condvar, Mutex};
+#[cfg(feature = "std")]
+use std::thread::JoinHandle;
+#[cfg(feature = "std")]
+use std::time::{Duration, Instant};
+
+const NOT_SUPPORTED: ErrorCode = ErrorCode(0x8007_0032_u32 as i32);
+
+// Allocations are prefixed with their size, which `HeapFree` needs to rebuild the layout.
+const PREFIX: usize = 16;
+
+// There is only the one heap, so any non-null handle will do.
+pub unsafe fn GetProcessHeap() -> RawPtr {
+    1 as RawPtr
+}
+
+pub unsafe fn HeapAlloc(_heap: RawPtr, _flags: u32, bytes: usize) -> RawPtr {
+    let layout = Layout::from_size_align_unchecked(bytes + PREFIX, PREFIX);
+    let ptr = alloc(layout);
+
+    if ptr.is_null() {
+        return std::ptr::null_mut();
+    }
+
+    *(ptr as *mut usize) = bytes;
+    ptr.add(PREFIX) as RawPtr
+}
+
+pub unsafe fn HeapFree(_heap: RawPtr, _flags: u32, ptr: RawPtr) -> i32 {
+    let ptr = (ptr as *mut u8).sub(PREFIX);
+    let bytes = *(ptr as *const usize);
+    dealloc(
+        ptr,
+        Layout::from_size_align_unchecked(bytes + PREFIX, PREFIX),
+    );
+    1
+}
+
+// The task allocator shares the one heap.
+pub unsafe fn CoTaskMemAlloc(bytes: usize) -> RawPtr {
+    HeapAlloc(GetProcessHeap(), 0, bytes)
+}
+
+pub unsafe fn CoTaskMemFree(ptr: RawPtr) {
+    HeapFree(GetProcessHeap(), 0, ptr);
+}
+
+#[cfg(feature = "std")]
+struct Event {
+    manual_reset: bool,
+    signaled: Mutex<bool>,
+    condvar: Condvar,
+}
+
+// Without `std` there is no way to park a thread, so a wait spins until the event is signaled.
+#[cfg(not(feature = "std"))]
+struct Event {
+    manual_reset: bool,
+    signaled: AtomicBool,
+}
+
+pub unsafe fn CreateEventW(
+    _attributes: RawPtr,
+    manual_reset: i32,
+    initial_state: i32,
+    _name: *const u16,
+) -> RawPtr {
+    Box::into_raw(Box::new(Event {
+        manual_reset: manual_reset != 0,
+        #[cfg(feature = "std")]
+        signaled: Mutex::new(initial_state != 0),
+        #[cfg(feature = "std")]
+        condvar: Condvar::new(),
+        #[cfg(not(feature = "std"))]
+        signaled: AtomicBool::new(initial_state != 0),
+    })) as RawPtr
+}
+
+#[cfg(feature = "std")]
+pub unsafe fn SetEvent(handle: RawPtr) -> i32 {
+    let event = &*(handle as *const Event);
+    *event.signaled.lock().unwrap() = true;
+    event.condvar.notify_all();
+    1
+}
+
+#[cfg(not(feature = "std"))]
+pub unsafe fn SetEvent(handle: RawPtr) -> i32 {
+    let event = &*(handle as *const Event);
+    event.signaled.store(true, Ordering::Release);
+    1
+}
+
+pub unsafe fn CloseHandle(handle: RawPtr) -> i32 {
+    std::mem::drop(Box::from_raw(handle as *mut Event));
+    1
+}
+
+// Only infinite waits are used by the runtime.
+#[cfg(feature = "std")]
+pub unsafe fn WaitForSingleObject(handle: RawPtr, _milliseconds: u32) -> u32 {
+    let event = &*(handle as *const Event);
+    let mut signaled = event.signaled.lock().unwrap();
+
+    while !*signaled {
+        signaled = event.condvar.wait(signaled).unwrap();
+    }
+
+    if !event.manual_reset {
+        *signaled = false;
+    }
+
+    0
+}
+
+#[cfg(not(feature = "std"))]
+pub unsafe fn WaitForSingleObject(handle: RawPtr, _milliseconds: u32) -> u32 {
+    let event = &*(handle as *const Event);
+
+    if event.manual_reset {
+        while !event.signaled.load(Ordering::Acquire) {
+            core::hint::spin_loop();
+        }
+    } else {
+        while event
+            .signaled
+            .compare_exchange_weak(true, false, Ordering::Acquire, Ordering::Relaxed)
+            .is_err()
+        {
+            core::hint::spin_loop();
+        }
+    }
+
+    0
+}
+
+#[cfg(feature = "std")]
+struct Timer {
+    canceled: Mutex<bool>,
+    condvar: Condvar,
+    thread: Mutex<Option<JoinHandle<()>>>,
+}
+
+// Only one-shot timers are used by the runtime.
+#[cfg(feature = "std")]
+pub unsafe fn CreateTimerQueueTimer(
+    timer: *mut RawPtr,
+    _queue: RawPtr,
+    callback: extern "system" fn(RawPtr, u8),
+    parameter: RawPtr,
+    due_time: u32,
+    _period: u32,
+    _flags: u32,
+) -> i32 {
+    let state = std::sync::Arc::new(Timer {
+        canceled: Mutex::new(false),
+        condvar: Condvar::new(),
+        thread: Mutex::new(None),
+    });
+
+    let shared = state.clone();
+    let parameter = parameter as usize;
+
+    let thread = std::thread::spawn(move || {
+        let deadline = Instant::now() + Duration::from_millis(due_time.into());
+        let mut canceled = shared.canceled.lock().unwrap();
+
+        while !*canceled {
+            let now = Instant::now();
+
+            if now >= deadline {
+                std::mem::drop(canceled);
+                callback(parameter as RawPtr, 1);
+                return;
+            }
+
+            canceled = shared
+                .condvar
+                .wait_timeout(canceled, deadline - now)
+                .unwrap()
+                .0;
+        }
+    });
+
+    *state.thread.lock().unwrap() = Some(thread);
+    *timer = std::sync::Arc::into_raw(state) as RawPtr;
+    1
+}
+
+// Waits for any running callback to complete, as with INVALID_HANDLE_VALUE.
+#[cfg(feature = "std")]
+pub unsafe fn DeleteTimerQueueTimer(_queue: RawPtr, timer: RawPtr, _event: RawPtr) -> i32 {
+    let state = std::sync::Arc::from_raw(timer as *const Timer);
+    *state.canceled.lock().unwrap() = true;
+    state.condvar.notify_all();
+
+    if let Some(thread) = state.thread.lock().unwrap().take() {
+        let _ = thread.join();
+    }
+
+    1
+}
+
+pub unsafe fn CoIncrementMTAUsage(_cookie: *mut RawPtr) -> ErrorCode {
+    ErrorCode(0)
+}
+
+pub unsafe fn RoGetActivationFactory(
+    _hstring: *mut hstring::Header,
+    _interface: &Guid,
+    result: *mut RawPtr,
+) -> ErrorCode {
+    *result = std::ptr::null_mut();
+    NOT_SUPPORTED
+}
+
+// Processes elsewhere never have package identity. This returns a Win32 error rather than an
+// HRESULT, as the real function does.
+pub unsafe fn GetCurrentPackageFullName(_length: *mut u32, _name: *mut u16) -> ErrorCode {
+    ErrorCode(15700)
+}
+
+// There is no metadata to resolve types with elsewhere (RO_E_METADATA_NAME_NOT_FOUND). The
+// arguments are checked as the real function does, which only returns the type's token along
+// with the metadata import that it's a token of.
+#[cfg(feature = "reflection")]
+pub unsafe fn RoGetMetaDataFile(
+    _name: *mut hstring::Header,
+    _dispenser: RawPtr,
+    _path: *mut *mut hstring::Header,
+    import: RawPtr,
+    token: *mut u32,
+) -> ErrorCode {
+    if import.is_null() != token.is_null() {
+        return ErrorCode::INVALID_ARGUMENT;
+    }
+
+    ErrorCode(0x8000_000F_u32 as i32)
+}
+
+#[cfg(feature = "tracelogging")]
+pub unsafe fn EventRegister(
+    _provider: &Guid,
+    _callback: RawPtr,
+    _context: RawPtr,
+    _handle: *mut u64,
+) -> ErrorCode {
+    NOT_SUPPORTED
+}
+
+#[cfg(feature = "tracelogging")]
+pub unsafe fn EventWriteTransfer(
+    _handle: u64,
+    _descriptor: RawPtr,
+    _activity: *const Guid,
+    _related: *const Guid,
+    _count: u32,
+    _data: RawPtr,
+) -> ErrorCode {
+    NOT_SUPPORTED
+}
+
+pub unsafe fn CoGetApartmentType(_apartment_type: *mut i32, _qualifier: *mut i32) -> ErrorCode {
+    NOT_SUPPORTED
+}
+
+// Each thread is its own apartment, so the token is unique to the thread.
+#[cfg(feature = "std")]
+pub unsafe fn CoGetContextToken(token: *mut usize) -> ErrorCode {
+    std::thread_local!(static CONTEXT: u8 = const { 0 });
+    *token = CONTEXT.with(|context| context as *const u8 as usize);
+    ErrorCode(0)
+}
+
+pub unsafe fn CoWaitForMultipleHandles(
+    _flags: u32,
+    _timeout: u32,
+    _count: u32,
+    _handles: *const RawPtr,
+    _index: *mut u32,
+) -> ErrorCode {
+    NOT_SUPPORTED
+}
+
+pub unsafe fn CoMarshalInterThreadInterfaceInStream(
+    _iid: &Guid,
+    _unknown: RawComPtr<IUnknown>,
+    stream: *mut RawPtr,
+) -> ErrorCode {
+    *stream = std::ptr::null_mut();
+    NOT_SUPPORTED
+}
+
+pub unsafe fn CoGetInterfaceAndReleaseStream(
+    _stream: RawComPtr<IUnknown>,
+    _iid: &Guid,
+    interface: *mut RawPtr,
+) -> ErrorCode {
+    *interface = std::ptr::null_mut();
+    NOT_SUPPORTED
+}
+
+pub unsafe fn CoReleaseMarshalData(_stream: RawComPtr<IUnknown>) -> ErrorCode {
+    NOT_SUPPORTED
+}
+
+pub unsafe fn CreateDirect3D11DeviceFromDXGIDevice(
+    _dxgi_device: RawPtr,
+    _graphics_device: *mut RawComPtr<Object>,
+) -> ErrorCode {
+    NOT_SUPPORTED
+}
+
+pub unsafe fn CreateDirect3D11SurfaceFromDXGISurface(
+    _dxgi_surface: RawPtr,
+    _graphics_surface: *mut RawComPtr<Object>,
+) -> ErrorCode {
+    NOT_SUPPORTED
+}
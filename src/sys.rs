@@ -0,0 +1,160 @@
+//! Conversions between this crate's types and the equivalent types of the `winapi` and
+//! `windows-sys` crates.
+//!
+//! Enable the `winapi` or `windows-sys` feature to use these. The types share the same layout so
+//! every conversion is free, letting projects that mix crates pass values across without
+//! resorting to `transmute`. `Guid` converts to and from `GUID`, and `WindowHandle` to and from
+//! `HWND`. Both crates define `HRESULT` as `i32`, which `ErrorCode` converts to and from without
+//! either feature. `HString` owns its string reference, so its conversions are the functions in
+//! the submodules below.
+
+#[cfg(any(feature = "winapi", feature = "windows-sys"))]
+use crate::interop::WindowHandle;
+#[cfg(any(feature = "winapi", feature = "windows-sys"))]
+use crate::*;
+
+/// Conversions for the `winapi` crate
+#[cfg(feature = "winapi")]
+pub mod winapi {
+    use super::*;
+    use ::winapi::shared::guiddef::GUID;
+    use ::winapi::shared::windef::HWND;
+    use ::winapi::winrt::hstring::HSTRING;
+
+    impl From<GUID> for Guid {
+        fn from(value: GUID) -> Guid {
+            Guid::from_values(value.Data1, value.Data2, value.Data3, value.Data4)
+        }
+    }
+
+    impl From<&GUID> for Guid {
+        fn from(value: &GUID) -> Guid {
+            Guid::from_values(value.Data1, value.Data2, value.Data3, value.Data4)
+        }
+    }
+
+    impl From<Guid> for GUID {
+        fn from(value: Guid) -> GUID {
+            (&value).into()
+        }
+    }
+
+    impl From<&Guid> for GUID {
+        fn from(value: &Guid) -> GUID {
+            GUID {
+                Data1: value.data1,
+                Data2: value.data2,
+                Data3: value.data3,
+                Data4: value.data4,
+            }
+        }
+    }
+
+    impl From<HWND> for WindowHandle {
+        fn from(value: HWND) -> WindowHandle {
+            WindowHandle(value as RawPtr)
+        }
+    }
+
+    impl From<WindowHandle> for HWND {
+        fn from(value: WindowHandle) -> HWND {
+            value.0 as HWND
+        }
+    }
+
+    /// Returns the `HSTRING` of the string without affecting its ownership.
+    ///
+    /// The handle is only valid for as long as the `HString` is alive and unchanged.
+    pub fn hstring_as_raw(value: &HString) -> HSTRING {
+        value.abi() as HSTRING
+    }
+
+    /// Transfers ownership of the string to the returned `HSTRING`, which must eventually be
+    /// freed with `WindowsDeleteString`.
+    pub fn hstring_into_raw(value: HString) -> HSTRING {
+        value.into_abi() as HSTRING
+    }
+
+    /// Takes ownership of an `HSTRING`, which is freed when the returned `HString` is dropped.
+    ///
+    /// # Safety
+    ///
+    /// The handle must be null or a valid `HSTRING` that the caller owns.
+    pub unsafe fn hstring_from_raw(raw: HSTRING) -> HString {
+        HString::from_abi(raw as *mut hstring::Header)
+    }
+}
+
+/// Conversions for the `windows-sys` crate
+#[cfg(feature = "windows-sys")]
+pub mod windows_sys {
+    use super::*;
+    use ::windows_sys::core::GUID;
+    use ::windows_sys::Win32::Foundation::HWND;
+
+    impl From<GUID> for Guid {
+        fn from(value: GUID) -> Guid {
+            Guid::from_values(value.data1, value.data2, value.data3, value.data4)
+        }
+    }
+
+    impl From<&GUID> for Guid {
+        fn from(value: &GUID) -> Guid {
+            Guid::from_values(value.data1, value.data2, value.data3, value.data4)
+        }
+    }
+
+    impl From<Guid> for GUID {
+        fn from(value: Guid) -> GUID {
+            (&value).into()
+        }
+    }
+
+    impl From<&Guid> for GUID {
+        fn from(value: &Guid) -> GUID {
+            GUID {
+                data1: value.data1,
+                data2: value.data2,
+                data3: value.data3,
+                data4: value.data4,
+            }
+        }
+    }
+
+    impl From<HWND> for WindowHandle {
+        fn from(value: HWND) -> WindowHandle {
+            WindowHandle(value as RawPtr)
+        }
+    }
+
+    impl From<WindowHandle> for HWND {
+        fn from(value: WindowHandle) -> HWND {
+            value.0 as HWND
+        }
+    }
+
+    // The `windows-sys` crate does not include the WinRT string functions and has no `HSTRING`
+    // type, so strings are passed as plain pointers.
+
+    /// Returns the `HSTRING` of the string without affecting its ownership.
+    ///
+    /// The handle is only valid for as long as the `HString` is alive and unchanged.
+    pub fn hstring_as_raw(value: &HString) -> RawPtr {
+        value.abi() as RawPtr
+    }
+
+    /// Transfers ownership of the string to the returned `HSTRING`, which must eventually be
+    /// freed with `WindowsDeleteString`.
+    pub fn hstring_into_raw(value: HString) -> RawPtr {
+        value.into_abi() as RawPtr
+    }
+
+    /// Takes ownership of an `HSTRING`, which is freed when the returned `HString` is dropped.
+    ///
+    /// # Safety
+    ///
+    /// The handle must be null or a valid `HSTRING` that the caller owns.
+    pub unsafe fn hstring_from_raw(raw: RawPtr) -> HString {
+        HString::from_abi(raw as *mut hstring::Header)
+    }
+}
@@ -0,0 +1,161 @@
+use crate::activation::factory;
+use crate::delegate::{Delegate, DelegateVtable};
+use crate::*;
+
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Waker};
+
+/// Runs a closure on the WinRT thread pool via `ThreadPool::RunAsync`.
+///
+/// This is useful for moving blocking or compute-bound work off of a UI thread. The returned
+/// handle may be awaited, or joined synchronously, to retrieve the closure's result. A panic
+/// inside the closure is caught on the thread pool and resumed when the handle is joined.
+pub fn spawn<F, T>(work: F) -> Result<JoinHandle<T>>
+where
+    F: FnOnce() -> T + Send + 'static,
+    T: Send + 'static,
+{
+    let shared = Arc::new(Mutex::new(Shared {
+        result: None,
+        waker: None,
+    }));
+
+    let completion = shared.clone();
+    let handler = WorkItemHandler::new(Box::new(move || {
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(work));
+        let mut shared = completion.lock().unwrap();
+        shared.result = Some(result);
+
+        if let Some(waker) = shared.waker.take() {
+            waker.wake();
+        }
+    }));
+
+    let statics = factory::<ThreadPool, IThreadPoolStatics>()?;
+    let mut action = IUnknown::default();
+
+    unsafe {
+        ((*(*(statics.ptr.as_raw()))).run_async)(
+            statics.ptr.as_raw(),
+            handler.as_raw(),
+            action.set_abi() as _,
+        )
+        .ok()?;
+    }
+
+    Ok(JoinHandle { shared })
+}
+
+/// A handle to a closure running on the WinRT thread pool, returned by [`spawn`]
+///
+/// Dropping the handle does not stop the closure from running.
+pub struct JoinHandle<T> {
+    shared: Arc<Mutex<Shared<T>>>,
+}
+
+struct Shared<T> {
+    result: Option<std::thread::Result<T>>,
+    waker: Option<Waker>,
+}
+
+impl<T> JoinHandle<T> {
+    /// Blocks the calling thread until the closure has completed and returns its result.
+    pub fn join(self) -> T {
+        block_on(self)
+    }
+}
+
+impl<T> Future for JoinHandle<T> {
+    type Output = T;
+
+    fn poll(self: Pin<&mut Self>, context: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut shared = self.shared.lock().unwrap();
+
+        match shared.result.take() {
+            Some(Ok(value)) => Poll::Ready(value),
+            Some(Err(panic)) => std::panic::resume_unwind(panic),
+            None => {
+                shared.waker = Some(context.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+struct ThreadPool;
+
+impl RuntimeName for ThreadPool {
+    const NAME: &'static str = "Windows.System.Threading.ThreadPool";
+}
+
+#[repr(transparent)]
+#[derive(Default, Clone)]
+struct IThreadPoolStatics {
+    ptr: ComPtr<IThreadPoolStatics>,
+}
+
+unsafe impl ComInterface for IThreadPoolStatics {
+    type VTable = abi_IThreadPoolStatics;
+    const IID: Guid = Guid::from_values(
+        0xB6BF_67DD,
+        0x84BD,
+        0x44F8,
+        [0xAC, 0x1C, 0x93, 0xEB, 0xCB, 0x9D, 0xBA, 0x91],
+    );
+}
+
+#[repr(C)]
+#[allow(non_camel_case_types)]
+struct abi_IThreadPoolStatics {
+    __base: [usize; 6],
+    run_async: extern "system" fn(
+        RawComPtr<IThreadPoolStatics>,
+        RawComPtr<WorkItemHandler>,
+        *mut RawPtr,
+    ) -> ErrorCode,
+}
+
+#[repr(transparent)]
+#[derive(Default, Clone)]
+struct WorkItemHandler {
+    ptr: ComPtr<WorkItemHandler>,
+}
+
+unsafe impl ComInterface for WorkItemHandler {
+    type VTable = WorkItemVtable;
+    const IID: Guid = Guid::from_values(
+        0x1D1A_8B8B,
+        0xFA66,
+        0x414F,
+        [0x9C, 0xBD, 0xB6, 0x5F, 0xC9, 0x9D, 0x17, 0xFA],
+    );
+}
+
+// A WorkItemHandler delegate that runs the boxed closure the first time it is invoked. Like the
+// completed handlers in the `future` module, it is agile as the thread pool invokes it from
+// whichever thread it chooses.
+type Work = Mutex<Option<Box<dyn FnOnce() + Send>>>;
+type WorkItem = Delegate<Work>;
+type WorkItemVtable = DelegateVtable<Work, extern "system" fn(*mut WorkItem, RawPtr) -> ErrorCode>;
+
+impl WorkItemHandler {
+    fn new(work: Box<dyn FnOnce() + Send>) -> Self {
+        Delegate::new(&VTABLE, Mutex::new(Some(work)))
+    }
+}
+
+static VTABLE: WorkItemVtable = DelegateVtable::new(invoke);
+
+extern "system" fn invoke(this: *mut WorkItem, _operation: RawPtr) -> ErrorCode {
+    let work = unsafe { (*this).state.lock().unwrap().take() };
+
+    authoring::invoke(|| {
+        if let Some(work) = work {
+            work();
+        }
+
+        Ok(())
+    })
+}
@@ -0,0 +1,293 @@
+// A TraceLogging provider for runtime errors, enabled by the `tracelogging` feature so that
+// failures in production components show up in standard Windows diagnostics tooling. The provider
+// is named `Rust.WinRT`, and its GUID is derived from the name as for any TraceLogging or
+// EventSource provider, so tools that accept a provider name prefixed with `*`, such as
+// `tracelog` and WPR, can enable it by name. It is registered on first use and stays registered
+// for the life of the process. Without the feature the functions here do nothing.
+
+use crate::*;
+
+/// Logs an error returned across the ABI to a caller as a failed HRESULT.
+#[inline]
+pub(crate) fn error_originated(code: ErrorCode) {
+    #[cfg(feature = "tracelogging")]
+    provider::write(provider::WARNING, "ErrorOriginated", &[], code);
+
+    #[cfg(not(feature = "tracelogging"))]
+    let _ = code;
+}
+
+/// Logs a failure to get the activation factory of a class.
+#[inline]
+pub(crate) fn activation_failed(class: &str, code: ErrorCode) {
+    #[cfg(feature = "tracelogging")]
+    provider::write(
+        provider::ERROR,
+        "ActivationFailed",
+        &[("Class", class)],
+        code,
+    );
+
+    #[cfg(not(feature = "tracelogging"))]
+    let _ = (class, code);
+}
+
+/// Logs a panic that was caught and returned to the caller as an HRESULT.
+#[inline]
+#[cfg(feature = "std")]
+pub(crate) fn panic_converted(message: &str, code: ErrorCode) {
+    #[cfg(feature = "tracelogging")]
+    provider::write(
+        provider::ERROR,
+        "PanicConverted",
+        &[("Message", message)],
+        code,
+    );
+
+    #[cfg(not(feature = "tracelogging"))]
+    let _ = (message, code);
+}
+
+/// Logs a failure to query an object for an interface, naming the interface if its name has
+/// been registered with the `diagnostics` module.
+#[inline]
+pub(crate) fn query_failed(iid: &Guid, code: ErrorCode) {
+    #[cfg(all(feature = "tracelogging", feature = "std"))]
+    provider::write(
+        provider::WARNING,
+        "QueryFailed",
+        &[("Interface", &diagnostics::describe_interface(iid))],
+        code,
+    );
+
+    #[cfg(all(feature = "tracelogging", not(feature = "std")))]
+    provider::write(
+        provider::WARNING,
+        "QueryFailed",
+        &[("Interface", &format!("{:?}", iid))],
+        code,
+    );
+
+    #[cfg(not(feature = "tracelogging"))]
+    let _ = (iid, code);
+}
+
+#[cfg(feature = "tracelogging")]
+mod provider {
+    use crate::*;
+
+    use std::sync::atomic::{AtomicU64, AtomicU8, Ordering};
+
+    pub const ERROR: u8 = 2;
+    pub const WARNING: u8 = 3;
+
+    const NAME: &str = "Rust.WinRT";
+
+    // The channel that identifies TraceLogging events.
+    const CHANNEL: u8 = 11;
+
+    // The field types of the event metadata.
+    const IN_UNICODE_STRING: u8 = 1;
+    const IN_INT32: u8 = 7;
+    const OUT_HRESULT: u8 = 15;
+    const CHAIN: u8 = 0x80;
+
+    // The types of the data descriptors holding the metadata.
+    const EVENT_METADATA: u32 = 1;
+    const PROVIDER_METADATA: u32 = 2;
+
+    const UNREGISTERED: u8 = 0;
+    const REGISTERING: u8 = 1;
+    const REGISTERED: u8 = 2;
+    const FAILED: u8 = 3;
+
+    static STATE: AtomicU8 = AtomicU8::new(UNREGISTERED);
+    static HANDLE: AtomicU64 = AtomicU64::new(0);
+
+    #[repr(C)]
+    struct EventDescriptor {
+        id: u16,
+        version: u8,
+        channel: u8,
+        level: u8,
+        opcode: u8,
+        task: u16,
+        keyword: u64,
+    }
+
+    #[repr(C)]
+    struct EventDataDescriptor {
+        ptr: u64,
+        size: u32,
+        kind: u32,
+    }
+
+    impl EventDataDescriptor {
+        fn new<T>(data: &[T], kind: u32) -> Self {
+            Self {
+                ptr: data.as_ptr() as u64,
+                size: std::mem::size_of_val(data) as u32,
+                kind,
+            }
+        }
+    }
+
+    /// Writes an event with the given string fields followed by an HRESULT field named `Code`.
+    pub fn write(level: u8, event: &str, fields: &[(&str, &str)], code: ErrorCode) {
+        let handle = match handle() {
+            Some(handle) => handle,
+            None => return,
+        };
+
+        let provider = metadata(&[NAME.as_bytes(), &[0]]);
+
+        let mut event_metadata = vec![0u8];
+        event_metadata.extend_from_slice(event.as_bytes());
+        event_metadata.push(0);
+
+        for (name, _) in fields {
+            event_metadata.extend_from_slice(name.as_bytes());
+            event_metadata.extend_from_slice(&[0, IN_UNICODE_STRING]);
+        }
+
+        event_metadata.extend_from_slice(b"Code\0");
+        event_metadata.extend_from_slice(&[IN_INT32 | CHAIN, OUT_HRESULT]);
+        let event_metadata = metadata(&[&event_metadata]);
+
+        let values: Vec<Vec<u16>> = fields
+            .iter()
+            .map(|(_, value)| value.encode_utf16().chain(std::iter::once(0)).collect())
+            .collect();
+
+        let mut data = vec![
+            EventDataDescriptor::new(&provider, PROVIDER_METADATA),
+            EventDataDescriptor::new(&event_metadata, EVENT_METADATA),
+        ];
+
+        data.extend(
+            values
+                .iter()
+                .map(|value| EventDataDescriptor::new(value, 0)),
+        );
+        data.push(EventDataDescriptor::new(&[code.0], 0));
+
+        let descriptor = EventDescriptor {
+            id: 0,
+            version: 0,
+            channel: CHANNEL,
+            level,
+            opcode: 0,
+            task: 0,
+            keyword: 0,
+        };
+
+        unsafe {
+            runtime::EventWriteTransfer(
+                handle,
+                &descriptor as *const _ as RawPtr,
+                std::ptr::null(),
+                std::ptr::null(),
+                data.len() as u32,
+                data.as_ptr() as RawPtr,
+            );
+        }
+    }
+
+    // Prefixes the metadata with its size, including the size itself.
+    pub(super) fn metadata(parts: &[&[u8]]) -> Vec<u8> {
+        let len: usize = parts.iter().map(|part| part.len()).sum();
+        let mut metadata = ((len + 2) as u16).to_le_bytes().to_vec();
+        parts
+            .iter()
+            .for_each(|part| metadata.extend_from_slice(part));
+        metadata
+    }
+
+    // Registers the provider the first time it is needed. Errors originating while it is being
+    // registered, such as from loading advapi32, aren't logged so as not to recurse.
+    fn handle() -> Option<u64> {
+        match STATE.compare_exchange(
+            UNREGISTERED,
+            REGISTERING,
+            Ordering::Acquire,
+            Ordering::Acquire,
+        ) {
+            Ok(_) => {}
+            Err(REGISTERED) => return Some(HANDLE.load(Ordering::Relaxed)),
+            Err(_) => return None,
+        }
+
+        let mut handle = 0;
+        let code = unsafe {
+            runtime::EventRegister(
+                &provider_guid(NAME),
+                std::ptr::null_mut(),
+                std::ptr::null_mut(),
+                &mut handle,
+            )
+        };
+
+        if code.0 == 0 {
+            HANDLE.store(handle, Ordering::Relaxed);
+            STATE.store(REGISTERED, Ordering::Release);
+            Some(handle)
+        } else {
+            STATE.store(FAILED, Ordering::Release);
+            None
+        }
+    }
+
+    // The GUID of a named provider is the name-based GUID of the upper case name, encoded as
+    // big endian UTF-16, in the EventSource namespace with the bytes of its fields in little
+    // endian order.
+    pub(super) fn provider_guid(name: &str) -> Guid {
+        const NAMESPACE: [u8; 16] = [
+            0x48, 0x2C, 0x2D, 0xB2, 0xC3, 0x90, 0x47, 0xC8, 0x87, 0xF8, 0x1A, 0x15, 0xBF, 0xC1,
+            0x30, 0xFB,
+        ];
+
+        let name: Vec<u8> = name
+            .to_uppercase()
+            .encode_utf16()
+            .flat_map(|c| c.to_be_bytes())
+            .collect();
+
+        let bytes = winrt_sha1::sha1(&NAMESPACE, &name);
+
+        Guid::from_values(
+            u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]),
+            u16::from_le_bytes([bytes[4], bytes[5]]),
+            u16::from_le_bytes([bytes[6], (bytes[7] & 0x0F) | 0x50]),
+            [
+                bytes[8], bytes[9], bytes[10], bytes[11], bytes[12], bytes[13], bytes[14],
+                bytes[15],
+            ],
+        )
+    }
+}
+
+#[cfg(all(test, feature = "tracelogging"))]
+mod tests {
+    use super::provider::*;
+
+    #[test]
+    fn metadata_size() {
+        assert_eq!(metadata(&[b"ab", b"c\0"]), [6, 0, b'a', b'b', b'c', 0]);
+    }
+
+    #[test]
+    fn provider_guids() {
+        // The example from the documentation of TraceLoggingProvider.h.
+        assert_eq!(
+            provider_guid("MyCompany.MyComponent"),
+            crate::Guid::from("CE5FA4EA-AB00-5402-8B76-9F76AC858FB5")
+        );
+
+        // The name is case insensitive.
+        assert_eq!(
+            provider_guid("Rust.WinRT"),
+            crate::Guid::from("C9B29FDE-11B8-5ADA-1F3A-CD16ADBE52C6")
+        );
+        assert_eq!(provider_guid("RUST.WINRT"), provider_guid("Rust.WinRT"));
+    }
+}
@@ -1,7 +1,10 @@
+use crate::com_interface::is_same_interface;
 use crate::unknown::abi_IUnknown;
-use crate::{ComInterface, RawPtr, Result};
+use crate::*;
 
 /// An equivalent to `std::convert::TryInto` for converting between interfaces
+///
+/// If the object doesn't implement the interface the error's message names the interface.
 pub trait TryInto<T: ComInterface> {
     fn try_into(self) -> Result<T>;
 }
@@ -16,12 +19,28 @@ impl<From: ComInterface + Sized, Into: ComInterface> TryInto<Into> for &From {
                 return Ok(std::mem::transmute_copy(&into));
             }
 
-            ((*(*(from as *const *const abi_IUnknown))).unknown_query_interface)(
+            // Casting to an interface that the source already is only needs an AddRef.
+            if !Into::GENERIC && is_same_interface::<From>(&Into::IID) {
+                ((*(*(from as *const *const abi_IUnknown))).unknown_add_ref)(
+                    from as *const *const abi_IUnknown,
+                );
+
+                return Ok(std::mem::transmute_copy(&from));
+            }
+
+            let code = ((*(*(from as *const *const abi_IUnknown))).unknown_query_interface)(
                 from as *const *const abi_IUnknown,
                 &Into::IID,
                 &mut into,
-            )
-            .ok()?;
+            );
+
+            if !code.is_ok() {
+                crate::trace::query_failed(&Into::IID, code);
+                return Err(Error::from(code).with_message(format!(
+                    "the object does not implement `{}`",
+                    std::any::type_name::<Into>()
+                )));
+            }
 
             debug_assert!(!into.is_null());
 
@@ -29,3 +48,25 @@ impl<From: ComInterface + Sized, Into: ComInterface> TryInto<Into> for &From {
         }
     }
 }
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use crate::authoring::tests::{widget, LOCK};
+
+    #[test]
+    fn names_interface() {
+        let _lock = LOCK.lock().unwrap();
+        let widget = widget();
+
+        let error = match TryInto::<IActivationFactory>::try_into(&widget) {
+            Err(error) => error,
+            Ok(_) => panic!("the widget isn't an activation factory"),
+        };
+        assert_eq!(error.code(), ErrorCode::NO_INTERFACE);
+        assert_eq!(
+            error.message(),
+            Some("the object does not implement `winrt::activation::IActivationFactory`")
+        );
+    }
+}
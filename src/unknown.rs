@@ -13,6 +13,14 @@ impl IUnknown {
     }
 }
 
+impl PartialEq for IUnknown {
+    fn eq(&self, other: &Self) -> bool {
+        is_same_object(self, other)
+    }
+}
+
+impl Eq for IUnknown {}
+
 unsafe impl ComInterface for IUnknown {
     type VTable = abi_IUnknown;
     const IID: Guid = Guid::from_values(
@@ -30,3 +38,13 @@ pub struct abi_IUnknown {
     pub(crate) unknown_add_ref: extern "system" fn(RawComPtr<IUnknown>) -> u32,
     pub(crate) unknown_release: extern "system" fn(RawComPtr<IUnknown>) -> u32,
 }
+
+/// The IID of IAgileObject, which the runtime's own objects implement as they may be called
+/// from any thread.
+#[cfg(feature = "std")]
+pub(crate) const IAGILE_OBJECT: Guid = Guid::from_values(
+    0x94EA_2B94,
+    0xE9CC,
+    0x49E0,
+    [0xC0, 0xFF, 0xEE, 0x64, 0xCA, 0x8F, 0x5B, 0x90],
+);
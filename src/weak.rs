@@ -0,0 +1,116 @@
+use alloc::{rc, sync};
+
+/// A strong reference, such as `Rc` or `Arc`, that can be downgraded to a weak reference
+///
+/// This is what allows [`weak_handler!`](macro.weak_handler.html) to capture its subscriber
+/// weakly.
+pub trait Downgrade {
+    type Weak: Upgrade<Strong = Self>;
+
+    /// Creates a weak reference to the same value.
+    fn downgrade(&self) -> Self::Weak;
+}
+
+/// A weak reference, such as `rc::Weak` or `sync::Weak`, that can be upgraded to a strong
+/// reference while the value is alive
+pub trait Upgrade {
+    type Strong;
+
+    /// Returns a strong reference, or `None` if the value has been dropped.
+    fn upgrade(&self) -> Option<Self::Strong>;
+}
+
+impl<T: ?Sized> Downgrade for rc::Rc<T> {
+    type Weak = rc::Weak<T>;
+
+    fn downgrade(&self) -> Self::Weak {
+        rc::Rc::downgrade(self)
+    }
+}
+
+impl<T: ?Sized> Upgrade for rc::Weak<T> {
+    type Strong = rc::Rc<T>;
+
+    fn upgrade(&self) -> Option<Self::Strong> {
+        self.upgrade()
+    }
+}
+
+impl<T: ?Sized> Downgrade for sync::Arc<T> {
+    type Weak = sync::Weak<T>;
+
+    fn downgrade(&self) -> Self::Weak {
+        sync::Arc::downgrade(self)
+    }
+}
+
+impl<T: ?Sized> Upgrade for sync::Weak<T> {
+    type Strong = sync::Arc<T>;
+
+    fn upgrade(&self) -> Option<Self::Strong> {
+        self.upgrade()
+    }
+}
+
+/// Creates an event handler closure that holds only a weak reference to its subscriber
+///
+/// An event source holds a strong reference to each of its handlers, so a handler that captures
+/// the object subscribing to the event keeps it alive for as long as the source, and forms a
+/// reference cycle if the subscriber also holds the source. The handler instead captures a weak
+/// reference to the `Rc` or `Arc` given as the first argument and upgrades it each time it is
+/// invoked. Once the subscriber has been dropped the handler does nothing and returns
+/// `Ok(Default::default())`.
+///
+/// ```ignore
+/// let page = Rc::new(Page::new()?);
+/// button.click(winrt::weak_handler!(page, |page, sender, args| page.on_click(sender, args)))?;
+/// ```
+#[macro_export]
+macro_rules! weak_handler {
+    ($this:expr, |$strong:pat $(, $param:pat)*| $body:expr) => {{
+        let weak = $crate::Downgrade::downgrade(&$this);
+        move |$($param),*| match $crate::Upgrade::upgrade(&weak) {
+            ::core::option::Option::Some($strong) => $body,
+            ::core::option::Option::None => ::core::result::Result::Ok(::core::default::Default::default()),
+        }
+    }};
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+    use std::cell::Cell;
+    use std::rc::Rc;
+    use std::sync::Arc;
+
+    // Stands in for a delegate constructor, which is what infers the parameter types.
+    fn handler<F: Fn(&u32, &u32) -> Result<()>>(handler: F) -> F {
+        handler
+    }
+
+    #[test]
+    fn rc() {
+        let count = Rc::new(Cell::new(0));
+        let handler = handler(weak_handler!(count, |count, _, args| {
+            count.set(count.get() + *args);
+            Ok(())
+        }));
+
+        handler(&0, &2).unwrap();
+        assert_eq!(count.get(), 2);
+        assert_eq!(Rc::strong_count(&count), 1);
+
+        drop(count);
+        handler(&0, &2).unwrap();
+    }
+
+    #[test]
+    fn arc() {
+        let value = Arc::new(7);
+        let handler = weak_handler!(value, |value| Ok::<_, Error>(*value));
+
+        assert_eq!(handler().unwrap(), 7);
+        drop(value);
+        assert_eq!(handler().unwrap(), 0);
+    }
+}
@@ -0,0 +1,215 @@
+//! Hosting XAML content in Win32 windows via [XAML Islands](https://docs.microsoft.com/en-us/windows/apps/desktop/modernize/xaml-islands)
+//!
+//! A typical Win32 app initializes XAML once per UI thread, creates a `DesktopWindowXamlSource`,
+//! attaches it to its window, and then positions the returned island window as a child:
+//!
+//! ```ignore
+//! let _manager: WindowsXamlManager = xaml_islands::initialize_for_current_thread()?;
+//! let source: DesktopWindowXamlSource = xaml_islands::create_source()?;
+//! let island = xaml_islands::attach_to_window(&source, WindowHandle(hwnd))?;
+//! source.set_content(content)?;
+//! ```
+//!
+//! The functions are generic so that they return whichever projected types the app has imported
+//! from `Windows.UI.Xaml.Hosting`.
+
+use crate::activation::factory;
+use crate::interop::WindowHandle;
+use crate::*;
+
+/// Initializes the XAML framework for the calling thread, returning a `WindowsXamlManager`.
+///
+/// XAML remains initialized until the returned manager is closed or released, so it should be kept
+/// alive for as long as the thread hosts XAML content.
+pub fn initialize_for_current_thread<T: ComInterface>() -> Result<T> {
+    let statics = factory::<WindowsXamlManager, IWindowsXamlManagerStatics>()?;
+    let this = statics.ptr.as_raw();
+    let mut manager = Object::default();
+
+    unsafe {
+        ((*(*(this))).initialize_for_current_thread)(this, manager.set_abi()).ok()?;
+    }
+
+    TryInto::<T>::try_into(&manager)
+}
+
+/// Creates a `DesktopWindowXamlSource` for hosting XAML content.
+pub fn create_source<T: ComInterface>() -> Result<T> {
+    let factory = factory::<DesktopWindowXamlSource, IDesktopWindowXamlSourceFactory>()?;
+    let this = factory.ptr.as_raw();
+    let mut inner = Object::default();
+    let mut source = Object::default();
+
+    unsafe {
+        ((*(*(this))).create_instance)(this, std::ptr::null(), inner.set_abi(), source.set_abi())
+            .ok()?;
+    }
+
+    TryInto::<T>::try_into(&source)
+}
+
+/// Attaches the XAML source to a parent window and returns the island's child window.
+///
+/// The island window is initially sized to zero and should be positioned by the app, typically
+/// whenever the parent window is resized.
+pub fn attach_to_window<T: ComInterface>(source: &T, parent: WindowHandle) -> Result<WindowHandle> {
+    let native: IDesktopWindowXamlSourceNative = source.try_into()?;
+    native.attach_to_window(parent)?;
+    native.window_handle()
+}
+
+struct WindowsXamlManager;
+
+impl RuntimeName for WindowsXamlManager {
+    const NAME: &'static str = "Windows.UI.Xaml.Hosting.WindowsXamlManager";
+}
+
+struct DesktopWindowXamlSource;
+
+impl RuntimeName for DesktopWindowXamlSource {
+    const NAME: &'static str = "Windows.UI.Xaml.Hosting.DesktopWindowXamlSource";
+}
+
+#[repr(transparent)]
+#[derive(Default, Clone)]
+struct IWindowsXamlManagerStatics {
+    ptr: ComPtr<IWindowsXamlManagerStatics>,
+}
+
+unsafe impl ComInterface for IWindowsXamlManagerStatics {
+    type VTable = abi_IWindowsXamlManagerStatics;
+    const IID: Guid = Guid::from_values(
+        0x2825_8A12,
+        0x7D82,
+        0x505B,
+        [0xB2, 0x10, 0x71, 0x2B, 0x04, 0xA5, 0x88, 0x82],
+    );
+}
+
+#[repr(C)]
+#[allow(non_camel_case_types)]
+struct abi_IWindowsXamlManagerStatics {
+    __base: [usize; 6],
+    initialize_for_current_thread: extern "system" fn(
+        RawComPtr<IWindowsXamlManagerStatics>,
+        *mut RawComPtr<Object>,
+    ) -> ErrorCode,
+}
+
+#[repr(transparent)]
+#[derive(Default, Clone)]
+struct IDesktopWindowXamlSourceFactory {
+    ptr: ComPtr<IDesktopWindowXamlSourceFactory>,
+}
+
+unsafe impl ComInterface for IDesktopWindowXamlSourceFactory {
+    type VTable = abi_IDesktopWindowXamlSourceFactory;
+    const IID: Guid = Guid::from_values(
+        0x5CD6_1DC0,
+        0x2561,
+        0x56E1,
+        [0x8E, 0x75, 0x6E, 0x44, 0x17, 0x38, 0x05, 0xE3],
+    );
+}
+
+#[repr(C)]
+#[allow(non_camel_case_types)]
+struct abi_IDesktopWindowXamlSourceFactory {
+    __base: [usize; 6],
+    create_instance: extern "system" fn(
+        RawComPtr<IDesktopWindowXamlSourceFactory>,
+        RawComPtr<Object>,
+        *mut RawComPtr<Object>,
+        *mut RawComPtr<Object>,
+    ) -> ErrorCode,
+}
+
+/// The [IDesktopWindowXamlSourceNative interface](https://docs.microsoft.com/en-us/windows/win32/api/windows.ui.xaml.hosting.desktopwindowxamlsource/nn-windows-ui-xaml-hosting-desktopwindowxamlsource-idesktopwindowxamlsourcenative)
+/// implemented by `DesktopWindowXamlSource`
+#[repr(transparent)]
+#[derive(Default, Clone)]
+pub struct IDesktopWindowXamlSourceNative {
+    ptr: ComPtr<IDesktopWindowXamlSourceNative>,
+}
+
+impl IDesktopWindowXamlSourceNative {
+    pub fn attach_to_window(&self, parent: WindowHandle) -> Result<()> {
+        let this = self.ptr.as_raw();
+        if this.is_null() {
+            panic!("The `this` pointer was null when calling method");
+        }
+        unsafe { ((*(*(this))).attach_to_window)(this, parent.0).ok() }
+    }
+
+    pub fn window_handle(&self) -> Result<WindowHandle> {
+        let this = self.ptr.as_raw();
+        if this.is_null() {
+            panic!("The `this` pointer was null when calling method");
+        }
+        let mut hwnd = std::ptr::null_mut();
+        unsafe { ((*(*(this))).window_handle)(this, &mut hwnd).and_then(|| WindowHandle(hwnd)) }
+    }
+}
+
+unsafe impl ComInterface for IDesktopWindowXamlSourceNative {
+    type VTable = abi_IDesktopWindowXamlSourceNative;
+    const IID: Guid = Guid::from_values(
+        0x3CBC_F1BF,
+        0x2F76,
+        0x4E9C,
+        [0x96, 0xAB, 0xE8, 0x4B, 0x37, 0x97, 0x25, 0x54],
+    );
+}
+
+#[repr(C)]
+pub struct abi_IDesktopWindowXamlSourceNative {
+    __base: [usize; 3],
+    attach_to_window:
+        extern "system" fn(RawComPtr<IDesktopWindowXamlSourceNative>, RawPtr) -> ErrorCode,
+    window_handle:
+        extern "system" fn(RawComPtr<IDesktopWindowXamlSourceNative>, *mut RawPtr) -> ErrorCode,
+}
+
+/// The [IDesktopWindowXamlSourceNative2 interface](https://docs.microsoft.com/en-us/windows/win32/api/windows.ui.xaml.hosting.desktopwindowxamlsource/nn-windows-ui-xaml-hosting-desktopwindowxamlsource-idesktopwindowxamlsourcenative2)
+/// used to give XAML content the first chance at handling keyboard input
+#[repr(transparent)]
+#[derive(Default, Clone)]
+pub struct IDesktopWindowXamlSourceNative2 {
+    ptr: ComPtr<IDesktopWindowXamlSourceNative2>,
+}
+
+impl IDesktopWindowXamlSourceNative2 {
+    /// Returns whether the XAML content handled the message, in which case it should not be
+    /// translated or dispatched by the app's message loop.
+    ///
+    /// # Safety
+    /// The `message` must point to a valid `MSG` structure.
+    pub unsafe fn pre_translate_message(&self, message: RawPtr) -> Result<bool> {
+        let this = self.ptr.as_raw();
+        if this.is_null() {
+            panic!("The `this` pointer was null when calling method");
+        }
+        let mut handled = 0;
+        ((*(*(this))).pre_translate_message)(this, message, &mut handled).and_then(|| handled != 0)
+    }
+}
+
+unsafe impl ComInterface for IDesktopWindowXamlSourceNative2 {
+    type VTable = abi_IDesktopWindowXamlSourceNative2;
+    const IID: Guid = Guid::from_values(
+        0xE3DC_D8C7,
+        0x3057,
+        0x4692,
+        [0x99, 0xC3, 0x7B, 0x77, 0x20, 0xAF, 0xDA, 0x31],
+    );
+}
+
+#[repr(C)]
+pub struct abi_IDesktopWindowXamlSourceNative2 {
+    __base: [usize; 5],
+    pre_translate_message: extern "system" fn(
+        RawComPtr<IDesktopWindowXamlSourceNative2>,
+        RawPtr,
+        *mut i32,
+    ) -> ErrorCode,
+}
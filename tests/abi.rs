@@ -0,0 +1,72 @@
+winrt::import!(
+    dependencies
+        "os"
+    modules
+        "windows.foundation"
+);
+
+use std::mem::{align_of, size_of};
+use windows::foundation::{DateTime, Point, Rect, Uri};
+use winrt::{Char16, EventRegistrationToken, Guid, HString, Object, RuntimeType, StaticHeader};
+
+#[test]
+fn string() {
+    let value = HString::from("hello");
+    let abi = value.into_abi();
+    assert!(!abi.is_null());
+
+    let value = unsafe { HString::from_abi(abi) };
+    assert_eq!(value, "hello");
+
+    let empty = HString::new().into_abi();
+    assert!(empty.is_null());
+    assert!(unsafe { HString::from_abi(empty) }.is_empty());
+}
+
+#[test]
+fn class() -> winrt::Result<()> {
+    let uri = Uri::create_uri("http://kennykerr.ca")?;
+    let abi = uri.into_abi();
+
+    let uri = unsafe { Uri::from_abi(abi) };
+    assert_eq!(uri.domain()?, "kennykerr.ca");
+
+    Ok(())
+}
+
+#[test]
+fn value() {
+    let point = Point { x: 1.0, y: 2.0 };
+    let abi = point.clone().into_abi();
+    assert_eq!(unsafe { Point::from_abi(abi) }, point);
+
+    assert_eq!(unsafe { i32::from_abi(123i32.into_abi()) }, 123);
+}
+
+// These layouts are fixed by the Windows ABI, and the pointer-sized ones differ between x86 and
+// 64-bit targets.
+#[test]
+fn layout() {
+    let pointer = size_of::<usize>();
+    assert_eq!(size_of::<HString>(), pointer);
+    assert_eq!(size_of::<Object>(), pointer);
+    assert_eq!(size_of::<Uri>(), pointer);
+
+    // HSTRING_HEADER is 20 bytes on x86 and 24 bytes on 64-bit targets.
+    assert_eq!(size_of::<StaticHeader>(), 16 + pointer);
+
+    assert_eq!((size_of::<Guid>(), align_of::<Guid>()), (16, 4));
+    assert_eq!((size_of::<Char16>(), align_of::<Char16>()), (2, 2));
+    assert_eq!((size_of::<Point>(), align_of::<Point>()), (8, 4));
+    assert_eq!((size_of::<Rect>(), align_of::<Rect>()), (16, 4));
+
+    // 64-bit integers are 8-byte aligned even on x86, as they are with MSVC.
+    assert_eq!((size_of::<DateTime>(), align_of::<DateTime>()), (8, 8));
+    assert_eq!(
+        (
+            size_of::<EventRegistrationToken>(),
+            align_of::<EventRegistrationToken>()
+        ),
+        (8, 8)
+    );
+}
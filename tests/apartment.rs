@@ -0,0 +1,68 @@
+winrt::import!(
+    dependencies
+        "os"
+    modules
+        "windows.ui.core"
+);
+
+use windows::ui::core::CoreWindow;
+use winrt::{ErrorCode, Guid, Object, RawPtr, RuntimeType, TryInto};
+
+// A window that behaves as one created by another thread does: every property fails with
+// RPC_E_WRONG_THREAD. The object is static, so QueryInterface returns it for any interface and
+// its reference count is never used. The getters that follow IInspectable's methods all take a
+// single out parameter.
+#[repr(C)]
+struct Window {
+    vtable: &'static VTable,
+}
+
+#[repr(C)]
+struct VTable {
+    query_interface: extern "system" fn(RawPtr, &Guid, *mut RawPtr) -> ErrorCode,
+    add_ref: extern "system" fn(RawPtr) -> u32,
+    release: extern "system" fn(RawPtr) -> u32,
+    methods: [extern "system" fn(RawPtr, RawPtr) -> ErrorCode; 15],
+}
+
+static WINDOW: Window = Window { vtable: &VTABLE };
+
+static VTABLE: VTable = VTable {
+    query_interface,
+    add_ref,
+    release,
+    methods: [wrong_thread; 15],
+};
+
+extern "system" fn query_interface(this: RawPtr, _iid: &Guid, interface: *mut RawPtr) -> ErrorCode {
+    unsafe { *interface = this };
+    ErrorCode(0)
+}
+
+extern "system" fn add_ref(_this: RawPtr) -> u32 {
+    1
+}
+
+extern "system" fn release(_this: RawPtr) -> u32 {
+    1
+}
+
+extern "system" fn wrong_thread(_this: RawPtr, _value: RawPtr) -> ErrorCode {
+    ErrorCode::WRONG_THREAD
+}
+
+#[test]
+fn wrong_thread_message() -> winrt::Result<()> {
+    let mut object = Object::default();
+    unsafe { *object.set_abi() = &WINDOW as *const Window as _ };
+    let window: CoreWindow = object.try_into()?;
+
+    let error = window.visible().unwrap_err();
+    assert_eq!(error.code(), ErrorCode::WRONG_THREAD);
+    assert!(error
+        .message()
+        .unwrap()
+        .starts_with("`Windows.UI.Core.CoreWindow::visible` was called from"));
+
+    Ok(())
+}
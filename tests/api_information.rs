@@ -0,0 +1,65 @@
+winrt::import!(
+    dependencies
+        "os"
+    modules
+        "windows.foundation"
+);
+
+use windows::foundation::{AsyncStatus, Uri};
+use winrt::api_information::*;
+
+#[test]
+fn present() -> winrt::Result<()> {
+    assert!(is_type_present::<Uri>()?);
+    assert!(is_method_present::<Uri>("CombineUri")?);
+    assert!(is_method_present_with_arity::<Uri>("CombineUri", 1)?);
+    assert!(is_property_present::<Uri>("Domain")?);
+    assert!(is_read_only_property_present::<Uri>("Domain")?);
+    assert!(!is_writeable_property_present::<Uri>("Domain")?);
+    assert!(is_enum_named_value_present::<AsyncStatus>("Completed")?);
+    assert!(is_api_contract_present(
+        "Windows.Foundation.UniversalApiContract",
+        1,
+        0
+    )?);
+
+    Ok(())
+}
+
+#[test]
+fn missing() -> winrt::Result<()> {
+    assert!(!is_method_present::<Uri>("Missing")?);
+    assert!(!is_method_present_with_arity::<Uri>("CombineUri", 3)?);
+    assert!(!is_event_present::<Uri>("Missing")?);
+    assert!(!is_api_contract_present(
+        "Windows.Foundation.UniversalApiContract",
+        u16::MAX,
+        0
+    )?);
+
+    Ok(())
+}
+
+#[test]
+fn require_contract() {
+    use std::sync::atomic::AtomicU8;
+
+    let present = AtomicU8::new(0);
+    assert!(winrt::api_information::require_contract(
+        &present,
+        "Windows.Foundation.UniversalApiContract",
+        1,
+        0
+    )
+    .is_ok());
+
+    let missing = AtomicU8::new(0);
+    let error = winrt::api_information::require_contract(
+        &missing,
+        "Windows.Foundation.UniversalApiContract",
+        u16::MAX,
+        0,
+    )
+    .unwrap_err();
+    assert_eq!(error.kind(), winrt::ErrorKind::NotSupportedOnThisVersion);
+}
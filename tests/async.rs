@@ -0,0 +1,147 @@
+winrt::import!(
+    dependencies
+        "os"
+    modules
+        "windows.storage.streams"
+);
+
+use windows::storage::streams::{DataReader, DataWriter, InMemoryRandomAccessStream};
+
+// Writes to and then reads back from an in-memory stream. DataWriter::store_async and
+// DataReader::load_async return classes implementing IAsyncOperation<u32> so this exercises
+// the forwarding Future implementation as well as the interface's own implementation.
+async fn round_trip() -> winrt::Result<()> {
+    let stream = InMemoryRandomAccessStream::new()?;
+
+    let writer = DataWriter::create_data_writer(stream.get_output_stream_at(0)?)?;
+    writer.write_bytes(&[1, 2, 3])?;
+    assert!(writer.store_async()?.await? == 3);
+
+    let reader = DataReader::create_data_reader(stream.get_input_stream_at(0)?)?;
+    assert!(reader.load_async(3)?.await? == 3);
+    assert!(reader.read_byte()? == 1);
+    assert!(reader.read_byte()? == 2);
+    assert!(reader.read_byte()? == 3);
+
+    // The async interfaces themselves are also futures.
+    let operation: windows::foundation::IAsyncOperation<u32> = writer.store_async()?.into();
+    assert!(operation.await? == 0);
+
+    Ok(())
+}
+
+#[test]
+fn block_on() -> winrt::Result<()> {
+    winrt::block_on(round_trip())
+}
+
+#[test]
+fn single_threaded() -> winrt::Result<()> {
+    futures::executor::LocalPool::new().run_until(round_trip())
+}
+
+#[tokio::test]
+async fn tokio_current_thread() -> winrt::Result<()> {
+    round_trip().await
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn tokio_multi_thread() -> winrt::Result<()> {
+    round_trip().await
+}
+
+#[async_std::test]
+async fn async_std() -> winrt::Result<()> {
+    round_trip().await
+}
+
+// A future that never completes but records whether it was canceled.
+struct Never(std::rc::Rc<std::cell::Cell<bool>>);
+
+impl std::future::Future for Never {
+    type Output = winrt::Result<u32>;
+
+    fn poll(
+        self: std::pin::Pin<&mut Self>,
+        _: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Self::Output> {
+        std::task::Poll::Pending
+    }
+}
+
+impl winrt::Cancelable for Never {
+    fn cancel(&self) -> winrt::Result<()> {
+        self.0.set(true);
+        Ok(())
+    }
+}
+
+#[test]
+fn timeout() -> winrt::Result<()> {
+    use winrt::Cancelable;
+
+    let canceled = std::rc::Rc::new(std::cell::Cell::new(false));
+    let never = Never(canceled.clone());
+    let result = winrt::block_on(never.with_timeout(std::time::Duration::from_millis(10)));
+
+    assert!(result.unwrap_err().code() == winrt::ErrorCode(0x8007_05B4u32 as i32));
+    assert!(canceled.get());
+
+    let stream = InMemoryRandomAccessStream::new()?;
+    let writer = DataWriter::create_data_writer(stream.get_output_stream_at(0)?)?;
+    writer.write_bytes(&[1, 2, 3])?;
+    let operation = writer.store_async()?;
+    assert!(winrt::block_on(operation.with_timeout(std::time::Duration::from_secs(10)))? == 3);
+
+    Ok(())
+}
+
+#[test]
+fn race() -> winrt::Result<()> {
+    use winrt::Cancelable;
+
+    let stream = InMemoryRandomAccessStream::new()?;
+    let writer = DataWriter::create_data_writer(stream.get_output_stream_at(0)?)?;
+    writer.write_bytes(&[1, 2, 3])?;
+
+    let canceled = std::rc::Rc::new(std::cell::Cell::new(false));
+    let never = Never(canceled.clone());
+    let operation: windows::foundation::IAsyncOperation<u32> = writer.store_async()?.into();
+
+    assert!(winrt::block_on(operation.race(never))? == 3);
+    assert!(canceled.get());
+
+    Ok(())
+}
+
+#[test]
+fn dropped() {
+    use winrt::Cancelable;
+
+    let canceled = std::rc::Rc::new(std::cell::Cell::new(false));
+    drop(Never(canceled.clone()).with_timeout(std::time::Duration::from_secs(10)));
+    assert!(canceled.get());
+
+    let first = std::rc::Rc::new(std::cell::Cell::new(false));
+    let second = std::rc::Rc::new(std::cell::Cell::new(false));
+    drop(Never(first.clone()).race(Never(second.clone())));
+    assert!(first.get() && second.get());
+}
+
+#[test]
+fn async_info() -> winrt::Result<()> {
+    use winrt::{AsyncInfo, AsyncState};
+
+    let stream = InMemoryRandomAccessStream::new()?;
+    let writer = DataWriter::create_data_writer(stream.get_output_stream_at(0)?)?;
+    writer.write_bytes(&[1, 2, 3])?;
+
+    let operation = writer.store_async()?;
+    let id = AsyncInfo::id(&operation)?;
+    assert!(winrt::block_on(operation.clone())? == 3);
+
+    assert!(AsyncInfo::status(&operation)? == AsyncState::Completed);
+    assert!(AsyncInfo::error_code(&operation)?.is_ok());
+    assert!(AsyncInfo::id(&operation)? == id);
+    Ok(())
+}
@@ -0,0 +1,66 @@
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Waker};
+
+#[test]
+fn ready() {
+    assert!(winrt::block_on(async { 123 }) == 123);
+}
+
+// A future that completes on another thread, much like a WinRT async operation would.
+struct Delayed {
+    state: Arc<Mutex<(bool, Option<Waker>)>>,
+}
+
+impl Future for Delayed {
+    type Output = &'static str;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut state = self.state.lock().unwrap();
+
+        if state.0 {
+            Poll::Ready("done")
+        } else {
+            state.1 = Some(cx.waker().clone());
+            Poll::Pending
+        }
+    }
+}
+
+#[test]
+fn completed_on_another_thread() {
+    let state = Arc::new(Mutex::new((false, None::<Waker>)));
+    let remote = state.clone();
+
+    let thread = std::thread::spawn(move || {
+        std::thread::sleep(std::time::Duration::from_millis(10));
+        let mut state = remote.lock().unwrap();
+        state.0 = true;
+
+        if let Some(waker) = state.1.take() {
+            waker.wake();
+        }
+    });
+
+    assert!(winrt::block_on(Delayed { state }) == "done");
+    thread.join().unwrap();
+}
+
+#[link(name = "ole32")]
+extern "system" {
+    fn CoInitializeEx(reserved: winrt::RawPtr, flags: u32) -> winrt::ErrorCode;
+    fn CoUninitialize();
+}
+
+#[test]
+fn sta() {
+    std::thread::spawn(|| unsafe {
+        // COINIT_APARTMENTTHREADED
+        CoInitializeEx(std::ptr::null_mut(), 2).unwrap();
+        completed_on_another_thread();
+        CoUninitialize();
+    })
+    .join()
+    .unwrap();
+}
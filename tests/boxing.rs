@@ -24,3 +24,56 @@ fn boxing() -> winrt::Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn typed_getters() -> winrt::Result<()> {
+    use winrt::Cast;
+
+    let object = PropertyValue::create_int32(42)?;
+    let pv: IPropertyValue = object.cast()?;
+    assert!(pv.as_i32()? == 42);
+
+    let error = pv.as_string().unwrap_err();
+    assert!(error.expected_name() == "String");
+    assert!(error.actual_name() == Some("Int32"));
+    assert!(
+        error.to_string()
+            == "expected a property value of type `String` but found `Int32` (HRESULT 0x80028CA0)"
+    );
+
+    let object = PropertyValue::create_double_array(&[1.5, 2.5])?;
+    let pv: IPropertyValue = object.cast()?;
+    assert!(pv.as_f64_array()?.as_slice() == [1.5, 2.5]);
+    assert!(pv.as_f64().unwrap_err().actual_name() == Some("DoubleArray"));
+
+    Ok(())
+}
+
+#[test]
+fn object_getters() -> winrt::Result<()> {
+    use windows::foundation::TypedPropertyValue;
+
+    let object = PropertyValue::create_string("hello")?;
+    assert!(object.as_string()? == "hello");
+
+    let error = object.as_u32_array().unwrap_err();
+    assert!(error.actual_name() == Some("String"));
+
+    // The names of the types are kept when the error is converted.
+    let error: winrt::Error = error.into();
+    assert!(
+        error.message()
+            == Some("expected a property value of type `UInt32Array` but found `String`")
+    );
+
+    let object = PropertyValue::create_uint32_array(&[1, 2, 3])?;
+    assert!(object.as_u32_array()?.as_slice() == [1, 2, 3]);
+
+    // An object that isn't a property value has no type to name.
+    let object: winrt::Object = windows::foundation::Uri::create_uri("http://kennykerr.ca")?.into();
+    let error = object.as_i32().unwrap_err();
+    assert!(error.code() == winrt::ErrorCode::NO_INTERFACE);
+    assert!(error.actual_name().is_none());
+
+    Ok(())
+}
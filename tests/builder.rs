@@ -0,0 +1,32 @@
+winrt::import!(
+    dependencies
+        "os"
+    modules
+        "windows.globalization"
+);
+
+use windows::globalization::{ApplicationLanguages, Calendar};
+
+#[test]
+fn calendar() -> winrt::Result<()> {
+    let calendar = Calendar::builder().build()?;
+    assert!(!calendar.get_time_zone()?.is_empty());
+
+    let calendar = Calendar::builder()
+        .time_zone_id("UTC")
+        .clock("24HourClock")
+        .calendar("GregorianCalendar")
+        .languages(ApplicationLanguages::languages()?)
+        .build()?;
+
+    assert!(calendar.get_time_zone()? == "UTC");
+    assert!(calendar.get_clock()? == "24HourClock");
+
+    // The calendar can't be chosen without the languages.
+    assert!(Calendar::builder()
+        .calendar("GregorianCalendar")
+        .build()
+        .is_err());
+
+    Ok(())
+}
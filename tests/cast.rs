@@ -0,0 +1,58 @@
+winrt::import!(
+    dependencies
+        "os"
+    modules
+        "windows.foundation"
+);
+
+use std::convert::TryFrom;
+use windows::foundation::{IClosable, IStringable, Uri};
+use winrt::{Cast, Object};
+
+#[test]
+fn try_from_object() -> winrt::Result<()> {
+    let uri = Uri::create_uri("http://kennykerr.ca")?;
+    let object: Object = uri.into();
+
+    let stringable = IStringable::try_from(&object)?;
+    assert!(stringable.to_string()? == "http://kennykerr.ca/");
+
+    let uri = Uri::try_from(object)?;
+    assert!(uri.domain()? == "kennykerr.ca");
+
+    Ok(())
+}
+
+#[test]
+fn cast_error() -> winrt::Result<()> {
+    let uri = Uri::create_uri("http://kennykerr.ca")?;
+
+    let stringable: IStringable = uri.cast()?;
+    assert!(stringable.to_string()? == "http://kennykerr.ca/");
+
+    // Uri does not implement IClosable, and the error says so.
+    let error = match uri.cast::<IClosable>() {
+        Ok(_) => panic!("Uri is not closable"),
+        Err(error) => error,
+    };
+    assert_eq!(error.source_name(), "Windows.Foundation.Uri");
+    assert!(error.target_name().ends_with("IClosable"));
+    assert_eq!(error.code(), winrt::ErrorCode(0x8000_4002_u32 as i32));
+    assert!(error
+        .to_string()
+        .starts_with("cannot cast `Windows.Foundation.Uri` to `"));
+
+    // The names are kept when the error is converted for use with `?`.
+    let error = winrt::Error::from(error);
+    assert_eq!(error.code(), winrt::ErrorCode(0x8000_4002_u32 as i32));
+    assert!(error
+        .message()
+        .unwrap()
+        .starts_with("cannot cast `Windows.Foundation.Uri` to `"));
+
+    let object: Object = uri.into();
+    let error = IClosable::try_from(&object).err().unwrap();
+    assert_eq!(error.source_name(), "Windows.Foundation.Uri");
+
+    Ok(())
+}
@@ -0,0 +1,38 @@
+winrt::import!(
+    dependencies
+        "os"
+    modules
+        "windows.foundation"
+);
+
+use std::convert::TryFrom;
+use windows::foundation::{IPropertyValue, PropertyValue};
+use winrt::{Char16, TryInto};
+
+#[test]
+fn conversions() {
+    let a = Char16::try_from('a').unwrap();
+    assert_eq!(a, Char16(0x61));
+    assert!(a == 'a');
+    assert_eq!(char::from(a), 'a');
+    assert_eq!(a.to_string(), "a");
+    assert_eq!(format!("{:?}", a), "'a'");
+
+    // Characters outside of the Basic Multilingual Plane need two code units.
+    assert!(Char16::try_from('😀').is_err());
+
+    let surrogate = Char16(0xD83D);
+    assert!(surrogate.is_surrogate());
+    assert_eq!(surrogate.to_char(), None);
+    assert_eq!(char::from(surrogate), std::char::REPLACEMENT_CHARACTER);
+    assert_eq!(format!("{:?}", surrogate), "Char16(0xD83D)");
+}
+
+#[test]
+fn property_value() -> winrt::Result<()> {
+    let value = PropertyValue::create_char16(Char16::try_from('é').unwrap())?;
+    let value: IPropertyValue = value.try_into()?;
+    assert!(value.get_char16()? == 'é');
+
+    Ok(())
+}
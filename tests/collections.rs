@@ -6,7 +6,9 @@ winrt::import!(
 );
 
 use std::iter::FromIterator;
-use windows::foundation::collections::{IIterable, IVectorView, PropertySet};
+use windows::foundation::collections::{
+    IIterable, IKeyValuePair, IObservableMap, IVectorView, PropertySet,
+};
 use windows::foundation::{IPropertyValue, IWwwFormUrlDecoderEntry, PropertyValue, Uri};
 use winrt::TryInto;
 
@@ -95,3 +97,49 @@ fn property_set() -> winrt::Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn changed() -> winrt::Result<()> {
+    let set = PropertySet::new()?;
+    set.insert("A", PropertyValue::create_uint32(1)?)?;
+    set.insert("B", PropertyValue::create_uint32(2)?)?;
+
+    let iterable: IIterable<IKeyValuePair<winrt::HString, winrt::Object>> = (&set).into();
+    assert!(iterable.to_vec_snapshot()?.len() == 2);
+
+    // Changing the set invalidates the iterator, which is reported rather than ending the loop.
+    let mut iter = iterable.checked_iter()?;
+    assert!(iter.next().unwrap().is_ok());
+    set.insert("C", PropertyValue::create_uint32(3)?)?;
+
+    let error = iter.next().unwrap().unwrap_err();
+    assert!(error.kind() == winrt::ErrorKind::CollectionChanged);
+    assert!(iter.next().is_none());
+
+    // A new snapshot sees the change.
+    assert!(iterable.to_vec_snapshot()?.len() == 3);
+
+    let uri = Uri::create_uri("http://kennykerr.ca?A=1&B=2&C=3")?;
+    let view: IVectorView<IWwwFormUrlDecoderEntry> = uri.query_parsed()?.into();
+    assert!(view.to_vec_snapshot()?.len() == 3);
+
+    Ok(())
+}
+
+#[test]
+fn inherited() -> winrt::Result<()> {
+    let set = PropertySet::new()?;
+    set.insert("A", PropertyValue::create_uint32(1)?)?;
+
+    // The methods of IMap and IIterable are available on IObservableMap without a cast.
+    let map: IObservableMap<winrt::HString, winrt::Object> = (&set).into();
+    assert!(map.size()? == 1);
+    assert!(map.has_key("A")?);
+    assert!(map.first()?.current()?.key()? == "A");
+
+    let uri = Uri::create_uri("http://kennykerr.ca?A=1&B=2&C=3")?;
+    let view: IVectorView<IWwwFormUrlDecoderEntry> = uri.query_parsed()?.into();
+    assert!(view.first()?.current()?.name()? == "A");
+
+    Ok(())
+}
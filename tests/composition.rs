@@ -6,7 +6,7 @@ winrt::import!(
 );
 
 #[link(name = "coremessaging")]
-extern "stdcall" {
+extern "system" {
     fn CreateDispatcherQueueController(
         options: DispatcherQueueOptions,
         dispatcherQueueController: *mut winrt::RawComPtr<winrt::IUnknown>,
@@ -196,3 +196,31 @@ fn composition() -> winrt::Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn large_struct() -> winrt::Result<()> {
+    use windows::foundation::numerics::Matrix4x4;
+    use windows::ui::composition::Compositor;
+
+    let _dispatcher = create_dispatcher();
+    let compositor = Compositor::new()?;
+    let visual = compositor.create_sprite_visual()?;
+
+    let mut matrix = Matrix4x4::default();
+    matrix.m11 = 1.0;
+    matrix.m22 = 2.0;
+    matrix.m33 = 3.0;
+    matrix.m44 = 1.0;
+    matrix.m41 = 4.0;
+
+    // Large structs may be passed by reference to avoid a copy.
+    visual.set_transform_matrix(&matrix)?;
+    assert!(visual.transform_matrix()? == matrix);
+
+    // Getters returning large structs may write directly into existing storage.
+    let mut value = Matrix4x4::default();
+    visual.transform_matrix_into(&mut value)?;
+    assert!(value == matrix);
+
+    Ok(())
+}
@@ -0,0 +1,85 @@
+winrt::import!(
+    dependencies
+        "os"
+    modules
+        "windows.storage.streams"
+);
+
+use windows::storage::streams::{DataReader, DataWriter};
+
+#[test]
+fn integers() -> winrt::Result<()> {
+    let writer = DataWriter::new()?;
+    writer.write_u16_le(0x0102)?;
+    writer.write_u16_be(0x0102)?;
+    writer.write_i32_le(-2)?;
+    writer.write_u64_be(0x0102_0304_0506_0708)?;
+    writer.write_f32_le(1.5)?;
+
+    let bytes = writer.detach_bytes()?;
+    assert_eq!(bytes.len(), 20);
+    assert_eq!(&bytes[..8], &[2, 1, 1, 2, 0xFE, 0xFF, 0xFF, 0xFF]);
+
+    let reader = DataReader::from_bytes(&bytes)?;
+    assert_eq!(reader.read_u16_le()?, 0x0102);
+    assert_eq!(reader.read_u16_be()?, 0x0102);
+    assert_eq!(reader.read_i32_le()?, -2);
+    assert_eq!(reader.read_u64_be()?, 0x0102_0304_0506_0708);
+    assert_eq!(reader.read_f32_le()?, 1.5);
+    assert_eq!(reader.unconsumed_buffer_length()?, 0);
+
+    Ok(())
+}
+
+#[test]
+fn bytes() -> winrt::Result<()> {
+    let writer = DataWriter::new()?;
+    assert_eq!(writer.write_utf8("héllo")?, 6);
+    writer.write_bytes(&[1, 2, 3])?;
+
+    let reader = DataReader::from_bytes(&writer.detach_bytes()?)?;
+    assert_eq!(reader.read_utf8(6)?, "héllo");
+    assert_eq!(reader.read_vec(1)?, vec![1]);
+    assert_eq!(reader.read_to_vec()?, vec![2, 3]);
+    assert_eq!(reader.read_to_vec()?, Vec::<u8>::new());
+
+    assert_eq!(writer.detach_bytes()?, Vec::<u8>::new());
+
+    Ok(())
+}
+
+#[test]
+fn io_buffer() -> std::io::Result<()> {
+    use std::io::{Read, Write};
+
+    let mut writer = DataWriter::new()?;
+    writer.write_all(b"hello")?;
+    writer.flush()?;
+
+    let mut reader = DataReader::from_bytes(&writer.detach_bytes()?)?;
+    let mut text = String::new();
+    reader.read_to_string(&mut text)?;
+    assert_eq!(text, "hello");
+
+    Ok(())
+}
+
+#[test]
+fn io_stream() -> std::io::Result<()> {
+    use std::io::{Read, Write};
+    use windows::storage::streams::InMemoryRandomAccessStream;
+
+    let stream = InMemoryRandomAccessStream::new()?;
+    let bytes: Vec<u8> = (0..100_000).map(|i| i as u8).collect();
+
+    let mut writer = DataWriter::create_data_writer(stream.get_output_stream_at(0)?)?;
+    writer.write_all(&bytes)?;
+    writer.flush()?;
+
+    let mut reader = DataReader::create_data_reader(stream.get_input_stream_at(0)?)?;
+    let mut read = Vec::new();
+    reader.read_to_end(&mut read)?;
+    assert_eq!(read, bytes);
+
+    Ok(())
+}
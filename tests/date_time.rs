@@ -0,0 +1,73 @@
+winrt::import!(
+    dependencies
+        "os"
+    modules
+        "windows.foundation"
+);
+
+#[allow(unused_imports)]
+use std::convert::{TryFrom, TryInto};
+#[allow(unused_imports)]
+use windows::foundation::{DateTime, TimeSpan};
+
+#[cfg(feature = "chrono")]
+#[test]
+fn chrono() -> winrt::Result<()> {
+    use chrono::{Duration, TimeZone, Utc};
+
+    // The Unix epoch is 11,644,473,600 seconds after the FILETIME epoch.
+    let epoch = DateTime {
+        universal_time: 116_444_736_000_000_000,
+    };
+    assert!(chrono::DateTime::<Utc>::from(epoch.clone()) == Utc.timestamp_opt(0, 0).unwrap());
+
+    let value = Utc.timestamp_opt(1_600_000_000, 123_456_700).unwrap();
+    let converted: DateTime = value.try_into()?;
+    assert!(chrono::DateTime::<Utc>::from(converted) == value);
+
+    // Dates before 1970 and before 1601 are both representable.
+    let value = Utc.timestamp_opt(-20_000_000_000, 100).unwrap();
+    let converted = DateTime::try_from(value)?;
+    assert!(converted.universal_time < 0);
+    assert!(chrono::DateTime::<Utc>::from(converted) == value);
+
+    let span = TimeSpan {
+        duration: -15_000_001,
+    };
+    let duration = Duration::from(span.clone());
+    assert!(duration == -(Duration::milliseconds(1500) + Duration::nanoseconds(100)));
+    assert!(TimeSpan::try_from(duration)? == span);
+
+    Ok(())
+}
+
+#[cfg(feature = "time")]
+#[test]
+fn time() -> winrt::Result<()> {
+    use time::{Duration, OffsetDateTime};
+
+    let epoch = DateTime {
+        universal_time: 116_444_736_000_000_000,
+    };
+    assert!(OffsetDateTime::try_from(epoch.clone())? == OffsetDateTime::UNIX_EPOCH);
+    assert!(DateTime::try_from(OffsetDateTime::UNIX_EPOCH)? == epoch);
+
+    let value = OffsetDateTime::from_unix_timestamp_nanos(-1_234_567_800).unwrap();
+    let converted = DateTime::try_from(value)?;
+    assert!(OffsetDateTime::try_from(converted)? == value);
+
+    // The year 30000 is beyond the range of OffsetDateTime.
+    let far = DateTime {
+        universal_time: i64::MAX,
+    };
+    assert!(OffsetDateTime::try_from(far).is_err());
+
+    let span = TimeSpan {
+        duration: 25_000_001,
+    };
+    let duration = Duration::from(span.clone());
+    assert!(duration == Duration::new(2, 500_000_100));
+    assert!(TimeSpan::try_from(duration)? == span);
+
+    Ok(())
+}
@@ -0,0 +1,71 @@
+winrt::import!(
+    dependencies
+        "os"
+    modules
+        "windows.application_model"
+);
+
+use std::cell::Cell;
+use std::rc::Rc;
+use windows::application_model::{SuspendingDeferral, SuspendingOperation};
+use winrt::{block_on, DeferralGuard, GetDeferral};
+
+#[derive(Default, Clone)]
+struct Counter(Rc<Cell<u32>>);
+
+impl winrt::Deferral for Counter {
+    fn complete(&self) -> winrt::Result<()> {
+        self.0.set(self.0.get() + 1);
+        Ok(())
+    }
+}
+
+impl GetDeferral for Counter {
+    type Deferral = Counter;
+
+    fn get_deferral(&self) -> winrt::Result<Counter> {
+        Ok(self.clone())
+    }
+}
+
+#[test]
+fn projected() {
+    fn get_deferral<A: GetDeferral<Deferral = SuspendingDeferral>>() {}
+    get_deferral::<SuspendingOperation>();
+}
+
+#[test]
+fn drop() -> winrt::Result<()> {
+    let args = Counter::default();
+
+    {
+        let _guard = DeferralGuard::new(&args)?;
+        assert!(args.0.get() == 0);
+    }
+
+    assert!(args.0.get() == 1);
+    Ok(())
+}
+
+#[test]
+fn complete() -> winrt::Result<()> {
+    let args = Counter::default();
+    DeferralGuard::new(&args)?.complete()?;
+    assert!(args.0.get() == 1);
+    Ok(())
+}
+
+#[test]
+fn complete_after() -> winrt::Result<()> {
+    let args = Counter::default();
+    let guard = DeferralGuard::new(&args)?;
+
+    let value = block_on(guard.complete_after(async {
+        assert!(args.0.get() == 0);
+        123
+    }));
+
+    assert!(value == 123);
+    assert!(args.0.get() == 1);
+    Ok(())
+}
@@ -0,0 +1,29 @@
+use winrt::delay_load::{get_proc, is_available};
+use winrt::ErrorCode;
+
+#[test]
+fn missing_library() {
+    type Function = extern "system" fn() -> u32;
+    let error = unsafe { get_proc::<Function>("winrt-missing-library", "Missing") }
+        .err()
+        .unwrap();
+
+    // ERROR_MOD_NOT_FOUND
+    assert_eq!(error.code(), ErrorCode(0x8007_007E_u32 as i32));
+    assert!(!is_available("winrt-missing-library", "Missing"));
+}
+
+#[cfg(windows)]
+#[test]
+fn available() {
+    type GetTickCount = extern "system" fn() -> u32;
+    let function = unsafe { get_proc::<GetTickCount>("kernel32", "GetTickCount") }.unwrap();
+    assert!(function() > 0);
+
+    let error = unsafe { get_proc::<GetTickCount>("kernel32", "WinrtMissingFunction") }
+        .err()
+        .unwrap();
+
+    // ERROR_PROC_NOT_FOUND
+    assert_eq!(error.code(), ErrorCode(0x8007_007F_u32 as i32));
+}
@@ -0,0 +1,23 @@
+winrt::import!(
+    dependencies
+        "os"
+    modules
+        "windows.foundation"
+    options
+        "interface_names"
+);
+
+use windows::foundation::{IStringable, Uri};
+use winrt::diagnostics::interface_name;
+use winrt::ComInterface;
+
+#[test]
+fn interface_names() {
+    assert!(interface_name(&IStringable::IID).is_none());
+
+    register_interface_names();
+    assert!(interface_name(&IStringable::IID) == Some("Windows.Foundation.IStringable"));
+
+    // A class's IID is that of its default interface.
+    assert!(interface_name(&Uri::IID) == Some("Windows.Foundation.IUriRuntimeClass"));
+}
@@ -0,0 +1,76 @@
+winrt::import!(
+    dependencies
+        "os"
+    modules
+        "windows.graphics.directx.direct3d11"
+);
+
+use windows::graphics::directx::direct3d11::IDirect3DDevice;
+use winrt::interop::{create_direct3d_device, get_dxgi_interface};
+use winrt::{ComInterface, Guid, IUnknown, RawPtr};
+
+#[link(name = "d3d11")]
+extern "system" {
+    fn D3D11CreateDevice(
+        adapter: RawPtr,
+        driver_type: i32,
+        software: RawPtr,
+        flags: u32,
+        feature_levels: *const i32,
+        feature_level_count: u32,
+        sdk_version: u32,
+        device: *mut RawPtr,
+        feature_level: *mut i32,
+        context: *mut RawPtr,
+    ) -> winrt::ErrorCode;
+}
+
+const D3D_DRIVER_TYPE_WARP: i32 = 5;
+const D3D11_CREATE_DEVICE_BGRA_SUPPORT: u32 = 0x20;
+const D3D11_SDK_VERSION: u32 = 7;
+
+const IDXGI_DEVICE: Guid = Guid::from_values(
+    0x54EC_77FA,
+    0x1377,
+    0x44E6,
+    [0x8C, 0x32, 0x88, 0xFD, 0x5F, 0x44, 0xC8, 0x4C],
+);
+
+// Takes ownership of a raw reference so that it's released at the end of the test.
+fn owned(ptr: RawPtr) -> IUnknown {
+    unsafe { std::mem::transmute(ptr) }
+}
+
+#[test]
+fn device_round_trip() -> winrt::Result<()> {
+    let mut d3d_device = std::ptr::null_mut();
+
+    unsafe {
+        D3D11CreateDevice(
+            std::ptr::null_mut(),
+            D3D_DRIVER_TYPE_WARP,
+            std::ptr::null_mut(),
+            D3D11_CREATE_DEVICE_BGRA_SUPPORT,
+            std::ptr::null(),
+            0,
+            D3D11_SDK_VERSION,
+            &mut d3d_device,
+            std::ptr::null_mut(),
+            std::ptr::null_mut(),
+        )
+        .ok()?;
+    }
+
+    let d3d_device = owned(d3d_device);
+    let dxgi_device: IUnknown = unsafe { d3d_device.query_with_iid(&IDXGI_DEVICE) };
+    assert!(!dxgi_device.is_null());
+
+    let device: IDirect3DDevice =
+        unsafe { create_direct3d_device(dxgi_device.as_raw() as RawPtr)? };
+
+    let round_trip = owned(get_dxgi_interface(&device, &IDXGI_DEVICE)?);
+    assert!(round_trip.as_raw() == dxgi_device.as_raw());
+
+    device.trim()?;
+    Ok(())
+}
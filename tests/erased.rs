@@ -0,0 +1,72 @@
+winrt::import!(
+    dependencies
+        "os"
+    modules
+        "windows.foundation.collections"
+    options
+        "erased"
+);
+
+use windows::foundation::collections::{IIterable, IMap, IVectorView, PropertySet, StringMap};
+use windows::foundation::{IReference, IWwwFormUrlDecoderEntry, PropertyValue, Uri};
+use winrt::{HString, TryInto};
+
+#[test]
+fn strings() -> winrt::Result<()> {
+    // IMap<HString, HString> is forwarded to IMap<Erased, Erased>.
+    let map: IMap<HString, HString> = StringMap::new()?.into();
+    assert!(!map.insert("key", "value")?);
+    assert!(map.lookup("key")? == "value");
+    assert!(map.has_key("key")?);
+    assert!(map.size()? == 1);
+
+    let view = map.get_view()?;
+    assert!(view.lookup("key")? == "value");
+
+    Ok(())
+}
+
+#[test]
+fn objects() -> winrt::Result<()> {
+    let set = PropertySet::new()?;
+    let map: IMap<HString, winrt::Object> = set.into();
+    map.insert("uri", &Uri::create_uri("http://kennykerr.ca")?)?;
+
+    let uri: Uri = map.lookup("uri")?.try_into()?;
+    assert!(uri.domain()? == "kennykerr.ca");
+
+    Ok(())
+}
+
+#[test]
+fn interfaces() -> winrt::Result<()> {
+    let uri = Uri::create_uri("http://kennykerr.ca?A=1&B=2")?;
+    let view: IVectorView<IWwwFormUrlDecoderEntry> = uri.query_parsed()?.into();
+
+    assert!(view.size()? == 2);
+    assert!(view.get_at(1)?.value()? == "2");
+
+    let mut items = vec![IWwwFormUrlDecoderEntry::default(); 2];
+    assert!(view.get_many(0, &mut items)? == 2);
+    assert!(items[0].name()? == "A");
+
+    let mut names = String::new();
+    let iterable: IIterable<IWwwFormUrlDecoderEntry> = view.into();
+
+    for entry in iterable {
+        names.push_str(&entry.name()?.to_string());
+    }
+
+    assert!(names == "AB");
+
+    Ok(())
+}
+
+#[test]
+fn values() -> winrt::Result<()> {
+    // Type arguments that aren't pointers still call through their own specialization.
+    let value: IReference<i32> = PropertyValue::create_int32(123)?.try_into()?;
+    assert!(value.value()? == 123);
+
+    Ok(())
+}
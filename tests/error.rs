@@ -0,0 +1,67 @@
+use winrt::{Error, ErrorCode};
+
+fn get(values: &[i32], index: usize) -> winrt::Result<i32> {
+    Ok(*values.get(index).ok_or(ErrorCode::BOUNDS)?)
+}
+
+#[test]
+fn conversions() {
+    assert_eq!(get(&[1, 2], 1).unwrap(), 2);
+
+    let error = get(&[1, 2], 2).unwrap_err();
+    assert_eq!(error.code(), ErrorCode::BOUNDS);
+    assert_eq!(ErrorCode::from(error), ErrorCode::BOUNDS);
+
+    assert_eq!(ErrorCode::from(Ok(())), ErrorCode(0));
+    assert_eq!(
+        ErrorCode::from(Err(Error::from(ErrorCode::FAIL))),
+        ErrorCode::FAIL
+    );
+
+    assert!(ErrorCode(0).ok().is_ok());
+    assert_eq!(
+        ErrorCode::INVALID_ARGUMENT.ok().unwrap_err().code(),
+        ErrorCode::INVALID_ARGUMENT
+    );
+}
+
+#[test]
+fn win32() {
+    assert_eq!(ErrorCode::from_win32(0), ErrorCode(0));
+    assert_eq!(ErrorCode::from_win32(87), ErrorCode::INVALID_ARGUMENT);
+
+    // HRESULTs are passed through unchanged.
+    assert_eq!(ErrorCode::from_win32(0x8000_4005), ErrorCode::FAIL);
+
+    let error = Error::from(std::io::Error::from_raw_os_error(87));
+    assert_eq!(error.code(), ErrorCode::INVALID_ARGUMENT);
+}
+
+#[test]
+fn into_io_error() {
+    // Win32 errors map back to their code, and so to their kind.
+    let error = std::io::Error::from(Error::from(ErrorCode::from_win32(2)));
+    assert_eq!(error.raw_os_error(), Some(2));
+    assert_eq!(error.kind(), std::io::ErrorKind::NotFound);
+
+    // Others are wrapped and come back unchanged.
+    let error = std::io::Error::from(Error::from(ErrorCode::BOUNDS));
+    assert_eq!(error.raw_os_error(), None);
+    assert_eq!(error.kind(), std::io::ErrorKind::Other);
+    assert_eq!(error.to_string(), "HRESULT 0x8000000B");
+    assert_eq!(Error::from(error).code(), ErrorCode::BOUNDS);
+}
+
+#[test]
+fn hresult() {
+    assert_eq!(ErrorCode::from(-2147467259), ErrorCode::FAIL);
+    assert_eq!(i32::from(ErrorCode::BOUNDS), -2147483637);
+}
+
+#[test]
+fn display() {
+    assert_eq!(ErrorCode::BOUNDS.to_string(), "HRESULT 0x8000000B");
+
+    let error: Box<dyn std::error::Error> = Box::new(Error::from(ErrorCode::FAIL));
+    assert_eq!(error.to_string(), "HRESULT 0x80004005");
+}
@@ -0,0 +1,52 @@
+winrt::import!(
+    dependencies
+        "os"
+    modules
+        "windows.foundation"
+);
+
+use winrt::{ErrorCode, EventRegistrationToken};
+
+#[test]
+fn projected() {
+    // The projected struct is the runtime crate's token.
+    let token: windows::foundation::EventRegistrationToken = EventRegistrationToken::new(1);
+    let copy = token;
+    assert_eq!(token, copy);
+    assert!(token.is_valid());
+    assert!(!EventRegistrationToken::default().is_valid());
+    assert_eq!(
+        format!("{:?}", token),
+        "EventRegistrationToken { value: 1 }"
+    );
+}
+
+#[test]
+fn remove() -> winrt::Result<()> {
+    let mut token = EventRegistrationToken::new(123);
+    let mut removed = Vec::new();
+
+    token.remove(|token| {
+        removed.push(token);
+        Ok(())
+    })?;
+
+    assert!(!token.is_valid());
+    assert_eq!(removed, [EventRegistrationToken::new(123)]);
+
+    // Removing again is a no-op since the token has been reset.
+    token.remove(|token| {
+        removed.push(token);
+        Ok(())
+    })?;
+
+    assert_eq!(removed.len(), 1);
+
+    // The token is reset even if the event source fails to remove the handler.
+    let mut token = EventRegistrationToken::new(456);
+    let result = token.remove(|_| Err(ErrorCode(0x8007_0057_u32 as i32).into()));
+    assert!(result.is_err());
+    assert!(!token.is_valid());
+
+    Ok(())
+}
@@ -0,0 +1,69 @@
+winrt::import!(
+    dependencies
+        "os"
+    modules
+        "windows.storage.streams"
+);
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use windows::storage::streams::{DataWriter, InMemoryRandomAccessStream};
+use winrt::heap::{self, GlobalHeap};
+use winrt::AsyncReader;
+
+// Counts the allocations and frees of the capacity used by the test, which nothing else in the
+// process allocates, so that the runtime's own buffers can be told apart.
+struct Counting;
+
+// The reader's capacity plus the size prefix that `GlobalHeap` adds.
+const CAPACITY: u32 = 4099;
+const SIZE: usize = CAPACITY as usize + 2 * std::mem::size_of::<usize>();
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+static FREES: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        if layout.size() == SIZE {
+            ALLOCATIONS.fetch_add(1, Ordering::SeqCst);
+        }
+
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        if layout.size() == SIZE {
+            FREES.fetch_add(1, Ordering::SeqCst);
+        }
+
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: Counting = Counting;
+
+#[test]
+fn global_heap() -> winrt::Result<()> {
+    assert!(heap::set_allocator(&GlobalHeap));
+
+    winrt::block_on(async {
+        let stream = InMemoryRandomAccessStream::new()?;
+        let writer = DataWriter::create_data_writer(stream.get_output_stream_at(0)?)?;
+        writer.write_bytes(&[1, 2, 3])?;
+        writer.store_async()?.await?;
+
+        // The reader's buffer is allocated through the global allocator and freed with it.
+        let mut reader = AsyncReader::with_capacity(stream.get_input_stream_at(0)?, CAPACITY);
+        assert_eq!(reader.next_chunk().await?, Some(vec![1, 2, 3]));
+        assert!(ALLOCATIONS.load(Ordering::SeqCst) > 0);
+
+        drop(reader);
+        assert_eq!(
+            ALLOCATIONS.load(Ordering::SeqCst),
+            FREES.load(Ordering::SeqCst)
+        );
+
+        Ok(())
+    })
+}
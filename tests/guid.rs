@@ -33,3 +33,53 @@ fn guid_from_string() {
 
     assert!(a == b);
 }
+
+#[test]
+fn guid_from_signature() {
+    // IVector<IStringable>
+    const IID: Guid = Guid::from_signature(
+        b"pinterface({913337e9-11a1-4345-a3a2-4e7f956e222d};{96369f54-8eb6-48f0-abce-c1b211e627c3})",
+    );
+
+    assert!(IID == Guid::from("14B954C2-2914-530E-84A7-9473E2FB24E2"));
+}
+
+#[test]
+fn guid_from_signature_parts() {
+    const ISTRINGABLE: Guid = Guid::from_values(
+        0x96369F54,
+        0x8EB6,
+        0x48F0,
+        [0xAB, 0xCE, 0xC1, 0xB2, 0x11, 0xE6, 0x27, 0xC3],
+    );
+
+    assert!(&ISTRINGABLE.signature() == b"{96369f54-8eb6-48f0-abce-c1b211e627c3}");
+
+    // IVector<IStringable>
+    const IID: Guid = Guid::from_signature_parts(&[
+        b"pinterface({913337e9-11a1-4345-a3a2-4e7f956e222d};",
+        &ISTRINGABLE.signature(),
+        b")",
+    ]);
+
+    assert!(IID == Guid::from("14B954C2-2914-530E-84A7-9473E2FB24E2"));
+}
+
+#[cfg(feature = "uuid")]
+#[test]
+fn uuid() {
+    let guid = Guid::from("CFF52E04-CCA6-4614-A17E-754910C84A99");
+    let uuid = uuid::Uuid::from(&guid);
+    assert!(uuid.to_string() == "cff52e04-cca6-4614-a17e-754910c84a99");
+    assert!(Guid::from(uuid) == guid);
+
+    assert!(Guid::new_v4() != Guid::new_v4());
+    assert!(uuid::Uuid::from(Guid::new_v4()).get_version_num() == 4);
+
+    // The DNS namespace from RFC 4122
+    let dns = Guid::from("6BA7B810-9DAD-11D1-80B4-00C04FD430C8");
+    let a = Guid::new_v5(&dns, b"kennykerr.ca");
+    assert!(a == Guid::new_v5(&dns, b"kennykerr.ca"));
+    assert!(a != Guid::new_v5(&dns, b"microsoft.com"));
+    assert!(uuid::Uuid::from(a).get_version_num() == 5);
+}
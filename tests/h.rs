@@ -0,0 +1,43 @@
+winrt::import!(
+    dependencies
+        "os"
+    modules
+        "windows.foundation"
+);
+
+use windows::foundation::Uri;
+use winrt::{HString, Param};
+
+#[test]
+fn literal() {
+    let value = winrt::h!("hello");
+    assert_eq!(value.len(), 5);
+    assert!(**value == "hello");
+
+    let wide: Vec<u16> = "héllo wörld 😀".encode_utf16().collect();
+    assert_eq!(winrt::h!("héllo wörld 😀").as_wide(), &wide[..]);
+
+    assert!(winrt::h!("").is_empty());
+}
+
+#[test]
+fn clone() {
+    // Cloning a reference string copies it into an owned string.
+    let value: HString = HString::clone(winrt::h!("hello"));
+    assert!(value == "hello");
+}
+
+#[test]
+fn param() -> winrt::Result<()> {
+    // String literals are borrowed rather than being converted into a new string.
+    let param: Param<HString> = winrt::h!("hello").into();
+    assert!(match param {
+        Param::Borrowed(value) => *value == "hello",
+        Param::Owned(_) => false,
+    });
+
+    let uri = Uri::create_uri(winrt::h!("http://kennykerr.ca"))?;
+    assert!(uri.domain()? == "kennykerr.ca");
+
+    Ok(())
+}
@@ -0,0 +1,95 @@
+winrt::import!(
+    dependencies
+        "os"
+    modules
+        "windows.foundation"
+);
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use windows::foundation::Uri;
+use winrt::heap::{self, Allocator, ProcessHeap};
+use winrt::{Array, HString};
+
+// An allocator that doesn't use the process heap at all, so that freeing memory it didn't
+// allocate, or handing its memory to the operating system to free, corrupts the heap.
+struct Separate {
+    allocations: AtomicUsize,
+    frees: AtomicUsize,
+}
+
+// Each allocation is prefixed with its size, which `dealloc` needs to rebuild the layout.
+const PREFIX: usize = 16;
+
+unsafe impl Allocator for Separate {
+    unsafe fn alloc(&self, bytes: usize) -> winrt::RawPtr {
+        self.allocations.fetch_add(1, Ordering::SeqCst);
+        let ptr = System.alloc(Layout::from_size_align_unchecked(bytes + PREFIX, PREFIX));
+
+        if ptr.is_null() {
+            return std::ptr::null_mut();
+        }
+
+        *(ptr as *mut usize) = bytes;
+        ptr.add(PREFIX) as winrt::RawPtr
+    }
+
+    unsafe fn free(&self, ptr: winrt::RawPtr) {
+        self.frees.fetch_add(1, Ordering::SeqCst);
+        let ptr = (ptr as *mut u8).sub(PREFIX);
+        let bytes = *(ptr as *mut usize);
+        System.dealloc(
+            ptr,
+            Layout::from_size_align_unchecked(bytes + PREFIX, PREFIX),
+        );
+    }
+}
+
+static SEPARATE: Separate = Separate {
+    allocations: AtomicUsize::new(0),
+    frees: AtomicUsize::new(0),
+};
+
+#[test]
+fn allocator() -> winrt::Result<()> {
+    assert!(heap::set_allocator(&SEPARATE));
+    assert!(!heap::set_allocator(&ProcessHeap));
+
+    // Strings are shared with the operating system and so stay on the process heap.
+    let value = HString::from("hello");
+    let copy = value.clone();
+    drop(value);
+    drop(copy);
+
+    // A string allocated by the operating system is freed where it came from, as is one that
+    // the operating system frees after it was passed in.
+    let uri = Uri::create_uri("http://kennykerr.ca/path")?;
+    let domain = uri.domain()?;
+    assert!(domain == "kennykerr.ca");
+    drop(domain);
+    drop(uri);
+
+    assert_eq!(SEPARATE.allocations.load(Ordering::SeqCst), 0);
+    assert_eq!(SEPARATE.frees.load(Ordering::SeqCst), 0);
+
+    Ok(())
+}
+
+#[test]
+fn receive_array() {
+    let mut array = Array::<u32>::new();
+
+    unsafe {
+        let data = heap::co_task_mem_alloc(3 * std::mem::size_of::<u32>()) as *mut u32;
+        assert!(!data.is_null());
+        data.copy_from_nonoverlapping([1, 2, 3].as_ptr(), 3);
+
+        *array.set_abi() = data;
+        *array.set_abi_len() = 3;
+    }
+
+    assert_eq!(array.as_slice(), [1, 2, 3]);
+
+    array.clear();
+    assert!(array.as_slice().is_empty());
+}
@@ -0,0 +1,34 @@
+use winrt::HString;
+
+#[test]
+fn wide() {
+    let wide: Vec<u16> = "one\0two".encode_utf16().collect();
+    let value = HString::from_wide(&wide);
+    assert_eq!(value.len(), 7);
+    assert_eq!(value.as_wide(), wide.as_slice());
+    assert_eq!(value, "one\0two");
+
+    let terminated = unsafe { std::slice::from_raw_parts(value.as_ptr(), 8) };
+    assert_eq!(&terminated[..7], wide.as_slice());
+    assert_eq!(terminated[7], 0);
+
+    let empty = HString::new();
+    assert!(empty.as_wide().is_empty());
+    assert_eq!(unsafe { *empty.as_ptr() }, 0);
+}
+
+#[cfg(feature = "widestring")]
+#[test]
+fn widestring() {
+    use widestring::{U16CString, U16Str, U16String};
+
+    let value = HString::from("hello");
+    assert_eq!(value.as_u16str(), U16Str::from_slice(value.as_wide()));
+    assert_eq!(U16String::from(&value).to_string_lossy(), "hello");
+
+    let embedded = U16String::from_str("one\0two");
+    assert_eq!(HString::from(embedded.as_ustr()), "one\0two");
+
+    let terminated = U16CString::from_str("hello").unwrap();
+    assert_eq!(HString::from(terminated.as_ucstr()), "hello");
+}
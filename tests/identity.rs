@@ -0,0 +1,35 @@
+winrt::import!(
+    dependencies
+        "os"
+    modules
+        "windows.foundation"
+);
+
+use windows::foundation::{IStringable, Uri};
+use winrt::{Object, TryInto};
+
+#[test]
+fn identity() -> winrt::Result<()> {
+    let a = Uri::create_uri("http://kennykerr.ca")?;
+    let b = Uri::create_uri("http://kennykerr.ca")?;
+
+    // Equal values are still distinct objects.
+    assert!(a == a.clone());
+    assert!(a != b);
+
+    // Different interfaces on the same object compare equal by identity.
+    let stringable: IStringable = a.clone().into();
+    let other: IStringable = b.try_into()?;
+    assert!(stringable != other);
+    assert!(winrt::is_same_object(&a, &stringable));
+    assert!(!winrt::is_same_object(&b, &stringable));
+
+    let object: Object = a.clone().into();
+    assert!(object == stringable.into());
+    assert!(winrt::is_same_object(&object, &a));
+
+    assert!(Uri::default() == Uri::default());
+    assert!(Uri::default() != a);
+
+    Ok(())
+}
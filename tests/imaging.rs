@@ -0,0 +1,55 @@
+winrt::import!(
+    dependencies
+        "os"
+    modules
+        "windows.graphics.imaging"
+);
+
+use windows::graphics::imaging::SoftwareBitmap;
+use winrt::imaging::{PixelFormat, Pixels, Plane};
+
+#[test]
+fn bgra8_round_trip() -> winrt::Result<()> {
+    let data: Vec<u8> = (0..3 * 2 * 4).map(|value| value as u8).collect();
+    let pixels = Pixels::new(3, 2, PixelFormat::Bgra8, data)?;
+
+    let bitmap = SoftwareBitmap::from_pixels(&pixels)?;
+    assert!(bitmap.pixel_width()? == 3);
+    assert!(bitmap.pixel_height()? == 2);
+
+    assert!(bitmap.to_pixels()? == pixels);
+    Ok(())
+}
+
+#[test]
+fn nv12_round_trip() -> winrt::Result<()> {
+    // NV12 requires even dimensions: a 4x2 luma plane followed by a 4x1 chroma plane.
+    let data: Vec<u8> = (0..4 * 2 + 4).map(|value| value as u8).collect();
+    let pixels = Pixels::new(4, 2, PixelFormat::Nv12, data)?;
+
+    let bitmap = SoftwareBitmap::from_pixels(&pixels)?;
+    assert!(bitmap.to_pixels()? == pixels);
+    Ok(())
+}
+
+#[test]
+fn invalid_length() {
+    assert!(Pixels::new(3, 2, PixelFormat::Gray8, vec![0; 5]).is_err());
+    assert!(Pixels::new(3, 2, PixelFormat::Other(0), Vec::new()).is_err());
+}
+
+#[test]
+fn short_data() {
+    // The data of a `Pixels` may be replaced after it has been created.
+    let mut pixels = Pixels::new(3, 2, PixelFormat::Gray8, vec![0; 6]).unwrap();
+    pixels.data.truncate(5);
+
+    let planes = [Plane {
+        start: 0,
+        stride: 4,
+    }];
+    let mut buffer = [0; 8];
+    let error = winrt::imaging::unpack(&pixels, &planes, &mut buffer).unwrap_err();
+    assert!(error.code() == winrt::ErrorCode(0x8007_0057u32 as i32));
+    assert!(SoftwareBitmap::from_pixels(&pixels).is_err());
+}
@@ -0,0 +1,20 @@
+winrt::import!(
+    dependencies
+        "os"
+    types
+        "windows.foundation.uri"
+);
+
+use windows::foundation::Uri;
+
+#[test]
+fn uri() -> winrt::Result<()> {
+    let uri = Uri::create_uri("http://kennykerr.ca/?a=b")?;
+    assert!(uri.domain()? == "kennykerr.ca");
+
+    // WwwFormUrlDecoder is reachable from Uri::query_parsed so it's included as well.
+    let decoder = uri.query_parsed()?;
+    assert!(decoder.get_first_value_by_name("a")? == "b");
+
+    Ok(())
+}
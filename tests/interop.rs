@@ -0,0 +1,67 @@
+winrt::import!(
+    dependencies
+        "os"
+    modules
+        "windows.ui.composition"
+);
+
+use windows::ui::composition::Compositor;
+use winrt::interop::{ICompositorDesktopInterop, WindowHandle};
+use winrt::TryInto;
+
+#[link(name = "coremessaging")]
+extern "system" {
+    fn CreateDispatcherQueueController(
+        options: DispatcherQueueOptions,
+        dispatcherQueueController: *mut winrt::RawComPtr<winrt::IUnknown>,
+    ) -> winrt::ErrorCode;
+}
+
+#[repr(C)]
+struct DispatcherQueueOptions {
+    size: u32,
+    thread_type: i32,
+    apartment_type: i32,
+}
+
+fn create_dispatcher() -> winrt::IUnknown {
+    let options = DispatcherQueueOptions {
+        size: std::mem::size_of::<DispatcherQueueOptions>() as u32,
+        thread_type: 2,    // DQTYPE_THREAD_CURRENT
+        apartment_type: 0, // DQTAT_COM_NONE
+    };
+
+    let mut interop_ptr = winrt::IUnknown::default();
+    unsafe {
+        CreateDispatcherQueueController(options, interop_ptr.set_abi())
+            .ok()
+            .unwrap();
+    }
+    interop_ptr
+}
+
+#[test]
+fn compositor_desktop_interop() -> winrt::Result<()> {
+    let _dispatcher = create_dispatcher();
+    let compositor = Compositor::new()?;
+
+    let interop: ICompositorDesktopInterop = (&compositor).try_into()?;
+    assert!(!winrt::ComInterface::is_null(&interop));
+
+    // A null window handle is rejected rather than producing a target.
+    let target: winrt::Result<winrt::Object> =
+        interop.create_desktop_window_target(WindowHandle(std::ptr::null_mut()), false);
+    assert!(target.is_err());
+
+    Ok(())
+}
+
+#[test]
+fn not_a_core_window() -> winrt::Result<()> {
+    let _dispatcher = create_dispatcher();
+    let compositor = Compositor::new()?;
+
+    assert!(WindowHandle::from_core_window(&compositor).is_err());
+
+    Ok(())
+}
@@ -0,0 +1,43 @@
+#![cfg(feature = "serde_json")]
+
+winrt::import!(
+    dependencies
+        "os"
+    modules
+        "windows.data.json"
+);
+
+use serde_json::json;
+use windows::data::json::{JsonObject, JsonValue};
+
+#[test]
+fn to_serde_json() -> winrt::Result<()> {
+    let object =
+        JsonObject::parse(r#"{"name":"winrt","version":1.5,"count":3,"tags":["a",null,true]}"#)?;
+
+    assert!(
+        object.to_serde_json()?
+            == json!({ "name": "winrt", "version": 1.5, "count": 3, "tags": ["a", null, true] })
+    );
+
+    Ok(())
+}
+
+#[test]
+fn from_serde_json() -> winrt::Result<()> {
+    let value = json!({ "nested": { "list": [1, 2.5, "three"] }, "flag": false });
+    let converted = JsonValue::from_serde_json(&value)?;
+
+    let object = converted.get_object()?;
+    assert!(
+        object
+            .get_named_object("nested")?
+            .get_named_array("list")?
+            .size()?
+            == 3
+    );
+    assert!(object.get_named_boolean("flag")? == false);
+
+    assert!(converted.to_serde_json()? == value);
+    Ok(())
+}
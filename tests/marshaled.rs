@@ -0,0 +1,39 @@
+winrt::import!(
+    dependencies
+        "os"
+    modules
+        "windows.foundation"
+);
+
+use windows::foundation::Uri;
+use winrt::Marshaled;
+
+#[test]
+fn marshaled() -> winrt::Result<()> {
+    let uri = Uri::create_uri("http://kennykerr.ca")?;
+    let marshaled = Marshaled::new(&uri)?;
+
+    let domain = std::thread::spawn(move || -> winrt::Result<String> {
+        let uri = marshaled.resolve()?;
+        Ok(uri.domain()?.to_string())
+    })
+    .join()
+    .unwrap()?;
+
+    assert!(domain == "kennykerr.ca");
+
+    Ok(())
+}
+
+#[test]
+fn unresolved() -> winrt::Result<()> {
+    let uri = Uri::create_uri("http://kennykerr.ca")?;
+
+    // Dropping an unresolved interface releases the marshaled reference.
+    let marshaled = Marshaled::new(&uri)?;
+    std::thread::spawn(move || drop(marshaled)).join().unwrap();
+
+    assert!(uri.domain()? == "kennykerr.ca");
+
+    Ok(())
+}
@@ -0,0 +1,29 @@
+winrt::import!(
+    dependencies
+        "os"
+    modules
+        "windows.foundation"
+);
+
+use windows::foundation::MemoryBuffer;
+use winrt::interop::MemoryBufferGuard;
+
+#[test]
+fn guard() -> winrt::Result<()> {
+    let buffer = MemoryBuffer::create(4)?;
+
+    let mut bytes = MemoryBufferGuard::new(buffer.create_reference()?)?;
+    assert!(bytes.len() == 4);
+    bytes.copy_from_slice(&[1, 2, 3, 4]);
+    assert!(bytes.reference().capacity()? == 4);
+    bytes.close()?;
+
+    let reference = buffer.create_reference()?;
+    let bytes = MemoryBufferGuard::new(reference.clone())?;
+    assert!(*bytes == [1, 2, 3, 4]);
+    drop(bytes);
+
+    // The reference was closed along with the guard.
+    assert!(reference.capacity()? == 0);
+    Ok(())
+}
@@ -0,0 +1,85 @@
+winrt::import!(
+    dependencies
+        "os"
+    modules
+        "windows.application_model"
+        "windows.foundation.collections"
+        "windows.data.json"
+        "windows.graphics.imaging"
+        "windows.storage"
+    options
+        "nullable"
+);
+
+use windows::foundation::collections::{IIterable, StringMap};
+use windows::foundation::{IStringable, Uri};
+
+#[test]
+fn nullable() -> winrt::Result<()> {
+    // Factory methods return classes so they return options too.
+    let uri = Uri::create_uri("http://kennykerr.ca/?a=1")?.unwrap();
+
+    let decoder = uri.query_parsed()?.unwrap();
+    assert!(decoder.get_first_value_by_name("a")? == "1");
+
+    // A Uri without a query string still has an empty decoder.
+    let uri = Uri::create_uri("http://kennykerr.ca")?.unwrap();
+    assert!(uri.query_parsed()?.unwrap().size()? == 0);
+
+    let stringable: IStringable = uri.into();
+    assert!(stringable.to_string()? == "http://kennykerr.ca/");
+
+    // Helpers such as the iterators work the same with this option.
+    let map = StringMap::new()?;
+    map.insert("key", "value")?;
+    let iterable: IIterable<_> = map.into();
+    assert!(iterable.into_iter().count() == 1);
+
+    Ok(())
+}
+
+#[test]
+fn returns_none() -> winrt::Result<()> {
+    use windows::foundation::collections::PropertySet;
+    use winrt::Object;
+
+    let set = PropertySet::new()?;
+    set.insert("null", Object::default())?;
+    assert!(set.lookup("null")?.is_none());
+
+    set.insert("set", PropertySet::new()?)?;
+    assert!(set.lookup("set")?.is_some());
+
+    Ok(())
+}
+
+mod erased {
+    winrt::import!(
+        dependencies
+            "os"
+        modules
+            "windows.foundation.collections"
+        options
+            "erased"
+            "nullable"
+    );
+
+    use windows::foundation::collections::{IIterable, IVector, StringMap};
+    use winrt::ComInterface;
+
+    #[test]
+    fn erased() -> winrt::Result<()> {
+        let map = StringMap::new()?;
+        map.insert("key", "value")?;
+
+        let iterable: IIterable<_> = map.into();
+        let iterator = iterable.first()?.unwrap();
+        assert!(iterator.current()?.key()? == "key");
+
+        // Generic type arguments are returned as they are.
+        let vector = IVector::<winrt::Object>::default();
+        assert!(vector.is_null());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,167 @@
+winrt::import!(
+    dependencies
+        "os"
+    modules
+        "windows.foundation.numerics"
+);
+
+use windows::foundation::numerics::{Matrix3x2, Matrix4x4, Quaternion, Vector2, Vector3};
+
+fn matrix4x4(values: [f32; 16]) -> Matrix4x4 {
+    Matrix4x4 {
+        m11: values[0],
+        m12: values[1],
+        m13: values[2],
+        m14: values[3],
+        m21: values[4],
+        m22: values[5],
+        m23: values[6],
+        m24: values[7],
+        m31: values[8],
+        m32: values[9],
+        m33: values[10],
+        m34: values[11],
+        m41: values[12],
+        m42: values[13],
+        m43: values[14],
+        m44: values[15],
+    }
+}
+
+#[test]
+fn vectors() {
+    let a = Vector2 { x: 1.0, y: 2.0 };
+    let b = Vector2 { x: 3.0, y: 5.0 };
+
+    assert!(&a + &b == Vector2 { x: 4.0, y: 7.0 });
+    assert!(&b - &a == Vector2 { x: 2.0, y: 3.0 });
+    assert!(&a * &b == Vector2 { x: 3.0, y: 10.0 });
+    assert!(&a * 2.0 == Vector2 { x: 2.0, y: 4.0 });
+    assert!(b.clone() / 2.0 == Vector2 { x: 1.5, y: 2.5 });
+    assert!(-a == Vector2 { x: -1.0, y: -2.0 });
+
+    let c = Vector3 {
+        x: 1.0,
+        y: 2.0,
+        z: 3.0,
+    };
+    assert!(
+        c.clone() + c
+            == Vector3 {
+                x: 2.0,
+                y: 4.0,
+                z: 6.0
+            }
+    );
+}
+
+#[test]
+fn matrices() {
+    // Scale by 2 and then translate by (10, 20).
+    let scale = Matrix3x2 {
+        m11: 2.0,
+        m12: 0.0,
+        m21: 0.0,
+        m22: 2.0,
+        m31: 0.0,
+        m32: 0.0,
+    };
+    let translate = Matrix3x2 {
+        m11: 1.0,
+        m12: 0.0,
+        m21: 0.0,
+        m22: 1.0,
+        m31: 10.0,
+        m32: 20.0,
+    };
+    assert!(
+        scale * translate
+            == Matrix3x2 {
+                m11: 2.0,
+                m12: 0.0,
+                m21: 0.0,
+                m22: 2.0,
+                m31: 10.0,
+                m32: 20.0,
+            }
+    );
+
+    let a = matrix4x4([
+        1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0, 10.0, 11.0, 12.0, 13.0, 14.0, 15.0, 16.0,
+    ]);
+    let identity = matrix4x4([
+        1.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 1.0,
+    ]);
+    assert!(&a * &identity == a);
+    assert!(&identity * &a == a);
+    assert!((&a * &a).m11 == 90.0);
+    assert!((&a - &a) == Matrix4x4::default());
+}
+
+#[test]
+fn quaternions() {
+    // i * j = k
+    let i = Quaternion {
+        x: 1.0,
+        y: 0.0,
+        z: 0.0,
+        w: 0.0,
+    };
+    let j = Quaternion {
+        x: 0.0,
+        y: 1.0,
+        z: 0.0,
+        w: 0.0,
+    };
+    assert!(
+        i * j
+            == Quaternion {
+                x: 0.0,
+                y: 0.0,
+                z: 1.0,
+                w: 0.0
+            }
+    );
+}
+
+#[cfg(feature = "mint")]
+#[test]
+fn mint() {
+    let vector = Vector3 {
+        x: 1.0,
+        y: 2.0,
+        z: 3.0,
+    };
+    let converted: mint::Vector3<f32> = vector.clone().into();
+    assert!(
+        converted
+            == mint::Vector3 {
+                x: 1.0,
+                y: 2.0,
+                z: 3.0
+            }
+    );
+    assert!(Vector3::from(converted) == vector);
+
+    let matrix = Matrix3x2 {
+        m11: 1.0,
+        m12: 2.0,
+        m21: 3.0,
+        m22: 4.0,
+        m31: 5.0,
+        m32: 6.0,
+    };
+    let converted: mint::RowMatrix3x2<f32> = matrix.clone().into();
+    assert!(converted.z == mint::Vector2 { x: 5.0, y: 6.0 });
+    assert!(Matrix3x2::from(converted) == matrix);
+
+    let quaternion = Quaternion {
+        x: 1.0,
+        y: 2.0,
+        z: 3.0,
+        w: 4.0,
+    };
+    let converted: mint::Quaternion<f32> = quaternion.clone().into();
+    assert!(converted.s == 4.0);
+    assert!(Quaternion::from(converted) == quaternion);
+}
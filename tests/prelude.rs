@@ -0,0 +1,26 @@
+use winrt::prelude::*;
+
+import!(
+    dependencies
+        "os"
+    modules
+        "windows.foundation"
+);
+
+use windows::foundation::{IStringable, Uri};
+
+#[test]
+fn prelude() -> Result<()> {
+    let uri = Uri::create_uri(h!("http://kennykerr.ca"))?;
+    let stringable: IStringable = uri.try_into()?;
+    assert!(stringable.to_string()? == "http://kennykerr.ca/");
+
+    let object: Object = uri.into();
+    assert_eq!(Uri::NAME, "Windows.Foundation.Uri");
+    assert!(!object.is_null());
+
+    let value: HString = "hello".into();
+    assert!(value == "hello");
+
+    Ok(())
+}
@@ -0,0 +1,81 @@
+winrt::import!(
+    dependencies
+        "os"
+    modules
+        "windows.foundation"
+        "windows.foundation.collections"
+);
+
+use windows::foundation::collections::{PropertySet, ValueSet};
+use windows::foundation::{IPropertyValue, IReference, IReferenceArray, Uri};
+use winrt::{Cast, IntoPropertyValue, Object, TryInto};
+
+#[test]
+fn value_set() -> winrt::Result<()> {
+    let set: ValueSet = winrt::property_set! {
+        "key" => 42,
+        "name" => "foo",
+        "ratio" => 0.5,
+        "enabled" => true,
+    }?;
+
+    assert!(set.size()? == 4);
+
+    let key: IPropertyValue = set.lookup("key")?.cast()?;
+    assert!(key.as_i32()? == 42);
+
+    let name: IPropertyValue = set.lookup("name")?.cast()?;
+    assert!(name.as_string()? == "foo");
+
+    let ratio: IPropertyValue = set.lookup("ratio")?.cast()?;
+    assert!(ratio.as_f64()? == 0.5);
+
+    Ok(())
+}
+
+#[test]
+fn property_set() -> winrt::Result<()> {
+    let uri = Uri::create_uri("http://kennykerr.ca")?;
+    let set: PropertySet = winrt::property_set! { "uri" => Object::from(&uri) }?;
+    assert!(set.lookup("uri")? == Object::from(&uri));
+
+    let empty: PropertySet = winrt::property_set! {}?;
+    assert!(empty.size()? == 0);
+
+    Ok(())
+}
+
+#[test]
+fn boxing() -> winrt::Result<()> {
+    let value: IPropertyValue = 7u8.into_property_value()?.cast()?;
+    assert!(value.as_u8()? == 7);
+
+    Ok(())
+}
+
+#[test]
+fn arrays() -> winrt::Result<()> {
+    let value: IReferenceArray<i32> = [1, 2, 3][..].into_property_value()?.try_into()?;
+    assert!(value.to_vec()? == [1, 2, 3]);
+
+    let value: IReferenceArray<winrt::HString> =
+        vec![winrt::HString::from("a"), winrt::HString::from("b")]
+            .into_property_value()?
+            .try_into()?;
+    assert!(value.to_vec()? == ["a", "b"]);
+
+    Ok(())
+}
+
+#[test]
+fn options() -> winrt::Result<()> {
+    assert!(None::<i32>.into_property_value()? == Object::default());
+
+    let values: Vec<IReference<f64>> = vec![
+        Some(0.5).into_property_value()?.try_into()?,
+        IReference::default(),
+    ];
+    assert!(IReference::to_options(&values)? == [Some(0.5), None]);
+
+    Ok(())
+}
@@ -0,0 +1,17 @@
+winrt::import!(
+    dependencies
+        "os"
+    types
+        "windows.foundation.rect"
+);
+
+use windows::foundation::Rect;
+
+// Rect's fields don't depend on Point and Size, so they aren't generated, and neither is the
+// conversion from them.
+#[test]
+fn rect() {
+    let rect = Rect::from((1.0, 2.0, 3.0, 4.0));
+    assert_eq!(rect, Rect::new(1.0, 2.0, 3.0, 4.0));
+    assert_eq!(<[f32; 4]>::from(rect), [1.0, 2.0, 3.0, 4.0]);
+}
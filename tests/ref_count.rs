@@ -0,0 +1,87 @@
+winrt::import!(
+    dependencies
+        "os"
+    modules
+        "windows.foundation.collections"
+);
+
+use windows::foundation::collections::PropertySet;
+use windows::foundation::{IStringable, Uri};
+use winrt::{ComInterface, Object, Param};
+
+// Returns the current reference count of the object. The values returned by AddRef and Release
+// are only reliable for in-process objects, which is all that these tests use.
+fn ref_count<T: ComInterface>(value: &T) -> u32 {
+    unsafe {
+        type Unknown = [extern "system" fn(winrt::RawPtr) -> u32; 3];
+        let this = value.as_raw() as winrt::RawPtr;
+        let vtable = *(this as *const *const Unknown);
+        ((*vtable)[1])(this);
+        ((*vtable)[2])(this)
+    }
+}
+
+#[test]
+fn returns() -> winrt::Result<()> {
+    // Return values take ownership of the reference handed out by the callee.
+    let uri = Uri::create_uri("http://kennykerr.ca/?a=1")?;
+    assert_eq!(ref_count(&uri), 1);
+
+    // The same is true of methods called through a non-default interface.
+    let decoder = uri.query_parsed()?;
+    assert_eq!(ref_count(&decoder), 1);
+    assert_eq!(ref_count(&uri), 1);
+
+    Ok(())
+}
+
+#[test]
+fn borrowed_params() -> winrt::Result<()> {
+    let uri = Uri::create_uri("http://kennykerr.ca")?;
+
+    // A class may be borrowed as its default interface or as an Object without an AddRef.
+    let param: Param<Object> = (&uri).into();
+    assert!(match param {
+        Param::Borrowed(_) => true,
+        Param::Owned(_) => false,
+    });
+    assert_eq!(ref_count(&uri), 1);
+
+    // Passing a class by reference to a method only adds the references the callee keeps.
+    assert!(uri.equals(&uri)?);
+    assert_eq!(ref_count(&uri), 1);
+
+    let set = PropertySet::new()?;
+    set.insert("uri", &uri)?;
+    assert_eq!(ref_count(&uri), 2);
+    set.clear()?;
+    assert_eq!(ref_count(&uri), 1);
+
+    Ok(())
+}
+
+#[test]
+fn optional_params() -> winrt::Result<()> {
+    let uri = Uri::create_uri("http://kennykerr.ca")?;
+
+    // An optional reference is borrowed as well, and `None` is passed as null.
+    assert!(uri.equals(Some(&uri))?);
+    assert_eq!(ref_count(&uri), 1);
+    assert!(!uri.equals(None)?);
+
+    Ok(())
+}
+
+#[test]
+fn owned_params() -> winrt::Result<()> {
+    let uri = Uri::create_uri("http://kennykerr.ca")?;
+
+    // A non-default interface requires a QueryInterface, which holds a single reference for the
+    // duration of the call.
+    let param: Param<IStringable> = (&uri).into();
+    assert_eq!(ref_count(&uri), 2);
+    drop(param);
+    assert_eq!(ref_count(&uri), 1);
+
+    Ok(())
+}
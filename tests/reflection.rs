@@ -0,0 +1,51 @@
+#![cfg(feature = "reflection")]
+
+use winrt::reflection::TypeInfo;
+
+#[test]
+fn uri() -> winrt::Result<()> {
+    let info = TypeInfo::resolve("Windows.Foundation.Uri")?;
+    assert!(info.name() == ("Windows.Foundation", "Uri"));
+    assert!(info.iid().is_none());
+
+    let interfaces = info.interfaces();
+    let default = interfaces
+        .iter()
+        .find(|interface| interface.default)
+        .unwrap();
+    assert!(default.name == "Windows.Foundation.IUriRuntimeClass");
+
+    let default = info.interface(default)?;
+    assert!(default.iid().is_some());
+
+    let domain = default
+        .methods()
+        .into_iter()
+        .find(|method| method.name == "get_Domain")
+        .unwrap();
+    assert!(domain.slot >= 6);
+    assert!(domain.params.is_empty());
+
+    assert!(TypeInfo::resolve("Contoso.Missing").is_err());
+    Ok(())
+}
+
+#[test]
+fn dynamic() -> winrt::Result<()> {
+    use winrt::dynamic::{DynamicObject, Value};
+
+    let statics = DynamicObject::statics("Windows.Foundation.Uri")?;
+    let uri = match statics.call("CreateUri", &[Value::from("http://kennykerr.ca/feed")])? {
+        Value::Object(uri) => DynamicObject::new(&uri)?,
+        value => panic!("{:?}", value),
+    };
+
+    assert!(uri.type_info().full_name() == "Windows.Foundation.Uri");
+    assert!(uri.get("Domain")? == Value::from("kennykerr.ca"));
+    assert!(uri.get("Port")? == Value::I32(80));
+    assert!(uri.call("ToString", &[])? == Value::from("http://kennykerr.ca/feed"));
+
+    assert!(uri.call("Missing", &[]).is_err());
+    assert!(uri.call("Equals", &[Value::I32(1)]).is_err());
+    Ok(())
+}
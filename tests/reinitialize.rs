@@ -0,0 +1,51 @@
+winrt::import!(
+    dependencies
+        "os"
+    types
+        "windows.foundation.uri"
+);
+
+use windows::foundation::Uri;
+
+#[link(name = "runtimeobject")]
+extern "system" {
+    fn RoInitialize(init_type: i32) -> winrt::ErrorCode;
+    fn RoUninitialize();
+}
+
+// RO_INIT_MULTITHREADED
+const MULTITHREADED: i32 = 1;
+
+fn domain() -> winrt::Result<winrt::HString> {
+    Uri::create_uri("http://kennykerr.ca")?.domain()
+}
+
+// Factories are looked up on every activation, so none is left dangling when the runtime is
+// uninitialized and initialized again, as test runners and plugin hosts may do.
+#[test]
+fn reinitialize() {
+    std::thread::spawn(|| unsafe {
+        RoInitialize(MULTITHREADED).unwrap();
+        assert!(domain().unwrap() == "kennykerr.ca");
+        RoUninitialize();
+
+        RoInitialize(MULTITHREADED).unwrap();
+        assert!(domain().unwrap() == "kennykerr.ca");
+        RoUninitialize();
+    })
+    .join()
+    .unwrap();
+}
+
+// A thread that has uninitialized the runtime gets CO_E_NOTINITIALIZED from the lookup, which
+// joins the MTA and tries again.
+#[test]
+fn uninitialized() {
+    std::thread::spawn(|| unsafe {
+        RoInitialize(MULTITHREADED).unwrap();
+        RoUninitialize();
+        assert!(domain().unwrap() == "kennykerr.ca");
+    })
+    .join()
+    .unwrap();
+}
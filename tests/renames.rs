@@ -0,0 +1,43 @@
+winrt::import!(
+    dependencies
+        "os"
+    modules
+        "windows.foundation"
+        "windows.ui"
+    renames
+        "tests/renames.toml"
+);
+
+use windows::foundation::{Uri, WinUri};
+use windows::ui::Color;
+
+#[test]
+fn methods() -> winrt::Result<()> {
+    let uri = &WinUri::CreateUri("http://kennykerr.ca")?;
+
+    assert!(uri.Host()? == "kennykerr.ca");
+    assert!(uri.Port()? == 80);
+    assert!(uri.ToString()? == "http://kennykerr.ca/");
+
+    // The projection's own names remain available.
+    let uri: &Uri = uri;
+    assert!(uri.domain()? == "kennykerr.ca");
+
+    Ok(())
+}
+
+#[test]
+fn fields() {
+    let color = Color {
+        Alpha: 255,
+        R: 1,
+        G: 2,
+        B: 3,
+    };
+
+    assert!(color == Color::new(255, 1, 2, 3));
+
+    // Conversions use the renamed fields.
+    assert!(<(u8, u8, u8, u8)>::from(color.clone()) == (1, 2, 3, 255));
+    assert!(Color::from([1, 2, 3, 255]) == color);
+}
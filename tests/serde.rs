@@ -0,0 +1,65 @@
+#![cfg(feature = "serde")]
+
+winrt::import!(
+    dependencies
+        "os"
+    modules
+        "windows.foundation"
+        "windows.storage"
+);
+
+use windows::foundation::{DateTime, Point, TimeSpan};
+use windows::storage::FileAttributes;
+use winrt::{Guid, HString};
+
+#[test]
+fn hstring() {
+    let value = HString::from("hello");
+    let json = serde_json::to_string(&value).unwrap();
+    assert!(json == r#""hello""#);
+    assert!(serde_json::from_str::<HString>(&json).unwrap() == value);
+
+    let unpaired = HString::from_wide(&[0x68, 0xD800, 0x69]);
+    assert!(serde_json::to_string(&unpaired).is_err());
+}
+
+#[test]
+fn guid() {
+    let value = Guid::from("00000000-0000-0000-C000-000000000046");
+    let json = serde_json::to_string(&value).unwrap();
+    assert!(json == r#""00000000-0000-0000-C000-000000000046""#);
+    assert!(serde_json::from_str::<Guid>(&json).unwrap() == value);
+
+    assert!(serde_json::from_str::<Guid>(r#""not a guid""#).is_err());
+    assert!(serde_json::from_str::<Guid>(r#""00000000-0000-0000-C000-00000000004G""#).is_err());
+}
+
+#[test]
+fn time() {
+    let value = DateTime {
+        universal_time: 132_000_000_000_000_000,
+    };
+    let json = serde_json::to_string(&value).unwrap();
+    assert!(serde_json::from_str::<DateTime>(&json).unwrap() == value);
+
+    let value = TimeSpan { duration: 10_000 };
+    let json = serde_json::to_string(&value).unwrap();
+    assert!(serde_json::from_str::<TimeSpan>(&json).unwrap() == value);
+}
+
+#[test]
+fn structs() {
+    let value = Point { x: 1.5, y: -2.0 };
+    let json = serde_json::to_string(&value).unwrap();
+    assert!(json == r#"{"x":1.5,"y":-2.0}"#);
+    assert!(serde_json::from_str::<Point>(&json).unwrap() == value);
+}
+
+#[test]
+fn enums() {
+    // Flags are serialized by value so that combinations round-trip.
+    let value = FileAttributes::ReadOnly | FileAttributes::Archive;
+    let json = serde_json::to_string(&value).unwrap();
+    assert!(json == "33");
+    assert!(serde_json::from_str::<FileAttributes>(&json).unwrap() == value);
+}
@@ -0,0 +1,67 @@
+winrt::import!(
+    dependencies
+        "os"
+    modules
+        "windows.storage"
+);
+
+use windows::storage::{StorageFile, StorageFolder};
+
+#[test]
+fn storage_file() -> winrt::Result<()> {
+    winrt::block_on(async {
+        let path = std::env::temp_dir().join("winrt-storage-file.txt");
+        std::fs::write(&path, b"hello world")?;
+
+        let file = StorageFile::from_path(&path)?.await?;
+        assert_eq!(file.to_path_buf()?, Some(path.clone()));
+
+        let mut bytes = Vec::new();
+        assert_eq!(file.copy_to_file(&mut bytes)?.await?, 11);
+        assert_eq!(bytes, b"hello world");
+
+        let mut reader: &[u8] = b"bye";
+        assert_eq!(file.copy_from_file(&mut reader)?.await?, 3);
+        assert_eq!(std::fs::read(&path)?, b"bye");
+
+        std::fs::remove_file(&path)?;
+        Ok(())
+    })
+}
+
+#[test]
+fn storage_folder() -> winrt::Result<()> {
+    winrt::block_on(async {
+        let path = std::env::temp_dir();
+        let folder = StorageFolder::from_path(&path)?.await?;
+        assert_eq!(
+            folder
+                .to_path_buf()?
+                .map(|folder| folder.canonicalize().unwrap()),
+            Some(path.canonicalize()?)
+        );
+
+        Ok(())
+    })
+}
+
+#[test]
+fn io_error() {
+    let error = winrt::Error::from(std::io::Error::new(
+        std::io::ErrorKind::NotFound,
+        "missing.txt",
+    ));
+    assert_eq!(error.code(), winrt::ErrorCode(0x8007_0002u32 as i32));
+    assert_eq!(error.message(), Some("missing.txt"));
+
+    let error = winrt::Error::from(std::io::Error::from(std::io::ErrorKind::InvalidInput));
+    assert_eq!(error.code(), winrt::ErrorCode(0x8007_0057u32 as i32));
+}
+
+#[test]
+fn io_error_from_win32() {
+    // ERROR_ACCESS_DENIED, as returned for a file that's in use.
+    let error = winrt::Error::from(std::io::Error::from_raw_os_error(5));
+    assert_eq!(error.code(), winrt::ErrorCode::from_win32(5));
+    assert_eq!(error.code(), winrt::ErrorCode(0x8007_0005u32 as i32));
+}
@@ -0,0 +1,92 @@
+winrt::import!(
+    dependencies
+        "os"
+    modules
+        "windows.storage.streams"
+);
+
+use windows::storage::streams::{DataWriter, IInputStream, InMemoryRandomAccessStream};
+use winrt::{AsyncReader, InputStream};
+
+async fn input_stream(bytes: &[u8]) -> winrt::Result<IInputStream> {
+    let stream = InMemoryRandomAccessStream::new()?;
+
+    let writer = DataWriter::create_data_writer(stream.get_output_stream_at(0)?)?;
+    writer.write_bytes(bytes)?;
+    writer.store_async()?.await?;
+
+    stream.get_input_stream_at(0)
+}
+
+#[test]
+fn next_chunk() -> winrt::Result<()> {
+    winrt::block_on(async {
+        let mut reader = AsyncReader::with_capacity(input_stream(&[1, 2, 3, 4, 5]).await?, 2);
+
+        assert_eq!(reader.next_chunk().await?, Some(vec![1, 2]));
+        assert_eq!(reader.next_chunk().await?, Some(vec![3, 4]));
+        assert_eq!(reader.next_chunk().await?, Some(vec![5]));
+        assert_eq!(reader.next_chunk().await?, None);
+        assert_eq!(reader.next_chunk().await?, None);
+
+        Ok(())
+    })
+}
+
+#[test]
+fn class() -> winrt::Result<()> {
+    winrt::block_on(async {
+        let stream = InMemoryRandomAccessStream::new()?;
+        let mut reader = stream.into_async_read();
+        assert_eq!(reader.next_chunk().await?, None);
+
+        Ok(())
+    })
+}
+
+#[cfg(feature = "futures")]
+#[test]
+fn async_read() -> winrt::Result<()> {
+    use futures::{AsyncReadExt, TryStreamExt};
+
+    winrt::block_on(async {
+        let mut reader = AsyncReader::with_capacity(input_stream(&[1, 2, 3, 4, 5]).await?, 2);
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await.unwrap();
+        assert_eq!(bytes, [1, 2, 3, 4, 5]);
+
+        let reader = input_stream(b"hello").await?.into_async_read();
+        let chunks: Vec<Vec<u8>> = reader.try_collect().await?;
+        assert_eq!(chunks.concat(), b"hello");
+
+        Ok(())
+    })
+}
+
+#[cfg(feature = "futures")]
+#[test]
+fn async_write() -> winrt::Result<()> {
+    use futures::AsyncWriteExt;
+    use winrt::OutputStream;
+
+    winrt::block_on(async {
+        let stream = InMemoryRandomAccessStream::new()?;
+        let bytes: Vec<u8> = (0..100_000).map(|i| i as u8).collect();
+
+        let mut writer = stream.get_output_stream_at(0)?.into_async_write();
+        writer.write_all(&bytes).await.unwrap();
+        writer.write_all(b"hello").await.unwrap();
+        writer.close().await.unwrap();
+        assert_eq!(stream.size()?, 100_005);
+
+        let mut reader = stream.get_input_stream_at(0)?.into_async_read();
+        let mut read = Vec::new();
+        futures::AsyncReadExt::read_to_end(&mut reader, &mut read)
+            .await
+            .unwrap();
+        assert_eq!(&read[..100_000], &bytes[..]);
+        assert_eq!(&read[100_000..], b"hello");
+
+        Ok(())
+    })
+}
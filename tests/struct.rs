@@ -0,0 +1,75 @@
+winrt::import!(
+    dependencies
+        "os"
+    modules
+        "windows.foundation"
+        "windows.ui"
+);
+
+use std::collections::HashSet;
+use windows::foundation::{Point, Rect, Size, TimeSpan};
+use windows::ui::Color;
+
+#[test]
+fn new() {
+    let point = Point::new(1.0, 2.0);
+    assert_eq!(point, Point { x: 1.0, y: 2.0 });
+
+    let rect = Rect::new(1.0, 2.0, 3.0, 4.0);
+    assert_eq!(rect.width, 3.0);
+    assert_eq!(rect.height, 4.0);
+    assert_eq!(Rect::default(), Rect::new(0.0, 0.0, 0.0, 0.0));
+}
+
+#[test]
+fn copy() {
+    let a = Point::new(1.0, 2.0);
+    let b = a;
+    assert_eq!(a, b);
+}
+
+#[test]
+fn hash() {
+    let mut colors = HashSet::new();
+    colors.insert(Color::new(255, 0, 0, 0));
+    colors.insert(Color::new(255, 255, 255, 255));
+    colors.insert(Color::new(255, 0, 0, 0));
+    assert_eq!(colors.len(), 2);
+
+    let mut spans = HashSet::new();
+    spans.insert(TimeSpan::new(10));
+    assert!(spans.contains(&TimeSpan { duration: 10 }));
+}
+
+#[test]
+fn tuples() {
+    assert_eq!(Point::from((1.0, 2.0)), Point::new(1.0, 2.0));
+    assert_eq!(<[f32; 2]>::from(Size::new(3.0, 4.0)), [3.0, 4.0]);
+
+    let rect = Rect::from((Point::new(1.0, 2.0), Size::new(3.0, 4.0)));
+    assert_eq!(rect, Rect::from([1.0, 2.0, 3.0, 4.0]));
+    assert_eq!(<(f32, f32, f32, f32)>::from(rect), (1.0, 2.0, 3.0, 4.0));
+
+    // Colors are converted in RGBA order.
+    let color = Color::from((1, 2, 3, 255));
+    assert_eq!(color, Color::new(255, 1, 2, 3));
+    assert_eq!(<[u8; 4]>::from(color), [1, 2, 3, 255]);
+}
+
+#[cfg(feature = "euclid")]
+#[test]
+fn euclid() {
+    let rect: euclid::default::Rect<f32> = Rect::new(1.0, 2.0, 3.0, 4.0).into();
+    assert_eq!(rect.origin, euclid::point2(1.0, 2.0));
+    assert_eq!(Rect::from(rect), Rect::new(1.0, 2.0, 3.0, 4.0));
+    assert_eq!(Point::from(rect.origin), Point::new(1.0, 2.0));
+    assert_eq!(Size::from(rect.size), Size::new(3.0, 4.0));
+}
+
+#[cfg(feature = "palette")]
+#[test]
+fn palette() {
+    let color: palette::Srgba<u8> = Color::new(255, 1, 2, 3).into();
+    assert_eq!(color, palette::Srgba::new(1, 2, 3, 255));
+    assert_eq!(Color::from(color), Color::new(255, 1, 2, 3));
+}
@@ -0,0 +1,64 @@
+// These tests cover the stand-in runtime used on targets other than Windows.
+#![cfg(not(windows))]
+
+use winrt::{ErrorCode, HString, IActivationFactory, RuntimeName};
+
+struct Uri;
+
+impl RuntimeName for Uri {
+    const NAME: &'static str = "Windows.Foundation.Uri";
+}
+
+#[test]
+fn activation() {
+    let error = winrt::activation::factory::<Uri, IActivationFactory>()
+        .err()
+        .unwrap();
+
+    // ERROR_NOT_SUPPORTED
+    assert_eq!(error.code(), ErrorCode(0x8007_0032_u32 as i32));
+}
+
+#[test]
+fn string() {
+    let value = HString::from("hello");
+    let copy = value.clone();
+    assert_eq!(copy, "hello");
+    assert_eq!(value.to_string(), "hello");
+    assert_eq!(HString::from(String::from("")).len(), 0);
+}
+
+// A future that never completes but records whether it was canceled.
+struct Never(std::rc::Rc<std::cell::Cell<bool>>);
+
+impl std::future::Future for Never {
+    type Output = winrt::Result<u32>;
+
+    fn poll(
+        self: std::pin::Pin<&mut Self>,
+        _: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Self::Output> {
+        std::task::Poll::Pending
+    }
+}
+
+impl winrt::Cancelable for Never {
+    fn cancel(&self) -> winrt::Result<()> {
+        self.0.set(true);
+        Ok(())
+    }
+}
+
+#[test]
+fn timeout() {
+    use winrt::Cancelable;
+
+    assert_eq!(winrt::block_on(async { 123 }), 123);
+
+    let canceled = std::rc::Rc::new(std::cell::Cell::new(false));
+    let never = Never(canceled.clone());
+    let result = winrt::block_on(never.with_timeout(std::time::Duration::from_millis(10)));
+
+    assert!(result.unwrap_err().code() == ErrorCode(0x8007_05B4u32 as i32));
+    assert!(canceled.get());
+}
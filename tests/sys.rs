@@ -0,0 +1,61 @@
+#![cfg(any(feature = "winapi", feature = "windows-sys"))]
+
+use winrt::interop::WindowHandle;
+use winrt::*;
+
+#[test]
+fn error_code() {
+    let code = ErrorCode::from(0x8007_0057u32 as i32);
+    assert_eq!(i32::from(code), 0x8007_0057u32 as i32);
+    assert_eq!(ErrorCode::from(0), ErrorCode(0));
+}
+
+#[cfg(feature = "winapi")]
+#[test]
+fn winapi() {
+    use winapi::shared::guiddef::GUID;
+    use winapi::shared::windef::HWND;
+    use winrt::sys::winapi::*;
+
+    let guid = Guid::from("CFF52E04-CCA6-4614-A17E-754910C84A99");
+    let raw: GUID = (&guid).into();
+    assert_eq!(raw.Data1, 0xCFF5_2E04);
+    assert_eq!(raw.Data4, [0xA1, 0x7E, 0x75, 0x49, 0x10, 0xC8, 0x4A, 0x99]);
+    assert_eq!(Guid::from(raw), guid);
+
+    let handle = WindowHandle(0x1234 as RawPtr);
+    let hwnd: HWND = handle.into();
+    assert_eq!(WindowHandle::from(hwnd), handle);
+
+    let value = HString::from("hello");
+    let raw = hstring_into_raw(value);
+    assert!(!raw.is_null());
+    let value = unsafe { hstring_from_raw(raw) };
+    assert_eq!(value, "hello");
+    assert_eq!(hstring_as_raw(&value), raw);
+}
+
+#[cfg(feature = "windows-sys")]
+#[test]
+fn windows_sys() {
+    use windows_sys::core::GUID;
+    use windows_sys::Win32::Foundation::HWND;
+    use winrt::sys::windows_sys::*;
+
+    let guid = Guid::from("CFF52E04-CCA6-4614-A17E-754910C84A99");
+    let raw: GUID = (&guid).into();
+    assert_eq!(raw.data1, 0xCFF5_2E04);
+    assert_eq!(raw.data4, [0xA1, 0x7E, 0x75, 0x49, 0x10, 0xC8, 0x4A, 0x99]);
+    assert_eq!(Guid::from(raw), guid);
+
+    let handle = WindowHandle(0x1234 as RawPtr);
+    let hwnd: HWND = handle.into();
+    assert_eq!(WindowHandle::from(hwnd), handle);
+
+    let value = HString::from("hello");
+    let raw = hstring_into_raw(value);
+    assert!(!raw.is_null());
+    let value = unsafe { hstring_from_raw(raw) };
+    assert_eq!(value, "hello");
+    assert_eq!(hstring_as_raw(&value), raw);
+}
@@ -0,0 +1,31 @@
+use winrt::block_on;
+
+#[test]
+fn join() -> winrt::Result<()> {
+    let handle = winrt::spawn(|| 1 + 2)?;
+    assert!(handle.join() == 3);
+
+    Ok(())
+}
+
+#[test]
+fn await_result() -> winrt::Result<()> {
+    let main = std::thread::current().id();
+
+    let value = block_on(async {
+        let other = winrt::spawn(|| std::thread::current().id())?.await;
+        winrt::Result::Ok(other)
+    })?;
+
+    assert!(value != main);
+
+    Ok(())
+}
+
+#[test]
+#[should_panic(expected = "work item panicked")]
+fn panic() {
+    winrt::spawn(|| panic!("work item panicked"))
+        .unwrap()
+        .join();
+}
@@ -39,3 +39,24 @@ fn try_into() -> winrt::Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn same_interface() -> winrt::Result<()> {
+    use winrt::ComInterface;
+    use winrt::TryInto;
+
+    use windows::foundation::IUriRuntimeClass;
+    use windows::foundation::Uri;
+
+    let uri = Uri::create_uri("http://kennykerr.ca")?;
+
+    // Casting a class to its default interface, or back again, doesn't need a QueryInterface
+    // and simply shares the same pointer.
+    let default: IUriRuntimeClass = (&uri).try_into()?;
+    assert!(default.as_raw() as usize == uri.as_raw() as usize);
+
+    let uri: Uri = (&default).try_into()?;
+    assert!(uri.domain()? == "kennykerr.ca");
+
+    Ok(())
+}
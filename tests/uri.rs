@@ -72,3 +72,64 @@ fn interface_conversion() -> winrt::Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn debug() -> winrt::Result<()> {
+    use windows::foundation::{Uri, WwwFormUrlDecoder};
+
+    let uri = Uri::create_uri("http://kennykerr.ca")?;
+    assert_eq!(
+        format!("{:?}", uri),
+        r#"Windows.Foundation.Uri("http://kennykerr.ca/")"#
+    );
+
+    assert_eq!(
+        format!("{:?}", Uri::default()),
+        "Windows.Foundation.Uri(null)"
+    );
+
+    // WwwFormUrlDecoder does not implement IStringable.
+    let decoder = WwwFormUrlDecoder::create_www_form_url_decoder("?a=1")?;
+    assert!(format!("{:?}", decoder).starts_with("Windows.Foundation.WwwFormUrlDecoder(0x"));
+
+    Ok(())
+}
+
+#[test]
+fn display() -> winrt::Result<()> {
+    let uri = Uri::create_uri("http://kennykerr.ca")?;
+    assert_eq!(format!("{}", uri), "http://kennykerr.ca/");
+    assert_eq!(format!("{}", Uri::default()), "");
+
+    Ok(())
+}
+
+#[test]
+fn from_str() -> winrt::Result<()> {
+    use std::convert::TryFrom;
+
+    let uri = Uri::try_from("http://kennykerr.ca")?;
+    assert!(uri.domain()? == "kennykerr.ca");
+
+    let uri: Uri = "http://kennykerr.ca/path?query".parse()?;
+    assert!(uri.path()? == "/path");
+
+    assert!("not a uri".parse::<Uri>().is_err());
+
+    Ok(())
+}
+
+#[cfg(feature = "url")]
+#[test]
+fn url() -> winrt::Result<()> {
+    use std::convert::TryFrom;
+
+    let uri = Uri::create_uri("http://kennykerr.ca/path?query")?;
+    let url = url::Url::try_from(&uri)?;
+    assert_eq!(url.as_str(), "http://kennykerr.ca/path?query");
+
+    let uri = Uri::try_from(url)?;
+    assert!(uri.query()? == "?query");
+
+    Ok(())
+}
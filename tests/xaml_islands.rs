@@ -0,0 +1,29 @@
+winrt::import!(
+    dependencies
+        "os"
+    modules
+        "windows.foundation"
+        "windows.ui.xaml.hosting"
+);
+
+use windows::foundation::Uri;
+use windows::ui::xaml::hosting::{DesktopWindowXamlSource, WindowsXamlManager};
+use winrt::interop::WindowHandle;
+use winrt::xaml_islands;
+
+// Hosting XAML requires a window and an app manifest declaring Windows 10 1903 or later, so this
+// only checks that the projected hosting types can be used with the helpers.
+#[allow(dead_code)]
+fn host(parent: WindowHandle) -> winrt::Result<(WindowsXamlManager, DesktopWindowXamlSource)> {
+    let manager: WindowsXamlManager = xaml_islands::initialize_for_current_thread()?;
+    let source: DesktopWindowXamlSource = xaml_islands::create_source()?;
+    xaml_islands::attach_to_window(&source, parent)?;
+    Ok((manager, source))
+}
+
+#[test]
+fn not_a_xaml_source() -> winrt::Result<()> {
+    let uri = Uri::create_uri("http://kennykerr.ca")?;
+    assert!(xaml_islands::attach_to_window(&uri, WindowHandle(std::ptr::null_mut())).is_err());
+    Ok(())
+}